@@ -1,8 +1,14 @@
 use base64::Engine as _;
 
-use common_core::{Block, BlockAttrs, CellAttrs, Inline, TiptapMark, TiptapNode};
+use std::collections::HashMap;
 
-use crate::ImageAsset;
+use common_core::bookmark::bookmark_names;
+use common_core::toc::for_each_toc_mut;
+use common_core::walk::for_each_inline_mut;
+use common_core::{Block, BlockAttrs, CellAttrs, Inline, LinkAttrs, TiptapMark, TiptapNode};
+
+use crate::html::bookmark_anchor;
+use crate::{ContentSection, ImageAsset};
 
 // ---------------------------------------------------------------------------
 // TiptapNode → Block conversion
@@ -17,6 +23,7 @@ pub(crate) fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
             let block_attrs = attrs.map(|a| BlockAttrs {
                 text_align: a.text_align,
                 indent: a.indent,
+                ..BlockAttrs::default()
             });
             Some(Block::Paragraph {
                 style_name,
@@ -30,6 +37,7 @@ pub(crate) fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
             let block_attrs = attrs.map(|a| BlockAttrs {
                 text_align: a.text_align,
                 indent: a.indent,
+                ..BlockAttrs::default()
             });
             Some(Block::Heading {
                 level,
@@ -42,14 +50,18 @@ pub(crate) fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
             src: attrs.src,
             alt: attrs.alt,
             title: attrs.title,
+            frame: attrs.frame,
+            caption: attrs.caption,
         }),
-        TiptapNode::BulletList { content } => Some(Block::BulletList {
+        TiptapNode::BulletList { attrs, content } => Some(Block::BulletList {
+            attrs,
             content: content
                 .into_iter()
                 .filter_map(tiptap_node_to_block)
                 .collect(),
         }),
-        TiptapNode::OrderedList { content } => Some(Block::OrderedList {
+        TiptapNode::OrderedList { attrs, content } => Some(Block::OrderedList {
+            attrs,
             content: content
                 .into_iter()
                 .filter_map(tiptap_node_to_block)
@@ -67,6 +79,15 @@ pub(crate) fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
                 .filter_map(tiptap_node_to_block)
                 .collect(),
         }),
+        TiptapNode::Section { attrs, content } => Some(Block::Section {
+            name: attrs.name,
+            attrs: Some(attrs.columns).filter(|c| !c.is_empty()),
+            protected: attrs.protected,
+            content: content
+                .into_iter()
+                .filter_map(tiptap_node_to_block)
+                .collect(),
+        }),
         TiptapNode::Table { content } => Some(Block::Table {
             content: content
                 .into_iter()
@@ -95,6 +116,7 @@ pub(crate) fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
         }),
         TiptapNode::HorizontalRule => Some(Block::HorizontalRule),
         TiptapNode::PageBreak => Some(Block::PageBreak),
+        TiptapNode::TableOfContents { attrs } => Some(Block::TableOfContents(attrs)),
         _ => None,
     }
 }
@@ -113,12 +135,105 @@ pub(crate) fn tiptap_content_to_inlines(nodes: Vec<TiptapNode>) -> Vec<Inline> {
             TiptapNode::HardBreak => {
                 inlines.push(Inline::LineBreak);
             }
+            TiptapNode::Note { attrs, content } => {
+                inlines.push(Inline::Note {
+                    id: attrs.id,
+                    note_class: attrs.note_class,
+                    citation: attrs.citation,
+                    content: content
+                        .into_iter()
+                        .filter_map(tiptap_node_to_block)
+                        .collect(),
+                });
+            }
+            TiptapNode::Field { attrs } => {
+                inlines.push(Inline::Field {
+                    kind: attrs.kind,
+                    value: attrs.value,
+                    fixed: false,
+                    date_value: None,
+                    data_style_name: None,
+                });
+            }
+            TiptapNode::Bookmark { attrs } => {
+                inlines.push(Inline::Bookmark { name: attrs.name });
+            }
+            TiptapNode::Reference { attrs } => {
+                inlines.push(Inline::Reference(attrs));
+            }
             _ => {}
         }
     }
     inlines
 }
 
+// ---------------------------------------------------------------------------
+// Cross-references
+// ---------------------------------------------------------------------------
+
+/// Turn cross-references into links to their bookmark, which may be in
+/// another section's XHTML file. References to a bookmark that is not in
+/// the book keep their text without a link.
+///
+/// The targets of table of contents entries become the href of their
+/// heading's bookmark, or `None` when it is not in the book.
+pub(crate) fn link_references(sections: &mut [ContentSection]) {
+    let mut targets: HashMap<String, String> = HashMap::new();
+    for section in sections.iter() {
+        for name in bookmark_names(&section.blocks) {
+            targets
+                .entry(name.to_string())
+                .or_insert_with(|| format!("{}.xhtml#{}", section.id, bookmark_anchor(name)));
+        }
+    }
+    for section in sections.iter_mut() {
+        for_each_inline_mut(&mut section.blocks, &mut |inline| {
+            let linked = match inline {
+                Inline::Reference(reference) => {
+                    targets.get(&reference.name).map(|href| Inline::Text {
+                        text: reference.value.clone(),
+                        style_name: None,
+                        marks: vec![TiptapMark::Link {
+                            attrs: LinkAttrs {
+                                href: href.clone(),
+                                target: None,
+                            },
+                        }],
+                    })
+                }
+                _ => None,
+            };
+            if let Some(linked) = linked {
+                *inline = linked;
+            }
+        });
+        for_each_toc_mut(&mut section.blocks, &mut |toc| {
+            for entry in &mut toc.entries {
+                entry.target = entry.target.take().and_then(|t| targets.get(&t).cloned());
+            }
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Notes
+// ---------------------------------------------------------------------------
+
+/// Number every note in the book with a running counter, so each note
+/// reference links to its own `<aside>` even when notes share a citation or
+/// their imported ids clash.
+pub(crate) fn number_notes(sections: &mut [ContentSection]) {
+    let mut counter = 0usize;
+    for section in sections.iter_mut() {
+        for_each_inline_mut(&mut section.blocks, &mut |inline| {
+            if let Inline::Note { id, .. } = inline {
+                counter += 1;
+                *id = Some(counter.to_string());
+            }
+        });
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Image asset extraction from Block tree
 // ---------------------------------------------------------------------------
@@ -148,10 +263,11 @@ fn extract_images_from_block(block: &Block, assets: &mut Vec<ImageAsset>, counte
             // Inline images are not in the Block::Image path, nothing to do.
             let _ = content;
         }
-        Block::BulletList { content }
-        | Block::OrderedList { content }
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
         | Block::ListItem { content }
         | Block::Blockquote { content }
+        | Block::Section { content, .. }
         | Block::Table { content }
         | Block::TableRow { content } => {
             for child in content {
@@ -163,7 +279,7 @@ fn extract_images_from_block(block: &Block, assets: &mut Vec<ImageAsset>, counte
                 extract_images_from_block(child, assets, counter);
             }
         }
-        Block::HorizontalRule | Block::PageBreak => {}
+        Block::HorizontalRule | Block::PageBreak | Block::TableOfContents(_) => {}
     }
 }
 
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use common_core::StyleDefinition;
+use common_core::{StyleDefinition, StyleFamily, StyleResolver};
 
 use crate::FontAsset;
 
@@ -19,22 +19,24 @@ pub(crate) fn generate_css(
         ));
     }
 
-    // One CSS class per named style
-    for (name, style) in styles {
+    // One CSS class per named paragraph or text style, carrying its
+    // effective properties since classes do not inherit from each other
+    let resolver = StyleResolver::new(styles);
+    let named = styles.iter().filter(|(_, s)| {
+        matches!(s.family, StyleFamily::Paragraph | StyleFamily::Text) && !s.is_default_style()
+    });
+    for (name, style) in named {
         let class_name = name.replace(' ', "-");
         css.push_str(&format!(".style-{} {{\n", class_name));
 
-        for (key, value) in &style.attributes {
+        let effective = resolver.resolve(Some(name), style.family);
+        for (key, value) in &effective.attributes {
             let css_prop = odf_to_css_property(key);
             if !css_prop.is_empty() {
                 css.push_str(&format!("  {}: {};\n", css_prop, value));
             }
         }
 
-        if let Some(transform) = &style.text_transform {
-            css.push_str(&format!("  text-transform: {};\n", transform));
-        }
-
         css.push_str("}\n\n");
     }
 
@@ -43,7 +45,7 @@ pub(crate) fn generate_css(
 
 /// Map an ODF property name (prefixed) to its CSS equivalent.
 /// Returns an empty string for properties with no direct CSS mapping.
-fn odf_to_css_property(odf_prop: &str) -> &'static str {
+pub(crate) fn odf_to_css_property(odf_prop: &str) -> &'static str {
     match odf_prop {
         // Typography
         "fo:font-family" => "font-family",
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use common_core::{Block, BlockAttrs, Inline, StyleDefinition, TiptapMark};
+use common_core::{Block, BlockAttrs, Inline, ListAttrs, NoteClass, StyleDefinition, TiptapMark};
 
+use crate::css::odf_to_css_property;
 use crate::{table, ImageAsset};
 
 // ---------------------------------------------------------------------------
@@ -23,19 +24,23 @@ pub(crate) fn escape_xml(text: &str) -> String {
 
 /// Build an inline `style="..."` attribute string from `BlockAttrs`.
 /// Returns an empty string when no style properties are needed.
+///
+/// Direct formatting maps to the same ODF properties the style resolver
+/// applies, so indents match the PDF export. Properties without a CSS
+/// equivalent are left out.
 fn build_style_attr(attrs: Option<&BlockAttrs>) -> String {
     let Some(attrs) = attrs else {
         return String::new();
     };
-    let mut props: Vec<String> = Vec::new();
-    if let Some(ref align) = attrs.text_align {
-        props.push(format!("text-align:{}", align));
-    }
-    if let Some(indent) = attrs.indent {
-        if indent > 0 {
-            props.push(format!("padding-left:{}em", indent));
-        }
-    }
+    let mut props: Vec<String> = attrs
+        .to_odf_attributes()
+        .iter()
+        .filter_map(|(key, value)| {
+            let prop = odf_to_css_property(key);
+            (!prop.is_empty()).then(|| format!("{}:{}", prop, value))
+        })
+        .collect();
+    props.sort();
     if props.is_empty() {
         String::new()
     } else {
@@ -43,6 +48,28 @@ fn build_style_attr(attrs: Option<&BlockAttrs>) -> String {
     }
 }
 
+/// Build the `start` and `list-style-type` attributes of an `<ol>` element.
+fn ordered_list_attrs(attrs: Option<&ListAttrs>) -> String {
+    let Some(attrs) = attrs else {
+        return String::new();
+    };
+    let mut out = String::new();
+    if let Some(start) = attrs.start {
+        out.push_str(&format!(" start=\"{}\"", start));
+    }
+    let list_style = match attrs.num_format.as_deref() {
+        Some("a") => Some("lower-alpha"),
+        Some("A") => Some("upper-alpha"),
+        Some("i") => Some("lower-roman"),
+        Some("I") => Some("upper-roman"),
+        _ => None,
+    };
+    if let Some(list_style) = list_style {
+        out.push_str(&format!(" style=\"list-style-type:{}\"", list_style));
+    }
+    out
+}
+
 // ---------------------------------------------------------------------------
 // Inline rendering (G3, G4, G8, G9)
 // ---------------------------------------------------------------------------
@@ -69,6 +96,14 @@ pub(crate) fn inlines_to_html(inlines: &[Inline]) -> String {
                         TiptapMark::Strike => format!("<s>{}</s>", content),
                         TiptapMark::Superscript => format!("<sup>{}</sup>", content),
                         TiptapMark::Subscript => format!("<sub>{}</sub>", content),
+                        TiptapMark::TextStyle { attrs } => {
+                            let css = attrs.to_css();
+                            if css.is_empty() {
+                                content
+                            } else {
+                                format!("<span style=\"{}\">{}</span>", escape_xml(&css), content)
+                            }
+                        }
                         // G4: escape href
                         TiptapMark::Link { attrs } => {
                             format!("<a href=\"{}\">{}</a>", escape_xml(&attrs.href), content)
@@ -102,11 +137,126 @@ pub(crate) fn inlines_to_html(inlines: &[Inline]) -> String {
             Inline::LineBreak => {
                 html.push_str("<br/>");
             }
+            // Non-breaking spaces keep significant runs from collapsing.
+            Inline::Space { count } => {
+                html.push_str(&"&#160;".repeat(*count as usize));
+            }
+            Inline::Tab => {
+                html.push_str("<span style=\"white-space:pre\">\t</span>");
+            }
+            // The note body is emitted separately by `notes_to_html`.
+            Inline::Note { id, citation, .. } => {
+                html.push_str(&format!(
+                    "<sup><a epub:type=\"noteref\" href=\"#{}\">{}</a></sup>",
+                    note_anchor(id.as_deref(), citation),
+                    escape_xml(citation)
+                ));
+            }
+            // Reviewer comments are not part of the published text.
+            Inline::Comment(_) | Inline::CommentEnd { .. } => {}
+            // Pending changes are published as the text currently reads.
+            Inline::ChangeStart(_) | Inline::ChangeEnd { .. } | Inline::Change(_) => {}
+            // Fields are evaluated when the document is built.
+            Inline::Field { value, .. } => html.push_str(&escape_xml(value)),
+            Inline::Bookmark { name } | Inline::BookmarkStart { name } => {
+                html.push_str(&format!("<span id=\"{}\"></span>", bookmark_anchor(name)));
+            }
+            Inline::BookmarkEnd { .. } => {}
+            // Resolvable references have been turned into links already.
+            Inline::Reference(reference) => html.push_str(&escape_xml(&reference.value)),
+        }
+    }
+    html
+}
+
+// ---------------------------------------------------------------------------
+// Footnotes / endnotes
+// ---------------------------------------------------------------------------
+
+/// Build the fragment id linking a note reference to its `<aside>`.
+pub(crate) fn note_anchor(id: Option<&str>, citation: &str) -> String {
+    let raw = id.unwrap_or(citation);
+    let safe: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("note-{}", safe)
+}
+
+/// Build the fragment id of a bookmark.
+pub(crate) fn bookmark_anchor(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("bookmark-{}", safe)
+}
+
+/// Render every note referenced from `blocks` as an EPUB 3 `<aside>`.
+///
+/// Footnotes come first in document order, followed by endnotes.
+pub(crate) fn notes_to_html(
+    blocks: &[Block],
+    styles: &HashMap<String, StyleDefinition>,
+    images: &[ImageAsset],
+) -> String {
+    let mut notes = Vec::new();
+    for block in blocks {
+        collect_notes(block, &mut notes);
+    }
+    let mut html = String::new();
+    for class in [NoteClass::Footnote, NoteClass::Endnote] {
+        for note in &notes {
+            if let Inline::Note {
+                id,
+                note_class,
+                citation,
+                content,
+            } = note
+            {
+                if *note_class != class {
+                    continue;
+                }
+                html.push_str(&format!(
+                    "  <aside epub:type=\"{}\" id=\"{}\">\n",
+                    class.to_odf_str(),
+                    note_anchor(id.as_deref(), citation)
+                ));
+                for b in content {
+                    html.push_str(&block_to_html(b, styles, images));
+                }
+                html.push_str("  </aside>\n");
+            }
         }
     }
     html
 }
 
+fn collect_notes<'a>(block: &'a Block, out: &mut Vec<&'a Inline>) {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+            out.extend(content.iter().filter(|i| matches!(i, Inline::Note { .. })));
+        }
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => {
+            for child in content {
+                collect_notes(child, out);
+            }
+        }
+        Block::Image { .. }
+        | Block::HorizontalRule
+        | Block::PageBreak
+        | Block::TableOfContents(_) => {}
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Block rendering (G1, G2, G6, G7)
 // ---------------------------------------------------------------------------
@@ -172,7 +322,13 @@ pub(crate) fn block_to_html(
         }
 
         // ---- Image (G1) ----
-        Block::Image { src, alt, title } => {
+        Block::Image {
+            src,
+            alt,
+            title,
+            frame,
+            caption,
+        } => {
             // Resolve to an OPS-relative path when the image was embedded.
             let img_src = images
                 .iter()
@@ -187,14 +343,30 @@ pub(crate) fn block_to_html(
                 .map(|t| format!(" title=\"{}\"", escape_xml(t)))
                 .unwrap_or_default();
 
-            format!(
-                "  <img src=\"{}\" alt=\"{}\"{}/>\n",
-                img_src, alt_text, title_attr
-            )
+            let size_attrs: String = [
+                ("width", frame.as_ref().and_then(|f| f.width)),
+                ("height", frame.as_ref().and_then(|f| f.height)),
+            ]
+            .iter()
+            .filter_map(|(name, px)| px.map(|px| format!(" {name}=\"{}\"", px.round())))
+            .collect();
+
+            let img = format!(
+                "<img src=\"{}\" alt=\"{}\"{}{}/>",
+                img_src, alt_text, title_attr, size_attrs
+            );
+            match caption {
+                Some(caption) => format!(
+                    "  <figure>\n    {}\n    <figcaption>{}</figcaption>\n  </figure>\n",
+                    img,
+                    escape_xml(caption)
+                ),
+                None => format!("  {}\n", img),
+            }
         }
 
         // ---- Lists ----
-        Block::BulletList { content } => {
+        Block::BulletList { content, .. } => {
             let mut html = String::from("  <ul>\n");
             for item in content {
                 html.push_str(&block_to_html(item, styles, images));
@@ -202,8 +374,8 @@ pub(crate) fn block_to_html(
             html.push_str("  </ul>\n");
             html
         }
-        Block::OrderedList { content } => {
-            let mut html = String::from("  <ol>\n");
+        Block::OrderedList { attrs, content } => {
+            let mut html = format!("  <ol{}>\n", ordered_list_attrs(attrs.as_ref()));
             for item in content {
                 html.push_str(&block_to_html(item, styles, images));
             }
@@ -229,6 +401,23 @@ pub(crate) fn block_to_html(
             html
         }
 
+        // ---- Section ----
+        Block::Section { attrs, content, .. } => {
+            let columns = attrs.as_ref().filter(|a| !a.is_empty()).map(|a| {
+                let gap = a
+                    .column_gap
+                    .map(|px| format!("; column-gap: {}px", px.round()))
+                    .unwrap_or_default();
+                format!(" style=\"column-count: {}{}\"", a.columns(), gap)
+            });
+            let mut html = format!("  <section{}>\n", columns.unwrap_or_default());
+            for b in content {
+                html.push_str(&block_to_html(b, styles, images));
+            }
+            html.push_str("  </section>\n");
+            html
+        }
+
         // ---- Table (G2) ----
         Block::Table { content } => table::render_table(content, styles, images),
 
@@ -250,5 +439,29 @@ pub(crate) fn block_to_html(
 
         Block::HorizontalRule => String::from("  <hr/>\n"),
         Block::PageBreak => String::new(),
+
+        // ---- Table of contents ----
+        // Entry targets are hrefs by now; see `link_references`.
+        Block::TableOfContents(toc) => {
+            let mut html = String::from("  <nav class=\"toc\">\n");
+            if let Some(title) = &toc.title {
+                html.push_str(&format!("    <h2>{}</h2>\n", escape_xml(title)));
+            }
+            html.push_str("    <ol style=\"list-style: none\">\n");
+            for entry in &toc.entries {
+                let text = escape_xml(&entry.text);
+                let text = match &entry.target {
+                    Some(href) => format!("<a href=\"{}\">{}</a>", escape_xml(href), text),
+                    None => text,
+                };
+                html.push_str(&format!(
+                    "      <li style=\"margin-left: {}em\">{}</li>\n",
+                    1.5 * entry.level.saturating_sub(1) as f64,
+                    text
+                ));
+            }
+            html.push_str("    </ol>\n  </nav>\n");
+            html
+        }
     }
 }
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use common_core::field::{evaluate_fields, FieldValues};
+use common_core::toc::regenerate_toc;
+
 // Re-use types from common-core
 pub use common_core::{
     Block, Inline, Metadata, StyleDefinition, StyleFamily, TiptapAttrs, TiptapMark, TiptapNode,
@@ -104,7 +107,7 @@ impl EpubDocument {
     pub fn from_tiptap(
         root: TiptapNode,
         styles: HashMap<String, StyleDefinition>,
-        metadata: Metadata,
+        mut metadata: Metadata,
         fonts: Vec<FontAsset>,
         mut images: Vec<ImageAsset>,
     ) -> Self {
@@ -113,7 +116,7 @@ impl EpubDocument {
         let mut section_counter = 1usize;
 
         // Convert TiptapNode tree to flat Block list
-        let blocks = match root {
+        let mut blocks = match root {
             TiptapNode::Doc { content } => content
                 .into_iter()
                 .filter_map(conversion::tiptap_node_to_block)
@@ -121,6 +124,15 @@ impl EpubDocument {
             _ => Vec::new(),
         };
 
+        // Bring tables of contents up to date with the headings.
+        regenerate_toc(&mut blocks);
+
+        // Fill in text fields; an EPUB has no fixed pages, so page fields
+        // keep the value they last showed.
+        let values = FieldValues::for_document(&blocks, &metadata);
+        evaluate_fields(&mut blocks, &values);
+        metadata.update_statistics(&blocks);
+
         // Decode any data-URI images found in the block tree
         let mut data_uri_images = conversion::extract_images_from_blocks(&blocks);
         images.append(&mut data_uri_images);
@@ -199,6 +211,9 @@ impl EpubDocument {
             });
         }
 
+        conversion::link_references(&mut sections);
+        conversion::number_notes(&mut sections);
+
         EpubDocument {
             sections,
             styles,
@@ -231,6 +246,11 @@ impl EpubDocument {
         for block in &section.blocks {
             out.push_str(&html::block_to_html(block, &self.styles, &self.images));
         }
+        out.push_str(&html::notes_to_html(
+            &section.blocks,
+            &self.styles,
+            &self.images,
+        ));
         out.push_str("</body>\n");
         out.push_str("</html>\n");
         out
@@ -14,7 +14,8 @@ pub(crate) fn generate_package_opf(
     opf.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     opf.push_str(
         "<package xmlns=\"http://www.idpf.org/2007/opf\" \
-         version=\"3.0\" unique-identifier=\"uuid\">\n",
+         version=\"3.0\" unique-identifier=\"uuid\" \
+         prefix=\"odf: urn:oasis:names:tc:opendocument:xmlns:meta:1.0#\">\n",
     );
 
     // ---- Metadata ----
@@ -66,6 +67,40 @@ pub(crate) fn generate_package_opf(
         ));
     }
 
+    // Keywords are subjects too
+    for keyword in &metadata.keywords {
+        opf.push_str(&format!(
+            "    <dc:subject>{}</dc:subject>\n",
+            escape_xml(keyword)
+        ));
+    }
+
+    // The date the source document was last modified
+    if let Some(date) = &metadata.date {
+        opf.push_str(&format!(
+            "    <meta property=\"dcterms:date\">{}</meta>\n",
+            escape_xml(date)
+        ));
+    }
+
+    // ODF properties with no Dublin Core equivalent
+    for (property, value) in odf_properties(metadata) {
+        opf.push_str(&format!(
+            "    <meta property=\"odf:{}\">{}</meta>\n",
+            property,
+            escape_xml(&value)
+        ));
+    }
+
+    // User-defined properties as name/content pairs
+    for field in &metadata.user_defined {
+        opf.push_str(&format!(
+            "    <meta name=\"{}\" content=\"{}\"/>\n",
+            escape_xml(&field.name),
+            escape_xml(&field.value)
+        ));
+    }
+
     let modified = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
     opf.push_str(&format!(
         "    <meta property=\"dcterms:modified\">{}</meta>\n",
@@ -121,3 +156,30 @@ pub(crate) fn generate_package_opf(
     opf.push_str("</package>\n");
     opf
 }
+
+/// Returns the ODF meta properties of `metadata` that have no Dublin Core
+/// equivalent, named as in `meta.xml`.
+fn odf_properties(metadata: &Metadata) -> Vec<(&'static str, String)> {
+    let mut props = Vec::new();
+    if let Some(creator) = &metadata.initial_creator {
+        props.push(("initial-creator", creator.clone()));
+    }
+    if let Some(cycles) = metadata.editing_cycles {
+        props.push(("editing-cycles", cycles.to_string()));
+    }
+    if let Some(duration) = &metadata.editing_duration {
+        props.push(("editing-duration", duration.clone()));
+    }
+    if let Some(stats) = &metadata.statistics {
+        if let Some(pages) = stats.page_count {
+            props.push(("page-count", pages.to_string()));
+        }
+        props.extend(
+            stats
+                .odf_counts()
+                .into_iter()
+                .map(|(name, value)| (name, value.to_string())),
+        );
+    }
+    props
+}
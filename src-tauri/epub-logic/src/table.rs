@@ -4,7 +4,7 @@ use common_core::{Block, CellAttrs, StyleDefinition};
 
 use crate::ImageAsset;
 
-use super::html::block_to_html;
+use super::html::{block_to_html, escape_xml};
 
 /// Returns `true` when every cell in a `TableRow` block is a `TableHeader`.
 pub(crate) fn is_header_row(row: &Block) -> bool {
@@ -47,7 +47,8 @@ pub(crate) fn render_table(
 }
 
 /// Render a single table cell (`<th>` or `<td>`) with optional spanning
-/// attributes expressed as inline HTML attributes.
+/// attributes expressed as inline HTML attributes, and width, background
+/// and borders as an inline `style`.
 pub(crate) fn render_table_cell(
     tag: &str,
     attrs: Option<&CellAttrs>,
@@ -63,6 +64,10 @@ pub(crate) fn render_table_cell(
         if let Some(n) = a.rowspan.filter(|&n| n > 1) {
             attr_str.push_str(&format!(" rowspan=\"{}\"", n));
         }
+        let css = cell_css(a);
+        if !css.is_empty() {
+            attr_str.push_str(&format!(" style=\"{}\"", escape_xml(&css)));
+        }
     }
     let mut html = format!("        <{}{}>\n", tag, attr_str);
     for b in content {
@@ -71,3 +76,28 @@ pub(crate) fn render_table_cell(
     html.push_str(&format!("        </{}>\n", tag));
     html
 }
+
+/// Builds the inline CSS for a cell's width, background and borders.
+fn cell_css(attrs: &CellAttrs) -> String {
+    let mut rules = Vec::new();
+    if let Some(widths) = &attrs.colwidth {
+        rules.push(format!("width: {}px", widths.iter().sum::<u32>()));
+    }
+    if let Some(colour) = &attrs.background_color {
+        rules.push(format!("background-color: {colour}"));
+    }
+    if let Some(border) = &attrs.border {
+        let sides = [
+            ("top", &border.top),
+            ("right", &border.right),
+            ("bottom", &border.bottom),
+            ("left", &border.left),
+        ];
+        for (side, value) in sides {
+            if let Some(value) = value {
+                rules.push(format!("border-{side}: {value}"));
+            }
+        }
+    }
+    rules.join("; ")
+}
@@ -1,4 +1,5 @@
 use super::*;
+use common_core::{FieldKind, ListAttrs, NoteAttrs, NoteClass};
 use std::collections::HashMap;
 
 fn create_mock_tiptap_doc() -> TiptapNode {
@@ -65,6 +66,7 @@ fn test_block_to_html() {
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_opf_generation() {
     let mut metadata = Metadata::default();
     metadata.title = Some("My Book".to_string());
@@ -101,8 +103,11 @@ fn test_page_break_splitting() {
         text_transform: None,
         outline_level: None,
         autocomplete: None,
+        master_page_name: None,
         font_colour: None,
         background_colour: None,
+        properties: Default::default(),
+        list_levels: Vec::new(),
     };
     style_def
         .attributes
@@ -135,3 +140,369 @@ fn test_page_break_splitting() {
     // Should be split into 2 sections because of break-before
     assert_eq!(epub.sections.len(), 2);
 }
+
+#[test]
+fn test_footnote_renders_noteref_and_aside() {
+    let block = Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Text {
+                text: "Claim".to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+            Inline::Note {
+                id: Some("ftn1".to_string()),
+                note_class: NoteClass::Footnote,
+                citation: "1".to_string(),
+                content: vec![Block::Paragraph {
+                    style_name: None,
+                    attrs: None,
+                    content: vec![Inline::Text {
+                        text: "Source".to_string(),
+                        style_name: None,
+                        marks: vec![],
+                    }],
+                }],
+            },
+        ],
+    };
+
+    let html = crate::html::block_to_html(&block, &HashMap::new(), &[]);
+    assert!(html.contains("<a epub:type=\"noteref\" href=\"#note-ftn1\">1</a>"));
+    assert!(!html.contains("Source"));
+
+    let notes = crate::html::notes_to_html(&[block], &HashMap::new(), &[]);
+    assert!(notes.contains("<aside epub:type=\"footnote\" id=\"note-ftn1\">"));
+    assert!(notes.contains("<p>Source</p>"));
+}
+
+#[test]
+fn test_notes_sharing_a_citation_get_distinct_anchors() {
+    let note = || TiptapNode::Note {
+        attrs: NoteAttrs {
+            id: None,
+            note_class: NoteClass::Footnote,
+            citation: "*".to_string(),
+        },
+        content: vec![],
+    };
+    let root = TiptapNode::Doc {
+        content: vec![TiptapNode::Paragraph {
+            attrs: None,
+            content: Some(vec![note(), note()]),
+        }],
+    };
+
+    let epub = EpubDocument::from_tiptap(root, HashMap::new(), Metadata::default(), vec![], vec![]);
+    let xhtml = epub.section_to_xhtml(&epub.sections[0]);
+    for anchor in ["note-1", "note-2"] {
+        assert!(xhtml.contains(&format!("href=\"#{anchor}\"")));
+        assert!(xhtml.contains(&format!("id=\"{anchor}\"")));
+    }
+}
+
+#[test]
+fn test_ordered_list_start_and_format() {
+    let block = Block::OrderedList {
+        attrs: Some(ListAttrs {
+            num_format: Some("i".to_string()),
+            start: Some(4),
+            ..Default::default()
+        }),
+        content: vec![],
+    };
+    let html = crate::html::block_to_html(&block, &HashMap::new(), &[]);
+    assert!(html.contains("<ol start=\"4\" style=\"list-style-type:lower-roman\">"));
+}
+
+#[test]
+fn test_text_style_mark_renders_css_span() {
+    use common_core::colour_management::Colour;
+    use common_core::TextStyleAttrs;
+
+    let block = Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Text {
+            text: "red".to_string(),
+            style_name: None,
+            marks: vec![TiptapMark::TextStyle {
+                attrs: TextStyleAttrs {
+                    colour: Some(Colour::from_u8_rgb(255, 0, 0)),
+                    font_size: Some(9.0),
+                    ..Default::default()
+                },
+            }],
+        }],
+    };
+    let html = crate::html::block_to_html(&block, &HashMap::new(), &[]);
+    assert!(html.contains("<span style=\"color: #ff0000; font-size: 9pt\">red</span>"));
+}
+
+#[test]
+fn test_table_cell_renders_width_background_and_borders() {
+    use common_core::{CellAttrs, CellBorders};
+
+    let block = Block::TableCell {
+        attrs: Some(CellAttrs {
+            colspan: Some(2),
+            colwidth: Some(vec![100, 50]),
+            background_color: Some("#dddddd".to_string()),
+            border: Some(CellBorders {
+                bottom: Some("1pt solid #000000".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        content: vec![],
+    };
+    let html = crate::html::block_to_html(&block, &HashMap::new(), &[]);
+    assert!(html.contains(
+        "<td colspan=\"2\" style=\"width: 150px; background-color: #dddddd; \
+         border-bottom: 1pt solid #000000\">"
+    ));
+}
+
+#[test]
+fn test_image_renders_size_and_caption() {
+    let block = Block::Image {
+        src: "cat.png".to_string(),
+        alt: Some("A cat".to_string()),
+        title: None,
+        frame: Some(common_core::ImageFrame {
+            width: Some(120.4),
+            height: Some(80.0),
+            ..Default::default()
+        }),
+        caption: Some("Figure 1: Cat & mouse".to_string()),
+    };
+    let html = crate::html::block_to_html(&block, &HashMap::new(), &[]);
+    assert!(html.contains("<img src=\"cat.png\" alt=\"A cat\" width=\"120\" height=\"80\"/>"));
+    assert!(html.contains("<figcaption>Figure 1: Cat &amp; mouse</figcaption>"));
+}
+
+#[test]
+fn test_section_renders_columns() {
+    let block = Block::Section {
+        name: "Columns".to_string(),
+        attrs: Some(common_core::SectionAttrs {
+            column_count: Some(2),
+            column_gap: Some(18.9),
+        }),
+        protected: false,
+        content: vec![Block::HorizontalRule],
+    };
+    let html = crate::html::block_to_html(&block, &HashMap::new(), &[]);
+    assert!(html.contains("<section style=\"column-count: 2; column-gap: 19px\">"));
+    assert!(html.contains("<hr/>"));
+}
+
+#[test]
+fn test_fields_are_evaluated_from_metadata() {
+    let field = |kind, value: &str| TiptapNode::Field {
+        attrs: common_core::FieldAttrs {
+            kind,
+            value: value.to_string(),
+        },
+    };
+    let root = TiptapNode::Doc {
+        content: vec![TiptapNode::Paragraph {
+            attrs: None,
+            content: Some(vec![
+                field(FieldKind::Title, "Draft"),
+                TiptapNode::Text {
+                    text: " by ".to_string(),
+                    marks: None,
+                },
+                field(FieldKind::Author, "Nobody"),
+                TiptapNode::Text {
+                    text: ", page ".to_string(),
+                    marks: None,
+                },
+                field(FieldKind::PageNumber, "2"),
+            ]),
+        }],
+    };
+    let metadata = Metadata {
+        title: Some("Fish & Chips".to_string()),
+        creator: Some("Ada".to_string()),
+        ..Metadata::default()
+    };
+    let epub = EpubDocument::from_tiptap(root, HashMap::new(), metadata, vec![], vec![]);
+    let xhtml = epub.section_to_xhtml(&epub.sections[0]);
+    assert!(xhtml.contains("Fish &amp; Chips by Ada, page 2"));
+}
+
+#[test]
+fn test_reference_links_to_bookmark_in_other_section() {
+    let root = TiptapNode::Doc {
+        content: vec![
+            TiptapNode::Paragraph {
+                attrs: None,
+                content: Some(vec![
+                    TiptapNode::Text {
+                        text: "See ".to_string(),
+                        marks: None,
+                    },
+                    TiptapNode::Reference {
+                        attrs: common_core::CrossReference {
+                            name: "Methods 1".to_string(),
+                            value: "section 3.2".to_string(),
+                            ..Default::default()
+                        },
+                    },
+                    TiptapNode::Reference {
+                        attrs: common_core::CrossReference {
+                            name: "missing".to_string(),
+                            value: "nowhere".to_string(),
+                            ..Default::default()
+                        },
+                    },
+                ]),
+            },
+            TiptapNode::PageBreak,
+            TiptapNode::Paragraph {
+                attrs: None,
+                content: Some(vec![
+                    TiptapNode::Bookmark {
+                        attrs: common_core::BookmarkAttrs {
+                            name: "Methods 1".to_string(),
+                        },
+                    },
+                    TiptapNode::Text {
+                        text: "Methods".to_string(),
+                        marks: None,
+                    },
+                ]),
+            },
+        ],
+    };
+    let epub = EpubDocument::from_tiptap(root, HashMap::new(), Metadata::default(), vec![], vec![]);
+    assert_eq!(epub.sections.len(), 2);
+    let first = epub.section_to_xhtml(&epub.sections[0]);
+    assert!(first.contains("<a href=\"section-2.xhtml#bookmark-Methods-1\">section 3.2</a>"));
+    assert!(first.contains("nowhere"));
+    assert!(!first.contains("#bookmark-missing"));
+    let second = epub.section_to_xhtml(&epub.sections[1]);
+    assert!(second.contains("<span id=\"bookmark-Methods-1\"></span>Methods"));
+}
+
+#[test]
+fn test_table_of_contents_is_regenerated_and_linked() {
+    let root = TiptapNode::Doc {
+        content: vec![
+            TiptapNode::TableOfContents {
+                attrs: common_core::TableOfContents {
+                    title: Some("Contents".to_string()),
+                    outline_level: 2,
+                    ..Default::default()
+                },
+            },
+            TiptapNode::PageBreak,
+            TiptapNode::Heading {
+                attrs: Some(TiptapAttrs {
+                    level: Some(2),
+                    ..Default::default()
+                }),
+                content: Some(vec![TiptapNode::Text {
+                    text: "Methods & Data".to_string(),
+                    marks: None,
+                }]),
+            },
+        ],
+    };
+    let epub = EpubDocument::from_tiptap(root, HashMap::new(), Metadata::default(), vec![], vec![]);
+    let toc = epub.section_to_xhtml(&epub.sections[0]);
+    assert!(toc.contains("<nav class=\"toc\">"));
+    assert!(toc.contains("<h2>Contents</h2>"));
+    assert!(toc.contains(
+        "<li style=\"margin-left: 1.5em\"><a href=\"section-2.xhtml#bookmark---RefHeading---Toc1\">Methods &amp; Data</a></li>"
+    ));
+    let chapter = epub.section_to_xhtml(&epub.sections[1]);
+    assert!(chapter.contains("<span id=\"bookmark---RefHeading---Toc1\"></span>"));
+}
+
+#[test]
+fn test_css_carries_inherited_properties_and_direct_indent() {
+    let style = |name: &str, parent: Option<&str>, attrs: &[(&str, &str)]| StyleDefinition {
+        name: name.to_string(),
+        family: StyleFamily::Paragraph,
+        parent: parent.map(str::to_string),
+        next: None,
+        display_name: None,
+        attributes: attrs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        text_transform: None,
+        outline_level: None,
+        autocomplete: None,
+        master_page_name: None,
+        font_colour: None,
+        background_colour: None,
+        properties: Default::default(),
+        list_levels: Vec::new(),
+    };
+    let styles: HashMap<String, StyleDefinition> = [
+        style("_Default_Paragraph", None, &[("fo:font-size", "12pt")]),
+        style("Quote", None, &[("fo:font-style", "italic")]),
+        style("Epigraph", Some("Quote"), &[("fo:text-align", "end")]),
+    ]
+    .into_iter()
+    .map(|s| (s.name.clone(), s))
+    .collect();
+
+    let css = crate::css::generate_css(&styles, &[]);
+    let epigraph = css.split(".style-Epigraph {").nth(1).unwrap();
+    let epigraph = &epigraph[..epigraph.find('}').unwrap()];
+    assert!(epigraph.contains("font-style: italic;"));
+    assert!(epigraph.contains("font-size: 12pt;"));
+    assert!(epigraph.contains("text-align: end;"));
+    assert!(!css.contains(".style-_Default_Paragraph"));
+
+    let block = Block::Paragraph {
+        style_name: Some("Epigraph".to_string()),
+        attrs: Some(common_core::BlockAttrs {
+            text_align: None,
+            indent: Some(2),
+            ..common_core::BlockAttrs::default()
+        }),
+        content: vec![],
+    };
+    let html = crate::html::block_to_html(&block, &styles, &[]);
+    assert!(html.contains("style=\"margin-left:2.540cm\""));
+}
+
+#[test]
+fn test_opf_carries_extended_metadata_and_statistics() {
+    let root = TiptapNode::Doc {
+        content: vec![TiptapNode::Paragraph {
+            attrs: None,
+            content: Some(vec![TiptapNode::Text {
+                text: "Three short words".to_string(),
+                marks: None,
+            }]),
+        }],
+    };
+    let metadata = Metadata {
+        keywords: vec!["travel".to_string()],
+        date: Some("2024-06-01T08:00:00".to_string()),
+        initial_creator: Some("Dana".to_string()),
+        user_defined: vec![common_core::UserDefinedField {
+            name: "Edition".to_string(),
+            value_type: None,
+            value: "2nd".to_string(),
+        }],
+        ..Metadata::default()
+    };
+    let epub = EpubDocument::from_tiptap(root, HashMap::new(), metadata, vec![], vec![]);
+    let opf = epub.to_package_opf();
+    assert!(opf.contains("prefix=\"odf: urn:oasis:names:tc:opendocument:xmlns:meta:1.0#\""));
+    assert!(opf.contains("<dc:subject>travel</dc:subject>"));
+    assert!(opf.contains("<meta property=\"dcterms:date\">2024-06-01T08:00:00</meta>"));
+    assert!(opf.contains("<meta property=\"odf:initial-creator\">Dana</meta>"));
+    assert!(opf.contains("<meta property=\"odf:word-count\">3</meta>"));
+    assert!(opf.contains("<meta name=\"Edition\" content=\"2nd\"/>"));
+}
@@ -2,7 +2,7 @@
 //!
 //! This module defines the [`Block`] enum which represents all block-level
 //! structural elements in a document: paragraphs, headings, lists, tables,
-//! images, and special elements like page breaks.
+//! sections, images, and special elements like page breaks.
 //!
 //! # Examples
 //!
@@ -22,9 +22,18 @@
 //! };
 //! ```
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::inline::Inline;
+use crate::toc::TableOfContents;
+
+/// Width of one paragraph indent level, in centimetres.
+///
+/// Indent levels in [`BlockAttrs`] map to `fo:margin-left` multiples of this
+/// step.
+pub const INDENT_STEP_CM: f64 = 1.27;
 
 /// Paragraph and block alignment / indentation attributes.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -34,17 +43,273 @@ pub struct BlockAttrs {
     pub text_align: Option<String>,
     /// Indentation level.
     pub indent: Option<u32>,
+    /// Other direct paragraph and text properties, such as spacing and line
+    /// height, keyed by their prefixed ODF names (`fo:margin-top`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+    /// The attributes of each `style:tab-stop`, in order.
+    #[serde(default, rename = "tabStops", skip_serializing_if = "Vec::is_empty")]
+    pub tab_stops: Vec<HashMap<String, String>>,
+    /// The master page that starts with this block.
+    #[serde(
+        default,
+        rename = "masterPageName",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub master_page_name: Option<String>,
 }
 
-/// Table cell spanning attributes.
+impl BlockAttrs {
+    /// Returns the direct formatting as ODF properties: the
+    /// [`properties`](Self::properties), `fo:text-align` and an
+    /// `fo:margin-left` of [`INDENT_STEP_CM`] per indent level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common_core::BlockAttrs;
+    ///
+    /// let attrs = BlockAttrs { indent: Some(2), ..BlockAttrs::default() };
+    /// assert_eq!(attrs.to_odf_attributes()["fo:margin-left"], "2.540cm");
+    /// ```
+    #[must_use]
+    pub fn to_odf_attributes(&self) -> HashMap<String, String> {
+        let mut attrs = self.properties.clone();
+        if let Some(align) = &self.text_align {
+            attrs.insert("fo:text-align".to_string(), align.clone());
+        }
+        if let Some(indent) = self.indent.filter(|&i| i > 0) {
+            attrs.insert(
+                "fo:margin-left".to_string(),
+                format!("{:.3}cm", f64::from(indent) * INDENT_STEP_CM),
+            );
+        }
+        attrs
+    }
+}
+
+/// Table cell spanning, width and style attributes.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct CellAttrs {
     /// Number of columns this cell spans.
     pub colspan: Option<u32>,
     /// Number of rows this cell spans.
     pub rowspan: Option<u32>,
-    /// Column widths for merged cells.
+    /// Width in pixels (96 dpi) of each column the cell spans.
     pub colwidth: Option<Vec<u32>>,
+    /// Background colour as a CSS hex string (e.g. `"#dddddd"`).
+    #[serde(
+        rename = "backgroundColor",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub background_color: Option<String>,
+    /// Cell borders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<CellBorders>,
+}
+
+/// The borders of a table cell, one per side.
+///
+/// Each value is an ODF/CSS border shorthand such as `"0.5pt solid #000000"`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CellBorders {
+    /// Top border.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<String>,
+    /// Right border.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<String>,
+    /// Bottom border.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<String>,
+    /// Left border.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<String>,
+}
+
+impl CellBorders {
+    /// Returns `true` when no side has a border.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.top.is_none() && self.right.is_none() && self.bottom.is_none() && self.left.is_none()
+    }
+
+    /// Returns the common border when all four sides are the same.
+    #[must_use]
+    pub fn uniform(&self) -> Option<&str> {
+        let top = self.top.as_deref()?;
+        [&self.right, &self.bottom, &self.left]
+            .iter()
+            .all(|side| side.as_deref() == Some(top))
+            .then_some(top)
+    }
+}
+
+/// Size, anchoring, text wrapping and storage of an image frame.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageFrame {
+    /// Frame width in pixels (96 dpi).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    /// Frame height in pixels (96 dpi).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    /// What the frame is anchored to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<ImageAnchor>,
+    /// How text flows around the frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrap: Option<ImageWrap>,
+    /// Whether the image links to a file outside the document instead of
+    /// being embedded in it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub linked: bool,
+}
+
+impl ImageFrame {
+    /// Returns `true` when no frame property is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.width.is_none()
+            && self.height.is_none()
+            && self.anchor.is_none()
+            && self.wrap.is_none()
+            && !self.linked
+    }
+}
+
+/// The anchor of an image frame (`text:anchor-type` in ODT).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageAnchor {
+    /// Anchored to the paragraph.
+    Paragraph,
+    /// Anchored to a character position.
+    Char,
+    /// Placed in the line like a character.
+    AsChar,
+    /// Anchored to the page.
+    Page,
+}
+
+impl ImageAnchor {
+    /// Returns the ODF `text:anchor-type` attribute value.
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            ImageAnchor::Paragraph => "paragraph",
+            ImageAnchor::Char => "char",
+            ImageAnchor::AsChar => "as-char",
+            ImageAnchor::Page => "page",
+        }
+    }
+
+    /// Parses an ODF `text:anchor-type` value.
+    pub fn from_odf_str(s: &str) -> Option<Self> {
+        match s {
+            "paragraph" => Some(ImageAnchor::Paragraph),
+            "char" => Some(ImageAnchor::Char),
+            "as-char" => Some(ImageAnchor::AsChar),
+            "page" => Some(ImageAnchor::Page),
+            _ => None,
+        }
+    }
+}
+
+/// Text wrapping around an image frame (`style:wrap` in ODT).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageWrap {
+    /// No text beside the frame.
+    None,
+    /// Text on the left of the frame only.
+    Left,
+    /// Text on the right of the frame only.
+    Right,
+    /// Text on both sides of the frame.
+    Parallel,
+    /// Text on the side with more room.
+    Dynamic,
+    /// The frame is drawn over or behind the text.
+    RunThrough,
+}
+
+impl ImageWrap {
+    /// Returns the ODF `style:wrap` attribute value.
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            ImageWrap::None => "none",
+            ImageWrap::Left => "left",
+            ImageWrap::Right => "right",
+            ImageWrap::Parallel => "parallel",
+            ImageWrap::Dynamic => "dynamic",
+            ImageWrap::RunThrough => "run-through",
+        }
+    }
+
+    /// Parses an ODF `style:wrap` value. `biggest` is read as dynamic.
+    pub fn from_odf_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(ImageWrap::None),
+            "left" => Some(ImageWrap::Left),
+            "right" => Some(ImageWrap::Right),
+            "parallel" => Some(ImageWrap::Parallel),
+            "dynamic" | "biggest" => Some(ImageWrap::Dynamic),
+            "run-through" => Some(ImageWrap::RunThrough),
+            _ => None,
+        }
+    }
+}
+
+/// Numbering attributes of a bullet or ordered list.
+///
+/// Mirrors the ODT `text:list-style` level that applies to the list, plus the
+/// per-list `text:start-value` and `text:continue-numbering` overrides.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAttrs {
+    /// The ODT list style name (`text:style-name`).
+    pub style_name: Option<String>,
+    /// Number format (`style:num-format`, e.g. `"1"`, `"a"`, `"I"`).
+    pub num_format: Option<String>,
+    /// Text shown before the number (`style:num-prefix`).
+    pub num_prefix: Option<String>,
+    /// Text shown after the number (`style:num-suffix`, e.g. `"."`).
+    pub num_suffix: Option<String>,
+    /// Bullet character for bullet lists (`text:bullet-char`).
+    pub bullet_char: Option<String>,
+    /// The number of the first item, when it is not 1.
+    pub start: Option<u32>,
+    /// Whether numbering continues from the preceding list.
+    #[serde(default)]
+    pub continue_numbering: bool,
+}
+
+/// Column layout of a section (`style:columns` in ODT).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionAttrs {
+    /// Number of text columns; `None` for a single column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column_count: Option<u32>,
+    /// Space between columns in pixels (96 dpi).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column_gap: Option<f64>,
+}
+
+impl SectionAttrs {
+    /// Returns the number of columns, at least 1.
+    #[must_use]
+    pub fn columns(&self) -> u32 {
+        self.column_count.unwrap_or(1).max(1)
+    }
+
+    /// Returns `true` when the section has a single column and no gap.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.columns() == 1 && self.column_gap.is_none()
+    }
 }
 
 /// A block-level element in a document.
@@ -86,14 +351,26 @@ pub enum Block {
         alt: Option<String>,
         /// Image title tooltip.
         title: Option<String>,
+        /// Frame size, anchoring and wrapping.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        frame: Option<ImageFrame>,
+        /// Caption text shown with the image.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        caption: Option<String>,
     },
     /// An unordered (bullet) list containing list items.
     BulletList {
+        /// Optional list style and numbering attributes.
+        #[serde(default)]
+        attrs: Option<ListAttrs>,
         /// The list items.
         content: Vec<Block>,
     },
     /// An ordered (numbered) list containing list items.
     OrderedList {
+        /// Optional list style and numbering attributes.
+        #[serde(default)]
+        attrs: Option<ListAttrs>,
         /// The list items.
         content: Vec<Block>,
     },
@@ -107,6 +384,20 @@ pub enum Block {
         /// The quoted content blocks.
         content: Vec<Block>,
     },
+    /// A named region of the document, optionally laid out in columns.
+    Section {
+        /// The section name (`text:name`).
+        #[serde(default)]
+        name: String,
+        /// Optional column layout.
+        #[serde(default)]
+        attrs: Option<SectionAttrs>,
+        /// Whether the section is protected against editing.
+        #[serde(default)]
+        protected: bool,
+        /// The blocks inside this section.
+        content: Vec<Block>,
+    },
     /// A table containing rows.
     Table {
         /// The table rows.
@@ -137,6 +428,8 @@ pub enum Block {
     HorizontalRule,
     /// A page break.
     PageBreak,
+    /// A generated table of contents.
+    TableOfContents(TableOfContents),
 }
 
 #[cfg(test)]
@@ -157,6 +450,42 @@ mod tests {
         assert!(attrs.colspan.is_none());
         assert!(attrs.rowspan.is_none());
         assert!(attrs.colwidth.is_none());
+        assert!(attrs.background_color.is_none());
+        assert!(attrs.border.is_none());
+    }
+
+    #[test]
+    fn image_anchor_and_wrap_odf_values_round_trip() {
+        for anchor in [
+            ImageAnchor::Paragraph,
+            ImageAnchor::Char,
+            ImageAnchor::AsChar,
+            ImageAnchor::Page,
+        ] {
+            assert_eq!(ImageAnchor::from_odf_str(anchor.to_odf_str()), Some(anchor));
+        }
+        assert_eq!(ImageWrap::from_odf_str("biggest"), Some(ImageWrap::Dynamic));
+        assert_eq!(
+            ImageWrap::from_odf_str("run-through"),
+            Some(ImageWrap::RunThrough)
+        );
+        assert_eq!(ImageAnchor::from_odf_str("frame"), None);
+    }
+
+    #[test]
+    fn cell_borders_uniform() {
+        let side = Some("0.5pt solid #000000".to_string());
+        let mut borders = CellBorders {
+            top: side.clone(),
+            right: side.clone(),
+            bottom: side.clone(),
+            left: side,
+        };
+        assert_eq!(borders.uniform(), Some("0.5pt solid #000000"));
+        borders.left = None;
+        assert_eq!(borders.uniform(), None);
+        assert!(!borders.is_empty());
+        assert!(CellBorders::default().is_empty());
     }
 
     #[test]
@@ -204,8 +533,13 @@ mod tests {
             src: "image.png".to_string(),
             alt: Some("A picture".to_string()),
             title: None,
+            frame: None,
+            caption: None,
         };
-        if let Block::Image { src, alt, title } = &block {
+        if let Block::Image {
+            src, alt, title, ..
+        } = &block
+        {
             assert_eq!(src, "image.png");
             assert_eq!(alt.as_deref(), Some("A picture"));
             assert!(title.is_none());
@@ -230,15 +564,54 @@ mod tests {
             }],
         };
         let list = Block::BulletList {
+            attrs: None,
             content: vec![inner],
         };
-        if let Block::BulletList { content } = &list {
+        if let Block::BulletList { content, .. } = &list {
             assert_eq!(content.len(), 1);
         } else {
             panic!("expected BulletList");
         }
     }
 
+    #[test]
+    fn ordered_list_attrs_serde_roundtrip() {
+        let list = Block::OrderedList {
+            attrs: Some(ListAttrs {
+                style_name: Some("L1".to_string()),
+                num_format: Some("a".to_string()),
+                num_suffix: Some(")".to_string()),
+                start: Some(3),
+                ..ListAttrs::default()
+            }),
+            content: vec![],
+        };
+        let json = serde_json::to_string(&list).unwrap();
+        assert!(json.contains("\"numFormat\":\"a\""));
+        let decoded: Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, list);
+    }
+
+    #[test]
+    fn section_serde_roundtrip() {
+        let section = Block::Section {
+            name: "Columns".to_string(),
+            attrs: Some(SectionAttrs {
+                column_count: Some(2),
+                column_gap: Some(18.9),
+            }),
+            protected: true,
+            content: vec![Block::HorizontalRule],
+        };
+        let json = serde_json::to_string(&section).unwrap();
+        assert!(json.contains("\"type\":\"section\""));
+        assert!(json.contains("\"columnCount\":2"));
+        let decoded: Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, section);
+        assert_eq!(SectionAttrs::default().columns(), 1);
+        assert!(SectionAttrs::default().is_empty());
+    }
+
     #[test]
     fn block_serde_roundtrip() {
         let block = Block::Heading {
@@ -246,7 +619,9 @@ mod tests {
             style_name: Some("Heading 1".to_string()),
             attrs: Some(BlockAttrs {
                 text_align: Some("center".to_string()),
-                indent: None,
+                properties: HashMap::from([("fo:margin-top".to_string(), "0.2cm".to_string())]),
+                master_page_name: Some("Landscape".to_string()),
+                ..BlockAttrs::default()
             }),
             content: vec![],
         };
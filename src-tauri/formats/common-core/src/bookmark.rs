@@ -0,0 +1,153 @@
+//! Bookmarks and cross-references.
+//!
+//! A bookmark names a position in the text ([`Inline::Bookmark`]) or a
+//! range of it ([`Inline::BookmarkStart`] to the [`Inline::BookmarkEnd`]
+//! with the same name). A [`CrossReference`] points at a bookmark or an ODF
+//! reference mark by name and keeps the text it last showed, such as
+//! `"3.2"` or `"page 14"`.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::bookmark::{bookmark_names, CrossReference, ReferenceKind};
+//! use common_core::{Block, Inline};
+//!
+//! let blocks = vec![Block::Paragraph {
+//!     style_name: None,
+//!     attrs: None,
+//!     content: vec![
+//!         Inline::Bookmark {
+//!             name: "intro".to_string(),
+//!         },
+//!         Inline::Reference(CrossReference {
+//!             kind: ReferenceKind::Bookmark,
+//!             name: "intro".to_string(),
+//!             format: Some("text".to_string()),
+//!             value: "Introduction".to_string(),
+//!         }),
+//!     ],
+//! }];
+//! assert_eq!(bookmark_names(&blocks), vec!["intro"]);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::inline::Inline;
+use crate::walk::for_each_inline;
+
+/// What a [`CrossReference`] points at.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    /// A bookmark (`text:bookmark-ref`).
+    #[default]
+    Bookmark,
+    /// An ODF reference mark (`text:reference-ref`).
+    ReferenceMark,
+}
+
+impl ReferenceKind {
+    /// Returns the local name of the ODF reference element.
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            ReferenceKind::Bookmark => "bookmark-ref",
+            ReferenceKind::ReferenceMark => "reference-ref",
+        }
+    }
+
+    /// Parses the local name of an ODF reference element.
+    pub fn from_odf_str(s: &str) -> Option<Self> {
+        match s {
+            "bookmark-ref" => Some(ReferenceKind::Bookmark),
+            "reference-ref" => Some(ReferenceKind::ReferenceMark),
+            _ => None,
+        }
+    }
+}
+
+/// A reference to a named position elsewhere in the document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossReference {
+    /// Whether the target is a bookmark or a reference mark.
+    #[serde(default)]
+    pub kind: ReferenceKind,
+    /// The name of the target (`text:ref-name`).
+    pub name: String,
+    /// What is shown of the target (`text:reference-format`), e.g.
+    /// `"page"`, `"chapter"` or `"text"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The text last shown for the reference.
+    #[serde(default)]
+    pub value: String,
+}
+
+/// Returns the names of the bookmarks in `blocks`, in document order.
+pub fn bookmark_names(blocks: &[Block]) -> Vec<&str> {
+    let mut names = Vec::new();
+    for_each_inline(blocks, &mut |inline| match inline {
+        Inline::Bookmark { name } | Inline::BookmarkStart { name } => names.push(name.as_str()),
+        _ => {}
+    });
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_kind_odf_str_round_trip() {
+        for kind in [ReferenceKind::Bookmark, ReferenceKind::ReferenceMark] {
+            assert_eq!(ReferenceKind::from_odf_str(kind.to_odf_str()), Some(kind));
+        }
+        assert_eq!(ReferenceKind::from_odf_str("sequence-ref"), None);
+    }
+
+    #[test]
+    fn bookmark_inlines_serde_roundtrip() {
+        let inlines = vec![
+            Inline::BookmarkStart {
+                name: "s1".to_string(),
+            },
+            Inline::BookmarkEnd {
+                name: "s1".to_string(),
+            },
+            Inline::Reference(CrossReference {
+                kind: ReferenceKind::ReferenceMark,
+                name: "s1".to_string(),
+                format: None,
+                value: "2".to_string(),
+            }),
+        ];
+        let json = serde_json::to_string(&inlines).unwrap();
+        assert!(json.contains(r#"{"type":"bookmarkStart","name":"s1"}"#));
+        assert!(json.contains(r#""kind":"referenceMark""#));
+        let decoded: Vec<Inline> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, inlines);
+    }
+
+    #[test]
+    fn bookmark_names_include_nested_blocks() {
+        let blocks = vec![Block::Blockquote {
+            content: vec![Block::Paragraph {
+                style_name: None,
+                attrs: None,
+                content: vec![
+                    Inline::BookmarkStart {
+                        name: "range".to_string(),
+                    },
+                    Inline::BookmarkEnd {
+                        name: "range".to_string(),
+                    },
+                    Inline::Bookmark {
+                        name: "point".to_string(),
+                    },
+                ],
+            }],
+        }];
+        assert_eq!(bookmark_names(&blocks), vec!["range", "point"]);
+    }
+}
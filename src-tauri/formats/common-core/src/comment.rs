@@ -0,0 +1,134 @@
+//! Reviewer comments.
+//!
+//! A [`Comment`] is anchored in the text by an
+//! [`Inline::Comment`](crate::Inline::Comment). A comment with an `id` covers
+//! the content from its anchor up to the
+//! [`Inline::CommentEnd`](crate::Inline::CommentEnd) carrying the same id,
+//! which may be in a later block; a comment without a matching end is
+//! attached to a single point. Replies are kept with the comment they answer.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::{Block, Comment, Inline};
+//!
+//! let comment = Comment {
+//!     id: Some("c1".to_string()),
+//!     author: Some("Ada".to_string()),
+//!     date: Some("2024-05-01T10:00:00".to_string()),
+//!     content: vec![Block::Paragraph {
+//!         style_name: None,
+//!         attrs: None,
+//!         content: vec![Inline::Text {
+//!             text: "Cite a source here.".to_string(),
+//!             style_name: None,
+//!             marks: vec![],
+//!         }],
+//!     }],
+//!     ..Comment::default()
+//! };
+//! let range = vec![
+//!     Inline::Comment(comment),
+//!     Inline::Text {
+//!         text: "a bold claim".to_string(),
+//!         style_name: None,
+//!         marks: vec![],
+//!     },
+//!     Inline::CommentEnd { id: "c1".to_string() },
+//! ];
+//! assert_eq!(range.len(), 3);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+
+/// A reviewer comment (`office:annotation` in ODT).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    /// The name linking the comment to the end of its range, if it covers
+    /// one (`office:name` in ODT).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The comment author (`dc:creator`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// ISO 8601 timestamp of the comment (`dc:date`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// Whether the discussion has been marked as resolved.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resolved: bool,
+    /// The comment text.
+    #[serde(default)]
+    pub content: Vec<Block>,
+    /// Replies to this comment, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<Comment>,
+}
+
+impl Comment {
+    /// Returns this comment followed by all of its replies, depth first.
+    #[must_use]
+    pub fn thread(&self) -> Vec<&Comment> {
+        let mut thread = vec![self];
+        for reply in &self.replies {
+            thread.extend(reply.thread());
+        }
+        thread
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Inline;
+
+    #[test]
+    fn comment_inline_serde_roundtrip() {
+        let inline = Inline::Comment(Comment {
+            id: Some("c1".to_string()),
+            author: Some("Ada".to_string()),
+            resolved: true,
+            replies: vec![Comment {
+                author: Some("Grace".to_string()),
+                ..Comment::default()
+            }],
+            ..Comment::default()
+        });
+        let json = serde_json::to_string(&inline).unwrap();
+        assert!(json.contains("\"type\":\"comment\""));
+        assert!(json.contains("\"resolved\":true"));
+        let decoded: Inline = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, inline);
+    }
+
+    #[test]
+    fn thread_lists_nested_replies_depth_first() {
+        let named = |name: &str, replies: Vec<Comment>| Comment {
+            id: Some(name.to_string()),
+            replies,
+            ..Comment::default()
+        };
+        let comment = named(
+            "a",
+            vec![named("b", vec![named("c", vec![])]), named("d", vec![])],
+        );
+        let ids: Vec<_> = comment
+            .thread()
+            .iter()
+            .filter_map(|c| c.id.as_deref())
+            .collect();
+        assert_eq!(ids, ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn comment_end_serializes_id() {
+        let json = serde_json::to_string(&Inline::CommentEnd {
+            id: "c1".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"commentEnd","id":"c1"}"#);
+    }
+}
@@ -0,0 +1,387 @@
+//! Text fields.
+//!
+//! A field is a piece of text the application fills in, such as the page
+//! number or the document title. An [`Inline::Field`] keeps the kind of the
+//! field and the value it showed when the document was last saved, so a
+//! document reads sensibly before its fields are evaluated. Exporters fill
+//! in current values with [`evaluate_fields`]; fixed fields keep theirs.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::field::{evaluate_fields, FieldKind, FieldValues};
+//! use common_core::{Block, Inline};
+//!
+//! let mut blocks = vec![Block::Paragraph {
+//!     style_name: None,
+//!     attrs: None,
+//!     content: vec![Inline::Field {
+//!         kind: FieldKind::Title,
+//!         value: "Untitled".to_string(),
+//!         fixed: false,
+//!         date_value: None,
+//!         data_style_name: None,
+//!     }],
+//! }];
+//! let values = FieldValues {
+//!     title: Some("Annual Report".to_string()),
+//!     ..FieldValues::default()
+//! };
+//! evaluate_fields(&mut blocks, &values);
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::inline::Inline;
+use crate::metadata::Metadata;
+use crate::walk::{for_each_inline, for_each_inline_mut};
+
+/// What a text field shows.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldKind {
+    /// The number of the current page (`text:page-number`).
+    PageNumber,
+    /// The number of pages in the document (`text:page-count`).
+    PageCount,
+    /// The current date (`text:date`).
+    Date,
+    /// The document title (`text:title`).
+    Title,
+    /// The document author (`text:author-name`).
+    Author,
+    /// The number of words in the document (`text:word-count`).
+    WordCount,
+}
+
+impl FieldKind {
+    /// Returns the local name of the ODF field element.
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            FieldKind::PageNumber => "page-number",
+            FieldKind::PageCount => "page-count",
+            FieldKind::Date => "date",
+            FieldKind::Title => "title",
+            FieldKind::Author => "author-name",
+            FieldKind::WordCount => "word-count",
+        }
+    }
+
+    /// Parses the local name of an ODF field element.
+    pub fn from_odf_str(s: &str) -> Option<Self> {
+        match s {
+            "page-number" => Some(FieldKind::PageNumber),
+            "page-count" => Some(FieldKind::PageCount),
+            "date" => Some(FieldKind::Date),
+            "title" => Some(FieldKind::Title),
+            "author-name" => Some(FieldKind::Author),
+            "word-count" => Some(FieldKind::WordCount),
+            _ => None,
+        }
+    }
+
+    /// Whether the value depends on how the document is laid out in pages.
+    pub fn is_paged(self) -> bool {
+        matches!(self, FieldKind::PageNumber | FieldKind::PageCount)
+    }
+}
+
+/// The values to fill fields in with. A field whose value is `None` keeps
+/// the value it has.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldValues {
+    /// The number of the page being laid out.
+    pub page_number: Option<usize>,
+    /// The number of pages in the document.
+    pub page_count: Option<usize>,
+    /// The date to show, already formatted.
+    pub date: Option<String>,
+    /// The document title.
+    pub title: Option<String>,
+    /// The document author.
+    pub author: Option<String>,
+    /// The number of words in the document.
+    pub word_count: Option<usize>,
+}
+
+impl FieldValues {
+    /// Values taken from the document itself: the title and author from
+    /// `metadata`, today's date and the word count of `blocks`.
+    pub fn for_document(blocks: &[Block], metadata: &Metadata) -> Self {
+        FieldValues {
+            page_number: None,
+            page_count: None,
+            date: Some(today()),
+            title: metadata.title.clone(),
+            author: metadata.creator.clone(),
+            word_count: Some(word_count(blocks)),
+        }
+    }
+
+    /// Returns the value for a field of the given kind, if known.
+    pub fn get(&self, kind: FieldKind) -> Option<String> {
+        match kind {
+            FieldKind::PageNumber => self.page_number.map(|n| n.to_string()),
+            FieldKind::PageCount => self.page_count.map(|n| n.to_string()),
+            FieldKind::Date => self.date.clone(),
+            FieldKind::Title => self.title.clone(),
+            FieldKind::Author => self.author.clone(),
+            FieldKind::WordCount => self.word_count.map(|n| n.to_string()),
+        }
+    }
+}
+
+/// Sets the value of every field in `blocks` that is not fixed, including
+/// those in notes, for which `values` has a value.
+pub fn evaluate_fields(blocks: &mut [Block], values: &FieldValues) {
+    for_each_inline_mut(blocks, &mut |inline| {
+        if let Inline::Field {
+            kind,
+            value,
+            fixed: false,
+            ..
+        } = inline
+        {
+            if let Some(new) = values.get(*kind) {
+                *value = new;
+            }
+        }
+    });
+}
+
+/// Whether `blocks` contain a field of the given kind.
+pub fn has_field(blocks: &[Block], kind: FieldKind) -> bool {
+    let mut found = false;
+    for_each_inline(blocks, &mut |inline| {
+        found |= matches!(inline, Inline::Field { kind: k, .. } if *k == kind);
+    });
+    found
+}
+
+/// Counts the words in the text of `blocks`, leaving out notes, comments,
+/// deleted text and the values of fields.
+pub fn word_count(blocks: &[Block]) -> usize {
+    let mut count = 0;
+    for block in blocks {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                let mut in_word = false;
+                for inline in content {
+                    match inline {
+                        Inline::Text { text, .. } => {
+                            for c in text.chars() {
+                                if c.is_whitespace() {
+                                    in_word = false;
+                                } else if !in_word {
+                                    in_word = true;
+                                    count += 1;
+                                }
+                            }
+                        }
+                        Inline::LineBreak | Inline::Space { .. } | Inline::Tab => {
+                            in_word = false;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => count += word_count(content),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+    count
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+pub fn today() -> String {
+    date_of(unix_seconds())
+}
+
+/// The current time in UTC as an ISO 8601 date and time,
+/// `YYYY-MM-DDTHH:MM:SS`.
+pub fn now() -> String {
+    let secs = unix_seconds();
+    let time = secs % 86_400;
+    format!(
+        "{}T{:02}:{:02}:{:02}",
+        date_of(secs),
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// The UTC date of a Unix time as `YYYY-MM-DD`.
+fn date_of(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Seconds since the Unix epoch.
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn para(content: Vec<Inline>) -> Block {
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content,
+        }
+    }
+
+    fn text(s: &str) -> Inline {
+        Inline::Text {
+            text: s.to_string(),
+            style_name: None,
+            marks: vec![],
+        }
+    }
+
+    fn field(kind: FieldKind, value: &str) -> Inline {
+        Inline::Field {
+            kind,
+            value: value.to_string(),
+            fixed: false,
+            date_value: None,
+            data_style_name: None,
+        }
+    }
+
+    #[test]
+    fn field_kind_odf_str_round_trip() {
+        for kind in [
+            FieldKind::PageNumber,
+            FieldKind::PageCount,
+            FieldKind::Date,
+            FieldKind::Title,
+            FieldKind::Author,
+            FieldKind::WordCount,
+        ] {
+            assert_eq!(FieldKind::from_odf_str(kind.to_odf_str()), Some(kind));
+        }
+        assert_eq!(FieldKind::from_odf_str("sequence"), None);
+    }
+
+    #[test]
+    fn field_inline_serde_roundtrip() {
+        let inline = field(FieldKind::PageCount, "12");
+        let json = serde_json::to_string(&inline).unwrap();
+        assert_eq!(json, r#"{"type":"field","kind":"pageCount","value":"12"}"#);
+        let decoded: Inline = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, inline);
+    }
+
+    #[test]
+    fn word_count_skips_fields_and_joins_spans() {
+        let blocks = vec![
+            para(vec![
+                text("Two wo"),
+                text("rds"),
+                Inline::Space { count: 1 },
+                text("and"),
+                field(FieldKind::Title, "Not Counted"),
+            ]),
+            Block::Blockquote {
+                content: vec![para(vec![text(" one ")])],
+            },
+        ];
+        assert_eq!(word_count(&blocks), 4);
+    }
+
+    #[test]
+    fn evaluate_fields_fills_known_values_only() {
+        let mut blocks = vec![para(vec![
+            field(FieldKind::Title, "Old"),
+            field(FieldKind::PageNumber, "7"),
+            field(FieldKind::WordCount, "0"),
+        ])];
+        let values = FieldValues::for_document(
+            &blocks,
+            &Metadata {
+                title: Some("New".to_string()),
+                ..Metadata::default()
+            },
+        );
+        evaluate_fields(&mut blocks, &values);
+        let Block::Paragraph { content, .. } = &blocks[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(content[0], field(FieldKind::Title, "New"));
+        assert_eq!(content[1], field(FieldKind::PageNumber, "7"));
+        assert_eq!(content[2], field(FieldKind::WordCount, "0"));
+        assert!(has_field(&blocks, FieldKind::PageNumber));
+        assert!(!has_field(&blocks, FieldKind::PageCount));
+    }
+
+    #[test]
+    fn fixed_fields_keep_their_value() {
+        let fixed = Inline::Field {
+            kind: FieldKind::Date,
+            value: "1 May 2024".to_string(),
+            fixed: true,
+            date_value: Some("2024-05-01".to_string()),
+            data_style_name: Some("N37".to_string()),
+        };
+        let mut blocks = vec![para(vec![fixed.clone(), field(FieldKind::Date, "")])];
+        let values = FieldValues {
+            date: Some("2026-10-17".to_string()),
+            ..FieldValues::default()
+        };
+        evaluate_fields(&mut blocks, &values);
+        let Block::Paragraph { content, .. } = &blocks[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(content[0], fixed);
+        assert_eq!(content[1], field(FieldKind::Date, "2026-10-17"));
+    }
+
+    #[test]
+    fn now_is_an_iso_date_and_time() {
+        let now = now();
+        assert_eq!(now.len(), 19);
+        assert!(now.starts_with(&today()[..8]));
+        assert_eq!(&now[10..11], "T");
+    }
+
+    #[test]
+    fn civil_from_days_known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_743), (2026, 10, 17));
+    }
+}
@@ -1,7 +1,9 @@
 //! Inline-level document content.
 //!
 //! This module defines the [`Inline`] enum which represents inline content
-//! within block elements such as styled text runs and line breaks.
+//! within block elements such as styled text runs, line breaks, preserved
+//! whitespace, footnotes/endnotes, comment anchors, tracked-change
+//! markers, text fields, bookmarks, and cross-references.
 //!
 //! # Examples
 //!
@@ -19,13 +21,53 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::block::Block;
+use crate::bookmark::CrossReference;
+use crate::comment::Comment;
+use crate::field::FieldKind;
 use crate::marks::TiptapMark;
+use crate::tracked_change::TrackedChange;
+
+fn default_space_count() -> u32 {
+    1
+}
+
+/// The class of a note (`text:note-class` in ODT).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum NoteClass {
+    /// A note rendered at the bottom of the page.
+    #[default]
+    Footnote,
+    /// A note collected at the end of the document.
+    Endnote,
+}
+
+impl NoteClass {
+    /// Returns the ODF `text:note-class` attribute value.
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            NoteClass::Footnote => "footnote",
+            NoteClass::Endnote => "endnote",
+        }
+    }
+
+    /// Parses an ODF `text:note-class` value, defaulting to footnote.
+    pub fn from_odf_str(s: &str) -> Self {
+        match s {
+            "endnote" => NoteClass::Endnote,
+            _ => NoteClass::Footnote,
+        }
+    }
+}
 
 /// An inline content element within a block.
 ///
 /// Inlines are the leaf-level content inside paragraphs, headings, and
-/// other block elements. Each inline is either a styled text run or a
-/// hard line break.
+/// other block elements. Each inline is a styled text run, a hard line
+/// break, a run of significant spaces, a tab, a footnote/endnote anchor
+/// carrying the note body, the start or end of a comment, a tracked-change
+/// marker, a text field, a bookmark, or a cross-reference.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum Inline {
@@ -53,6 +95,90 @@ pub enum Inline {
     },
     /// A hard line break (`text:line-break` in ODT).
     LineBreak,
+    /// A run of `count` significant spaces (`text:s` in ODT).
+    Space {
+        /// Number of spaces; at least `1`.
+        #[serde(default = "default_space_count")]
+        count: u32,
+    },
+    /// A tab character (`text:tab` in ODT).
+    Tab,
+    /// A footnote or endnote (`text:note` in ODT).
+    ///
+    /// The note is anchored at its position in the surrounding text; the
+    /// citation is the visible reference mark and `content` is the note body.
+    Note {
+        /// The ODT `text:id` of the note, if any.
+        #[serde(default)]
+        id: Option<String>,
+        /// Whether this is a footnote or an endnote.
+        #[serde(rename = "noteClass", default)]
+        note_class: NoteClass,
+        /// The citation mark shown in the text (e.g. `"1"`).
+        citation: String,
+        /// Block content of the note body.
+        #[serde(default)]
+        content: Vec<Block>,
+    },
+    /// A reviewer comment (`office:annotation` in ODT), anchored where its
+    /// range starts.
+    Comment(Comment),
+    /// The end of a comment's range (`office:annotation-end` in ODT).
+    CommentEnd {
+        /// The id of the comment whose range ends here.
+        id: String,
+    },
+    /// The start of a tracked insertion or format change
+    /// (`text:change-start` in ODT).
+    ChangeStart(TrackedChange),
+    /// The end of a tracked change's range (`text:change-end` in ODT).
+    ChangeEnd {
+        /// The id of the change whose range ends here.
+        id: String,
+    },
+    /// A tracked deletion (`text:change` in ODT), placed where the removed
+    /// content used to be.
+    Change(TrackedChange),
+    /// A text field such as the page number or the document title
+    /// (`text:page-number`, `text:title`, ... in ODT).
+    Field {
+        /// What the field shows.
+        kind: FieldKind,
+        /// The value last shown, used until the field is evaluated again.
+        #[serde(default)]
+        value: String,
+        /// Whether the value is fixed rather than evaluated (`text:fixed`).
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        fixed: bool,
+        /// The date a date field shows, in ISO 8601 (`text:date-value`).
+        #[serde(rename = "dateValue", default, skip_serializing_if = "Option::is_none")]
+        date_value: Option<String>,
+        /// The number style that formats the value (`style:data-style-name`).
+        #[serde(
+            rename = "dataStyleName",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        data_style_name: Option<String>,
+    },
+    /// A bookmark at a single position (`text:bookmark` in ODT).
+    Bookmark {
+        /// The bookmark name (`text:name`).
+        name: String,
+    },
+    /// The start of a bookmarked range (`text:bookmark-start` in ODT).
+    BookmarkStart {
+        /// The bookmark name (`text:name`).
+        name: String,
+    },
+    /// The end of a bookmarked range (`text:bookmark-end` in ODT).
+    BookmarkEnd {
+        /// The name of the bookmark whose range ends here.
+        name: String,
+    },
+    /// A cross-reference to a bookmark or reference mark
+    /// (`text:bookmark-ref` or `text:reference-ref` in ODT).
+    Reference(CrossReference),
 }
 
 #[cfg(test)]
@@ -100,4 +226,48 @@ mod tests {
         let decoded: Inline = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded, Inline::LineBreak);
     }
+
+    #[test]
+    fn space_and_tab_serde_roundtrip() {
+        let inlines = vec![Inline::Space { count: 4 }, Inline::Tab];
+        let json = serde_json::to_string(&inlines).unwrap();
+        let decoded: Vec<Inline> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, inlines);
+    }
+
+    #[test]
+    fn space_count_defaults_to_one() {
+        let decoded: Inline = serde_json::from_str(r#"{"type":"space"}"#).unwrap();
+        assert_eq!(decoded, Inline::Space { count: 1 });
+    }
+
+    #[test]
+    fn note_inline_serde_roundtrip() {
+        let inline = Inline::Note {
+            id: Some("ftn1".to_string()),
+            note_class: NoteClass::Endnote,
+            citation: "i".to_string(),
+            content: vec![Block::Paragraph {
+                style_name: Some("Endnote".to_string()),
+                attrs: None,
+                content: vec![Inline::Text {
+                    text: "See appendix.".to_string(),
+                    style_name: None,
+                    marks: vec![],
+                }],
+            }],
+        };
+        let json = serde_json::to_string(&inline).unwrap();
+        assert!(json.contains("\"noteClass\":\"endnote\""));
+        let decoded: Inline = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, inline);
+    }
+
+    #[test]
+    fn note_class_odf_str_round_trip() {
+        assert_eq!(NoteClass::from_odf_str("footnote"), NoteClass::Footnote);
+        assert_eq!(NoteClass::from_odf_str("endnote"), NoteClass::Endnote);
+        assert_eq!(NoteClass::from_odf_str("bogus"), NoteClass::Footnote);
+        assert_eq!(NoteClass::Endnote.to_odf_str(), "endnote");
+    }
 }
@@ -27,8 +27,10 @@
 //! ```
 
 mod node;
+mod region;
 
 pub use node::LexicalNode;
+pub use region::{LexicalPageRegion, PageRegionKind, PageRegionVariant};
 
 use serde::{Deserialize, Serialize};
 
@@ -119,6 +121,7 @@ mod tests {
         let node = LexicalNode::HeadingStyle {
             tag: "h2".to_string(),
             style_name: Some("Heading 2".to_string()),
+            number: None,
             children: vec![],
             direction: None,
             format: String::new(),
@@ -147,6 +150,14 @@ mod tests {
         assert!(json.contains("\"page-break\""));
     }
 
+    /// Lexical's built-in tab node must deserialise from its native JSON shape.
+    #[test]
+    fn tab_node_from_lexical_json() {
+        let json = r#"{"detail":2,"format":0,"mode":"normal","style":"","text":"\t","type":"tab","version":1}"#;
+        let node: LexicalNode = serde_json::from_str(json).unwrap();
+        assert!(matches!(node, LexicalNode::Tab { ref text, .. } if text == "\t"));
+    }
+
     /// `styleName: null` in the JSON (produced by the frontend when no style is
     /// assigned to a paragraph) must deserialise without error.
     #[test]
@@ -2,10 +2,20 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::block::{ImageFrame, ListAttrs};
+use crate::bookmark::ReferenceKind;
+use crate::field::FieldKind;
+use crate::toc::{default_outline_level, TocEntry};
+use crate::tracked_change::ChangeKind;
+
 fn default_mode() -> String {
     "normal".to_string()
 }
 
+fn default_tab_text() -> String {
+    "\t".to_string()
+}
+
 fn default_one() -> u32 {
     1
 }
@@ -41,6 +51,10 @@ pub enum LexicalNode {
         /// Optional ODT style name.
         #[serde(rename = "styleName", skip_serializing_if = "Option::is_none")]
         style_name: Option<String>,
+        /// The outline number shown before the heading (e.g. `"2.3.1"`).
+        /// Computed from the outline style on export and ignored on import.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        number: Option<String>,
         /// Inline children.
         children: Vec<LexicalNode>,
         /// Text direction.
@@ -74,6 +88,27 @@ pub enum LexicalNode {
         /// Always `1`.
         version: u32,
     },
+    /// A tab character (`"tab"`), Lexical's built-in `TabNode`.
+    #[serde(rename = "tab")]
+    Tab {
+        /// Always `"\t"`.
+        #[serde(default = "default_tab_text")]
+        text: String,
+        /// Bitmask of formatting flags (`FORMAT_*` constants).
+        #[serde(default)]
+        format: u32,
+        /// Inline CSS style string (usually empty).
+        #[serde(default)]
+        style: String,
+        /// Lexical rendering mode (usually `"normal"`).
+        #[serde(default = "default_mode")]
+        mode: String,
+        /// Lexical detail flags (`2`, unmergeable, for tabs).
+        #[serde(default)]
+        detail: u32,
+        /// Always `1`.
+        version: u32,
+    },
     /// A hyperlink wrapper (`"link"`).
     #[serde(rename = "link")]
     Link {
@@ -96,12 +131,197 @@ pub enum LexicalNode {
         /// Always `1`.
         version: u32,
     },
+    /// A footnote or endnote inline (`"note"`).
+    ///
+    /// Sits inline within a paragraph; `children` holds the note body blocks.
+    #[serde(rename = "note")]
+    Note {
+        /// `"footnote"` or `"endnote"`.
+        #[serde(rename = "noteClass")]
+        note_class: String,
+        /// The citation mark shown in the text.
+        citation: String,
+        /// Optional ODT note identifier.
+        #[serde(rename = "noteId", default, skip_serializing_if = "Option::is_none")]
+        note_id: Option<String>,
+        /// Block children forming the note body.
+        children: Vec<LexicalNode>,
+        /// Always `1`.
+        version: u32,
+    },
+    /// A reviewer comment anchor inline (`"comment"`).
+    ///
+    /// Marks where the comment's range starts; `children` holds the comment
+    /// text blocks and `replies` further `"comment"` nodes.
+    #[serde(rename = "comment")]
+    Comment {
+        /// Identifier shared with the `"comment-end"` node closing the range.
+        #[serde(rename = "commentId", default, skip_serializing_if = "Option::is_none")]
+        comment_id: Option<String>,
+        /// The comment author.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        author: Option<String>,
+        /// ISO 8601 timestamp of the comment.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        date: Option<String>,
+        /// Whether the discussion has been resolved.
+        #[serde(default)]
+        resolved: bool,
+        /// Block children forming the comment text.
+        children: Vec<LexicalNode>,
+        /// Reply `"comment"` nodes, oldest first.
+        #[serde(default)]
+        replies: Vec<LexicalNode>,
+        /// Always `1`.
+        version: u32,
+    },
+    /// The end of a comment's range (`"comment-end"`).
+    #[serde(rename = "comment-end")]
+    CommentEnd {
+        /// Identifier of the comment whose range ends here.
+        #[serde(rename = "commentId")]
+        comment_id: String,
+        /// Always `1`.
+        version: u32,
+    },
+    /// The start of a tracked insertion or format change (`"change-start"`).
+    #[serde(rename = "change-start")]
+    ChangeStart {
+        /// Identifier shared with the `"change-end"` node closing the range.
+        #[serde(rename = "changeId")]
+        change_id: String,
+        /// The kind of change.
+        #[serde(default)]
+        kind: ChangeKind,
+        /// The author of the change.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        author: Option<String>,
+        /// ISO 8601 timestamp of the change.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        date: Option<String>,
+        /// Always `1`.
+        version: u32,
+    },
+    /// The end of a tracked change's range (`"change-end"`).
+    #[serde(rename = "change-end")]
+    ChangeEnd {
+        /// Identifier of the change whose range ends here.
+        #[serde(rename = "changeId")]
+        change_id: String,
+        /// Always `1`.
+        version: u32,
+    },
+    /// A tracked deletion inline (`"change"`); `children` holds the removed
+    /// blocks.
+    #[serde(rename = "change")]
+    Change {
+        /// Identifier of the change.
+        #[serde(rename = "changeId")]
+        change_id: String,
+        /// The kind of change.
+        #[serde(default)]
+        kind: ChangeKind,
+        /// The author of the change.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        author: Option<String>,
+        /// ISO 8601 timestamp of the change.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        date: Option<String>,
+        /// The removed blocks.
+        #[serde(default)]
+        children: Vec<LexicalNode>,
+        /// Always `1`.
+        version: u32,
+    },
+    /// A text field inline (`"field"`).
+    #[serde(rename = "field")]
+    Field {
+        /// What the field shows.
+        kind: FieldKind,
+        /// The value last shown.
+        #[serde(default)]
+        value: String,
+        /// Whether the value is fixed rather than evaluated.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        fixed: bool,
+        /// The date a date field shows, in ISO 8601.
+        #[serde(rename = "dateValue", default, skip_serializing_if = "Option::is_none")]
+        date_value: Option<String>,
+        /// The number style that formats the value.
+        #[serde(
+            rename = "dataStyleName",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        data_style_name: Option<String>,
+        /// Always `1`.
+        version: u32,
+    },
+    /// A bookmark at a single position (`"bookmark"`).
+    #[serde(rename = "bookmark")]
+    Bookmark {
+        /// The bookmark name.
+        name: String,
+        /// Always `1`.
+        version: u32,
+    },
+    /// The start of a bookmarked range (`"bookmark-start"`).
+    #[serde(rename = "bookmark-start")]
+    BookmarkStart {
+        /// The bookmark name, shared with the `"bookmark-end"` node.
+        name: String,
+        /// Always `1`.
+        version: u32,
+    },
+    /// The end of a bookmarked range (`"bookmark-end"`).
+    #[serde(rename = "bookmark-end")]
+    BookmarkEnd {
+        /// The name of the bookmark whose range ends here.
+        name: String,
+        /// Always `1`.
+        version: u32,
+    },
+    /// A cross-reference inline (`"reference"`).
+    #[serde(rename = "reference")]
+    Reference {
+        /// Whether the target is a bookmark or a reference mark.
+        #[serde(default)]
+        kind: ReferenceKind,
+        /// The name of the target.
+        name: String,
+        /// What is shown of the target, e.g. `"page"` or `"text"`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        /// The text last shown.
+        #[serde(default)]
+        value: String,
+        /// Always `1`.
+        version: u32,
+    },
     /// A page break block (`"page-break"`).
     #[serde(rename = "page-break")]
     PageBreak {
         /// Always `1`.
         version: u32,
     },
+    /// A table of contents block (`"table-of-contents"`).
+    #[serde(rename = "table-of-contents")]
+    TableOfContents {
+        /// The name of the index.
+        #[serde(default)]
+        name: String,
+        /// The title shown above the entries.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// The deepest heading level listed.
+        #[serde(rename = "outlineLevel", default = "default_outline_level")]
+        outline_level: u32,
+        /// The entries as last generated.
+        #[serde(default)]
+        entries: Vec<TocEntry>,
+        /// Always `1`.
+        version: u32,
+    },
     /// A hard line-break inline (`"linebreak"`).
     #[serde(rename = "linebreak")]
     LineBreak {
@@ -116,6 +336,15 @@ pub enum LexicalNode {
         /// Alternative text.
         #[serde(rename = "altText")]
         alt_text: String,
+        /// Title tooltip.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Frame size, anchoring and wrapping.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        frame: Option<ImageFrame>,
+        /// Caption text.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        caption: Option<String>,
         /// Always `1`.
         version: u32,
     },
@@ -137,7 +366,8 @@ pub enum LexicalNode {
         indent: u32,
         version: u32,
     },
-    /// A table cell (`"tablecell"`). `header_state = 1` indicates a header.
+    /// A table cell (`"tablecell"`). Bit 1 of `header_state` marks a cell
+    /// in a header row.
     #[serde(rename = "tablecell")]
     TableCell {
         #[serde(rename = "colSpan", default = "default_one")]
@@ -146,6 +376,16 @@ pub enum LexicalNode {
         row_span: u32,
         #[serde(rename = "headerState", default)]
         header_state: u32,
+        /// Cell width in pixels.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        width: Option<f64>,
+        /// Background colour as a CSS colour string.
+        #[serde(
+            rename = "backgroundColor",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        background_color: Option<String>,
         children: Vec<LexicalNode>,
         direction: Option<String>,
         format: String,
@@ -162,6 +402,9 @@ pub enum LexicalNode {
         start: u32,
         /// HTML tag: `"ul"` or `"ol"`.
         tag: String,
+        /// ODT list style and numbering attributes, if any.
+        #[serde(rename = "listAttrs", default, skip_serializing_if = "Option::is_none")]
+        list_attrs: Option<ListAttrs>,
         children: Vec<LexicalNode>,
         direction: Option<String>,
         format: String,
@@ -188,4 +431,25 @@ pub enum LexicalNode {
         indent: u32,
         version: u32,
     },
+    /// A document section (`"section"`).
+    #[serde(rename = "section")]
+    Section {
+        /// Section name.
+        #[serde(default)]
+        name: String,
+        /// Number of text columns.
+        #[serde(rename = "columnCount", default = "default_one")]
+        column_count: u32,
+        /// Space between columns in pixels.
+        #[serde(rename = "columnGap", default, skip_serializing_if = "Option::is_none")]
+        column_gap: Option<f64>,
+        /// Whether the section is protected against editing.
+        #[serde(default)]
+        protected: bool,
+        children: Vec<LexicalNode>,
+        direction: Option<String>,
+        format: String,
+        indent: u32,
+        version: u32,
+    },
 }
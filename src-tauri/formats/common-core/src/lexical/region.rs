@@ -0,0 +1,46 @@
+//! Headers and footers in the Lexical editor.
+//!
+//! Each header or footer variant of a master page is edited as its own
+//! Lexical document, tagged with where it belongs.
+
+use serde::{Deserialize, Serialize};
+
+use super::LexicalDocument;
+
+/// Whether a page region is a header or a footer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PageRegionKind {
+    /// The running header (`style:header`).
+    Header,
+    /// The running footer (`style:footer`).
+    Footer,
+}
+
+/// Which pages of a master page run a header or footer variant is shown on.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PageRegionVariant {
+    /// Every page without a more specific variant.
+    #[default]
+    Default,
+    /// Left (even) pages.
+    Left,
+    /// The first page of the run.
+    First,
+}
+
+/// The editor state of one header or footer variant of a master page.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LexicalPageRegion {
+    /// The name of the master page the region belongs to.
+    pub master_page: String,
+    /// Header or footer.
+    pub kind: PageRegionKind,
+    /// The pages the content is shown on.
+    #[serde(default)]
+    pub variant: PageRegionVariant,
+    /// The region content.
+    pub content: LexicalDocument,
+}
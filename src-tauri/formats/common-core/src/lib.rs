@@ -30,20 +30,44 @@
 //! ```
 
 pub mod block;
+pub mod bookmark;
+pub mod comment;
+pub mod field;
 pub mod inline;
 pub mod lexical;
 pub mod marks;
 pub mod metadata;
+pub mod outline;
+pub mod page;
+pub mod resolve;
 pub mod style;
 pub mod tiptap;
+pub mod toc;
+pub mod tracked_change;
+pub mod units;
+pub mod walk;
 
-pub use block::{Block, BlockAttrs, CellAttrs};
-pub use inline::Inline;
-pub use lexical::{LexicalDocument, LexicalNode, LexicalRoot};
-pub use marks::{LinkAttrs, TiptapAttrsInline, TiptapMark};
-pub use metadata::Metadata;
-pub use style::{StyleDefinition, StyleFamily};
-pub use tiptap::{ImageAttrs, TiptapAttrs, TiptapNode, TiptapResponse};
+pub use block::{
+    Block, BlockAttrs, CellAttrs, CellBorders, ImageAnchor, ImageFrame, ImageWrap, ListAttrs,
+    SectionAttrs,
+};
+pub use bookmark::{CrossReference, ReferenceKind};
+pub use comment::Comment;
+pub use field::{FieldKind, FieldValues};
+pub use inline::{Inline, NoteClass};
+pub use lexical::{LexicalDocument, LexicalNode, LexicalPageRegion, LexicalRoot};
+pub use marks::{LinkAttrs, TextStyleAttrs, TiptapAttrsInline, TiptapMark};
+pub use metadata::{DocumentStatistics, Metadata, UserDefinedField};
+pub use outline::{OutlineLevel, OutlineStyle};
+pub use page::{HeaderFooter, MasterPage, PageLayout, PageOrientation, PageSetup};
+pub use resolve::{EffectiveStyle, StyleResolver};
+pub use style::{ListLevelKind, ListLevelStyle, PropertyGroup, StyleDefinition, StyleFamily};
+pub use tiptap::{
+    BookmarkAttrs, FieldAttrs, ImageAttrs, NoteAttrs, SectionNodeAttrs, TiptapAttrs, TiptapNode,
+    TiptapResponse,
+};
+pub use toc::{TableOfContents, TocEntry};
+pub use tracked_change::{ChangeKind, TrackedChange};
 
 #[cfg(feature = "colour-management")]
 pub mod colour_management;
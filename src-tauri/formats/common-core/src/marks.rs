@@ -18,8 +18,13 @@
 //! };
 //! ```
 
+use std::hash::{Hash, Hasher};
+
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "colour-management")]
+use crate::colour_management::Colour;
+
 /// A hyperlink attribute set for link marks.
 ///
 /// Contains the destination URL and optional target window specifier.
@@ -54,6 +59,106 @@ pub enum TiptapMark {
     Subscript,
     /// A hyperlink.
     Link { attrs: LinkAttrs },
+    /// Text colour, highlight, font family and size.
+    TextStyle { attrs: TextStyleAttrs },
+}
+
+/// Character formatting carried by a [`TiptapMark::TextStyle`] mark.
+///
+/// Every field is optional; unset fields inherit from the paragraph or
+/// character style. Sizes and colour channels are never NaN, so the
+/// attributes are [`Eq`] and can be hashed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStyleAttrs {
+    /// The text colour (`fo:color`).
+    #[cfg(feature = "colour-management")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colour: Option<Colour>,
+    /// The highlight colour behind the text (`fo:background-color`).
+    #[cfg(feature = "colour-management")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_colour: Option<Colour>,
+    /// The font family name (`fo:font-family`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    /// The font size in points (`fo:font-size`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f32>,
+}
+
+impl Eq for TextStyleAttrs {}
+
+impl Hash for TextStyleAttrs {
+    /// Hashes the font family and size; attributes that differ only in
+    /// colour share a hash, which keeps equal attributes hashing equally.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.font_family.hash(state);
+        // `0.0 == -0.0`, so both must hash the same.
+        self.font_size
+            .map(|size| if size == 0.0 { 0 } else { size.to_bits() })
+            .hash(state);
+    }
+}
+
+impl TextStyleAttrs {
+    /// Returns `true` when no property is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Overrides the properties of `self` with those set in `inner`.
+    pub fn merge(&mut self, inner: &TextStyleAttrs) {
+        #[cfg(feature = "colour-management")]
+        {
+            if inner.colour.is_some() {
+                self.colour.clone_from(&inner.colour);
+            }
+            if inner.background_colour.is_some() {
+                self.background_colour.clone_from(&inner.background_colour);
+            }
+        }
+        if inner.font_family.is_some() {
+            self.font_family.clone_from(&inner.font_family);
+        }
+        if inner.font_size.is_some() {
+            self.font_size = inner.font_size;
+        }
+    }
+
+    /// Returns the properties as CSS declarations, e.g.
+    /// `color: #ff0000; font-size: 12pt`.
+    ///
+    /// Colours are written as sRGB.
+    pub fn to_css(&self) -> String {
+        let mut decls = Vec::new();
+        #[cfg(feature = "colour-management")]
+        {
+            if let Some(c) = &self.colour {
+                decls.push(format!("color: {}", c.to_css_string()));
+            }
+            if let Some(c) = &self.background_colour {
+                decls.push(format!("background-color: {}", c.to_css_string()));
+            }
+        }
+        if let Some(family) = &self.font_family {
+            decls.push(format!("font-family: {}", css_font_family(family)));
+        }
+        if let Some(size) = self.font_size {
+            decls.push(format!("font-size: {}pt", size));
+        }
+        decls.join("; ")
+    }
+}
+
+/// Quotes a font family name for CSS, unless it is a generic family or
+/// already quoted.
+fn css_font_family(family: &str) -> String {
+    const GENERIC: [&str; 5] = ["serif", "sans-serif", "monospace", "cursive", "fantasy"];
+    if GENERIC.contains(&family) || family.starts_with(['"', '\'']) {
+        return family.to_string();
+    }
+    format!("\"{}\"", family.replace('\\', "\\\\").replace('"', "\\\""))
 }
 
 /// Minimal attrs struct used within `NamedSpanStyle` marks.
@@ -120,6 +225,39 @@ mod tests {
         assert!(attrs.target.is_none());
     }
 
+    #[test]
+    fn text_style_mark_serde_roundtrip() {
+        let mark = TiptapMark::TextStyle {
+            attrs: TextStyleAttrs {
+                font_family: Some("Liberation Serif".to_string()),
+                font_size: Some(14.0),
+                ..TextStyleAttrs::default()
+            },
+        };
+        let json = serde_json::to_string(&mark).unwrap();
+        assert!(json.contains("\"textStyle\""));
+        assert!(json.contains("\"fontSize\":14.0"));
+        let decoded: TiptapMark = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, mark);
+    }
+
+    #[test]
+    fn text_style_to_css() {
+        let mut attrs = TextStyleAttrs {
+            font_size: Some(10.5),
+            ..TextStyleAttrs::default()
+        };
+        assert_eq!(attrs.to_css(), "font-size: 10.5pt");
+        attrs.merge(&TextStyleAttrs {
+            font_family: Some("Arial".to_string()),
+            ..TextStyleAttrs::default()
+        });
+        assert_eq!(attrs.to_css(), "font-family: \"Arial\"; font-size: 10.5pt");
+        attrs.font_family = Some("serif".to_string());
+        assert_eq!(attrs.to_css(), "font-family: serif; font-size: 10.5pt");
+        assert!(TextStyleAttrs::default().is_empty());
+    }
+
     #[test]
     fn all_simple_mark_variants_serialize() {
         let variants = [
@@ -1,7 +1,8 @@
 //! Document metadata.
 //!
 //! This module defines the [`Metadata`] struct which holds Dublin Core and
-//! ODF meta fields for documents (title, creator, language, etc.).
+//! ODF meta fields for documents (title, creator, language, etc.), and the
+//! [`DocumentStatistics`] computed from a document's blocks.
 //!
 //! # Examples
 //!
@@ -19,6 +20,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::block::Block;
+use crate::inline::Inline;
+use crate::walk::for_each_block;
+
 /// Document-level metadata fields following Dublin Core conventions.
 ///
 /// All fields are optional; absent fields are omitted during serialization.
@@ -42,6 +47,179 @@ pub struct Metadata {
     pub creation_date: Option<String>,
     /// The application that created this document (meta:generator).
     pub generator: Option<String>,
+    /// Keywords, one per `meta:keyword`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// ISO 8601 timestamp of the last modification (dc:date).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// How many times the document has been saved (meta:editing-cycles).
+    #[serde(
+        rename = "editingCycles",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub editing_cycles: Option<u32>,
+    /// Total editing time as an ISO 8601 duration, e.g. `"PT1H30M"`
+    /// (meta:editing-duration).
+    #[serde(
+        rename = "editingDuration",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub editing_duration: Option<String>,
+    /// The author of the first version (meta:initial-creator).
+    #[serde(
+        rename = "initialCreator",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub initial_creator: Option<String>,
+    /// Custom properties, in document order (meta:user-defined).
+    #[serde(rename = "userDefined", default, skip_serializing_if = "Vec::is_empty")]
+    pub user_defined: Vec<UserDefinedField>,
+    /// Counts of the document content (meta:document-statistic).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statistics: Option<DocumentStatistics>,
+}
+
+impl Metadata {
+    /// Recomputes [`Metadata::statistics`] from `blocks`, keeping the last
+    /// known page count.
+    pub fn update_statistics(&mut self, blocks: &[Block]) {
+        let page_count = self.statistics.and_then(|s| s.page_count);
+        self.statistics = Some(DocumentStatistics {
+            page_count,
+            ..DocumentStatistics::from_blocks(blocks)
+        });
+    }
+}
+
+/// A custom document property (`meta:user-defined`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDefinedField {
+    /// The property name (`meta:name`).
+    pub name: String,
+    /// The ODF value type (`meta:value-type`): `"float"`, `"date"`,
+    /// `"time"`, `"boolean"` or `"string"`. `None` means string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+    /// The value as written in the document.
+    pub value: String,
+}
+
+/// Counts of the document content (`meta:document-statistic`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentStatistics {
+    /// The number of pages, as last laid out by the application that saved
+    /// the document. It depends on layout, so it is not computed here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<u32>,
+    /// The number of paragraphs and headings with text.
+    #[serde(default)]
+    pub paragraph_count: u32,
+    /// The number of words.
+    #[serde(default)]
+    pub word_count: u32,
+    /// The number of characters, including spaces.
+    #[serde(default)]
+    pub character_count: u32,
+    /// The number of characters that are not whitespace.
+    #[serde(default)]
+    pub non_whitespace_character_count: u32,
+    /// The number of tables.
+    #[serde(default)]
+    pub table_count: u32,
+    /// The number of images.
+    #[serde(default)]
+    pub image_count: u32,
+}
+
+impl DocumentStatistics {
+    /// Counts the content of `blocks`, including note bodies but not
+    /// comments or deleted text. Fields and cross-references count as the
+    /// text they last showed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common_core::{Block, DocumentStatistics, Inline};
+    ///
+    /// let blocks = vec![Block::Paragraph {
+    ///     style_name: None,
+    ///     attrs: None,
+    ///     content: vec![Inline::Text {
+    ///         text: "Hello, world".to_string(),
+    ///         style_name: None,
+    ///         marks: vec![],
+    ///     }],
+    /// }];
+    /// let stats = DocumentStatistics::from_blocks(&blocks);
+    /// assert_eq!(stats.word_count, 2);
+    /// assert_eq!(stats.character_count, 12);
+    /// assert_eq!(stats.non_whitespace_character_count, 11);
+    /// ```
+    #[must_use]
+    pub fn from_blocks(blocks: &[Block]) -> Self {
+        let mut stats = Self::default();
+        for_each_block(blocks, &mut |block| match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                let text = plain_text(content);
+                if text.trim().is_empty() {
+                    return;
+                }
+                stats.paragraph_count += 1;
+                stats.word_count += count(text.split_whitespace().count());
+                stats.character_count += count(text.chars().count());
+                stats.non_whitespace_character_count +=
+                    count(text.chars().filter(|c| !c.is_whitespace()).count());
+            }
+            Block::Table { .. } => stats.table_count += 1,
+            Block::Image { .. } => stats.image_count += 1,
+            _ => {}
+        });
+        stats
+    }
+
+    /// Returns the counts computed by [`Self::from_blocks`], named as the
+    /// `meta:document-statistic` attributes without their prefix.
+    #[must_use]
+    pub fn odf_counts(&self) -> [(&'static str, u32); 6] {
+        [
+            ("table-count", self.table_count),
+            ("image-count", self.image_count),
+            ("paragraph-count", self.paragraph_count),
+            ("word-count", self.word_count),
+            ("character-count", self.character_count),
+            (
+                "non-whitespace-character-count",
+                self.non_whitespace_character_count,
+            ),
+        ]
+    }
+}
+
+/// The text a paragraph shows, without its notes.
+fn plain_text(content: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in content {
+        match inline {
+            Inline::Text { text: t, .. } => text.push_str(t),
+            Inline::Space { count } => text.extend(std::iter::repeat_n(' ', *count as usize)),
+            Inline::Tab => text.push('\t'),
+            Inline::LineBreak => text.push('\n'),
+            Inline::Field { value, .. } => text.push_str(value),
+            Inline::Reference(reference) => text.push_str(&reference.value),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn count(n: usize) -> u32 {
+    u32::try_from(n).unwrap_or(u32::MAX)
 }
 
 #[cfg(test)]
@@ -59,6 +237,84 @@ mod tests {
         assert!(meta.creator.is_none());
         assert!(meta.creation_date.is_none());
         assert!(meta.generator.is_none());
+        assert!(meta.keywords.is_empty());
+        assert!(meta.statistics.is_none());
+    }
+
+    #[test]
+    fn statistics_count_text_tables_and_images() {
+        let para = |text: &str| Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::Text {
+                    text: text.to_string(),
+                    style_name: None,
+                    marks: vec![],
+                },
+                Inline::Space { count: 2 },
+                Inline::Text {
+                    text: "end".to_string(),
+                    style_name: None,
+                    marks: vec![],
+                },
+            ],
+        };
+        let blocks = vec![
+            para("One two"),
+            Block::Paragraph {
+                style_name: None,
+                attrs: None,
+                content: vec![],
+            },
+            Block::Table {
+                content: vec![Block::TableRow {
+                    content: vec![Block::TableCell {
+                        attrs: None,
+                        content: vec![para("cell")],
+                    }],
+                }],
+            },
+            Block::Image {
+                src: "a.png".to_string(),
+                alt: None,
+                title: None,
+                frame: None,
+                caption: None,
+            },
+        ];
+        let stats = DocumentStatistics::from_blocks(&blocks);
+        assert_eq!(
+            stats,
+            DocumentStatistics {
+                page_count: None,
+                paragraph_count: 2,
+                word_count: 5,
+                character_count: 21,
+                non_whitespace_character_count: 16,
+                table_count: 1,
+                image_count: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn extended_fields_use_camel_case_and_default_when_absent() {
+        let meta = Metadata {
+            keywords: vec!["odf".to_string()],
+            editing_cycles: Some(3),
+            user_defined: vec![UserDefinedField {
+                name: "Client".to_string(),
+                value_type: None,
+                value: "ACME".to_string(),
+            }],
+            ..Metadata::default()
+        };
+        let json = serde_json::to_string(&meta).unwrap();
+        assert!(json.contains("\"editingCycles\":3"));
+        assert!(json.contains("\"userDefined\":[{\"name\":\"Client\",\"value\":\"ACME\"}]"));
+        let decoded: Metadata = serde_json::from_str(r#"{"title":null}"#).unwrap();
+        assert_eq!(decoded, Metadata::default());
     }
 
     #[test]
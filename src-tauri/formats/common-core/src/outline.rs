@@ -0,0 +1,438 @@
+//! Heading outline numbering.
+//!
+//! Numbered headings such as "2.3.1" are not part of the heading text: they
+//! come from the document's outline style (`text:outline-style` in ODT),
+//! which gives the number format of each outline level. [`OutlineStyle`]
+//! models that scheme and computes the numbers of the headings of a block
+//! tree; exporters show them with [`OutlineStyle::number_headings`].
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::outline::{OutlineLevel, OutlineStyle};
+//! use common_core::Block;
+//!
+//! let outline = OutlineStyle {
+//!     levels: vec![
+//!         OutlineLevel { level: 1, num_format: Some("1".to_string()), ..OutlineLevel::default() },
+//!         OutlineLevel {
+//!             level: 2,
+//!             num_format: Some("a".to_string()),
+//!             num_suffix: Some(")".to_string()),
+//!             display_levels: 2,
+//!             ..OutlineLevel::default()
+//!         },
+//!     ],
+//!     ..OutlineStyle::default()
+//! };
+//! let heading = |level| Block::Heading { level, style_name: None, attrs: None, content: vec![] };
+//! let blocks = vec![heading(1), heading(2), heading(2)];
+//! assert_eq!(outline.heading_numbers(&blocks), vec!["1", "1.a)", "1.b)"]);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::inline::Inline;
+
+fn default_display_levels() -> u32 {
+    1
+}
+
+/// The numbering of headings by outline level (`text:outline-style`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineStyle {
+    /// The style name (`style:name`).
+    #[serde(default)]
+    pub name: String,
+    /// The numbering of each outline level.
+    #[serde(default)]
+    pub levels: Vec<OutlineLevel>,
+}
+
+impl Default for OutlineStyle {
+    fn default() -> Self {
+        OutlineStyle {
+            name: "Outline".to_string(),
+            levels: Vec::new(),
+        }
+    }
+}
+
+/// The numbering of one outline level (`text:outline-level-style`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineLevel {
+    /// The outline level, from 1.
+    pub level: u32,
+    /// Number format (`style:num-format`, e.g. `"1"`, `"a"`, `"I"`);
+    /// `None` when headings at this level are not numbered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_format: Option<String>,
+    /// Text shown before the number (`style:num-prefix`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_prefix: Option<String>,
+    /// Text shown after the number (`style:num-suffix`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_suffix: Option<String>,
+    /// The number of the first heading, when it is not 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<u32>,
+    /// How many levels the number shows, counting this one
+    /// (`text:display-levels`): 3 shows "2.3.1" at level 3.
+    #[serde(default = "default_display_levels")]
+    pub display_levels: u32,
+}
+
+impl Default for OutlineLevel {
+    fn default() -> Self {
+        OutlineLevel {
+            level: 1,
+            num_format: None,
+            num_prefix: None,
+            num_suffix: None,
+            start: None,
+            display_levels: default_display_levels(),
+        }
+    }
+}
+
+impl OutlineStyle {
+    /// The numbering of outline level `level`.
+    pub fn level(&self, level: u32) -> Option<&OutlineLevel> {
+        self.levels.iter().find(|l| l.level == level)
+    }
+
+    /// Whether any level is numbered.
+    pub fn is_numbered(&self) -> bool {
+        self.levels.iter().any(|l| l.num_format.is_some())
+    }
+
+    /// The number of every heading in `blocks` in document order, empty for
+    /// headings at a level that is not numbered.
+    pub fn heading_numbers(&self, blocks: &[Block]) -> Vec<String> {
+        let mut counters = Vec::new();
+        let mut out = Vec::new();
+        self.collect_numbers(blocks, &mut counters, &mut out);
+        out
+    }
+
+    /// Prefixes every numbered heading in `blocks` with its number and a
+    /// space, after any bookmarks it starts with.
+    pub fn number_headings(&self, blocks: &mut [Block]) {
+        if !self.is_numbered() {
+            return;
+        }
+        let numbers = self.heading_numbers(blocks);
+        let mut numbers = numbers.into_iter();
+        prefix_headings(blocks, &mut numbers);
+    }
+
+    fn collect_numbers(&self, blocks: &[Block], counters: &mut Vec<u32>, out: &mut Vec<String>) {
+        for block in blocks {
+            match block {
+                Block::Heading { level, .. } => {
+                    let depth = (*level).max(1) as usize;
+                    counters.resize(counters.len().max(depth), 0);
+                    counters.truncate(depth);
+                    let index = depth - 1;
+                    counters[index] = match counters[index] {
+                        0 => self.start(*level),
+                        n => n + 1,
+                    };
+                    out.push(self.label(*level, counters));
+                }
+                Block::BulletList { content, .. }
+                | Block::OrderedList { content, .. }
+                | Block::ListItem { content }
+                | Block::Blockquote { content }
+                | Block::Section { content, .. }
+                | Block::Table { content }
+                | Block::TableRow { content }
+                | Block::TableHeader { content, .. }
+                | Block::TableCell { content, .. } => self.collect_numbers(content, counters, out),
+                Block::Paragraph { .. }
+                | Block::Image { .. }
+                | Block::HorizontalRule
+                | Block::PageBreak
+                | Block::TableOfContents(_) => {}
+            }
+        }
+    }
+
+    fn start(&self, level: u32) -> u32 {
+        self.level(level).and_then(|l| l.start).unwrap_or(1)
+    }
+
+    /// The number of a heading at `level` given the counters of the levels
+    /// up to it. Levels above without a heading count as their start value.
+    fn label(&self, level: u32, counters: &[u32]) -> String {
+        let Some(style) = self.level(level) else {
+            return String::new();
+        };
+        let Some(format) = style.num_format.as_deref() else {
+            return String::new();
+        };
+        let shown = style.display_levels.clamp(1, level);
+        let mut parts = Vec::new();
+        for l in level + 1 - shown..level {
+            let value = match counters[l as usize - 1] {
+                0 => self.start(l),
+                n => n,
+            };
+            let parent_format = self
+                .level(l)
+                .and_then(|s| s.num_format.as_deref())
+                .unwrap_or("1");
+            parts.push(format_number(value, parent_format));
+        }
+        parts.push(format_number(counters[level as usize - 1], format));
+        format!(
+            "{}{}{}",
+            style.num_prefix.as_deref().unwrap_or_default(),
+            parts.join("."),
+            style.num_suffix.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+fn prefix_headings(blocks: &mut [Block], numbers: &mut impl Iterator<Item = String>) {
+    for block in blocks {
+        match block {
+            Block::Heading { content, .. } => {
+                let number = numbers.next().unwrap_or_default();
+                if number.is_empty() {
+                    continue;
+                }
+                let at = content
+                    .iter()
+                    .take_while(|i| {
+                        matches!(i, Inline::Bookmark { .. } | Inline::BookmarkStart { .. })
+                    })
+                    .count();
+                content.insert(
+                    at,
+                    Inline::Text {
+                        text: format!("{number} "),
+                        style_name: None,
+                        marks: vec![],
+                    },
+                );
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => prefix_headings(content, numbers),
+            Block::Paragraph { .. }
+            | Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+/// Formats `n` in an ODF number format: `"1"`, `"a"`, `"A"`, `"i"` or
+/// `"I"`. Other formats are shown as decimal numbers.
+///
+/// ```
+/// use common_core::outline::format_number;
+///
+/// assert_eq!(format_number(28, "a"), "ab");
+/// assert_eq!(format_number(14, "I"), "XIV");
+/// ```
+pub fn format_number(n: u32, format: &str) -> String {
+    match format {
+        "a" => alphabetic(n),
+        "A" => alphabetic(n).to_uppercase(),
+        "i" => roman(n).to_lowercase(),
+        "I" => roman(n),
+        _ => n.to_string(),
+    }
+}
+
+/// `a`, …, `z`, `aa`, `ab`, …; zero has no letters and is shown as `0`.
+fn alphabetic(mut n: u32) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+    let mut letters = Vec::new();
+    while n > 0 {
+        n -= 1;
+        letters.push(char::from(b'a' + (n % 26) as u8));
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Upper-case roman numerals; numbers outside 1–3999 are shown as decimal.
+fn roman(mut n: u32) -> String {
+    if n == 0 || n >= 4000 {
+        return n.to_string();
+    }
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading(level: u32) -> Block {
+        Block::Heading {
+            level,
+            style_name: None,
+            attrs: None,
+            content: vec![],
+        }
+    }
+
+    fn numbered(level: u32, format: &str, display_levels: u32) -> OutlineLevel {
+        OutlineLevel {
+            level,
+            num_format: Some(format.to_string()),
+            display_levels,
+            ..OutlineLevel::default()
+        }
+    }
+
+    #[test]
+    fn format_number_covers_odf_formats() {
+        assert_eq!(format_number(3, "1"), "3");
+        assert_eq!(format_number(1, "a"), "a");
+        assert_eq!(format_number(26, "A"), "Z");
+        assert_eq!(format_number(27, "a"), "aa");
+        assert_eq!(format_number(1994, "I"), "MCMXCIV");
+        assert_eq!(format_number(4, "i"), "iv");
+    }
+
+    #[test]
+    fn numbers_reset_below_a_higher_heading() {
+        let outline = OutlineStyle {
+            levels: vec![
+                numbered(1, "1", 1),
+                numbered(2, "1", 2),
+                numbered(3, "1", 3),
+            ],
+            ..OutlineStyle::default()
+        };
+        let blocks = vec![
+            heading(1),
+            heading(2),
+            heading(3),
+            heading(3),
+            heading(1),
+            Block::Section {
+                name: "s".to_string(),
+                attrs: None,
+                protected: false,
+                content: vec![heading(2), heading(2), heading(3)],
+            },
+        ];
+        assert_eq!(
+            outline.heading_numbers(&blocks),
+            vec!["1", "1.1", "1.1.1", "1.1.2", "2", "2.1", "2.2", "2.2.1"]
+        );
+    }
+
+    #[test]
+    fn unnumbered_levels_and_missing_parents() {
+        let outline = OutlineStyle {
+            levels: vec![
+                OutlineLevel {
+                    level: 1,
+                    ..OutlineLevel::default()
+                },
+                OutlineLevel {
+                    num_prefix: Some("§".to_string()),
+                    num_suffix: Some(".".to_string()),
+                    start: Some(5),
+                    ..numbered(2, "I", 2)
+                },
+            ],
+            ..OutlineStyle::default()
+        };
+        let blocks = vec![heading(2), heading(1), heading(2), heading(3)];
+        assert_eq!(
+            outline.heading_numbers(&blocks),
+            vec!["§1.V.", "", "§1.V.", ""]
+        );
+    }
+
+    #[test]
+    fn number_headings_goes_after_leading_bookmarks() {
+        let outline = OutlineStyle {
+            levels: vec![numbered(1, "1", 1)],
+            ..OutlineStyle::default()
+        };
+        let mut blocks = vec![Block::Heading {
+            level: 1,
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::Bookmark {
+                    name: "b".to_string(),
+                },
+                Inline::Text {
+                    text: "Intro".to_string(),
+                    style_name: None,
+                    marks: vec![],
+                },
+            ],
+        }];
+        outline.number_headings(&mut blocks);
+        let Block::Heading { content, .. } = &blocks[0] else {
+            panic!("expected heading");
+        };
+        assert_eq!(
+            content[1],
+            Inline::Text {
+                text: "1 ".to_string(),
+                style_name: None,
+                marks: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn outline_style_serde_roundtrip() {
+        let outline = OutlineStyle {
+            levels: vec![numbered(1, "A", 1)],
+            ..OutlineStyle::default()
+        };
+        let json = serde_json::to_string(&outline).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"Outline","levels":[{"level":1,"numFormat":"A","displayLevels":1}]}"#
+        );
+        let decoded: OutlineStyle = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, outline);
+    }
+}
@@ -0,0 +1,346 @@
+//! Page layouts and master pages.
+//!
+//! A [`PageLayout`] gives the size, margins and orientation of a page
+//! (`style:page-layout`); a [`MasterPage`] names the layout a run of pages
+//! uses (`style:master-page`) and the [`HeaderFooter`] content repeated on
+//! those pages. Paragraph styles start a new master page through
+//! [`StyleDefinition::master_page_name`].
+//!
+//! Lengths are in points.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::page::{MasterPage, PageLayout, PageSetup};
+//!
+//! let setup = PageSetup {
+//!     layouts: vec![PageLayout {
+//!         name: "Letter".to_string(),
+//!         width: 612.0,
+//!         height: 792.0,
+//!         ..PageLayout::default()
+//!     }],
+//!     master_pages: vec![MasterPage {
+//!         name: "Standard".to_string(),
+//!         page_layout: "Letter".to_string(),
+//!         ..MasterPage::default()
+//!     }],
+//! };
+//! assert_eq!(setup.layout_for(None).width, 612.0);
+//! ```
+//!
+//! [`StyleDefinition::master_page_name`]: crate::StyleDefinition::master_page_name
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+
+/// The name of the master page used when no style asks for another.
+pub const DEFAULT_MASTER_PAGE: &str = "Standard";
+
+/// Whether a page is printed upright or on its side.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PageOrientation {
+    /// Taller than wide (`style:print-orientation="portrait"`).
+    #[default]
+    Portrait,
+    /// Wider than tall (`style:print-orientation="landscape"`).
+    Landscape,
+}
+
+impl PageOrientation {
+    /// Returns the ODF `style:print-orientation` attribute value.
+    #[must_use]
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            PageOrientation::Portrait => "portrait",
+            PageOrientation::Landscape => "landscape",
+        }
+    }
+}
+
+/// The size, margins and orientation of a page.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PageLayout {
+    /// The layout name (`style:name` of the `style:page-layout`).
+    pub name: String,
+    /// The page width in points.
+    pub width: f64,
+    /// The page height in points.
+    pub height: f64,
+    /// The top margin in points.
+    pub margin_top: f64,
+    /// The bottom margin in points.
+    pub margin_bottom: f64,
+    /// The left margin in points.
+    pub margin_left: f64,
+    /// The right margin in points.
+    pub margin_right: f64,
+    /// The print orientation.
+    #[serde(default)]
+    pub orientation: PageOrientation,
+}
+
+impl Default for PageLayout {
+    /// An A4 portrait page with one-inch margins.
+    fn default() -> Self {
+        Self {
+            name: "pm1".to_string(),
+            width: 595.0,
+            height: 842.0,
+            margin_top: 72.0,
+            margin_bottom: 72.0,
+            margin_left: 72.0,
+            margin_right: 72.0,
+            orientation: PageOrientation::Portrait,
+        }
+    }
+}
+
+impl PageLayout {
+    /// Returns the width between the left and right margins.
+    #[must_use]
+    pub fn content_width(&self) -> f64 {
+        self.width - self.margin_left - self.margin_right
+    }
+}
+
+/// A named page style that pages of the document are laid out with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MasterPage {
+    /// The master page name (`style:name`).
+    pub name: String,
+    /// A human-readable name shown in the user interface.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// The name of the [`PageLayout`] the pages use.
+    pub page_layout: String,
+    /// The master page of the page that follows, when it differs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    /// The running header (`style:header`), if the pages have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<HeaderFooter>,
+    /// The running footer (`style:footer`), if the pages have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer: Option<HeaderFooter>,
+}
+
+/// The content of a running header or footer.
+///
+/// Left pages and the first page of a master page run can have their own
+/// content (`style:header-left`, `style:header-first`); other pages show
+/// [`HeaderFooter::content`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderFooter {
+    /// The content of every page, or of right pages when `left` is set.
+    pub content: Vec<Block>,
+    /// The content of left (even) pages, when it differs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<Vec<Block>>,
+    /// The content of the first page, when it differs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first: Option<Vec<Block>>,
+}
+
+impl HeaderFooter {
+    /// Returns the content shown on a page.
+    ///
+    /// `first` is whether the page starts its master page run, `page_number`
+    /// the number of the page in the document; even pages are left pages.
+    #[must_use]
+    pub fn content_for(&self, first: bool, page_number: usize) -> &[Block] {
+        if first {
+            if let Some(content) = &self.first {
+                return content;
+            }
+        }
+        match &self.left {
+            Some(content) if page_number.is_multiple_of(2) => content,
+            _ => &self.content,
+        }
+    }
+
+    /// Returns the content of every variant.
+    pub fn variants(&self) -> impl Iterator<Item = &Vec<Block>> {
+        std::iter::once(&self.content)
+            .chain(self.left.as_ref())
+            .chain(self.first.as_ref())
+    }
+
+    /// Returns the content of every variant, for passes over all of it.
+    pub fn variants_mut(&mut self) -> impl Iterator<Item = &mut Vec<Block>> {
+        std::iter::once(&mut self.content)
+            .chain(self.left.as_mut())
+            .chain(self.first.as_mut())
+    }
+}
+
+/// The page layouts and master pages of a document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSetup {
+    /// The page layouts, in document order.
+    pub layouts: Vec<PageLayout>,
+    /// The master pages, in document order.
+    pub master_pages: Vec<MasterPage>,
+}
+
+impl PageSetup {
+    /// Returns the master page with the given name.
+    #[must_use]
+    pub fn master_page(&self, name: &str) -> Option<&MasterPage> {
+        self.master_pages.iter().find(|m| m.name == name)
+    }
+
+    /// Returns the master page the document starts with: `Standard`, or the
+    /// first one when there is no `Standard`.
+    #[must_use]
+    pub fn default_master_page(&self) -> Option<&MasterPage> {
+        self.master_page(DEFAULT_MASTER_PAGE)
+            .or_else(|| self.master_pages.first())
+    }
+
+    /// Returns the layout of the named master page, or of the default master
+    /// page when `master` is `None` or unknown.
+    ///
+    /// Falls back to the first layout, then to [`PageLayout::default`].
+    #[must_use]
+    pub fn layout_for(&self, master: Option<&str>) -> PageLayout {
+        master
+            .and_then(|name| self.master_page(name))
+            .or_else(|| self.default_master_page())
+            .and_then(|m| self.layouts.iter().find(|l| l.name == m.page_layout))
+            .or_else(|| self.layouts.first())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the content of every header and footer variant.
+    pub fn header_footer_blocks(&self) -> impl Iterator<Item = &Vec<Block>> {
+        self.master_pages
+            .iter()
+            .flat_map(|m| m.header.iter().chain(m.footer.iter()))
+            .flat_map(HeaderFooter::variants)
+    }
+
+    /// Returns the content of every header and footer variant, for passes
+    /// that change it.
+    pub fn header_footer_blocks_mut(&mut self) -> impl Iterator<Item = &mut Vec<Block>> {
+        self.master_pages
+            .iter_mut()
+            .flat_map(|m| m.header.iter_mut().chain(m.footer.iter_mut()))
+            .flat_map(HeaderFooter::variants_mut)
+    }
+
+    /// Returns the master page of the page after one laid out with `master`.
+    #[must_use]
+    pub fn next_master<'a>(&'a self, master: &'a str) -> &'a str {
+        self.master_page(master)
+            .and_then(|m| m.next.as_deref())
+            .unwrap_or(master)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letter_and_a5() -> PageSetup {
+        PageSetup {
+            layouts: vec![
+                PageLayout {
+                    name: "pm1".to_string(),
+                    width: 612.0,
+                    height: 792.0,
+                    ..PageLayout::default()
+                },
+                PageLayout {
+                    name: "pm2".to_string(),
+                    width: 419.5,
+                    height: 595.3,
+                    margin_left: 54.0,
+                    ..PageLayout::default()
+                },
+            ],
+            master_pages: vec![
+                MasterPage {
+                    name: "A5".to_string(),
+                    page_layout: "pm2".to_string(),
+                    next: Some("Standard".to_string()),
+                    ..MasterPage::default()
+                },
+                MasterPage {
+                    name: "Standard".to_string(),
+                    page_layout: "pm1".to_string(),
+                    ..MasterPage::default()
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn layout_is_looked_up_through_the_master_page() {
+        let setup = letter_and_a5();
+        assert_eq!(setup.layout_for(None).width, 612.0);
+        assert_eq!(setup.layout_for(Some("A5")).name, "pm2");
+        assert_eq!(setup.layout_for(Some("A5")).content_width(), 419.5 - 126.0);
+        // Unknown master pages use the default one.
+        assert_eq!(setup.layout_for(Some("Missing")).name, "pm1");
+    }
+
+    #[test]
+    fn empty_setup_falls_back_to_a4() {
+        let layout = PageSetup::default().layout_for(Some("Standard"));
+        assert_eq!(layout, PageLayout::default());
+        assert_eq!(layout.content_width(), 451.0);
+    }
+
+    #[test]
+    fn next_master_follows_the_next_style() {
+        let setup = letter_and_a5();
+        assert_eq!(setup.next_master("A5"), "Standard");
+        assert_eq!(setup.next_master("Standard"), "Standard");
+    }
+
+    #[test]
+    fn header_variants_are_chosen_by_page() {
+        let text = |t: &str| {
+            vec![Block::Paragraph {
+                style_name: None,
+                attrs: None,
+                content: vec![crate::Inline::Text {
+                    text: t.to_string(),
+                    style_name: None,
+                    marks: vec![],
+                }],
+            }]
+        };
+        let mut header = HeaderFooter {
+            content: text("right"),
+            left: Some(text("left")),
+            first: Some(text("first")),
+        };
+        assert_eq!(header.content_for(true, 2), text("first"));
+        assert_eq!(header.content_for(false, 2), text("left"));
+        assert_eq!(header.content_for(false, 3), text("right"));
+        assert_eq!(header.variants().count(), 3);
+        assert_eq!(header.variants_mut().count(), 3);
+
+        header.left = None;
+        header.first = None;
+        assert_eq!(header.content_for(true, 2), text("right"));
+    }
+
+    #[test]
+    fn page_setup_serializes_in_camel_case() {
+        let json = serde_json::to_value(letter_and_a5()).unwrap();
+        assert_eq!(json["masterPages"][0]["pageLayout"], "pm2");
+        assert_eq!(json["layouts"][1]["marginLeft"], 54.0);
+        assert_eq!(json["layouts"][0]["orientation"], "portrait");
+    }
+}
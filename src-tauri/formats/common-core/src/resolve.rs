@@ -0,0 +1,273 @@
+//! Effective style resolution.
+//!
+//! A style inherits the paragraph and text properties of its parent, up to
+//! the `style:default-style` of its family, and direct formatting on a block
+//! applies on top. [`StyleResolver`] walks that chain in one place so that
+//! every exporter sees the same effective properties.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::resolve::StyleResolver;
+//! use common_core::StyleFamily;
+//! use std::collections::HashMap;
+//!
+//! let styles = HashMap::new();
+//! let effective = StyleResolver::new(&styles).resolve(Some("Missing"), StyleFamily::Paragraph);
+//! assert!(effective.attributes.is_empty());
+//! ```
+
+use std::collections::HashMap;
+
+use crate::block::BlockAttrs;
+use crate::style::{StyleDefinition, StyleFamily};
+use crate::units::length_to_pt;
+
+/// The effective paragraph and text properties of a style.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EffectiveStyle {
+    /// Properties as `"prefix:name"` → `"value"`, with inherited values
+    /// overridden by those closer to the style and by direct formatting.
+    ///
+    /// Relative `fo:font-size` values are resolved against the inherited
+    /// size when one is known.
+    pub attributes: HashMap<String, String>,
+    /// The outline level of the nearest style in the chain that has one.
+    pub outline_level: Option<u32>,
+    /// The names of the styles applied, from the style itself to the
+    /// default style.
+    pub chain: Vec<String>,
+}
+
+impl EffectiveStyle {
+    /// Returns the effective value of a property.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+}
+
+/// Resolves styles through their inheritance chain.
+#[derive(Debug, Clone, Copy)]
+pub struct StyleResolver<'a> {
+    styles: &'a HashMap<String, StyleDefinition>,
+}
+
+impl<'a> StyleResolver<'a> {
+    /// Creates a resolver over a document's style definitions.
+    #[must_use]
+    pub fn new(styles: &'a HashMap<String, StyleDefinition>) -> Self {
+        Self { styles }
+    }
+
+    /// Returns the style named `name` followed by its ancestors and the
+    /// default style of its family.
+    ///
+    /// A missing parent ends the chain. A style without a parent inherits
+    /// from the default style even when it does not name it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error naming the first style met twice when the
+    /// parents form a cycle.
+    pub fn chain(&self, name: &str) -> Result<Vec<&'a StyleDefinition>, String> {
+        match self.walk(name) {
+            (_, Some(cycle)) => Err(format!("Style inheritance cycle at '{cycle}'")),
+            (chain, None) => Ok(chain),
+        }
+    }
+
+    /// Computes the effective properties of the style `name`.
+    ///
+    /// When `name` is `None` or unknown, only the default style of `family`
+    /// applies. A cycle in the parents does not fail: the chain is cut where
+    /// it starts to repeat.
+    #[must_use]
+    pub fn resolve(&self, name: Option<&str>, family: StyleFamily) -> EffectiveStyle {
+        self.resolve_with(name, family, None)
+    }
+
+    /// Computes the effective properties of the style `name` with the
+    /// direct formatting of a block applied on top.
+    #[must_use]
+    pub fn resolve_with(
+        &self,
+        name: Option<&str>,
+        family: StyleFamily,
+        direct: Option<&BlockAttrs>,
+    ) -> EffectiveStyle {
+        let mut chain = match name {
+            Some(name) if self.styles.contains_key(name) => self.walk(name).0,
+            _ => self
+                .styles
+                .get(&family.default_style_name())
+                .into_iter()
+                .collect(),
+        };
+        // Apply from the root down to the style itself.
+        chain.reverse();
+
+        let mut effective = EffectiveStyle::default();
+        for style in &chain {
+            merge(&mut effective.attributes, &style.attributes);
+            if let Some(transform) = &style.text_transform {
+                effective
+                    .attributes
+                    .insert("fo:text-transform".to_string(), transform.clone());
+            }
+            effective.outline_level = style.outline_level.or(effective.outline_level);
+        }
+        if let Some(direct) = direct {
+            merge(&mut effective.attributes, &direct.to_odf_attributes());
+        }
+        effective.chain = chain.iter().rev().map(|s| s.name.clone()).collect();
+        effective
+    }
+
+    /// Walks the parents of `name`, returning the chain and the name at
+    /// which a cycle was detected, if any.
+    fn walk(&self, name: &str) -> (Vec<&'a StyleDefinition>, Option<String>) {
+        let mut chain: Vec<&'a StyleDefinition> = Vec::new();
+        let mut next = self.styles.get(name);
+        while let Some(style) = next {
+            if chain.iter().any(|s| s.name == style.name) {
+                return (chain, Some(style.name.clone()));
+            }
+            chain.push(style);
+            next = match &style.parent {
+                Some(parent) => self.styles.get(parent),
+                None if style.is_default_style() => None,
+                None => self.styles.get(&style.family.default_style_name()),
+            };
+        }
+        (chain, None)
+    }
+}
+
+/// Copies `props` over `into`, resolving a percentage `fo:font-size` against
+/// the size already in `into`.
+fn merge(into: &mut HashMap<String, String>, props: &HashMap<String, String>) {
+    for (key, value) in props {
+        let value = match (key.as_str(), value.trim().strip_suffix('%')) {
+            ("fo:font-size", Some(percent)) => into
+                .get(key)
+                .and_then(|base| length_to_pt(base))
+                .zip(percent.parse::<f64>().ok())
+                .map(|(base, percent)| format_pt(base * percent / 100.0))
+                .unwrap_or_else(|| value.clone()),
+            _ => value.clone(),
+        };
+        into.insert(key.clone(), value);
+    }
+}
+
+/// Formats points without trailing zeros, e.g. `"15.6pt"`.
+fn format_pt(pt: f64) -> String {
+    let rounded = (pt * 100.0).round() / 100.0;
+    format!("{rounded}pt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(name: &str, parent: Option<&str>, attrs: &[(&str, &str)]) -> StyleDefinition {
+        StyleDefinition {
+            name: name.to_string(),
+            family: StyleFamily::Paragraph,
+            parent: parent.map(str::to_string),
+            next: None,
+            display_name: None,
+            attributes: attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            text_transform: None,
+            outline_level: None,
+            autocomplete: None,
+            master_page_name: None,
+            #[cfg(feature = "colour-management")]
+            font_colour: None,
+            #[cfg(feature = "colour-management")]
+            background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
+        }
+    }
+
+    fn styles(list: Vec<StyleDefinition>) -> HashMap<String, StyleDefinition> {
+        list.into_iter().map(|s| (s.name.clone(), s)).collect()
+    }
+
+    #[test]
+    fn properties_inherit_from_parents_and_the_default_style() {
+        let styles = styles(vec![
+            style(
+                "_Default_Paragraph",
+                None,
+                &[("fo:font-size", "12pt"), ("fo:text-align", "start")],
+            ),
+            style("Heading", None, &[("fo:font-weight", "bold")]),
+            style(
+                "Heading 1",
+                Some("Heading"),
+                &[("fo:font-size", "130%"), ("fo:text-align", "center")],
+            ),
+        ]);
+        let effective =
+            StyleResolver::new(&styles).resolve(Some("Heading 1"), StyleFamily::Paragraph);
+        assert_eq!(effective.get("fo:font-size"), Some("15.6pt"));
+        assert_eq!(effective.get("fo:font-weight"), Some("bold"));
+        assert_eq!(effective.get("fo:text-align"), Some("center"));
+        assert_eq!(
+            effective.chain,
+            vec!["Heading 1", "Heading", "_Default_Paragraph"]
+        );
+    }
+
+    #[test]
+    fn direct_formatting_applies_last() {
+        let styles = styles(vec![style("Body", None, &[("fo:text-align", "justify")])]);
+        let direct = BlockAttrs {
+            text_align: Some("end".to_string()),
+            indent: Some(1),
+            ..BlockAttrs::default()
+        };
+        let effective = StyleResolver::new(&styles).resolve_with(
+            Some("Body"),
+            StyleFamily::Paragraph,
+            Some(&direct),
+        );
+        assert_eq!(effective.get("fo:text-align"), Some("end"));
+        assert_eq!(effective.get("fo:margin-left"), Some("1.270cm"));
+    }
+
+    #[test]
+    fn cycles_are_reported_and_cut() {
+        let styles = styles(vec![
+            style("A", Some("B"), &[("fo:font-size", "10pt")]),
+            style("B", Some("A"), &[("fo:font-size", "20pt")]),
+        ]);
+        let resolver = StyleResolver::new(&styles);
+        assert_eq!(
+            resolver.chain("A").unwrap_err(),
+            "Style inheritance cycle at 'A'"
+        );
+        let effective = resolver.resolve(Some("A"), StyleFamily::Paragraph);
+        assert_eq!(effective.get("fo:font-size"), Some("10pt"));
+        assert_eq!(effective.chain, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn unknown_styles_fall_back_to_the_family_default() {
+        let styles = styles(vec![style(
+            "_Default_Paragraph",
+            None,
+            &[("fo:font-size", "11pt")],
+        )]);
+        let effective = StyleResolver::new(&styles).resolve(None, StyleFamily::Paragraph);
+        assert_eq!(effective.get("fo:font-size"), Some("11pt"));
+        let text = StyleResolver::new(&styles).resolve(None, StyleFamily::Text);
+        assert!(text.attributes.is_empty());
+    }
+}
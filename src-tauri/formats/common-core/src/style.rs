@@ -19,10 +19,13 @@
 //!     text_transform: None,
 //!     outline_level: Some(1),
 //!     autocomplete: Some(false),
+//!     master_page_name: None,
 //!     #[cfg(feature = "colour-management")]
 //!     font_colour: None,
 //!     #[cfg(feature = "colour-management")]
 //!     background_colour: None,
+//!     properties: HashMap::new(),
+//!     list_levels: Vec::new(),
 //! };
 //! ```
 
@@ -35,45 +38,223 @@ use crate::colour_management::Colour;
 
 /// The family (scope) of a style definition.
 ///
-/// Determines whether the style applies to paragraphs or character spans.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Determines which kind of content the style applies to. Page layouts are
+/// not style definitions; they are modelled by [`crate::PageSetup`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum StyleFamily {
     /// A paragraph style (`style:family="paragraph"`).
     Paragraph,
     /// A character/text style (`style:family="text"`).
     Text,
+    /// A section style (`style:family="section"`).
+    Section,
+    /// A table style (`style:family="table"`).
+    Table,
+    /// A table column style (`style:family="table-column"`).
+    TableColumn,
+    /// A table row style (`style:family="table-row"`).
+    TableRow,
+    /// A table cell style (`style:family="table-cell"`).
+    TableCell,
+    /// A frame or image style (`style:family="graphic"`).
+    Graphic,
+    /// A list style, stored as `text:list-style` rather than `style:style`.
+    List,
 }
 
 impl StyleFamily {
+    /// Every family, in the order their styles are written.
+    pub const ALL: [StyleFamily; 9] = [
+        StyleFamily::Paragraph,
+        StyleFamily::Text,
+        StyleFamily::Section,
+        StyleFamily::Table,
+        StyleFamily::TableColumn,
+        StyleFamily::TableRow,
+        StyleFamily::TableCell,
+        StyleFamily::Graphic,
+        StyleFamily::List,
+    ];
+
     /// Returns the ODF `style:family` attribute value string.
     ///
+    /// List styles have no `style:family` attribute; `"list"` is returned
+    /// for them so that every family has a name.
+    ///
     /// # Examples
     ///
     /// ```
     /// use common_core::style::StyleFamily;
     /// assert_eq!(StyleFamily::Paragraph.to_odf_str(), "paragraph");
     /// assert_eq!(StyleFamily::Text.to_odf_str(), "text");
+    /// assert_eq!(StyleFamily::TableCell.to_odf_str(), "table-cell");
     /// ```
     #[must_use]
     pub fn to_odf_str(&self) -> &'static str {
         match self {
             StyleFamily::Paragraph => "paragraph",
             StyleFamily::Text => "text",
+            StyleFamily::Section => "section",
+            StyleFamily::Table => "table",
+            StyleFamily::TableColumn => "table-column",
+            StyleFamily::TableRow => "table-row",
+            StyleFamily::TableCell => "table-cell",
+            StyleFamily::Graphic => "graphic",
+            StyleFamily::List => "list",
+        }
+    }
+
+    /// Parses a `style:family` attribute value. Returns `None` for families
+    /// that do not occur in text documents (e.g. `chart`, `drawing-page`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common_core::style::StyleFamily;
+    /// assert_eq!(StyleFamily::from_odf_str("graphic"), Some(StyleFamily::Graphic));
+    /// assert_eq!(StyleFamily::from_odf_str("chart"), None);
+    /// ```
+    #[must_use]
+    pub fn from_odf_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.to_odf_str() == s)
+    }
+
+    /// Returns the name under which the family's `style:default-style` is
+    /// stored, e.g. `"_Default_Paragraph"` or `"_Default_TableRow"`.
+    #[must_use]
+    pub fn default_style_name(&self) -> String {
+        format!("{DEFAULT_STYLE_PREFIX}{self:?}")
+    }
+}
+
+/// Name prefix of the definitions that hold `style:default-style` elements.
+pub const DEFAULT_STYLE_PREFIX: &str = "_Default_";
+
+/// A group of formatting properties, written as one `style:*-properties`
+/// element.
+///
+/// Paragraph and text properties are kept in [`StyleDefinition::attributes`]
+/// for the editor; the other groups are kept per group in
+/// [`StyleDefinition::properties`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum PropertyGroup {
+    /// `style:section-properties`.
+    Section,
+    /// `style:table-properties`.
+    Table,
+    /// `style:table-column-properties`.
+    TableColumn,
+    /// `style:table-row-properties`.
+    TableRow,
+    /// `style:table-cell-properties`.
+    TableCell,
+    /// `style:graphic-properties`.
+    Graphic,
+}
+
+impl PropertyGroup {
+    /// Every group, in the order they are written inside a style.
+    pub const ALL: [PropertyGroup; 6] = [
+        PropertyGroup::Section,
+        PropertyGroup::Table,
+        PropertyGroup::TableColumn,
+        PropertyGroup::TableRow,
+        PropertyGroup::TableCell,
+        PropertyGroup::Graphic,
+    ];
+
+    /// Returns the qualified name of the properties element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common_core::style::PropertyGroup;
+    /// assert_eq!(PropertyGroup::TableCell.element_name(), "style:table-cell-properties");
+    /// ```
+    #[must_use]
+    pub fn element_name(&self) -> &'static str {
+        match self {
+            PropertyGroup::Section => "style:section-properties",
+            PropertyGroup::Table => "style:table-properties",
+            PropertyGroup::TableColumn => "style:table-column-properties",
+            PropertyGroup::TableRow => "style:table-row-properties",
+            PropertyGroup::TableCell => "style:table-cell-properties",
+            PropertyGroup::Graphic => "style:graphic-properties",
         }
     }
+
+    /// Finds the group of a properties element by its local name, e.g.
+    /// `"graphic-properties"`.
+    #[must_use]
+    pub fn from_local_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|g| g.element_name().strip_prefix("style:") == Some(name))
+    }
+}
+
+/// The kind of numbering of one list level.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ListLevelKind {
+    /// `text:list-level-style-number`.
+    #[default]
+    Number,
+    /// `text:list-level-style-bullet`.
+    Bullet,
+    /// `text:list-level-style-image`.
+    Image,
+}
+
+impl ListLevelKind {
+    /// Returns the qualified name of the level element.
+    #[must_use]
+    pub fn element_name(&self) -> &'static str {
+        match self {
+            ListLevelKind::Number => "text:list-level-style-number",
+            ListLevelKind::Bullet => "text:list-level-style-bullet",
+            ListLevelKind::Image => "text:list-level-style-image",
+        }
+    }
+}
+
+/// One level of a named list style (`text:list-level-style-*`).
+///
+/// All maps use prefixed attribute names, like
+/// [`StyleDefinition::attributes`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLevelStyle {
+    /// The level, from 1.
+    pub level: u32,
+    /// Whether the level is numbered, bulleted or uses an image.
+    pub kind: ListLevelKind,
+    /// Attributes of the level element other than `text:level`, e.g.
+    /// `"style:num-format"` or `"text:bullet-char"`.
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+    /// Attributes of `style:list-level-properties`.
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+    /// Attributes of `style:list-level-label-alignment`.
+    #[serde(default)]
+    pub label_alignment: HashMap<String, String>,
+    /// Attributes of the level's `style:text-properties`.
+    #[serde(default)]
+    pub text_properties: HashMap<String, String>,
 }
 
 /// A named style definition from an ODT document.
 ///
 /// Stores all ODF paragraph and text properties as a flat key-value map
 /// using prefixed attribute names (e.g. `"fo:font-size"`, `"style:font-name"`).
-/// This enables round-trip preservation of style data.
+/// Other property groups and list levels are kept in typed fields. This
+/// enables round-trip preservation of style data.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct StyleDefinition {
     /// The internal style name (used in `text:style-name` attributes).
     pub name: String,
-    /// Whether this is a paragraph or character style.
+    /// The kind of content the style applies to.
     pub family: StyleFamily,
     /// The parent style name for inheritance.
     pub parent: Option<String>,
@@ -92,6 +273,10 @@ pub struct StyleDefinition {
     /// Loki-specific: whether this style participates in autocomplete.
     #[serde(default)]
     pub autocomplete: Option<bool>,
+    /// The master page that a paragraph with this style starts
+    /// (`style:master-page-name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master_page_name: Option<String>,
     /// The typed font colour, if set. Populated from `fo:color` / `loki:colour`.
     #[cfg(feature = "colour-management")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -100,6 +285,79 @@ pub struct StyleDefinition {
     #[cfg(feature = "colour-management")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub background_colour: Option<Colour>,
+    /// Properties outside the paragraph and text groups, e.g. the
+    /// `style:graphic-properties` of a frame style.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<PropertyGroup, HashMap<String, String>>,
+    /// The levels of a [`StyleFamily::List`] style.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub list_levels: Vec<ListLevelStyle>,
+}
+
+impl StyleDefinition {
+    /// Returns `true` for the definitions that hold a `style:default-style`.
+    #[must_use]
+    pub fn is_default_style(&self) -> bool {
+        is_default_style_name(&self.name)
+    }
+
+    /// Looks up a property of a group, e.g. `"fo:background-color"` of
+    /// [`PropertyGroup::TableCell`].
+    #[must_use]
+    pub fn property(&self, group: PropertyGroup, key: &str) -> Option<&str> {
+        self.properties
+            .get(&group)
+            .and_then(|props| props.get(key))
+            .map(String::as_str)
+    }
+
+    /// Sets a property of a group.
+    pub fn set_property(&mut self, group: PropertyGroup, key: &str, value: &str) {
+        self.properties
+            .entry(group)
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+    }
+}
+
+/// Returns `true` if `name` is the name of a default style definition.
+///
+/// # Examples
+///
+/// ```
+/// use common_core::style::is_default_style_name;
+/// assert!(is_default_style_name("_Default_Paragraph"));
+/// assert!(!is_default_style_name("Standard"));
+/// ```
+#[must_use]
+pub fn is_default_style_name(name: &str) -> bool {
+    name.starts_with(DEFAULT_STYLE_PREFIX)
+}
+
+/// Turns a display name into a style name, the way LibreOffice does: a
+/// character that may not appear in an XML name is written as its code
+/// point in hex between underscores, so `"Body 2"` becomes `"Body_20_2"`.
+///
+/// # Examples
+///
+/// ```
+/// use common_core::style::encode_style_name;
+/// assert_eq!(encode_style_name("Body 2"), "Body_20_2");
+/// assert_eq!(encode_style_name("Standard (Centered)"), "Standard_20__28_Centered_29_");
+/// ```
+#[must_use]
+pub fn encode_style_name(display_name: &str) -> String {
+    let mut name = String::with_capacity(display_name.len());
+    for (i, c) in display_name.chars().enumerate() {
+        let valid =
+            c.is_alphabetic() || c == '_' || (i > 0 && (c.is_numeric() || c == '-' || c == '.'));
+        if valid {
+            name.push(c);
+        } else {
+            name.push_str(&format!("_{:x}_", u32::from(c)));
+        }
+    }
+    name
 }
 
 #[cfg(test)]
@@ -116,6 +374,34 @@ mod tests {
         assert_eq!(StyleFamily::Text.to_odf_str(), "text");
     }
 
+    #[test]
+    fn style_family_odf_str_round_trips() {
+        for family in StyleFamily::ALL {
+            assert_eq!(StyleFamily::from_odf_str(family.to_odf_str()), Some(family));
+        }
+        assert_eq!(StyleFamily::from_odf_str("drawing-page"), None);
+        assert_eq!(
+            StyleFamily::TableRow.default_style_name(),
+            "_Default_TableRow"
+        );
+    }
+
+    #[test]
+    fn property_groups_are_found_by_element_name() {
+        for group in PropertyGroup::ALL {
+            let local = group.element_name().trim_start_matches("style:");
+            assert_eq!(PropertyGroup::from_local_name(local), Some(group));
+        }
+        assert_eq!(PropertyGroup::from_local_name("text-properties"), None);
+    }
+
+    #[test]
+    fn style_names_are_encoded_as_xml_names() {
+        assert_eq!(encode_style_name("Heading 1"), "Heading_20_1");
+        assert_eq!(encode_style_name("1st"), "_31_st");
+        assert_eq!(encode_style_name("Zitat-Überschrift"), "Zitat-Überschrift");
+    }
+
     #[test]
     fn style_definition_serde_roundtrip() {
         let style = StyleDefinition {
@@ -128,16 +414,51 @@ mod tests {
             text_transform: None,
             outline_level: Some(1),
             autocomplete: Some(true),
+            master_page_name: None,
             #[cfg(feature = "colour-management")]
             font_colour: None,
             #[cfg(feature = "colour-management")]
             background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
         };
         let json = serde_json::to_string(&style).unwrap();
+        assert!(!json.contains("listLevels"));
         let decoded: StyleDefinition = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded, style);
     }
 
+    #[test]
+    fn grouped_properties_survive_serde() {
+        let mut style = StyleDefinition {
+            name: "Frame".to_string(),
+            family: StyleFamily::Graphic,
+            parent: None,
+            next: None,
+            display_name: None,
+            attributes: HashMap::new(),
+            text_transform: None,
+            outline_level: None,
+            autocomplete: None,
+            master_page_name: None,
+            #[cfg(feature = "colour-management")]
+            font_colour: None,
+            #[cfg(feature = "colour-management")]
+            background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
+        };
+        style.set_property(PropertyGroup::Graphic, "style:wrap", "parallel");
+        let json = serde_json::to_string(&style).unwrap();
+        let decoded: StyleDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            decoded.property(PropertyGroup::Graphic, "style:wrap"),
+            Some("parallel")
+        );
+        assert_eq!(decoded.property(PropertyGroup::Table, "style:wrap"), None);
+        assert!(!decoded.is_default_style());
+    }
+
     #[test]
     fn style_definition_minimal() {
         let style = StyleDefinition {
@@ -150,10 +471,13 @@ mod tests {
             text_transform: None,
             outline_level: None,
             autocomplete: None,
+            master_page_name: None,
             #[cfg(feature = "colour-management")]
             font_colour: None,
             #[cfg(feature = "colour-management")]
             background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
         };
         assert_eq!(style.name, "Default");
         assert_eq!(style.family, StyleFamily::Text);
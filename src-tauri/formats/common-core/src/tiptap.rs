@@ -33,10 +33,14 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::block::CellAttrs;
+use crate::block::{CellAttrs, ImageFrame, ListAttrs, SectionAttrs};
+use crate::bookmark::CrossReference;
+use crate::field::FieldKind;
+use crate::inline::NoteClass;
 use crate::marks::TiptapMark;
 use crate::metadata::Metadata;
 use crate::style::StyleDefinition;
+use crate::toc::TableOfContents;
 
 /// Shared attribute bag for Tiptap paragraph and heading nodes.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, Eq, Hash)]
@@ -63,6 +67,58 @@ pub struct ImageAttrs {
     pub alt: Option<String>,
     /// Image title tooltip.
     pub title: Option<String>,
+    /// Frame size, anchoring and wrapping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<ImageFrame>,
+    /// Caption text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+/// Footnote/endnote node attributes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteAttrs {
+    /// Optional ODT note identifier.
+    pub id: Option<String>,
+    /// Footnote or endnote.
+    #[serde(default)]
+    pub note_class: NoteClass,
+    /// The citation mark shown in the text.
+    pub citation: String,
+}
+
+/// Text field node attributes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldAttrs {
+    /// What the field shows.
+    pub kind: FieldKind,
+    /// The value last shown.
+    #[serde(default)]
+    pub value: String,
+}
+
+/// Bookmark node attributes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BookmarkAttrs {
+    /// The bookmark name.
+    pub name: String,
+}
+
+/// Section node attributes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionNodeAttrs {
+    /// The section name.
+    #[serde(default)]
+    pub name: String,
+    /// Column count and gap.
+    #[serde(default, flatten)]
+    pub columns: SectionAttrs,
+    /// Whether the section is protected against editing.
+    #[serde(default)]
+    pub protected: bool,
 }
 
 /// A Tiptap/Lexical JSON document node.
@@ -92,13 +148,26 @@ pub enum TiptapNode {
     /// An image node.
     Image { attrs: ImageAttrs },
     /// An unordered list.
-    BulletList { content: Vec<TiptapNode> },
+    BulletList {
+        #[serde(default)]
+        attrs: Option<ListAttrs>,
+        content: Vec<TiptapNode>,
+    },
     /// An ordered list.
-    OrderedList { content: Vec<TiptapNode> },
+    OrderedList {
+        #[serde(default)]
+        attrs: Option<ListAttrs>,
+        content: Vec<TiptapNode>,
+    },
     /// A list item.
     ListItem { content: Vec<TiptapNode> },
     /// A block quote.
     Blockquote { content: Vec<TiptapNode> },
+    /// A document section.
+    Section {
+        attrs: SectionNodeAttrs,
+        content: Vec<TiptapNode>,
+    },
     /// A table.
     Table { content: Vec<TiptapNode> },
     /// A table row.
@@ -117,8 +186,21 @@ pub enum TiptapNode {
     HorizontalRule,
     /// A page break.
     PageBreak,
+    /// A generated table of contents.
+    TableOfContents { attrs: TableOfContents },
     /// A hard line break within a paragraph.
     HardBreak,
+    /// An inline footnote or endnote whose `content` is the note body.
+    Note {
+        attrs: NoteAttrs,
+        content: Vec<TiptapNode>,
+    },
+    /// An inline text field.
+    Field { attrs: FieldAttrs },
+    /// A bookmarked position.
+    Bookmark { attrs: BookmarkAttrs },
+    /// A cross-reference to a bookmark.
+    Reference { attrs: CrossReference },
 }
 
 /// The response payload sent to the frontend when opening a document.
@@ -154,6 +236,8 @@ mod tests {
             src: "photo.jpg".to_string(),
             alt: Some("A photo".to_string()),
             title: None,
+            frame: None,
+            caption: None,
         };
         assert_eq!(attrs.src, "photo.jpg");
         assert_eq!(attrs.alt.as_deref(), Some("A photo"));
@@ -192,6 +276,21 @@ mod tests {
         assert!(matches!(node, TiptapNode::HardBreak));
     }
 
+    #[test]
+    fn note_node_serializes_attrs() {
+        let node = TiptapNode::Note {
+            attrs: NoteAttrs {
+                id: None,
+                note_class: NoteClass::Footnote,
+                citation: "1".to_string(),
+            },
+            content: vec![],
+        };
+        let json = serde_json::to_string(&node).unwrap();
+        assert!(json.contains("\"type\":\"note\""));
+        assert!(json.contains("\"noteClass\":\"footnote\""));
+    }
+
     #[test]
     fn paragraph_node_with_attrs() {
         let node = TiptapNode::Paragraph {
@@ -0,0 +1,344 @@
+//! Tables of contents.
+//!
+//! A [`Block::TableOfContents`] keeps the settings it was generated with
+//! (the deepest outline level and the title) and the entries it last
+//! showed. [`regenerate_toc`] rebuilds the entries from the headings of the
+//! document. Like LibreOffice, it points each entry at a bookmark at the
+//! start of its heading, adding the bookmark when the heading has none, so
+//! exporters can link entries with the same machinery as cross-references.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::toc::{regenerate_toc, TableOfContents};
+//! use common_core::{Block, Inline};
+//!
+//! let mut blocks = vec![
+//!     Block::TableOfContents(TableOfContents::default()),
+//!     Block::Heading {
+//!         level: 1,
+//!         style_name: None,
+//!         attrs: None,
+//!         content: vec![Inline::Text {
+//!             text: "Introduction".to_string(),
+//!             style_name: None,
+//!             marks: vec![],
+//!         }],
+//!     },
+//! ];
+//! regenerate_toc(&mut blocks);
+//! let Block::TableOfContents(toc) = &blocks[0] else { unreachable!() };
+//! assert_eq!(toc.entries[0].text, "Introduction");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::bookmark::bookmark_names;
+use crate::inline::Inline;
+use crate::walk::for_each_block_mut;
+
+/// The prefix of the bookmarks that mark headings listed in a table of
+/// contents. LibreOffice uses the same prefix.
+pub const HEADING_BOOKMARK_PREFIX: &str = "__RefHeading__";
+
+pub(crate) fn default_outline_level() -> u32 {
+    10
+}
+
+/// A generated table of contents (`text:table-of-content`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableOfContents {
+    /// The name of the index (`text:name`).
+    #[serde(default)]
+    pub name: String,
+    /// The title shown above the entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The deepest heading level listed (`text:outline-level`).
+    #[serde(default = "default_outline_level")]
+    pub outline_level: u32,
+    /// The entries as last generated.
+    #[serde(default)]
+    pub entries: Vec<TocEntry>,
+}
+
+impl Default for TableOfContents {
+    fn default() -> Self {
+        TableOfContents {
+            name: "Table of Contents1".to_string(),
+            title: Some("Table of Contents".to_string()),
+            outline_level: default_outline_level(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// One line of a table of contents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TocEntry {
+    /// The outline level of the heading.
+    pub level: u32,
+    /// The text of the heading.
+    pub text: String,
+    /// The name of the bookmark at the heading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// The page number last shown for the heading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+}
+
+/// Rebuilds the entries of every table of contents in `blocks` from the
+/// headings of the document.
+///
+/// Headings at a level listed by some table of contents get a bookmark
+/// named after [`HEADING_BOOKMARK_PREFIX`] unless they start with one.
+/// Entries keep the page number they showed for the same bookmark.
+pub fn regenerate_toc(blocks: &mut [Block]) {
+    let mut max_level = 0;
+    for_each_toc_mut(blocks, &mut |toc| {
+        max_level = max_level.max(toc.outline_level)
+    });
+    if max_level == 0 {
+        return;
+    }
+
+    let mut used: HashSet<String> = bookmark_names(blocks)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let mut next_id = 1;
+    let mut headings = Vec::new();
+    collect_headings(blocks, max_level, &mut used, &mut next_id, &mut headings);
+
+    for_each_toc_mut(blocks, &mut |toc| {
+        let pages: HashMap<String, String> = toc
+            .entries
+            .drain(..)
+            .filter_map(|e| Some((e.target?, e.page?)))
+            .collect();
+        toc.entries = headings
+            .iter()
+            .filter(|h| h.level <= toc.outline_level)
+            .map(|h| TocEntry {
+                page: h.target.as_ref().and_then(|t| pages.get(t).cloned()),
+                ..h.clone()
+            })
+            .collect();
+    });
+}
+
+/// Calls `f` with every table of contents in `blocks`.
+pub fn for_each_toc_mut(blocks: &mut [Block], f: &mut dyn FnMut(&mut TableOfContents)) {
+    for_each_block_mut(blocks, &mut |block| {
+        if let Block::TableOfContents(toc) = block {
+            f(toc);
+        }
+    });
+}
+
+/// Collects the headings up to `max_level` in document order, giving each
+/// a heading bookmark.
+fn collect_headings(
+    blocks: &mut [Block],
+    max_level: u32,
+    used: &mut HashSet<String>,
+    next_id: &mut usize,
+    out: &mut Vec<TocEntry>,
+) {
+    for_each_block_mut(blocks, &mut |block| {
+        let Block::Heading { level, content, .. } = block else {
+            return;
+        };
+        if *level > max_level {
+            return;
+        }
+        let text = heading_text(content);
+        if text.is_empty() {
+            return;
+        }
+        let target = heading_bookmark(content).unwrap_or_else(|| {
+            let name = loop {
+                let name = format!("{HEADING_BOOKMARK_PREFIX}_Toc{next_id}");
+                *next_id += 1;
+                if !used.contains(&name) {
+                    break name;
+                }
+            };
+            used.insert(name.clone());
+            content.insert(0, Inline::Bookmark { name: name.clone() });
+            name
+        });
+        out.push(TocEntry {
+            level: *level,
+            text,
+            target: Some(target),
+            page: None,
+        });
+    });
+}
+
+/// Returns the name of the heading bookmark among the leading bookmarks of
+/// a heading.
+fn heading_bookmark(content: &[Inline]) -> Option<String> {
+    content
+        .iter()
+        .take_while(|i| matches!(i, Inline::Bookmark { .. } | Inline::BookmarkStart { .. }))
+        .find_map(|i| match i {
+            Inline::Bookmark { name } | Inline::BookmarkStart { name }
+                if name.starts_with(HEADING_BOOKMARK_PREFIX) =>
+            {
+                Some(name.clone())
+            }
+            _ => None,
+        })
+}
+
+/// The visible text of a heading with runs of white space collapsed.
+fn heading_text(content: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in content {
+        match inline {
+            Inline::Text { text: t, .. } => text.push_str(t),
+            Inline::Space { .. } | Inline::Tab | Inline::LineBreak => text.push(' '),
+            Inline::Field { value, .. } => text.push_str(value),
+            Inline::Reference(reference) => text.push_str(&reference.value),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading(level: u32, content: Vec<Inline>) -> Block {
+        Block::Heading {
+            level,
+            style_name: None,
+            attrs: None,
+            content,
+        }
+    }
+
+    fn text(s: &str) -> Inline {
+        Inline::Text {
+            text: s.to_string(),
+            style_name: None,
+            marks: vec![],
+        }
+    }
+
+    fn toc(blocks: &[Block]) -> &TableOfContents {
+        match &blocks[0] {
+            Block::TableOfContents(toc) => toc,
+            other => panic!("expected a table of contents, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn toc_serde_roundtrip() {
+        let block = Block::TableOfContents(TableOfContents {
+            entries: vec![TocEntry {
+                level: 1,
+                text: "One".to_string(),
+                target: Some("__RefHeading___Toc1".to_string()),
+                page: Some("3".to_string()),
+            }],
+            ..TableOfContents::default()
+        });
+        let json = serde_json::to_string(&block).unwrap();
+        assert!(json.starts_with(r#"{"type":"tableOfContents","name":"Table of Contents1""#));
+        assert!(json.contains(r#""outlineLevel":10"#));
+        let decoded: Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, block);
+    }
+
+    #[test]
+    fn regenerate_lists_headings_up_to_the_outline_level() {
+        let mut blocks = vec![
+            Block::TableOfContents(TableOfContents {
+                outline_level: 2,
+                ..TableOfContents::default()
+            }),
+            heading(
+                1,
+                vec![text("First"), Inline::Space { count: 2 }, text("part")],
+            ),
+            Block::Section {
+                name: "s".to_string(),
+                attrs: None,
+                protected: false,
+                content: vec![heading(2, vec![text("Nested")])],
+            },
+            heading(3, vec![text("Too deep")]),
+            heading(1, vec![]),
+        ];
+        regenerate_toc(&mut blocks);
+
+        let entries = &toc(&blocks).entries;
+        let texts: Vec<(u32, &str)> = entries.iter().map(|e| (e.level, e.text.as_str())).collect();
+        assert_eq!(texts, vec![(1, "First part"), (2, "Nested")]);
+        assert_eq!(entries[0].target.as_deref(), Some("__RefHeading___Toc1"));
+        let Block::Heading { content, .. } = &blocks[1] else {
+            panic!("expected heading");
+        };
+        assert_eq!(
+            content[0],
+            Inline::Bookmark {
+                name: "__RefHeading___Toc1".to_string()
+            }
+        );
+        let Block::Heading { content, .. } = &blocks[3] else {
+            panic!("expected heading");
+        };
+        assert_eq!(content.len(), 1);
+    }
+
+    #[test]
+    fn regenerate_reuses_heading_bookmarks_and_pages() {
+        let mut blocks = vec![
+            Block::TableOfContents(TableOfContents {
+                entries: vec![TocEntry {
+                    level: 1,
+                    text: "Old title".to_string(),
+                    target: Some("__RefHeading___Toc42_1".to_string()),
+                    page: Some("7".to_string()),
+                }],
+                ..TableOfContents::default()
+            }),
+            heading(
+                1,
+                vec![
+                    Inline::BookmarkStart {
+                        name: "__RefHeading___Toc42_1".to_string(),
+                    },
+                    text("New title"),
+                    Inline::BookmarkEnd {
+                        name: "__RefHeading___Toc42_1".to_string(),
+                    },
+                ],
+            ),
+        ];
+        let before = blocks[1].clone();
+        regenerate_toc(&mut blocks);
+        regenerate_toc(&mut blocks);
+
+        assert_eq!(blocks[1], before);
+        assert_eq!(
+            toc(&blocks).entries,
+            vec![TocEntry {
+                level: 1,
+                text: "New title".to_string(),
+                target: Some("__RefHeading___Toc42_1".to_string()),
+                page: Some("7".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Tracked changes.
+//!
+//! A [`TrackedChange`] records one edit made while change tracking was on.
+//! Insertions and format changes cover a range of text, from an
+//! [`Inline::ChangeStart`](crate::Inline::ChangeStart) to the
+//! [`Inline::ChangeEnd`](crate::Inline::ChangeEnd) with the same id, which
+//! may be in a later block. A deletion is a single
+//! [`Inline::Change`](crate::Inline::Change) at the point where the text was
+//! removed and keeps the removed blocks in [`TrackedChange::content`].
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::{ChangeKind, Inline, TrackedChange};
+//!
+//! let inserted = vec![
+//!     Inline::ChangeStart(TrackedChange {
+//!         id: "ct1".to_string(),
+//!         kind: ChangeKind::Insertion,
+//!         author: Some("Ada".to_string()),
+//!         ..TrackedChange::default()
+//!     }),
+//!     Inline::Text {
+//!         text: "new words".to_string(),
+//!         style_name: None,
+//!         marks: vec![],
+//!     },
+//!     Inline::ChangeEnd { id: "ct1".to_string() },
+//! ];
+//! assert_eq!(inserted.len(), 3);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+
+/// The kind of edit a [`TrackedChange`] records.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    /// Text was inserted (`text:insertion`).
+    #[default]
+    Insertion,
+    /// Text was deleted (`text:deletion`).
+    Deletion,
+    /// Formatting was changed (`text:format-change`).
+    FormatChange,
+}
+
+impl ChangeKind {
+    /// Returns the local name of the ODF element describing this change.
+    pub fn to_odf_str(self) -> &'static str {
+        match self {
+            ChangeKind::Insertion => "insertion",
+            ChangeKind::Deletion => "deletion",
+            ChangeKind::FormatChange => "format-change",
+        }
+    }
+
+    /// Parses the local name of an ODF change element.
+    pub fn from_odf_str(s: &str) -> Option<Self> {
+        match s {
+            "insertion" => Some(ChangeKind::Insertion),
+            "deletion" => Some(ChangeKind::Deletion),
+            "format-change" => Some(ChangeKind::FormatChange),
+            _ => None,
+        }
+    }
+}
+
+/// A tracked change (`text:changed-region` in ODT).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedChange {
+    /// The id linking the change to its markers in the text (`text:id`).
+    pub id: String,
+    /// Whether this is an insertion, a deletion or a format change.
+    #[serde(default)]
+    pub kind: ChangeKind,
+    /// The author of the change (`dc:creator`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// ISO 8601 timestamp of the change (`dc:date`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// The removed content of a deletion; empty for other kinds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Block>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Inline;
+
+    #[test]
+    fn deletion_inline_serde_roundtrip() {
+        let inline = Inline::Change(TrackedChange {
+            id: "ct2".to_string(),
+            kind: ChangeKind::Deletion,
+            date: Some("2024-05-01T10:00:00".to_string()),
+            content: vec![Block::Paragraph {
+                style_name: None,
+                attrs: None,
+                content: vec![Inline::Text {
+                    text: "gone".to_string(),
+                    style_name: None,
+                    marks: vec![],
+                }],
+            }],
+            ..TrackedChange::default()
+        });
+        let json = serde_json::to_string(&inline).unwrap();
+        assert!(json.contains("\"type\":\"change\""));
+        assert!(json.contains("\"kind\":\"deletion\""));
+        let decoded: Inline = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, inline);
+    }
+
+    #[test]
+    fn change_kind_odf_names_round_trip() {
+        for kind in [
+            ChangeKind::Insertion,
+            ChangeKind::Deletion,
+            ChangeKind::FormatChange,
+        ] {
+            assert_eq!(ChangeKind::from_odf_str(kind.to_odf_str()), Some(kind));
+        }
+        assert_eq!(ChangeKind::from_odf_str("p"), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! ODF length units.
+//!
+//! Lengths in ODF attributes carry their unit (`"2.5cm"`, `"12pt"`); the
+//! editor measures widths in CSS pixels at 96 dpi.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::units::{length_to_cm, length_to_pt};
+//!
+//! assert_eq!(length_to_cm("10mm"), Some(1.0));
+//! assert_eq!(length_to_pt("0.5in"), Some(36.0));
+//! ```
+
+/// Pixels per centimetre at 96 dpi, the unit of table column widths in
+/// [`CellAttrs`](crate::CellAttrs).
+pub const PX_PER_CM: f64 = 96.0 / 2.54;
+
+/// An absolute length unit and its size.
+struct Unit {
+    name: &'static str,
+    pt: f64,
+    cm: f64,
+}
+
+/// The length units of ODF, plus CSS pixels.
+const UNITS: [Unit; 6] = [
+    Unit {
+        name: "pt",
+        pt: 1.0,
+        cm: 2.54 / 72.0,
+    },
+    Unit {
+        name: "pc",
+        pt: 12.0,
+        cm: 2.54 / 6.0,
+    },
+    Unit {
+        name: "in",
+        pt: 72.0,
+        cm: 2.54,
+    },
+    Unit {
+        name: "cm",
+        pt: 72.0 / 2.54,
+        cm: 1.0,
+    },
+    Unit {
+        name: "mm",
+        pt: 72.0 / 25.4,
+        cm: 0.1,
+    },
+    Unit {
+        name: "px",
+        pt: 0.75,
+        cm: 2.54 / 96.0,
+    },
+];
+
+/// Splits a length into its number and unit; a bare number is accepted
+/// only for zero.
+fn split_length(value: &str) -> Option<(f64, &str)> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    (!unit.is_empty() || number == 0.0).then_some((number, unit))
+}
+
+/// Converts an ODF length to the unit whose size `size` reads from a
+/// [`Unit`].
+fn convert(value: &str, size: fn(&Unit) -> f64) -> Option<f64> {
+    let (number, unit) = split_length(value)?;
+    if unit.is_empty() {
+        return Some(0.0);
+    }
+    UNITS
+        .iter()
+        .find(|u| u.name == unit)
+        .map(|u| number * size(u))
+}
+
+/// Converts an ODF length (`"2.5cm"`, `"10mm"`, `"0.5in"`, `"12pt"`,
+/// `"16px"`) to centimetres. Returns `None` for unknown units or malformed
+/// numbers.
+#[must_use]
+pub fn length_to_cm(value: &str) -> Option<f64> {
+    convert(value, |u| u.cm)
+}
+
+/// Converts an ODF length to points, like [`length_to_cm`].
+#[must_use]
+pub fn length_to_pt(value: &str) -> Option<f64> {
+    convert(value, |u| u.pt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_to_cm_converts_units() {
+        assert_eq!(length_to_cm("2.54cm"), Some(2.54));
+        assert_eq!(length_to_cm("10mm"), Some(1.0));
+        assert_eq!(length_to_cm("1in"), Some(2.54));
+        assert_eq!(length_to_cm("0"), Some(0.0));
+        assert_eq!(length_to_cm("3em"), None);
+        assert_eq!(length_to_cm("2"), None);
+    }
+
+    #[test]
+    fn length_to_pt_converts_units() {
+        assert_eq!(length_to_pt("12pt"), Some(12.0));
+        assert_eq!(length_to_pt("1in"), Some(72.0));
+        assert_eq!(length_to_pt("16px"), Some(12.0));
+        assert_eq!(length_to_pt("1pc"), Some(12.0));
+        assert!((length_to_pt("1cm").unwrap() - 28.3465).abs() < 0.001);
+        assert_eq!(length_to_pt("0"), Some(0.0));
+        assert_eq!(length_to_pt("120%"), None);
+    }
+}
@@ -0,0 +1,125 @@
+//! Traversal of a block tree.
+//!
+//! The walkers visit every block, or every inline of every paragraph and
+//! heading, in document order, descending into lists, quotations, sections,
+//! tables and the bodies of notes. Comments and deleted text are not
+//! visited.
+
+use crate::block::Block;
+use crate::inline::Inline;
+
+/// Calls `f` with every inline in `blocks`.
+pub fn for_each_inline<'a>(blocks: &'a [Block], f: &mut dyn FnMut(&'a Inline)) {
+    for block in blocks {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                for inline in content {
+                    f(inline);
+                    if let Inline::Note { content, .. } = inline {
+                        for_each_inline(content, f);
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_inline(content, f),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+/// Mutable counterpart of [`for_each_inline`].
+pub fn for_each_inline_mut(blocks: &mut [Block], f: &mut dyn FnMut(&mut Inline)) {
+    for block in blocks {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                for inline in content {
+                    f(inline);
+                    if let Inline::Note { content, .. } = inline {
+                        for_each_inline_mut(content, f);
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_inline_mut(content, f),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+/// Calls `f` with every block in `blocks`, each before its children.
+pub fn for_each_block<'a>(blocks: &'a [Block], f: &mut dyn FnMut(&'a Block)) {
+    for block in blocks {
+        f(block);
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                for inline in content {
+                    if let Inline::Note { content, .. } = inline {
+                        for_each_block(content, f);
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_block(content, f),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+/// Mutable counterpart of [`for_each_block`].
+pub fn for_each_block_mut(blocks: &mut [Block], f: &mut dyn FnMut(&mut Block)) {
+    for block in blocks {
+        f(block);
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                for inline in content {
+                    if let Inline::Note { content, .. } = inline {
+                        for_each_block_mut(content, f);
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_block_mut(content, f),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
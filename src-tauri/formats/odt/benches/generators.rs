@@ -177,8 +177,11 @@ pub fn styled_document(n_paras: usize, n_styles: usize) -> Document {
                 text_transform: None,
                 outline_level: None,
                 autocomplete: None,
+                master_page_name: None,
                 font_colour: None,
                 background_colour: None,
+                properties: Default::default(),
+                list_levels: Vec::new(),
             },
         );
     }
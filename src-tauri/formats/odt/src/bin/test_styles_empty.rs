@@ -16,13 +16,17 @@ fn main() {
             text_transform: None,
             outline_level: None,
             autocomplete: None,
+            master_page_name: None,
             font_colour: None,
             background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
         },
     );
 
     let styles_xml =
-        odt_format::writer::styles_writer::styles_to_xml(&styles, &None, &None, &None).unwrap();
+        odt_format::writer::styles_writer::styles_to_xml(&styles, None, &None, &None, &None)
+            .unwrap();
     println!("STYLES XML:\n{}\n", styles_xml);
 
     let blocks = vec![];
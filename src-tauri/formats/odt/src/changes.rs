@@ -0,0 +1,636 @@
+//! Accepting and rejecting tracked changes.
+//!
+//! Tracked changes live in the block tree as inline markers (see
+//! [`common_core::tracked_change`]). Accepting a change keeps the text as it
+//! currently reads and drops the change's markers. Rejecting a change undoes
+//! the edit: inserted content is removed, joining the paragraphs at either
+//! end of the range, and deleted content is put back where it was removed.
+//! ODF does not record the formatting a format change replaced, so rejecting
+//! one only drops its markers.
+//!
+//! The [`Document`](crate::Document) methods `accept_change`,
+//! `reject_change`, `accept_all_changes` and `reject_all_changes` wrap these
+//! functions.
+
+use common_core::{Block, ChangeKind, Inline, TrackedChange};
+
+/// Returns the tracked changes in `blocks`, in document order.
+///
+/// Changes inside notes and comments are included.
+#[must_use]
+pub fn tracked_changes(blocks: &[Block]) -> Vec<&TrackedChange> {
+    let mut changes = Vec::new();
+    for_each_inlines(blocks, &mut |inlines| {
+        for inline in inlines {
+            if let Inline::ChangeStart(change) | Inline::Change(change) = inline {
+                changes.push(change);
+            }
+        }
+    });
+    changes
+}
+
+/// Accepts the change with the given id.
+///
+/// # Errors
+///
+/// Returns a `String` error if there is no change with that id.
+pub fn accept_change(blocks: &mut [Block], id: &str) -> Result<(), String> {
+    find_kind(blocks, id)?;
+    remove_markers(blocks, id);
+    Ok(())
+}
+
+/// Rejects the change with the given id.
+///
+/// # Errors
+///
+/// Returns a `String` error if there is no change with that id.
+pub fn reject_change(blocks: &mut Vec<Block>, id: &str) -> Result<(), String> {
+    match find_kind(blocks, id)? {
+        ChangeKind::Insertion if has_range_end(blocks, id) => {
+            let mut inside = false;
+            *blocks = remove_range(std::mem::take(blocks), id, &mut inside);
+        }
+        ChangeKind::Deletion if restore_deletion(blocks, id) => {}
+        _ => remove_markers(blocks, id),
+    }
+    Ok(())
+}
+
+/// Accepts every change.
+pub fn accept_all(blocks: &mut [Block]) {
+    for_each_inlines_mut(blocks, &mut |inlines| {
+        inlines.retain(|inline| {
+            !matches!(
+                inline,
+                Inline::ChangeStart(_) | Inline::ChangeEnd { .. } | Inline::Change(_)
+            )
+        });
+    });
+}
+
+/// Rejects every change.
+pub fn reject_all(blocks: &mut Vec<Block>) {
+    let ids: Vec<String> = tracked_changes(blocks)
+        .into_iter()
+        .map(|change| change.id.clone())
+        .collect();
+    for id in ids {
+        // A change inside a rejected insertion has already gone with it.
+        let _ = reject_change(blocks, &id);
+    }
+}
+
+/// Calls `f` with the inline content of every paragraph and heading,
+/// including those inside notes and comments.
+pub(crate) fn for_each_inlines<'a>(blocks: &'a [Block], f: &mut dyn FnMut(&'a [Inline])) {
+    for block in blocks {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                f(content);
+                for inline in content {
+                    match inline {
+                        Inline::Note { content, .. } => for_each_inlines(content, f),
+                        Inline::Comment(comment) => {
+                            for c in comment.thread() {
+                                for_each_inlines(&c.content, f);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_inlines(content, f),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+/// Mutable counterpart of [`for_each_inlines`]; `f` runs before the notes
+/// in the content it was given are visited.
+pub(crate) fn for_each_inlines_mut(blocks: &mut [Block], f: &mut dyn FnMut(&mut Vec<Inline>)) {
+    for block in blocks {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                f(content);
+                for inline in content {
+                    if let Inline::Note { content, .. } = inline {
+                        for_each_inlines_mut(content, f);
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_inlines_mut(content, f),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+fn find_kind(blocks: &[Block], id: &str) -> Result<ChangeKind, String> {
+    tracked_changes(blocks)
+        .into_iter()
+        .find(|change| change.id == id)
+        .map(|change| change.kind)
+        .ok_or_else(|| format!("No tracked change with id '{id}'"))
+}
+
+fn is_marker_of(inline: &Inline, id: &str) -> bool {
+    match inline {
+        Inline::ChangeStart(change) | Inline::Change(change) => change.id == id,
+        Inline::ChangeEnd { id: end } => end == id,
+        _ => false,
+    }
+}
+
+fn remove_markers(blocks: &mut [Block], id: &str) {
+    for_each_inlines_mut(blocks, &mut |inlines| {
+        inlines.retain(|inline| !is_marker_of(inline, id));
+    });
+}
+
+fn has_range_end(blocks: &[Block], id: &str) -> bool {
+    let mut found = false;
+    for_each_inlines(blocks, &mut |inlines| {
+        found |= inlines
+            .iter()
+            .any(|inline| matches!(inline, Inline::ChangeEnd { id: end } if end == id));
+    });
+    found
+}
+
+fn inlines_mut(block: &mut Block) -> Option<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+/// Removes the content between the markers of insertion `id`.
+///
+/// `inside` tracks whether the walk is within the range. Blocks wholly
+/// inside the range are dropped, and the block where the range ends is
+/// joined to the one where it starts when both are in the same block list
+/// (see [`join_blocks`]).
+fn remove_range(blocks: Vec<Block>, id: &str, inside: &mut bool) -> Vec<Block> {
+    let mut out: Vec<Block> = Vec::new();
+    let mut open = None;
+    for mut block in blocks {
+        let was_inside = *inside;
+        match &mut block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                *content = remove_inlines(std::mem::take(content), id, inside);
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => {
+                *content = remove_range(std::mem::take(content), id, inside);
+            }
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+        if was_inside && *inside {
+            continue;
+        }
+        if was_inside {
+            if let Some(i) = open.take() {
+                let left = join_blocks(&mut out[i], block);
+                out.extend(left);
+                continue;
+            }
+        }
+        if *inside {
+            open = Some(out.len());
+        }
+        out.push(block);
+    }
+    out
+}
+
+/// Joins `tail`, where a removed range ends, to `head`, where it starts, and
+/// returns what is left of `tail`.
+///
+/// The paragraph holding the end of the range is appended to the one
+/// holding its start, however deeply either is nested; the lists, items and
+/// other containers it leaves empty go with it. If nothing is left of
+/// `head`, `tail` replaces it.
+fn join_blocks(head: &mut Block, mut tail: Block) -> Option<Block> {
+    if is_blank(head) {
+        *head = tail;
+        return None;
+    }
+    if let (Some(head), Some(rest)) = (inlines_mut(head), inlines_mut(&mut tail)) {
+        head.append(rest);
+        return None;
+    }
+    let same_kind = std::mem::discriminant(head) == std::mem::discriminant(&tail);
+    if let Some(children) = children_mut(head).filter(|_| !same_kind) {
+        // `tail` continues from the end of `head`.
+        return match children.last_mut() {
+            Some(last) => join_blocks(last, tail),
+            None => Some(tail),
+        };
+    }
+    let Some(rest) = children_mut(&mut tail).map(std::mem::take) else {
+        return Some(tail);
+    };
+    let mut rest = rest.into_iter();
+    match children_mut(head) {
+        // The last child of `head` takes the first child of `tail`, and the
+        // other children of `tail` follow it.
+        Some(children) => {
+            if let Some(first) = rest.next() {
+                match children.last_mut() {
+                    Some(last) => {
+                        let left = join_blocks(last, first);
+                        children.extend(left);
+                    }
+                    None => children.push(first),
+                }
+            }
+            children.extend(rest);
+            None
+        }
+        // `head` is a paragraph: it takes the first one inside `tail`.
+        None => {
+            let left = rest.next().and_then(|first| join_blocks(head, first));
+            let rest: Vec<Block> = left.into_iter().chain(rest).collect();
+            if rest.is_empty() {
+                return None;
+            }
+            if let Some(children) = children_mut(&mut tail) {
+                *children = rest;
+            }
+            Some(tail)
+        }
+    }
+}
+
+/// Whether `block` has no content left: an empty paragraph, or a container
+/// holding only blank blocks.
+fn is_blank(block: &Block) -> bool {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => content.is_empty(),
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => content.iter().all(is_blank),
+        Block::Image { .. }
+        | Block::HorizontalRule
+        | Block::PageBreak
+        | Block::TableOfContents(_) => false,
+    }
+}
+
+fn inlines(block: &Block) -> Option<&[Inline]> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+/// The content of the first paragraph in `block`, however deeply nested.
+fn first_inlines_mut(block: &mut Block) -> Option<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => children_mut(block)?.first_mut().and_then(first_inlines_mut),
+    }
+}
+
+/// The content of the last paragraph in `block`, however deeply nested.
+fn last_inlines_mut(block: &mut Block) -> Option<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => children_mut(block)?.last_mut().and_then(last_inlines_mut),
+    }
+}
+
+fn children_mut(block: &mut Block) -> Option<&mut Vec<Block>> {
+    match block {
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+fn remove_inlines(inlines: Vec<Inline>, id: &str, inside: &mut bool) -> Vec<Inline> {
+    let mut kept = Vec::new();
+    for mut inline in inlines {
+        match &mut inline {
+            Inline::ChangeStart(change) if change.id == id => *inside = true,
+            Inline::ChangeEnd { id: end } if end == id => *inside = false,
+            _ if *inside => {}
+            Inline::Note { content, .. } => {
+                *content = remove_range(std::mem::take(content), id, &mut false);
+                kept.push(inline);
+            }
+            _ => kept.push(inline),
+        }
+    }
+    kept
+}
+
+/// Puts the content of deletion `id` back in place of its marker.
+///
+/// Returns `false` if the marker was not found.
+fn restore_deletion(blocks: &mut Vec<Block>, id: &str) -> bool {
+    for i in 0..blocks.len() {
+        if let Some(replacement) = split_at_deletion(&mut blocks[i], id) {
+            blocks.splice(i..=i, replacement);
+            return true;
+        }
+        let found = match &mut blocks[i] {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                content.iter_mut().any(|inline| match inline {
+                    Inline::Note { content, .. } => restore_deletion(content, id),
+                    _ => false,
+                })
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => restore_deletion(content, id),
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => false,
+        };
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+/// Splits `block` at the marker of deletion `id` and returns the blocks
+/// replacing it.
+///
+/// The first removed paragraph is joined to the text before the marker and
+/// the last to the text after it, the way the deletion split them. A marker
+/// at the start of its paragraph has no text before it, so the removed
+/// blocks keep their own styles and come first. When the removed blocks
+/// start or end in a list or table, insertions ending right before the
+/// marker end at the start of its first paragraph, and those starting right
+/// after it start at the end of its last paragraph, rather than in a
+/// paragraph of their own.
+fn split_at_deletion(block: &mut Block, id: &str) -> Option<Vec<Block>> {
+    let content = inlines_mut(block)?;
+    let pos = content
+        .iter()
+        .position(|inline| matches!(inline, Inline::Change(change) if change.id == id))?;
+    let mut after = content.split_off(pos);
+    let Inline::Change(change) = after.remove(0) else {
+        return None;
+    };
+
+    let mut deleted = change.content;
+    let ends_only = content
+        .iter()
+        .all(|inline| matches!(inline, Inline::ChangeEnd { .. }));
+    if ends_only && deleted.first().is_some_and(|b| inlines(b).is_none()) {
+        if let Some(first) = deleted.first_mut().and_then(first_inlines_mut) {
+            first.splice(0..0, content.drain(..));
+        }
+    }
+    let mut out = Vec::new();
+    if !content.is_empty() {
+        let mut head = block.clone();
+        if let Some(first) = deleted.first_mut().and_then(inlines_mut) {
+            let first = std::mem::take(first);
+            inlines_mut(&mut head)?.extend(first);
+            deleted.remove(0);
+        }
+        if deleted.is_empty() {
+            inlines_mut(&mut head)?.append(&mut after);
+            return Some(vec![head]);
+        }
+        out.push(head);
+    } else if deleted.is_empty() {
+        *inlines_mut(block)? = after;
+        return Some(vec![block.clone()]);
+    }
+    match deleted.last_mut().and_then(inlines_mut) {
+        Some(last) => {
+            last.append(&mut after);
+            out.append(&mut deleted);
+        }
+        None => {
+            let starts_only = after
+                .iter()
+                .all(|inline| matches!(inline, Inline::ChangeStart(_)));
+            match deleted.last_mut().and_then(last_inlines_mut) {
+                Some(last) if starts_only => last.append(&mut after),
+                _ if after.is_empty() => {}
+                _ => {
+                    let mut tail = block.clone();
+                    *inlines_mut(&mut tail)? = after;
+                    deleted.push(tail);
+                }
+            }
+            out.append(&mut deleted);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text {
+            text: s.to_string(),
+            style_name: None,
+            marks: vec![],
+        }
+    }
+
+    fn para(content: Vec<Inline>) -> Block {
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content,
+        }
+    }
+
+    fn change(id: &str, kind: ChangeKind, content: Vec<Block>) -> TrackedChange {
+        TrackedChange {
+            id: id.to_string(),
+            kind,
+            content,
+            ..TrackedChange::default()
+        }
+    }
+
+    fn start(id: &str) -> Inline {
+        Inline::ChangeStart(change(id, ChangeKind::Insertion, vec![]))
+    }
+
+    fn end(id: &str) -> Inline {
+        Inline::ChangeEnd { id: id.to_string() }
+    }
+
+    fn plain(blocks: &[Block]) -> Vec<String> {
+        blocks
+            .iter()
+            .map(|block| match block {
+                Block::Paragraph { content, .. } => content
+                    .iter()
+                    .map(|inline| match inline {
+                        Inline::Text { text, .. } => text.as_str(),
+                        _ => "|",
+                    })
+                    .collect(),
+                _ => "#".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accepting_an_insertion_keeps_text_and_drops_markers() {
+        let mut blocks = vec![para(vec![text("a "), start("c1"), text("b"), end("c1")])];
+        accept_change(&mut blocks, "c1").unwrap();
+        assert_eq!(plain(&blocks), ["a b"]);
+        assert!(tracked_changes(&blocks).is_empty());
+    }
+
+    #[test]
+    fn rejecting_an_insertion_across_paragraphs_joins_its_ends() {
+        let mut blocks = vec![
+            para(vec![text("keep "), start("c1"), text("new")]),
+            para(vec![text("all new")]),
+            para(vec![text("tail"), end("c1"), text(" kept")]),
+            para(vec![text("after")]),
+        ];
+        reject_change(&mut blocks, "c1").unwrap();
+        assert_eq!(plain(&blocks), ["keep  kept", "after"]);
+    }
+
+    #[test]
+    fn rejecting_an_insertion_into_a_list_drops_the_emptied_items() {
+        let item = |content| Block::ListItem {
+            content: vec![para(content)],
+        };
+        let list = |items| Block::BulletList {
+            attrs: None,
+            content: items,
+        };
+        let mut blocks = vec![
+            para(vec![text("x"), start("c1")]),
+            list(vec![
+                item(vec![text("a")]),
+                item(vec![text("b"), end("c1")]),
+            ]),
+            para(vec![text("y")]),
+        ];
+        reject_change(&mut blocks, "c1").unwrap();
+        assert_eq!(plain(&blocks), ["x", "y"]);
+
+        let mut blocks = vec![
+            list(vec![
+                item(vec![text("a"), start("c2")]),
+                item(vec![text("b")]),
+            ]),
+            para(vec![text("c"), end("c2"), text("d")]),
+        ];
+        reject_change(&mut blocks, "c2").unwrap();
+        assert_eq!(blocks, vec![list(vec![item(vec![text("a"), text("d")])])]);
+    }
+
+    #[test]
+    fn rejecting_a_deletion_restores_its_paragraphs() {
+        let deleted = vec![para(vec![text("b")]), para(vec![text("c")])];
+        let mut blocks = vec![para(vec![
+            text("a"),
+            Inline::Change(change("c2", ChangeKind::Deletion, deleted)),
+            text("d"),
+        ])];
+        reject_change(&mut blocks, "c2").unwrap();
+        assert_eq!(plain(&blocks), ["ab", "cd"]);
+
+        let mut blocks = vec![para(vec![
+            text("a"),
+            Inline::Change(change(
+                "c3",
+                ChangeKind::Deletion,
+                vec![para(vec![text("b")])],
+            )),
+            text("c"),
+        ])];
+        accept_change(&mut blocks, "c3").unwrap();
+        assert_eq!(plain(&blocks), ["ac"]);
+    }
+
+    #[test]
+    fn all_changes_can_be_accepted_or_rejected() {
+        let blocks = vec![para(vec![
+            start("c1"),
+            text("in"),
+            end("c1"),
+            Inline::Change(change(
+                "c2",
+                ChangeKind::Deletion,
+                vec![para(vec![text("out")])],
+            )),
+        ])];
+        let mut accepted = blocks.clone();
+        accept_all(&mut accepted);
+        assert_eq!(plain(&accepted), ["in"]);
+        let mut rejected = blocks;
+        reject_all(&mut rejected);
+        assert_eq!(plain(&rejected), ["out"]);
+    }
+
+    #[test]
+    fn unknown_change_id_is_an_error() {
+        let mut blocks = vec![para(vec![text("a")])];
+        assert!(accept_change(&mut blocks, "missing").is_err());
+        assert!(reject_change(&mut blocks, "missing").is_err());
+    }
+}
@@ -0,0 +1,399 @@
+//! Style cleanup: direct formatting to named styles.
+//!
+//! Imported documents often format paragraphs and runs directly instead of
+//! through styles. [`analyze_direct_formatting`] groups identical
+//! combinations of direct formatting, each on top of the same named style
+//! and on the same kind of block, and proposes a named [`StyleDefinition`] for every group that is used
+//! often enough. [`apply_style_proposals`] adds the accepted styles and
+//! rewrites the blocks to use them, clearing the direct formatting.
+//!
+//! Paragraph direct formatting is the alignment and indentation in a
+//! block's [`BlockAttrs`]; run direct formatting is the bold, italic,
+//! underline, strikethrough, superscript, subscript and text style marks.
+//! Headings are grouped by level, apart from paragraphs, and an unstyled
+//! heading is based on the `Heading N` style of its level.
+//! Links and named span styles are not formatting and are kept. When a
+//! named style with exactly the same properties already exists, it is
+//! proposed instead of a new one.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::{Block, BlockAttrs, Inline};
+//! use odt_format::cleanup::{analyze_direct_formatting, apply_style_proposals, CleanupOptions};
+//! use odt_format::Document;
+//!
+//! let centred = || Block::Paragraph {
+//!     style_name: Some("Standard".to_string()),
+//!     attrs: Some(BlockAttrs {
+//!         text_align: Some("center".to_string()),
+//!         ..BlockAttrs::default()
+//!     }),
+//!     content: vec![],
+//! };
+//! let mut doc = Document::new();
+//! doc.blocks = vec![centred(), centred()];
+//!
+//! let proposals = analyze_direct_formatting(&doc, &CleanupOptions::default());
+//! let style = &proposals[0].style;
+//! assert_eq!(style.name, "Standard_20__28_Centered_29_");
+//! assert_eq!(style.display_name.as_deref(), Some("Standard (Centered)"));
+//! assert_eq!(apply_style_proposals(&mut doc, &proposals), 2);
+//! assert!(matches!(
+//!     &doc.blocks[0],
+//!     Block::Paragraph { style_name: Some(name), attrs: None, .. } if *name == style.name
+//! ));
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use common_core::style::encode_style_name;
+use common_core::walk::{for_each_block, for_each_block_mut, for_each_inline, for_each_inline_mut};
+use common_core::{Block, BlockAttrs, Inline, StyleDefinition, StyleFamily, TiptapMark};
+
+use crate::loki_ext::colour_to_odf_string;
+use crate::writer::auto_styles::{
+    formatting_marks, mark_properties, normalize_block_attrs, run_parent_style,
+};
+use crate::Document;
+
+/// Options for [`analyze_direct_formatting`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupOptions {
+    /// The fewest uses of a combination worth a style of its own.
+    pub min_uses: usize,
+}
+
+impl Default for CleanupOptions {
+    fn default() -> Self {
+        Self { min_uses: 2 }
+    }
+}
+
+/// A combination of direct formatting, normalized so that equivalent
+/// formatting compares equal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DirectFormatting {
+    /// Paragraph alignment and indentation.
+    Paragraph(BlockAttrs),
+    /// Character formatting marks, in a canonical order.
+    Text(Vec<TiptapMark>),
+}
+
+/// A named style proposed for a group of identically formatted blocks or
+/// runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleProposal {
+    /// The named style the formatting is applied on, if any.
+    pub base: Option<String>,
+    /// The level of the grouped headings, or `None` for paragraphs and runs.
+    pub level: Option<u32>,
+    /// The direct formatting the style replaces.
+    pub formatting: DirectFormatting,
+    /// The proposed style, whose parent is [`Self::base`].
+    pub style: StyleDefinition,
+    /// Whether [`Self::style`] is a style the document already has.
+    pub existing: bool,
+    /// How many paragraphs or runs use the formatting.
+    pub uses: usize,
+}
+
+/// Groups the direct formatting in `doc` and proposes a named style for
+/// each combination used at least [`CleanupOptions::min_uses`] times.
+///
+/// Proposals are ordered by decreasing use. New styles are shown under a
+/// name that describes the formatting, e.g. `"Text Body (Centered, Indent
+/// 1)"` or `"Character (Bold, Italic)"`, and their style name is encoded
+/// from it. Neither clashes with existing styles.
+#[must_use]
+pub fn analyze_direct_formatting(doc: &Document, options: &CleanupOptions) -> Vec<StyleProposal> {
+    let mut groups: Vec<(Option<String>, Option<u32>, DirectFormatting, usize)> = Vec::new();
+    let mut count =
+        |base: Option<&str>, level: Option<u32>, formatting: DirectFormatting| match groups
+            .iter_mut()
+            .find(|(b, l, f, _)| b.as_deref() == base && *l == level && *f == formatting)
+        {
+            Some((_, _, _, uses)) => *uses += 1,
+            None => groups.push((base.map(str::to_string), level, formatting, 1)),
+        };
+    for_each_block(&doc.blocks, &mut |block| {
+        if let Some((style_name, level, attrs)) = paragraph_formatting(block) {
+            count(
+                style_name.as_deref(),
+                level,
+                DirectFormatting::Paragraph(attrs),
+            );
+        }
+    });
+    for_each_inline(&doc.blocks, &mut |inline| {
+        if let Some((parent, marks)) = run_formatting(inline) {
+            count(parent, None, DirectFormatting::Text(marks));
+        }
+    });
+
+    groups.retain(|(_, _, _, uses)| *uses >= options.min_uses.max(1));
+    groups.sort_by_key(|(_, _, _, uses)| Reverse(*uses));
+
+    let mut taken: HashSet<String> = doc
+        .styles
+        .values()
+        .flat_map(|style| [Some(&style.name), style.display_name.as_ref()])
+        .flatten()
+        .cloned()
+        .collect();
+    groups
+        .into_iter()
+        .map(|(base, level, formatting, uses)| {
+            let mut style = proposed_style(base.as_deref(), &formatting, &doc.styles);
+            style.outline_level = level;
+            let existing = doc.styles.values().find(|s| {
+                s.family == style.family
+                    && s.parent == style.parent
+                    && s.outline_level == style.outline_level
+                    && s.attributes == style.attributes
+                    && s.text_transform.is_none()
+            });
+            if let Some(existing) = existing {
+                style = existing.clone();
+            } else {
+                let display_name = unique_name(style.display_name.as_deref().unwrap_or(""), &taken);
+                style.name = encode_style_name(&display_name);
+                taken.insert(style.name.clone());
+                taken.insert(display_name.clone());
+                style.display_name = Some(display_name);
+            }
+            StyleProposal {
+                base,
+                level,
+                formatting,
+                existing: existing.is_some(),
+                style,
+                uses,
+            }
+        })
+        .collect()
+}
+
+/// Adds the styles of `proposals` to `doc` and rewrites every paragraph
+/// and run whose direct formatting one of them replaces.
+///
+/// Pass a subset of the proposals from [`analyze_direct_formatting`] to
+/// apply only those. Returns the number of paragraphs and runs rewritten.
+pub fn apply_style_proposals(doc: &mut Document, proposals: &[StyleProposal]) -> usize {
+    for proposal in proposals.iter().filter(|p| !p.existing) {
+        doc.styles
+            .insert(proposal.style.name.clone(), proposal.style.clone());
+    }
+    let find = |base: Option<&str>, level: Option<u32>, formatting: &DirectFormatting| {
+        proposals
+            .iter()
+            .find(|p| p.base.as_deref() == base && p.level == level && p.formatting == *formatting)
+            .map(|p| p.style.name.clone())
+    };
+
+    let mut rewritten = 0;
+    for_each_block_mut(&mut doc.blocks, &mut |block| {
+        let Some(name) = paragraph_formatting(block).and_then(|(base, level, attrs)| {
+            find(base.as_deref(), level, &DirectFormatting::Paragraph(attrs))
+        }) else {
+            return;
+        };
+        if let Block::Paragraph {
+            style_name, attrs, ..
+        }
+        | Block::Heading {
+            style_name, attrs, ..
+        } = block
+        {
+            *style_name = Some(name);
+            *attrs = None;
+            rewritten += 1;
+        }
+    });
+    for_each_inline_mut(&mut doc.blocks, &mut |inline| {
+        let Some(name) = run_formatting(inline)
+            .and_then(|(parent, marks)| find(parent, None, &DirectFormatting::Text(marks)))
+        else {
+            return;
+        };
+        if let Inline::Text {
+            style_name, marks, ..
+        } = inline
+        {
+            marks.retain(|m| {
+                matches!(
+                    m,
+                    TiptapMark::Link { .. } | TiptapMark::NamedSpanStyle { .. }
+                )
+            });
+            // The new style derives from the named span style, so the mark
+            // moves on to it.
+            for mark in marks.iter_mut() {
+                if let TiptapMark::NamedSpanStyle { attrs } = mark {
+                    attrs.style_name = Some(name.clone());
+                }
+            }
+            *style_name = Some(name);
+            rewritten += 1;
+        }
+    });
+    rewritten
+}
+
+/// Returns the style name, heading level and normalized direct formatting
+/// of a paragraph or heading that has some.
+///
+/// An unstyled heading takes the `Heading N` style of its level. Tab stops
+/// have no place in a [`StyleDefinition`], so paragraphs with tab stops keep
+/// their direct formatting.
+fn paragraph_formatting(block: &Block) -> Option<(Option<String>, Option<u32>, BlockAttrs)> {
+    let (style_name, level, attrs) = match block {
+        Block::Paragraph {
+            style_name, attrs, ..
+        } => (style_name.clone(), None, attrs),
+        Block::Heading {
+            style_name,
+            level,
+            attrs,
+            ..
+        } => (
+            Some(
+                style_name
+                    .clone()
+                    .unwrap_or_else(|| encode_style_name(&format!("Heading {level}"))),
+            ),
+            Some(*level),
+            attrs,
+        ),
+        _ => return None,
+    };
+    let attrs = normalize_block_attrs(attrs.as_ref()?).filter(|a| a.tab_stops.is_empty())?;
+    Some((style_name, level, attrs))
+}
+
+/// Returns the parent span style and formatting marks of a text run that
+/// has some.
+fn run_formatting(inline: &Inline) -> Option<(Option<&str>, Vec<TiptapMark>)> {
+    let Inline::Text {
+        style_name, marks, ..
+    } = inline
+    else {
+        return None;
+    };
+    let formatting = formatting_marks(marks);
+    (!formatting.is_empty()).then(|| (run_parent_style(style_name.as_deref(), marks), formatting))
+}
+
+/// Builds the style for a group, with a display name made of its base's
+/// display name and its formatting.
+fn proposed_style(
+    base: Option<&str>,
+    formatting: &DirectFormatting,
+    styles: &HashMap<String, StyleDefinition>,
+) -> StyleDefinition {
+    let (family, attributes, parts): (_, HashMap<String, String>, Vec<String>) = match formatting {
+        DirectFormatting::Paragraph(attrs) => (
+            StyleFamily::Paragraph,
+            attrs.to_odf_attributes(),
+            paragraph_name_parts(attrs),
+        ),
+        DirectFormatting::Text(marks) => (
+            StyleFamily::Text,
+            marks
+                .iter()
+                .flat_map(mark_properties)
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+            marks.iter().flat_map(mark_name_parts).collect(),
+        ),
+    };
+    let fallback = match family {
+        StyleFamily::Paragraph => "Paragraph",
+        _ => "Character",
+    };
+    let base_display = base.map_or(fallback, |base| {
+        styles
+            .get(base)
+            .and_then(|style| style.display_name.as_deref())
+            .unwrap_or(base)
+    });
+    let display_name = format!("{base_display} ({})", parts.join(", "));
+    StyleDefinition {
+        name: encode_style_name(&display_name),
+        family,
+        parent: base.map(str::to_string),
+        next: None,
+        display_name: Some(display_name),
+        attributes,
+        text_transform: None,
+        outline_level: None,
+        autocomplete: None,
+        master_page_name: match formatting {
+            DirectFormatting::Paragraph(attrs) => attrs.master_page_name.clone(),
+            DirectFormatting::Text(_) => None,
+        },
+        font_colour: None,
+        background_colour: None,
+        properties: HashMap::new(),
+        list_levels: Vec::new(),
+    }
+}
+
+fn paragraph_name_parts(attrs: &BlockAttrs) -> Vec<String> {
+    let align = attrs.text_align.as_deref().map(|align| match align {
+        "center" => "Centered",
+        "end" | "right" => "Right",
+        "justify" => "Justified",
+        _ => "Left",
+    });
+    align
+        .map(str::to_string)
+        .into_iter()
+        .chain(attrs.indent.map(|indent| format!("Indent {indent}")))
+        .chain((!attrs.properties.is_empty()).then(|| "Custom".to_string()))
+        .chain(
+            attrs
+                .master_page_name
+                .as_ref()
+                .map(|master| format!("{master} Page")),
+        )
+        .collect()
+}
+
+fn mark_name_parts(mark: &TiptapMark) -> Vec<String> {
+    let name = |s: &str| vec![s.to_string()];
+    match mark {
+        TiptapMark::Bold => name("Bold"),
+        TiptapMark::Italic => name("Italic"),
+        TiptapMark::Underline => name("Underline"),
+        TiptapMark::Strike => name("Strikethrough"),
+        TiptapMark::Superscript => name("Superscript"),
+        TiptapMark::Subscript => name("Subscript"),
+        TiptapMark::TextStyle { attrs } => attrs
+            .font_family
+            .clone()
+            .into_iter()
+            .chain(attrs.font_size.map(|size| format!("{size}pt")))
+            .chain(attrs.colour.as_ref().map(colour_to_odf_string))
+            .chain(
+                attrs
+                    .background_colour
+                    .as_ref()
+                    .map(|c| format!("Highlight {}", colour_to_odf_string(c))),
+            )
+            .collect(),
+        TiptapMark::NamedSpanStyle { .. } | TiptapMark::Link { .. } => Vec::new(),
+    }
+}
+
+/// Returns `name`, or `"{name} 2"`, `"{name} 3"`, … if it is taken.
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{name} {n}"))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
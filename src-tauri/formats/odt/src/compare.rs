@@ -0,0 +1,969 @@
+//! Document comparison.
+//!
+//! [`compare_documents`] diffs two drafts and returns a redline document:
+//! the newer draft with every difference from the older one recorded as a
+//! tracked change (see [`crate::changes`]), ready for review in LibreOffice.
+//!
+//! Blocks are matched with a longest-common-subsequence diff. Between
+//! matches, a removed and an added paragraph that share most of their words
+//! are compared word by word, and words that only changed formatting are
+//! recorded as a format change; lists, quotations and tables of the same
+//! shape are compared item by item and cell by cell. Everything else becomes
+//! a block deletion or insertion.
+//!
+//! ODF tracks changes to text, so an inserted block is anchored in the
+//! paragraph before it or, failing that, in its own first paragraph and the
+//! one after it; either may be inside a list or table. An inserted block
+//! with no paragraph to anchor it on either side is recorded as insertions
+//! of the text inside it; rejecting those leaves the empty structure (and
+//! any images) behind. An empty draft is compared as a single empty
+//! paragraph, the way it is shown.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::{Block, Inline};
+//! use odt_format::compare::{compare_documents, CompareOptions};
+//! use odt_format::Document;
+//!
+//! let draft = |text: &str| {
+//!     let mut doc = Document::new();
+//!     doc.blocks = vec![Block::Paragraph {
+//!         style_name: None,
+//!         attrs: None,
+//!         content: vec![Inline::Text {
+//!             text: text.to_string(),
+//!             style_name: None,
+//!             marks: vec![],
+//!         }],
+//!     }];
+//!     doc
+//! };
+//! let redline = compare_documents(
+//!     &draft("The quick fox"),
+//!     &draft("The quick brown fox"),
+//!     &CompareOptions::default(),
+//! );
+//! assert_eq!(redline.tracked_changes().len(), 1);
+//! ```
+
+use common_core::field::now;
+use common_core::{Block, ChangeKind, Inline, TrackedChange};
+
+use crate::changes;
+use crate::Document;
+
+/// The largest diff table, in cells, before a diff falls back to treating
+/// the differing middle as wholly replaced.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Options for [`compare_documents`].
+#[derive(Debug, Clone)]
+pub struct CompareOptions {
+    /// Author recorded on every change.
+    pub author: Option<String>,
+    /// ISO 8601 timestamp recorded on every change.
+    pub date: Option<String>,
+}
+
+impl Default for CompareOptions {
+    /// No author, with changes dated now.
+    fn default() -> Self {
+        Self {
+            author: None,
+            date: Some(now()),
+        }
+    }
+}
+
+/// Compares two drafts and returns `new` with the differences from `old` as
+/// tracked changes.
+///
+/// Changes already pending in either draft are accepted before comparing.
+/// Accepting every change in the result gives the text of `new`; rejecting
+/// every change gives the text of `old`, short of the unanchored structure
+/// described in the [module documentation](self).
+#[must_use]
+pub fn compare_documents(old: &Document, new: &Document, options: &CompareOptions) -> Document {
+    let draft = |blocks: &[Block]| {
+        let mut blocks = blocks.to_vec();
+        changes::accept_all(&mut blocks);
+        if blocks.is_empty() {
+            blocks.push(paragraph(Vec::new()));
+        }
+        blocks
+    };
+    let (old_blocks, new_blocks) = (draft(&old.blocks), draft(&new.blocks));
+
+    let mut redliner = Redliner {
+        options,
+        next_id: 0,
+    };
+    let mut doc = new.clone();
+    doc.blocks = redliner.diff_blocks(&old_blocks, &new_blocks);
+    doc
+}
+
+/// Builds the redlined blocks and numbers the changes.
+struct Redliner<'a> {
+    options: &'a CompareOptions,
+    next_id: usize,
+}
+
+/// A change waiting for the next block to anchor it.
+enum Pending {
+    /// A marker to put at the start of the next paragraph.
+    Marker(Inline),
+    /// A deletion to put at the start of the next paragraph; the paragraph's
+    /// style is given to the empty paragraph that closes the removed blocks.
+    Deletion(TrackedChange),
+}
+
+/// Redlined output for one block list.
+#[derive(Default)]
+struct Output {
+    blocks: Vec<Block>,
+    pending: Vec<Pending>,
+}
+
+impl Output {
+    /// Appends `block`, anchoring pending changes at its start.
+    ///
+    /// Markers alone go into its first paragraph, however deeply nested;
+    /// deletions need a paragraph in this block list to be restored into.
+    fn push(&mut self, mut block: Block) {
+        if !self.pending.is_empty() {
+            let style = empty_like(&block);
+            let markers_only = self
+                .pending
+                .iter()
+                .all(|pending| matches!(pending, Pending::Marker(_)));
+            let prefix: Vec<Inline> = std::mem::take(&mut self.pending)
+                .into_iter()
+                .map(|pending| match pending {
+                    Pending::Marker(marker) => marker,
+                    Pending::Deletion(mut change) => {
+                        change.content.extend(style.clone());
+                        Inline::Change(change)
+                    }
+                })
+                .collect();
+            let target = if markers_only {
+                first_inlines_mut(&mut block)
+            } else {
+                inlines_mut(&mut block)
+            };
+            match target {
+                Some(content) => {
+                    content.splice(0..0, prefix);
+                }
+                None => self.blocks.push(paragraph(prefix)),
+            }
+        }
+        self.blocks.push(block);
+    }
+
+    /// Whether the last block is a paragraph that changes can be appended to.
+    fn can_append(&self) -> bool {
+        self.pending.is_empty() && self.blocks.last().and_then(inlines).is_some()
+    }
+
+    /// Whether the last block ends in a paragraph, however deeply nested,
+    /// that an insertion can start in.
+    fn can_extend(&self) -> bool {
+        self.pending.is_empty() && self.blocks.last().and_then(last_inlines).is_some()
+    }
+
+    /// Appends `inline` to the last paragraph of the last block; see
+    /// [`Output::can_append`] and [`Output::can_extend`].
+    fn append_to_last(&mut self, inline: Inline) {
+        if let Some(content) = self.blocks.last_mut().and_then(last_inlines_mut) {
+            content.push(inline);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Block> {
+        if !self.pending.is_empty() {
+            // Nothing follows to anchor the changes, so they get a paragraph
+            // of their own.
+            let markers = std::mem::take(&mut self.pending)
+                .into_iter()
+                .map(|pending| match pending {
+                    Pending::Marker(marker) => marker,
+                    Pending::Deletion(change) => Inline::Change(change),
+                })
+                .collect();
+            self.blocks.push(paragraph(markers));
+        }
+        self.blocks
+    }
+}
+
+impl Redliner<'_> {
+    fn change(&mut self, kind: ChangeKind, content: Vec<Block>) -> TrackedChange {
+        self.next_id += 1;
+        TrackedChange {
+            id: format!("ct{}", self.next_id),
+            kind,
+            author: self.options.author.clone(),
+            date: self.options.date.clone(),
+            content,
+        }
+    }
+
+    fn diff_blocks(&mut self, old: &[Block], new: &[Block]) -> Vec<Block> {
+        let mut out = Output::default();
+        let (mut i, mut j) = (0, 0);
+        let matches = lcs(old, new, |a, b| a == b);
+        for (mi, mj) in matches
+            .into_iter()
+            .chain(std::iter::once((old.len(), new.len())))
+        {
+            self.diff_run(&old[i..mi], &new[j..mj], new.get(mj), &mut out);
+            if let Some(block) = new.get(mj) {
+                out.push(block.clone());
+            }
+            (i, j) = (mi + 1, mj + 1);
+        }
+        out.finish()
+    }
+
+    /// Redlines a run of removed and added blocks between two matches.
+    ///
+    /// Blocks are paired greedily, looking one block ahead on either side.
+    fn diff_run(&mut self, old: &[Block], new: &[Block], next: Option<&Block>, out: &mut Output) {
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (old.get(i), new.get(j)) {
+            if pairable(a, b) {
+                let block = self.diff_pair(a, b);
+                out.push(block);
+                (i, j) = (i + 1, j + 1);
+            } else if new.get(j + 1).is_some_and(|b| pairable(a, b)) {
+                self.insert(&new[j..=j], new.get(j + 1), out);
+                j += 1;
+            } else if old.get(i + 1).is_some_and(|a| pairable(a, b)) {
+                self.delete(&old[i..=i], out);
+                i += 1;
+            } else if inlines(a).is_some() && inlines(b).is_some() {
+                // A paragraph replaced by another is redlined word by word
+                // in place, so rejecting the changes leaves no empty
+                // paragraph behind.
+                let block = self.diff_pair(a, b);
+                out.push(block);
+                (i, j) = (i + 1, j + 1);
+            } else {
+                let next = new.get(j + 1).or(next);
+                if out.can_append() {
+                    self.delete(&old[i..=i], out);
+                    self.insert(&new[j..=j], next, out);
+                } else {
+                    // With no paragraph before to take the deletion, it
+                    // waits for the next block; the insertion goes first so
+                    // that its markers are not held up behind it.
+                    self.insert(&new[j..=j], next, out);
+                    self.delete(&old[i..=i], out);
+                }
+                (i, j) = (i + 1, j + 1);
+            }
+        }
+        if i < old.len() {
+            self.delete(&old[i..], out);
+        }
+        if j < new.len() {
+            self.insert(&new[j..], next, out);
+        }
+    }
+
+    /// Records `removed` as deleted at the current position.
+    fn delete(&mut self, removed: &[Block], out: &mut Output) {
+        if let Some(last) = out.blocks.last().filter(|_| out.can_append()) {
+            // The deletion starts at the end of the previous paragraph,
+            // taking the paragraph break with it.
+            let mut content: Vec<Block> = empty_like(last).into_iter().collect();
+            content.extend_from_slice(removed);
+            let change = self.change(ChangeKind::Deletion, content);
+            out.append_to_last(Inline::Change(change));
+            return;
+        }
+        let change = self.change(ChangeKind::Deletion, removed.to_vec());
+        out.pending.push(Pending::Deletion(change));
+    }
+
+    /// Records `added` as inserted; `next` is the block that will follow.
+    fn insert(&mut self, added: &[Block], next: Option<&Block>, out: &mut Output) {
+        let (Some(first), Some(last)) = (added.first(), added.last()) else {
+            return;
+        };
+        let anchored_before = out.can_extend() && last_inlines(last).is_some();
+        let anchored_after =
+            first_inlines(first).is_some() && next.and_then(first_inlines).is_some();
+        if anchored_before {
+            // From the end of the previous paragraph to the end of the last
+            // added one.
+            let change = self.change(ChangeKind::Insertion, Vec::new());
+            let id = change.id.clone();
+            out.append_to_last(Inline::ChangeStart(change));
+            for block in added {
+                out.push(block.clone());
+            }
+            out.append_to_last(Inline::ChangeEnd { id });
+        } else if anchored_after {
+            // From the start of the first added paragraph to the start of
+            // the next one.
+            let change = self.change(ChangeKind::Insertion, Vec::new());
+            let id = change.id.clone();
+            out.pending
+                .push(Pending::Marker(Inline::ChangeStart(change)));
+            for block in added {
+                out.push(block.clone());
+            }
+            out.pending.push(Pending::Marker(Inline::ChangeEnd { id }));
+        } else {
+            for block in added {
+                let mut block = block.clone();
+                self.mark_text_inserted(&mut block);
+                out.push(block);
+            }
+        }
+    }
+
+    /// Marks the text of every paragraph in `block` as inserted.
+    fn mark_text_inserted(&mut self, block: &mut Block) {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                if content.is_empty() {
+                    return;
+                }
+                let change = self.change(ChangeKind::Insertion, Vec::new());
+                let id = change.id.clone();
+                content.insert(0, Inline::ChangeStart(change));
+                content.push(Inline::ChangeEnd { id });
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => {
+                for block in content {
+                    self.mark_text_inserted(block);
+                }
+            }
+            Block::Image { .. }
+            | Block::HorizontalRule
+            | Block::PageBreak
+            | Block::TableOfContents(_) => {}
+        }
+    }
+
+    /// Redlines two blocks that [`pairable`] matched.
+    fn diff_pair(&mut self, old: &Block, new: &Block) -> Block {
+        let mut block = new.clone();
+        match (old, &mut block) {
+            (
+                Block::Paragraph { .. } | Block::Heading { .. },
+                Block::Paragraph { content, .. } | Block::Heading { content, .. },
+            ) => {
+                let old_content = inlines(old).unwrap_or_default();
+                *content = self.diff_inlines(old, old_content, content);
+                if !same_format(old, new) {
+                    let change = self.change(ChangeKind::FormatChange, Vec::new());
+                    let id = change.id.clone();
+                    content.insert(0, Inline::ChangeStart(change));
+                    content.push(Inline::ChangeEnd { id });
+                }
+            }
+            (Block::BulletList { content: a, .. }, Block::BulletList { content: b, .. })
+            | (Block::OrderedList { content: a, .. }, Block::OrderedList { content: b, .. })
+            | (Block::Table { content: a }, Block::Table { content: b })
+            | (Block::TableRow { content: a }, Block::TableRow { content: b }) => {
+                *b = a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| self.diff_pair(a, b))
+                    .collect();
+            }
+            (Block::ListItem { content: a }, Block::ListItem { content: b })
+            | (Block::Blockquote { content: a }, Block::Blockquote { content: b })
+            | (Block::Section { content: a, .. }, Block::Section { content: b, .. })
+            | (
+                Block::TableCell { content: a, .. } | Block::TableHeader { content: a, .. },
+                Block::TableCell { content: b, .. } | Block::TableHeader { content: b, .. },
+            ) => *b = self.diff_blocks(a, b),
+            _ => {}
+        }
+        block
+    }
+
+    /// Redlines the words of a paragraph; removed words are kept in a
+    /// paragraph styled like `old_block`, and runs of words whose
+    /// formatting changed are marked as a format change.
+    fn diff_inlines(&mut self, old_block: &Block, old: &[Inline], new: &[Inline]) -> Vec<Inline> {
+        let old_tokens = tokenize(old);
+        let new_tokens = tokenize(new);
+        let mut out = Vec::new();
+        let mut restyled: Option<String> = None;
+        let (mut i, mut j) = (0, 0);
+        let matches = lcs(&old_tokens, &new_tokens, same_text);
+        for (mi, mj) in matches
+            .into_iter()
+            .chain(std::iter::once((old_tokens.len(), new_tokens.len())))
+        {
+            let same = old_tokens.get(mi) == new_tokens.get(mj);
+            if let Some(id) = restyled.take_if(|_| i < mi || j < mj || same) {
+                out.push(Inline::ChangeEnd { id });
+            }
+            if i < mi {
+                let mut removed = empty_like(old_block).unwrap_or_else(|| paragraph(Vec::new()));
+                if let Some(content) = inlines_mut(&mut removed) {
+                    *content = merge_runs(old_tokens[i..mi].to_vec());
+                }
+                let change = self.change(ChangeKind::Deletion, vec![removed]);
+                out.push(Inline::Change(change));
+            }
+            if j < mj {
+                let change = self.change(ChangeKind::Insertion, Vec::new());
+                let id = change.id.clone();
+                out.push(Inline::ChangeStart(change));
+                out.extend_from_slice(&new_tokens[j..mj]);
+                out.push(Inline::ChangeEnd { id });
+            }
+            if let Some(token) = new_tokens.get(mj) {
+                if !same && restyled.is_none() {
+                    let change = self.change(ChangeKind::FormatChange, Vec::new());
+                    restyled = Some(change.id.clone());
+                    out.push(Inline::ChangeStart(change));
+                }
+                out.push(token.clone());
+            }
+            (i, j) = (mi + 1, mj + 1);
+        }
+        if let Some(id) = restyled {
+            out.push(Inline::ChangeEnd { id });
+        }
+        merge_runs(out)
+    }
+}
+
+/// Whether two differing blocks are close enough to be compared in detail
+/// rather than replaced.
+fn pairable(a: &Block, b: &Block) -> bool {
+    match (a, b) {
+        (
+            Block::Paragraph { .. } | Block::Heading { .. },
+            Block::Paragraph { .. } | Block::Heading { .. },
+        ) => similar(
+            inlines(a).unwrap_or_default(),
+            inlines(b).unwrap_or_default(),
+        ),
+        (Block::BulletList { content: a, .. }, Block::BulletList { content: b, .. })
+        | (Block::OrderedList { content: a, .. }, Block::OrderedList { content: b, .. })
+        | (Block::Table { content: a }, Block::Table { content: b })
+        | (Block::TableRow { content: a }, Block::TableRow { content: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_shape(a, b))
+        }
+        (Block::Blockquote { .. }, Block::Blockquote { .. }) => true,
+        (Block::Section { name: a, .. }, Block::Section { name: b, .. }) => a == b,
+        _ => false,
+    }
+}
+
+/// Whether two list items, rows or cells can be compared one to one.
+fn same_shape(a: &Block, b: &Block) -> bool {
+    match (a, b) {
+        (Block::ListItem { .. }, Block::ListItem { .. }) => true,
+        (Block::TableRow { content: a }, Block::TableRow { content: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_shape(a, b))
+        }
+        (
+            Block::TableCell { .. } | Block::TableHeader { .. },
+            Block::TableCell { .. } | Block::TableHeader { .. },
+        ) => true,
+        _ => false,
+    }
+}
+
+/// Whether at least half of the words of two paragraphs are shared,
+/// whatever their formatting.
+fn similar(a: &[Inline], b: &[Inline]) -> bool {
+    let words = |inlines: &[Inline]| -> Vec<Inline> {
+        tokenize(inlines)
+            .into_iter()
+            .filter(|t| !matches!(t, Inline::Text { text, .. } if text.trim().is_empty()))
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return a.is_empty() && b.is_empty();
+    }
+    let common = lcs(&a, &b, same_text).len();
+    common * 2 >= (a.len() + b.len()) / 2
+}
+
+/// Whether two tokens read the same, ignoring the formatting of text.
+fn same_text(a: &Inline, b: &Inline) -> bool {
+    match (a, b) {
+        (Inline::Text { text: a, .. }, Inline::Text { text: b, .. }) => a == b,
+        _ => a == b,
+    }
+}
+
+fn same_format(a: &Block, b: &Block) -> bool {
+    match (a, b) {
+        (
+            Block::Paragraph {
+                style_name: sa,
+                attrs: aa,
+                ..
+            },
+            Block::Paragraph {
+                style_name: sb,
+                attrs: ab,
+                ..
+            },
+        ) => sa == sb && aa == ab,
+        (
+            Block::Heading {
+                level: la,
+                style_name: sa,
+                attrs: aa,
+                ..
+            },
+            Block::Heading {
+                level: lb,
+                style_name: sb,
+                attrs: ab,
+                ..
+            },
+        ) => la == lb && sa == sb && aa == ab,
+        _ => false,
+    }
+}
+
+/// Splits text runs into words and the whitespace between them; other
+/// inlines are single tokens.
+fn tokenize(inlines: &[Inline]) -> Vec<Inline> {
+    let mut tokens = Vec::new();
+    for inline in inlines {
+        let Inline::Text {
+            text,
+            style_name,
+            marks,
+        } = inline
+        else {
+            tokens.push(inline.clone());
+            continue;
+        };
+        let mut start = 0;
+        let mut in_space = None;
+        for (pos, c) in text.char_indices() {
+            let space = c.is_whitespace();
+            if in_space.is_some_and(|s| s != space) {
+                tokens.push(Inline::Text {
+                    text: text[start..pos].to_string(),
+                    style_name: style_name.clone(),
+                    marks: marks.clone(),
+                });
+                start = pos;
+            }
+            in_space = Some(space);
+        }
+        if start < text.len() {
+            tokens.push(Inline::Text {
+                text: text[start..].to_string(),
+                style_name: style_name.clone(),
+                marks: marks.clone(),
+            });
+        }
+    }
+    tokens
+}
+
+/// Joins adjacent text runs with the same style and marks.
+fn merge_runs(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut out: Vec<Inline> = Vec::with_capacity(inlines.len());
+    for inline in inlines {
+        if let (
+            Some(Inline::Text {
+                text,
+                style_name,
+                marks,
+            }),
+            Inline::Text {
+                text: next,
+                style_name: next_style,
+                marks: next_marks,
+            },
+        ) = (out.last_mut(), &inline)
+        {
+            if style_name == next_style && marks == next_marks {
+                text.push_str(next);
+                continue;
+            }
+        }
+        out.push(inline);
+    }
+    out
+}
+
+/// Returns the index pairs of a longest common subsequence of `a` and `b`.
+///
+/// Common leading and trailing items are matched directly; if the rest is
+/// too large to diff, it is left unmatched.
+fn lcs<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> Vec<(usize, usize)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| eq(x, y)).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| eq(x, y))
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|k| (k, k)).collect();
+    let (n, m) = (a_mid.len(), b_mid.len());
+    if n > 0 && m > 0 && n.saturating_mul(m) <= MAX_LCS_CELLS {
+        // lengths[x][y] is the LCS length of a_mid[x..] and b_mid[y..].
+        let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+        let at = |x: usize, y: usize| x * (m + 1) + y;
+        for x in (0..n).rev() {
+            for y in (0..m).rev() {
+                lengths[at(x, y)] = if eq(&a_mid[x], &b_mid[y]) {
+                    lengths[at(x + 1, y + 1)] + 1
+                } else {
+                    lengths[at(x + 1, y)].max(lengths[at(x, y + 1)])
+                };
+            }
+        }
+        let (mut x, mut y) = (0, 0);
+        while x < n && y < m {
+            if eq(&a_mid[x], &b_mid[y]) {
+                pairs.push((prefix + x, prefix + y));
+                (x, y) = (x + 1, y + 1);
+            } else if lengths[at(x + 1, y)] >= lengths[at(x, y + 1)] {
+                x += 1;
+            } else {
+                y += 1;
+            }
+        }
+    }
+    pairs.extend((0..suffix).map(|k| (a.len() - suffix + k, b.len() - suffix + k)));
+    pairs
+}
+
+fn inlines(block: &Block) -> Option<&[Inline]> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+fn inlines_mut(block: &mut Block) -> Option<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+/// The content of the first paragraph in `block`, however deeply nested.
+fn first_inlines(block: &Block) -> Option<&[Inline]> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => children(block)?.first().and_then(first_inlines),
+    }
+}
+
+fn first_inlines_mut(block: &mut Block) -> Option<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => children_mut(block)?.first_mut().and_then(first_inlines_mut),
+    }
+}
+
+/// The content of the last paragraph in `block`, however deeply nested.
+fn last_inlines(block: &Block) -> Option<&[Inline]> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => children(block)?.last().and_then(last_inlines),
+    }
+}
+
+fn last_inlines_mut(block: &mut Block) -> Option<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => Some(content),
+        _ => children_mut(block)?.last_mut().and_then(last_inlines_mut),
+    }
+}
+
+fn children(block: &Block) -> Option<&[Block]> {
+    match block {
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+fn children_mut(block: &mut Block) -> Option<&mut Vec<Block>> {
+    match block {
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+/// An empty copy of a paragraph or heading, keeping its style.
+fn empty_like(block: &Block) -> Option<Block> {
+    let mut block = block.clone();
+    inlines_mut(&mut block)?.clear();
+    Some(block)
+}
+
+fn paragraph(content: Vec<Inline>) -> Block {
+    Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn para(text: &str) -> Block {
+        paragraph(vec![Inline::Text {
+            text: text.to_string(),
+            style_name: None,
+            marks: vec![],
+        }])
+    }
+
+    fn doc(blocks: Vec<Block>) -> Document {
+        let mut doc = Document::new();
+        doc.blocks = blocks;
+        doc
+    }
+
+    fn list(items: &[&str]) -> Block {
+        Block::BulletList {
+            attrs: None,
+            content: items
+                .iter()
+                .map(|item| Block::ListItem {
+                    content: vec![para(item)],
+                })
+                .collect(),
+        }
+    }
+
+    /// The plain text of each top-level block, with the blocks inside lists
+    /// and other containers in brackets.
+    fn texts(blocks: &[Block]) -> Vec<String> {
+        blocks
+            .iter()
+            .map(|block| match children(block) {
+                Some(children) => format!("[{}]", texts(children).join("|")),
+                None => inlines(block)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|inline| match inline {
+                        Inline::Text { text, .. } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn assert_redline(old: Vec<Block>, new: Vec<Block>) -> Document {
+        // An empty draft reads as one empty paragraph.
+        let read = |blocks: &[Block]| match blocks {
+            [] => vec![String::new()],
+            blocks => texts(blocks),
+        };
+        let (old, new) = (doc(old), doc(new));
+        let redline = compare_documents(&old, &new, &CompareOptions::default());
+        let mut accepted = redline.clone();
+        accepted.accept_all_changes();
+        assert_eq!(texts(&accepted.blocks), read(&new.blocks), "accept all");
+        let mut rejected = redline.clone();
+        rejected.reject_all_changes();
+        assert_eq!(texts(&rejected.blocks), read(&old.blocks), "reject all");
+        // The order in which changes are rejected does not matter.
+        let mut rejected = redline.clone();
+        let ids: Vec<String> = redline
+            .tracked_changes()
+            .iter()
+            .map(|change| change.id.clone())
+            .collect();
+        for id in ids.iter().rev() {
+            let _ = rejected.reject_change(id);
+        }
+        assert_eq!(
+            texts(&rejected.blocks),
+            read(&old.blocks),
+            "reject in reverse"
+        );
+        redline
+    }
+
+    #[test]
+    fn identical_documents_have_no_changes() {
+        let blocks = vec![para("One"), para("Two")];
+        let redline = assert_redline(blocks.clone(), blocks);
+        assert!(redline.tracked_changes().is_empty());
+    }
+
+    #[test]
+    fn changed_words_become_a_deletion_and_an_insertion() {
+        let redline = assert_redline(
+            vec![para("The quick brown fox jumps")],
+            vec![para("The quick red fox jumps")],
+        );
+        let kinds: Vec<_> = redline.tracked_changes().iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, [ChangeKind::Deletion, ChangeKind::Insertion]);
+    }
+
+    #[test]
+    fn added_and_removed_paragraphs_are_tracked() {
+        assert_redline(
+            vec![para("Intro"), para("Old middle"), para("End")],
+            vec![para("Intro"), para("End"), para("New closing words")],
+        );
+        assert_redline(
+            vec![para("Body")],
+            vec![para("A new opening line"), para("Body")],
+        );
+        assert_redline(
+            vec![para("Dropped first line"), para("Body")],
+            vec![para("Body")],
+        );
+    }
+
+    #[test]
+    fn replaced_and_extended_lists_are_restored_on_reject() {
+        assert_redline(
+            vec![para("x"), list(&["a b"]), para("y")],
+            vec![para("x"), para("q r"), para("y")],
+        );
+        assert_redline(
+            vec![para("x"), para("a b"), para("y")],
+            vec![para("x"), list(&["q r"]), para("y")],
+        );
+        assert_redline(
+            vec![para("x"), list(&["a"]), para("y")],
+            vec![para("x"), list(&["a", "b"]), para("y")],
+        );
+        assert_redline(
+            vec![list(&["a"]), para("y")],
+            vec![list(&["a", "b"]), para("y")],
+        );
+        assert_redline(
+            vec![para("x"), list(&["a"])],
+            vec![para("x"), list(&["a", "b"])],
+        );
+    }
+
+    #[test]
+    fn comparing_with_an_empty_document_leaves_no_stray_paragraph() {
+        let redline = assert_redline(vec![], vec![para("a")]);
+        assert_eq!(redline.blocks.len(), 1);
+        let redline = assert_redline(vec![para("a")], vec![]);
+        assert_eq!(redline.blocks.len(), 1);
+        let redline = assert_redline(vec![], vec![para("a"), para("b")]);
+        let mut rejected = redline.clone();
+        rejected.reject_all_changes();
+        assert_eq!(rejected.blocks.len(), 1);
+    }
+
+    #[test]
+    fn restyled_paragraph_is_a_format_change() {
+        let heading = Block::Heading {
+            level: 1,
+            style_name: None,
+            attrs: None,
+            content: vec![Inline::Text {
+                text: "Title".to_string(),
+                style_name: None,
+                marks: vec![],
+            }],
+        };
+        let redline = assert_redline(vec![para("Title")], vec![heading]);
+        assert_eq!(redline.tracked_changes()[0].kind, ChangeKind::FormatChange);
+    }
+
+    #[test]
+    fn formatting_only_change_is_a_format_change() {
+        let bold = paragraph(vec![Inline::Text {
+            text: "bold me".to_string(),
+            style_name: None,
+            marks: vec![common_core::TiptapMark::Bold],
+        }]);
+        let redline = assert_redline(vec![para("bold me")], vec![bold.clone()]);
+        let kinds: Vec<_> = redline.tracked_changes().iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, [ChangeKind::FormatChange]);
+        let mut rejected = redline.clone();
+        rejected.reject_all_changes();
+        assert_eq!(rejected.blocks, vec![bold], "no paragraph is added");
+    }
+
+    #[test]
+    fn replaced_last_paragraph_leaves_no_empty_paragraph() {
+        let redline = assert_redline(
+            vec![para("Intro"), para("Something else entirely")],
+            vec![para("Intro"), para("A different ending")],
+        );
+        assert_eq!(redline.blocks.len(), 2);
+        let mut rejected = redline.clone();
+        rejected.reject_all_changes();
+        assert_eq!(rejected.blocks.len(), 2);
+    }
+
+    #[test]
+    fn default_options_date_changes_now() {
+        let date = CompareOptions::default().date.unwrap();
+        assert!(date.starts_with(&common_core::field::today()[..8]));
+    }
+
+    #[test]
+    fn changes_carry_author_and_date() {
+        let options = CompareOptions {
+            author: Some("Reviewer".to_string()),
+            date: Some("2024-06-01T12:00:00".to_string()),
+        };
+        let redline = compare_documents(&doc(vec![para("a b")]), &doc(vec![para("a c")]), &options);
+        for change in redline.tracked_changes() {
+            assert_eq!(change.author.as_deref(), Some("Reviewer"));
+            assert_eq!(change.date.as_deref(), Some("2024-06-01T12:00:00"));
+        }
+    }
+
+    #[test]
+    fn tokenize_splits_words_and_spaces() {
+        let tokens = tokenize(&[Inline::Text {
+            text: "two  words".to_string(),
+            style_name: None,
+            marks: vec![],
+        }]);
+        assert_eq!(texts(&[paragraph(tokens.clone())]), ["two  words"]);
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(merge_runs(tokens).len(), 1);
+    }
+}
@@ -16,11 +16,14 @@
 
 use std::collections::HashMap;
 
-use common_core::{Block, Metadata, StyleDefinition};
+use common_core::{
+    walk::for_each_inline, Block, Comment, Inline, Metadata, OutlineStyle, PageSetup,
+    StyleDefinition, TrackedChange,
+};
 
 use crate::{
-    parser,
-    writer::{content, fodt, meta, styles_writer},
+    changes, parser,
+    writer::{content, fodt, meta, page, styles_writer},
 };
 
 /// The top-level ODT document model.
@@ -36,6 +39,12 @@ pub struct Document {
     pub styles: HashMap<String, StyleDefinition>,
     /// Document metadata (title, author, language, etc.).
     pub metadata: Metadata,
+    /// Whether changes are being recorded (`text:track-changes`).
+    pub track_changes: bool,
+    /// The heading numbering (`text:outline-style`), if the document has one.
+    pub outline_style: Option<OutlineStyle>,
+    /// The page layouts and master pages, if the document defines any.
+    pub page_setup: Option<PageSetup>,
     /// Preserved `<office:font-face-decls>` XML for round-trip fidelity.
     pub font_face_decls: Option<String>,
     /// Preserved `<office:automatic-styles>` XML for round-trip fidelity.
@@ -68,6 +77,9 @@ impl Document {
             blocks: Vec::new(),
             styles: HashMap::new(),
             metadata: Metadata::default(),
+            track_changes: false,
+            outline_style: None,
+            page_setup: None,
             font_face_decls: None,
             automatic_styles: None,
             master_styles: None,
@@ -79,6 +91,12 @@ impl Document {
         parser::parse_document(xml)
     }
 
+    /// Parses a `content.xml` string with the `styles.xml` of the same
+    /// package, resolving the styles it references from there.
+    pub fn from_xml_with_styles(xml: &str, styles_xml: &str) -> Result<Self, String> {
+        parser::parse_document_with_styles(xml, styles_xml)
+    }
+
     /// Merges named styles from a `styles.xml` string into this document.
     pub fn add_styles_from_xml(&mut self, xml: &str) -> Result<(), String> {
         parser::add_styles_from_xml(self, xml)
@@ -86,14 +104,17 @@ impl Document {
 
     /// Serializes this document to a complete FODT XML string.
     pub fn to_xml(&self) -> Result<String, String> {
-        fodt::to_xml(
+        let xml = fodt::to_xml(
             &self.blocks,
+            self.track_changes,
             &self.styles,
-            &self.metadata,
+            self.outline_style.as_ref(),
+            &self.metadata_for_save(),
             &self.font_face_decls,
             &self.automatic_styles,
             &self.master_styles,
-        )
+        )?;
+        self.with_page_setup(xml)
     }
 
     /// Updates an existing FODT XML string with this document's content.
@@ -101,7 +122,7 @@ impl Document {
         let content_xml = self.to_content_xml()?;
         let styles_xml = self.styles_to_xml()?;
         let meta_xml = self.to_meta_xml()?;
-        fodt::update_fodt(
+        let xml = fodt::update_fodt(
             old_xml,
             &self.blocks,
             &self.styles,
@@ -109,26 +130,115 @@ impl Document {
             &content_xml,
             &styles_xml,
             &meta_xml,
-        )
+        )?;
+        self.with_page_setup(xml)
+    }
+
+    /// Updates an existing `styles.xml` string with this document's named
+    /// styles, page layouts and master pages.
+    pub fn update_styles_xml(&self, old_xml: &str) -> Result<String, String> {
+        let xml = fodt::update_styles(old_xml, &self.styles_to_xml()?)?;
+        self.with_page_setup(xml)
     }
 
     /// Generates a `content.xml` string for use in an ODT ZIP archive.
     pub fn to_content_xml(&self) -> Result<String, String> {
-        content::to_content_xml(&self.blocks)
+        content::to_content_xml(&self.blocks, self.track_changes)
     }
 
     /// Generates a `styles.xml` string for use in an ODT ZIP archive.
     pub fn styles_to_xml(&self) -> Result<String, String> {
-        styles_writer::styles_to_xml(
+        let xml = styles_writer::styles_to_xml(
             &self.styles,
+            self.outline_style.as_ref(),
             &self.font_face_decls,
             &self.automatic_styles,
             &self.master_styles,
-        )
+        )?;
+        self.with_page_setup(xml)
+    }
+
+    /// Rewrites the page layouts and master pages of a written FODT or
+    /// `styles.xml` from [`Document::page_setup`].
+    fn with_page_setup(&self, xml: String) -> Result<String, String> {
+        match &self.page_setup {
+            Some(setup) => page::write_page_setup(&xml, setup),
+            None => Ok(xml),
+        }
     }
 
     /// Generates a `meta.xml` string for use in an ODT ZIP archive.
+    ///
+    /// The document statistics are recomputed from the blocks.
     pub fn to_meta_xml(&self) -> Result<String, String> {
-        meta::to_meta_xml(&self.metadata)
+        meta::to_meta_xml(&self.metadata_for_save())
+    }
+
+    /// Returns the metadata with statistics counted from the blocks.
+    fn metadata_for_save(&self) -> Metadata {
+        let mut metadata = self.metadata.clone();
+        metadata.update_statistics(&self.blocks);
+        metadata
+    }
+
+    /// Returns the comments anchored in the document, in document order.
+    ///
+    /// Replies are reached through [`Comment::replies`]; comments inside
+    /// notes, lists and tables are included.
+    #[must_use]
+    pub fn comments(&self) -> Vec<&Comment> {
+        let mut comments = Vec::new();
+        for_each_inline(&self.blocks, &mut |inline| {
+            if let Inline::Comment(comment) = inline {
+                comments.push(comment);
+            }
+        });
+        comments
+    }
+
+    /// Returns the blocks with the outline number written before every
+    /// numbered heading, for exporters that show headings as plain text.
+    #[must_use]
+    pub fn numbered_blocks(&self) -> Vec<Block> {
+        let mut blocks = self.blocks.clone();
+        if let Some(outline) = &self.outline_style {
+            outline.number_headings(&mut blocks);
+        }
+        blocks
+    }
+
+    /// Returns the tracked changes in the document, in document order.
+    #[must_use]
+    pub fn tracked_changes(&self) -> Vec<&TrackedChange> {
+        changes::tracked_changes(&self.blocks)
+    }
+
+    /// Accepts the tracked change with the given id, keeping the text as it
+    /// currently reads.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if there is no change with that id.
+    pub fn accept_change(&mut self, id: &str) -> Result<(), String> {
+        changes::accept_change(&mut self.blocks, id)
+    }
+
+    /// Rejects the tracked change with the given id, undoing the edit.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if there is no change with that id.
+    pub fn reject_change(&mut self, id: &str) -> Result<(), String> {
+        changes::reject_change(&mut self.blocks, id)
+    }
+
+    /// Accepts every tracked change.
+    pub fn accept_all_changes(&mut self) {
+        changes::accept_all(&mut self.blocks);
+    }
+
+    /// Rejects every tracked change.
+    pub fn reject_all_changes(&mut self) {
+        changes::reject_all(&mut self.blocks);
     }
 }
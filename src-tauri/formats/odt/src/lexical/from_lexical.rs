@@ -6,13 +6,16 @@
 
 use std::collections::HashMap;
 
-use common_core::block::CellAttrs;
+use common_core::block::{CellAttrs, ListAttrs, SectionAttrs};
 use common_core::lexical::{
     LexicalDocument, LexicalNode, FORMAT_BOLD, FORMAT_ITALIC, FORMAT_STRIKETHROUGH,
     FORMAT_SUBSCRIPT, FORMAT_SUPERSCRIPT, FORMAT_UNDERLINE,
 };
-use common_core::marks::{LinkAttrs, TiptapAttrsInline, TiptapMark};
-use common_core::{Block, BlockAttrs, Inline, Metadata, StyleDefinition};
+use common_core::marks::{LinkAttrs, TextStyleAttrs, TiptapAttrsInline, TiptapMark};
+use common_core::{
+    Block, BlockAttrs, Comment, CrossReference, Inline, Metadata, NoteClass, StyleDefinition,
+    TableOfContents, TrackedChange,
+};
 
 use crate::lexical::style_has_break_before;
 use crate::Document;
@@ -77,6 +80,9 @@ pub fn from_lexical(
         blocks,
         styles,
         metadata,
+        track_changes: false,
+        outline_style: None,
+        page_setup: None,
         font_face_decls: None,
         automatic_styles: None,
         master_styles: None,
@@ -120,14 +126,23 @@ pub fn node_to_block(node: LexicalNode) -> Option<Block> {
                 content: children.into_iter().flat_map(node_to_inlines).collect(),
             })
         }
-        LexicalNode::Image { src, alt_text, .. } => Some(Block::Image {
+        LexicalNode::Image {
+            src,
+            alt_text,
+            title,
+            frame,
+            caption,
+            ..
+        } => Some(Block::Image {
             src,
             alt: if alt_text.is_empty() {
                 None
             } else {
                 Some(alt_text)
             },
-            title: None,
+            title,
+            frame,
+            caption,
         }),
         LexicalNode::Table { children, .. } => Some(Block::Table {
             content: children.into_iter().filter_map(node_to_block).collect(),
@@ -139,16 +154,25 @@ pub fn node_to_block(node: LexicalNode) -> Option<Block> {
             col_span,
             row_span,
             header_state,
+            width,
+            background_color,
             children,
             ..
         } => {
+            // Lexical keeps one width per cell; spread it over the columns
+            let colwidth = width.filter(|&w| w > 0.0).map(|w| {
+                let span = col_span.max(1);
+                vec![(w / f64::from(span)).round() as u32; span as usize]
+            });
             let attrs = Some(CellAttrs {
                 colspan: if col_span == 1 { None } else { Some(col_span) },
                 rowspan: if row_span == 1 { None } else { Some(row_span) },
-                colwidth: None,
+                colwidth,
+                background_color,
+                border: None,
             });
             let content = children.into_iter().filter_map(node_to_block).collect();
-            if header_state == 1 {
+            if header_state & 1 != 0 {
                 Some(Block::TableHeader { attrs, content })
             } else {
                 Some(Block::TableCell { attrs, content })
@@ -156,14 +180,28 @@ pub fn node_to_block(node: LexicalNode) -> Option<Block> {
         }
         LexicalNode::List {
             list_type,
+            start,
+            list_attrs,
             children,
             ..
         } => {
             let items = children.into_iter().filter_map(node_to_block).collect();
             if list_type == "number" {
-                Some(Block::OrderedList { content: items })
+                // The editor's `start` is authoritative over the stored attrs.
+                let mut attrs = list_attrs;
+                if start != 1 || attrs.is_some() {
+                    attrs.get_or_insert_with(ListAttrs::default).start =
+                        (start != 1).then_some(start);
+                }
+                Some(Block::OrderedList {
+                    attrs,
+                    content: items,
+                })
             } else {
-                Some(Block::BulletList { content: items })
+                Some(Block::BulletList {
+                    attrs: list_attrs,
+                    content: items,
+                })
             }
         }
         LexicalNode::ListItem { children, .. } => Some(Block::ListItem {
@@ -172,9 +210,54 @@ pub fn node_to_block(node: LexicalNode) -> Option<Block> {
         LexicalNode::Quote { children, .. } => Some(Block::Blockquote {
             content: children.into_iter().filter_map(node_to_block).collect(),
         }),
+        LexicalNode::Section {
+            name,
+            column_count,
+            column_gap,
+            protected,
+            children,
+            ..
+        } => {
+            let attrs = SectionAttrs {
+                column_count: (column_count > 1).then_some(column_count),
+                column_gap,
+            };
+            Some(Block::Section {
+                name,
+                attrs: (!attrs.is_empty()).then_some(attrs),
+                protected,
+                content: children.into_iter().filter_map(node_to_block).collect(),
+            })
+        }
         LexicalNode::PageBreak { .. } => Some(Block::PageBreak),
+        LexicalNode::TableOfContents {
+            name,
+            title,
+            outline_level,
+            entries,
+            ..
+        } => Some(Block::TableOfContents(TableOfContents {
+            name,
+            title,
+            outline_level,
+            entries,
+        })),
         // Inline-only nodes cannot appear at block level
-        LexicalNode::Text { .. } | LexicalNode::LineBreak { .. } | LexicalNode::Link { .. } => None,
+        LexicalNode::Text { .. }
+        | LexicalNode::Tab { .. }
+        | LexicalNode::LineBreak { .. }
+        | LexicalNode::Link { .. }
+        | LexicalNode::Note { .. }
+        | LexicalNode::Comment { .. }
+        | LexicalNode::CommentEnd { .. }
+        | LexicalNode::ChangeStart { .. }
+        | LexicalNode::ChangeEnd { .. }
+        | LexicalNode::Change { .. }
+        | LexicalNode::Field { .. }
+        | LexicalNode::Bookmark { .. }
+        | LexicalNode::BookmarkStart { .. }
+        | LexicalNode::BookmarkEnd { .. }
+        | LexicalNode::Reference { .. } => None,
     }
 }
 
@@ -186,16 +269,94 @@ pub fn node_to_inlines(node: LexicalNode) -> Vec<Inline> {
         LexicalNode::Text {
             text,
             format,
+            style,
             style_name,
             ..
         } => {
+            let mut marks = decode_format(format, style_name.clone());
+            marks.extend(decode_text_style(&style));
             vec![Inline::Text {
                 text,
-                style_name: style_name.clone(),
-                marks: decode_format(format, style_name),
+                style_name,
+                marks,
             }]
         }
         LexicalNode::LineBreak { .. } => vec![Inline::LineBreak],
+        LexicalNode::Tab { .. } => vec![Inline::Tab],
+        LexicalNode::Note {
+            note_class,
+            citation,
+            note_id,
+            children,
+            ..
+        } => vec![Inline::Note {
+            id: note_id,
+            note_class: NoteClass::from_odf_str(&note_class),
+            citation,
+            content: children.into_iter().filter_map(node_to_block).collect(),
+        }],
+        LexicalNode::Comment { .. } => node_to_comment(node)
+            .map(Inline::Comment)
+            .into_iter()
+            .collect(),
+        LexicalNode::CommentEnd { comment_id, .. } => vec![Inline::CommentEnd { id: comment_id }],
+        LexicalNode::ChangeStart {
+            change_id,
+            kind,
+            author,
+            date,
+            ..
+        } => vec![Inline::ChangeStart(TrackedChange {
+            id: change_id,
+            kind,
+            author,
+            date,
+            content: Vec::new(),
+        })],
+        LexicalNode::ChangeEnd { change_id, .. } => vec![Inline::ChangeEnd { id: change_id }],
+        LexicalNode::Change {
+            change_id,
+            kind,
+            author,
+            date,
+            children,
+            ..
+        } => vec![Inline::Change(TrackedChange {
+            id: change_id,
+            kind,
+            author,
+            date,
+            content: children.into_iter().filter_map(node_to_block).collect(),
+        })],
+        LexicalNode::Field {
+            kind,
+            value,
+            fixed,
+            date_value,
+            data_style_name,
+            ..
+        } => vec![Inline::Field {
+            kind,
+            value,
+            fixed,
+            date_value,
+            data_style_name,
+        }],
+        LexicalNode::Bookmark { name, .. } => vec![Inline::Bookmark { name }],
+        LexicalNode::BookmarkStart { name, .. } => vec![Inline::BookmarkStart { name }],
+        LexicalNode::BookmarkEnd { name, .. } => vec![Inline::BookmarkEnd { name }],
+        LexicalNode::Reference {
+            kind,
+            name,
+            format,
+            value,
+            ..
+        } => vec![Inline::Reference(CrossReference {
+            kind,
+            name,
+            format,
+            value,
+        })],
         LexicalNode::Link {
             url,
             target,
@@ -230,6 +391,30 @@ pub fn node_to_inlines(node: LexicalNode) -> Vec<Inline> {
     }
 }
 
+/// Converts a `"comment"` node and its replies to a [`Comment`].
+fn node_to_comment(node: LexicalNode) -> Option<Comment> {
+    let LexicalNode::Comment {
+        comment_id,
+        author,
+        date,
+        resolved,
+        children,
+        replies,
+        ..
+    } = node
+    else {
+        return None;
+    };
+    Some(Comment {
+        id: comment_id,
+        author,
+        date,
+        resolved,
+        content: children.into_iter().filter_map(node_to_block).collect(),
+        replies: replies.into_iter().filter_map(node_to_comment).collect(),
+    })
+}
+
 fn block_attrs(format: String, indent: u32) -> Option<BlockAttrs> {
     let text_align = if format.is_empty() {
         None
@@ -243,6 +428,7 @@ fn block_attrs(format: String, indent: u32) -> Option<BlockAttrs> {
         Some(BlockAttrs {
             text_align,
             indent: indent_val,
+            ..BlockAttrs::default()
         })
     }
 }
@@ -279,6 +465,41 @@ pub(crate) fn decode_format(format: u32, style_name: Option<String>) -> Vec<Tipt
     marks
 }
 
+/// Decodes a Lexical inline CSS `style` string into a `TextStyle` mark.
+///
+/// Reads `color`, `background-color`, `font-family` and `font-size`; other
+/// declarations are ignored. Pixel sizes are converted to points.
+pub(crate) fn decode_text_style(style: &str) -> Option<TiptapMark> {
+    use crate::loki_ext::parse_colour_str;
+
+    let mut attrs = TextStyleAttrs::default();
+    for decl in style.split(';') {
+        let Some((prop, value)) = decl.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match prop.trim() {
+            "color" => attrs.colour = parse_colour_str(value),
+            "background-color" => {
+                attrs.background_colour = parse_colour_str(value).filter(|c| !c.is_transparent())
+            }
+            "font-family" => {
+                let family = value.trim_matches(|c| c == '\'' || c == '"');
+                attrs.font_family = (!family.is_empty()).then(|| family.to_string());
+            }
+            "font-size" => {
+                attrs.font_size = if let Some(px) = value.strip_suffix("px") {
+                    px.trim().parse::<f32>().ok().map(|v| v * 0.75)
+                } else {
+                    value.trim_end_matches("pt").trim().parse::<f32>().ok()
+                };
+            }
+            _ => {}
+        }
+    }
+    (!attrs.is_empty()).then_some(TiptapMark::TextStyle { attrs })
+}
+
 #[cfg(test)]
 #[path = "from_lexical_tests.rs"]
 mod tests;
@@ -44,8 +44,11 @@ fn page_break_before_break_before_style_is_dropped() {
             text_transform: None,
             outline_level: Some(1),
             autocomplete: None,
+            master_page_name: None,
             font_colour: None,
             background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
         },
     );
 
@@ -54,6 +57,7 @@ fn page_break_before_break_before_style_is_dropped() {
         LexicalNode::HeadingStyle {
             tag: "h1".to_string(),
             style_name: Some("Heading1".to_string()),
+            number: None,
             children: vec![],
             direction: None,
             format: String::new(),
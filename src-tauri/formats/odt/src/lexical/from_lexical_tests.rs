@@ -2,9 +2,9 @@ use std::collections::HashMap;
 
 use common_core::lexical::{LexicalDocument, LexicalNode, LexicalRoot, FORMAT_BOLD, FORMAT_ITALIC};
 use common_core::marks::TiptapMark;
-use common_core::{Block, Inline, Metadata};
+use common_core::{Block, Inline, ListAttrs, Metadata};
 
-use super::{decode_format, from_lexical, node_to_block, node_to_inlines};
+use super::{decode_format, decode_text_style, from_lexical, node_to_block, node_to_inlines};
 use crate::lexical::to_lexical;
 
 fn make_lex(children: Vec<LexicalNode>) -> LexicalDocument {
@@ -64,6 +64,7 @@ fn heading_style_level_extracted_from_tag() {
     let node = LexicalNode::HeadingStyle {
         tag: "h2".to_string(),
         style_name: None,
+        number: None,
         children: vec![],
         direction: None,
         format: String::new(),
@@ -86,6 +87,31 @@ fn bold_italic_format_decodes_to_marks() {
     assert!(!marks.contains(&TiptapMark::Underline));
 }
 
+#[test]
+fn css_style_string_decodes_to_text_style_mark() {
+    use common_core::colour_management::Colour;
+
+    let Some(TiptapMark::TextStyle { attrs }) = decode_text_style(
+        "color: #ff0000; background-color: rgb(255, 255, 0); font-family: 'Noto Sans'; font-size: 16px",
+    ) else {
+        panic!("expected TextStyle mark");
+    };
+    assert_eq!(attrs.colour, Some(Colour::from_u8_rgb(255, 0, 0)));
+    assert_eq!(
+        attrs.background_colour,
+        Some(Colour::from_u8_rgb(255, 255, 0))
+    );
+    assert_eq!(attrs.font_family.as_deref(), Some("Noto Sans"));
+    assert_eq!(attrs.font_size, Some(12.0));
+
+    // The CSS written by to_lexical reads back unchanged.
+    assert_eq!(
+        decode_text_style(&attrs.to_css()),
+        Some(TiptapMark::TextStyle { attrs })
+    );
+    assert_eq!(decode_text_style(""), None);
+}
+
 #[test]
 fn link_node_becomes_link_mark_on_text() {
     let link_node = LexicalNode::Link {
@@ -167,6 +193,7 @@ fn list_type_number_produces_ordered_list() {
         list_type: "number".to_string(),
         start: 1,
         tag: "ol".to_string(),
+        list_attrs: None,
         children: vec![],
         direction: None,
         format: String::new(),
@@ -179,12 +206,40 @@ fn list_type_number_produces_ordered_list() {
     ));
 }
 
+#[test]
+fn list_start_is_carried_into_ordered_list_attrs() {
+    let node = LexicalNode::List {
+        list_type: "number".to_string(),
+        start: 4,
+        tag: "ol".to_string(),
+        list_attrs: Some(ListAttrs {
+            style_name: Some("L1".to_string()),
+            num_format: Some("i".to_string()),
+            ..ListAttrs::default()
+        }),
+        children: vec![],
+        direction: None,
+        format: String::new(),
+        indent: 0,
+        version: 1,
+    };
+    if let Some(Block::OrderedList { attrs: Some(a), .. }) = node_to_block(node) {
+        assert_eq!(a.start, Some(4));
+        assert_eq!(a.style_name.as_deref(), Some("L1"));
+        assert_eq!(a.num_format.as_deref(), Some("i"));
+    } else {
+        panic!("expected OrderedList with attrs");
+    }
+}
+
 #[test]
 fn table_cell_header_state_selects_variant() {
     let header = LexicalNode::TableCell {
         col_span: 1,
         row_span: 1,
         header_state: 1,
+        width: None,
+        background_color: None,
         children: vec![],
         direction: None,
         format: String::new(),
@@ -200,6 +255,8 @@ fn table_cell_header_state_selects_variant() {
         col_span: 2,
         row_span: 1,
         header_state: 0,
+        width: Some(200.0),
+        background_color: Some("#ffff00".to_string()),
         children: vec![],
         direction: None,
         format: String::new(),
@@ -207,7 +264,10 @@ fn table_cell_header_state_selects_variant() {
         version: 1,
     };
     if let Some(Block::TableCell { attrs, .. }) = node_to_block(cell) {
-        assert_eq!(attrs.unwrap().colspan, Some(2));
+        let attrs = attrs.unwrap();
+        assert_eq!(attrs.colspan, Some(2));
+        assert_eq!(attrs.colwidth, Some(vec![100, 100]));
+        assert_eq!(attrs.background_color.as_deref(), Some("#ffff00"));
     } else {
         panic!("expected TableCell");
     }
@@ -241,6 +301,8 @@ fn cell_node(text: &str, col_span: u32) -> LexicalNode {
         col_span,
         row_span: 1,
         header_state: 0,
+        width: None,
+        background_color: None,
         children: vec![para_node(text)],
         direction: None,
         format: String::new(),
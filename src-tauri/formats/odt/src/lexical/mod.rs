@@ -10,11 +10,16 @@
 //! open:  ODT file → Document → to_lexical() → LexicalDocument → frontend
 //! save:  frontend → LexicalDocument → from_lexical() → Document → ODT file
 //! ```
+//!
+//! Headers and footers are exchanged separately through
+//! [`page_regions_to_lexical`] and [`page_regions_from_lexical`].
 
 mod from_lexical;
+mod page_regions;
 mod to_lexical;
 
 pub use from_lexical::from_lexical;
+pub use page_regions::{page_regions_from_lexical, page_regions_to_lexical};
 pub use to_lexical::to_lexical;
 
 use common_core::StyleDefinition;
@@ -0,0 +1,155 @@
+//! Header and footer content ↔ Lexical editor states.
+//!
+//! The headers and footers of a [`PageSetup`] travel to the frontend as one
+//! [`LexicalPageRegion`] per variant, and the page setup itself without
+//! them.
+
+use std::collections::HashMap;
+
+use common_core::lexical::{PageRegionKind, PageRegionVariant};
+use common_core::{Block, HeaderFooter, LexicalPageRegion, Metadata, PageSetup};
+
+use super::{from_lexical, to_lexical};
+use crate::Document;
+
+/// Takes the headers and footers out of `setup` as Lexical editor states.
+#[must_use]
+pub fn page_regions_to_lexical(setup: &mut PageSetup) -> Vec<LexicalPageRegion> {
+    let mut regions = Vec::new();
+    for master in &mut setup.master_pages {
+        for (kind, region) in [
+            (PageRegionKind::Header, master.header.take()),
+            (PageRegionKind::Footer, master.footer.take()),
+        ] {
+            let Some(region) = region else {
+                continue;
+            };
+            let variants = [
+                (PageRegionVariant::Default, Some(region.content)),
+                (PageRegionVariant::Left, region.left),
+                (PageRegionVariant::First, region.first),
+            ];
+            for (variant, blocks) in variants {
+                if let Some(blocks) = blocks {
+                    let mut doc = Document::new();
+                    doc.blocks = blocks;
+                    regions.push(LexicalPageRegion {
+                        master_page: master.name.clone(),
+                        kind,
+                        variant,
+                        content: to_lexical(&doc),
+                    });
+                }
+            }
+        }
+    }
+    regions
+}
+
+/// Replaces the headers and footers of `setup` with `regions`.
+///
+/// Regions of master pages that `setup` does not define are ignored.
+pub fn page_regions_from_lexical(setup: &mut PageSetup, regions: Vec<LexicalPageRegion>) {
+    for master in &mut setup.master_pages {
+        master.header = None;
+        master.footer = None;
+    }
+    for region in regions {
+        let Some(master) = setup
+            .master_pages
+            .iter_mut()
+            .find(|m| m.name == region.master_page)
+        else {
+            continue;
+        };
+        let target = match region.kind {
+            PageRegionKind::Header => &mut master.header,
+            PageRegionKind::Footer => &mut master.footer,
+        };
+        let target = target.get_or_insert_with(HeaderFooter::default);
+        let blocks: Vec<Block> =
+            from_lexical(region.content, HashMap::new(), Metadata::default()).blocks;
+        match region.variant {
+            PageRegionVariant::Default => target.content = blocks,
+            PageRegionVariant::Left => target.left = Some(blocks),
+            PageRegionVariant::First => target.first = Some(blocks),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_core::{FieldKind, Inline, MasterPage};
+
+    fn paragraph(content: Vec<Inline>) -> Block {
+        Block::Paragraph {
+            style_name: Some("Footer".to_string()),
+            attrs: None,
+            content,
+        }
+    }
+
+    #[test]
+    fn regions_round_trip_through_lexical() {
+        let footer = HeaderFooter {
+            content: vec![paragraph(vec![
+                Inline::Text {
+                    text: "Page ".to_string(),
+                    style_name: None,
+                    marks: vec![],
+                },
+                Inline::Field {
+                    kind: FieldKind::PageNumber,
+                    value: "1".to_string(),
+                    fixed: false,
+                    date_value: None,
+                    data_style_name: None,
+                },
+            ])],
+            left: None,
+            first: Some(vec![]),
+        };
+        let mut setup = PageSetup {
+            layouts: vec![],
+            master_pages: vec![MasterPage {
+                name: "Standard".to_string(),
+                page_layout: "pm1".to_string(),
+                footer: Some(footer.clone()),
+                ..MasterPage::default()
+            }],
+        };
+        let original = setup.clone();
+
+        let regions = page_regions_to_lexical(&mut setup);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].kind, PageRegionKind::Footer);
+        assert_eq!(regions[1].variant, PageRegionVariant::First);
+        assert_eq!(setup.master_pages[0].footer, None);
+
+        let json = serde_json::to_value(&regions[0]).unwrap();
+        assert_eq!(json["masterPage"], "Standard");
+        assert_eq!(json["kind"], "footer");
+        assert_eq!(json["variant"], "default");
+
+        page_regions_from_lexical(&mut setup, regions);
+        assert_eq!(setup, original);
+    }
+
+    #[test]
+    fn regions_of_unknown_master_pages_are_ignored() {
+        let mut setup = PageSetup::default();
+        let mut doc = Document::new();
+        doc.blocks = vec![paragraph(vec![])];
+        page_regions_from_lexical(
+            &mut setup,
+            vec![LexicalPageRegion {
+                master_page: "Missing".to_string(),
+                kind: PageRegionKind::Header,
+                variant: PageRegionVariant::Default,
+                content: to_lexical(&doc),
+            }],
+        );
+        assert_eq!(setup, PageSetup::default());
+    }
+}
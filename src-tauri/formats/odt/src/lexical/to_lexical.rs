@@ -4,13 +4,13 @@
 //! Lexical editor expects, matching the custom node types registered in the
 //! TypeScript frontend (`paragraph-style`, `heading-style`, etc.).
 
-use common_core::block::CellAttrs;
+use common_core::block::{CellAttrs, SectionAttrs};
 use common_core::lexical::{
     LexicalDocument, LexicalNode, LexicalRoot, FORMAT_BOLD, FORMAT_ITALIC, FORMAT_STRIKETHROUGH,
     FORMAT_SUBSCRIPT, FORMAT_SUPERSCRIPT, FORMAT_UNDERLINE,
 };
 use common_core::marks::TiptapMark;
-use common_core::{Block, Inline};
+use common_core::{Block, Comment, Inline, TrackedChange};
 
 use crate::lexical::style_has_break_before;
 use crate::Document;
@@ -52,6 +52,10 @@ pub fn to_lexical(doc: &Document) -> LexicalDocument {
         prev_was_page_break = matches!(block, Block::PageBreak);
         children.push(block_to_node(block));
     }
+    if let Some(outline) = doc.outline_style.as_ref().filter(|o| o.is_numbered()) {
+        let mut numbers = outline.heading_numbers(&doc.blocks).into_iter();
+        number_heading_nodes(&mut children, &mut numbers);
+    }
     LexicalDocument {
         root: LexicalRoot {
             children,
@@ -64,6 +68,26 @@ pub fn to_lexical(doc: &Document) -> LexicalDocument {
     }
 }
 
+/// Gives the heading nodes among `nodes` their outline numbers, which come
+/// in the document order of the headings.
+fn number_heading_nodes(nodes: &mut [LexicalNode], numbers: &mut impl Iterator<Item = String>) {
+    for node in nodes {
+        match node {
+            LexicalNode::HeadingStyle { number, .. } => {
+                *number = numbers.next().filter(|n| !n.is_empty());
+            }
+            LexicalNode::List { children, .. }
+            | LexicalNode::ListItem { children, .. }
+            | LexicalNode::Quote { children, .. }
+            | LexicalNode::Section { children, .. }
+            | LexicalNode::Table { children, .. }
+            | LexicalNode::TableRow { children, .. }
+            | LexicalNode::TableCell { children, .. } => number_heading_nodes(children, numbers),
+            _ => {}
+        }
+    }
+}
+
 /// Converts a single [`Block`] to a [`LexicalNode`].
 pub fn block_to_node(block: &Block) -> LexicalNode {
     match block {
@@ -90,6 +114,7 @@ pub fn block_to_node(block: &Block) -> LexicalNode {
         } => LexicalNode::HeadingStyle {
             tag: format!("h{}", level.min(&6)),
             style_name: style_name.clone(),
+            number: None,
             children: inlines_to_nodes(content),
             direction: None,
             format: attrs
@@ -99,25 +124,36 @@ pub fn block_to_node(block: &Block) -> LexicalNode {
             indent: attrs.as_ref().and_then(|a| a.indent).unwrap_or(0),
             version: 1,
         },
-        Block::Image { src, alt, title } => LexicalNode::Image {
+        Block::Image {
+            src,
+            alt,
+            title,
+            frame,
+            caption,
+        } => LexicalNode::Image {
             src: src.clone(),
-            alt_text: alt.clone().or_else(|| title.clone()).unwrap_or_default(),
+            alt_text: alt.clone().unwrap_or_default(),
+            title: title.clone(),
+            frame: frame.clone(),
+            caption: caption.clone(),
             version: 1,
         },
-        Block::BulletList { content } => LexicalNode::List {
+        Block::BulletList { attrs, content } => LexicalNode::List {
             list_type: "bullet".to_string(),
             start: 1,
             tag: "ul".to_string(),
+            list_attrs: attrs.clone(),
             children: content.iter().map(block_to_node).collect(),
             direction: None,
             format: String::new(),
             indent: 0,
             version: 1,
         },
-        Block::OrderedList { content } => LexicalNode::List {
+        Block::OrderedList { attrs, content } => LexicalNode::List {
             list_type: "number".to_string(),
-            start: 1,
+            start: attrs.as_ref().and_then(|a| a.start).unwrap_or(1),
             tag: "ol".to_string(),
+            list_attrs: attrs.clone(),
             children: content.iter().map(block_to_node).collect(),
             direction: None,
             format: String::new(),
@@ -139,6 +175,22 @@ pub fn block_to_node(block: &Block) -> LexicalNode {
             indent: 0,
             version: 1,
         },
+        Block::Section {
+            name,
+            attrs,
+            protected,
+            content,
+        } => LexicalNode::Section {
+            name: name.clone(),
+            column_count: attrs.as_ref().map_or(1, SectionAttrs::columns),
+            column_gap: attrs.as_ref().and_then(|a| a.column_gap),
+            protected: *protected,
+            children: content.iter().map(block_to_node).collect(),
+            direction: None,
+            format: String::new(),
+            indent: 0,
+            version: 1,
+        },
         Block::Table { content } => LexicalNode::Table {
             children: content.iter().map(block_to_node).collect(),
             direction: None,
@@ -167,6 +219,13 @@ pub fn block_to_node(block: &Block) -> LexicalNode {
             }
         }
         Block::PageBreak => LexicalNode::PageBreak { version: 1 },
+        Block::TableOfContents(toc) => LexicalNode::TableOfContents {
+            name: toc.name.clone(),
+            title: toc.title.clone(),
+            outline_level: toc.outline_level,
+            entries: toc.entries.clone(),
+            version: 1,
+        },
     }
 }
 
@@ -175,6 +234,11 @@ fn table_cell_node(attrs: &Option<CellAttrs>, content: &[Block], is_header: bool
         col_span: attrs.as_ref().and_then(|a| a.colspan).unwrap_or(1),
         row_span: attrs.as_ref().and_then(|a| a.rowspan).unwrap_or(1),
         header_state: if is_header { 1 } else { 0 },
+        width: attrs
+            .as_ref()
+            .and_then(|a| a.colwidth.as_ref())
+            .map(|w| w.iter().map(|&px| f64::from(px)).sum()),
+        background_color: attrs.as_ref().and_then(|a| a.background_color.clone()),
         children: content.iter().map(block_to_node).collect(),
         direction: None,
         format: String::new(),
@@ -204,6 +268,7 @@ pub fn inlines_to_nodes(inlines: &[Inline]) -> Vec<LexicalNode> {
                 });
                 let mut format: u32 = 0;
                 let mut span_style: Option<String> = style_name.clone();
+                let mut css = String::new();
                 for mark in marks {
                     match mark {
                         TiptapMark::Bold => format |= FORMAT_BOLD,
@@ -215,13 +280,14 @@ pub fn inlines_to_nodes(inlines: &[Inline]) -> Vec<LexicalNode> {
                         TiptapMark::NamedSpanStyle { attrs } => {
                             span_style = attrs.style_name.clone();
                         }
+                        TiptapMark::TextStyle { attrs } => css = attrs.to_css(),
                         TiptapMark::Link { .. } => {}
                     }
                 }
                 let text_node = LexicalNode::Text {
                     text: text.clone(),
                     format,
-                    style: String::new(),
+                    style: css,
                     mode: "normal".to_string(),
                     detail: 0,
                     style_name: span_style,
@@ -243,11 +309,115 @@ pub fn inlines_to_nodes(inlines: &[Inline]) -> Vec<LexicalNode> {
                 }
             }
             Inline::LineBreak => out.push(LexicalNode::LineBreak { version: 1 }),
+            Inline::Space { count } => out.push(LexicalNode::Text {
+                text: " ".repeat(*count as usize),
+                format: 0,
+                style: String::new(),
+                mode: "normal".to_string(),
+                detail: 0,
+                style_name: None,
+                version: 1,
+            }),
+            Inline::Tab => out.push(LexicalNode::Tab {
+                text: "\t".to_string(),
+                format: 0,
+                style: String::new(),
+                mode: "normal".to_string(),
+                detail: 2,
+                version: 1,
+            }),
+            Inline::Note {
+                id,
+                note_class,
+                citation,
+                content,
+            } => out.push(LexicalNode::Note {
+                note_class: note_class.to_odf_str().to_string(),
+                citation: citation.clone(),
+                note_id: id.clone(),
+                children: content.iter().map(block_to_node).collect(),
+                version: 1,
+            }),
+            Inline::Comment(comment) => out.push(comment_to_node(comment)),
+            Inline::CommentEnd { id } => out.push(LexicalNode::CommentEnd {
+                comment_id: id.clone(),
+                version: 1,
+            }),
+            Inline::ChangeStart(change) => out.push(LexicalNode::ChangeStart {
+                change_id: change.id.clone(),
+                kind: change.kind,
+                author: change.author.clone(),
+                date: change.date.clone(),
+                version: 1,
+            }),
+            Inline::ChangeEnd { id } => out.push(LexicalNode::ChangeEnd {
+                change_id: id.clone(),
+                version: 1,
+            }),
+            Inline::Change(change) => out.push(change_to_node(change)),
+            Inline::Field {
+                kind,
+                value,
+                fixed,
+                date_value,
+                data_style_name,
+            } => out.push(LexicalNode::Field {
+                kind: *kind,
+                value: value.clone(),
+                fixed: *fixed,
+                date_value: date_value.clone(),
+                data_style_name: data_style_name.clone(),
+                version: 1,
+            }),
+            Inline::Bookmark { name } => out.push(LexicalNode::Bookmark {
+                name: name.clone(),
+                version: 1,
+            }),
+            Inline::BookmarkStart { name } => out.push(LexicalNode::BookmarkStart {
+                name: name.clone(),
+                version: 1,
+            }),
+            Inline::BookmarkEnd { name } => out.push(LexicalNode::BookmarkEnd {
+                name: name.clone(),
+                version: 1,
+            }),
+            Inline::Reference(reference) => out.push(LexicalNode::Reference {
+                kind: reference.kind,
+                name: reference.name.clone(),
+                format: reference.format.clone(),
+                value: reference.value.clone(),
+                version: 1,
+            }),
         }
     }
     out
 }
 
+/// Converts a [`Comment`] and its replies to a `"comment"` node.
+fn comment_to_node(comment: &Comment) -> LexicalNode {
+    LexicalNode::Comment {
+        comment_id: comment.id.clone(),
+        author: comment.author.clone(),
+        date: comment.date.clone(),
+        resolved: comment.resolved,
+        children: comment.content.iter().map(block_to_node).collect(),
+        replies: comment.replies.iter().map(comment_to_node).collect(),
+        version: 1,
+    }
+}
+
+/// Converts a tracked deletion to a `"change"` node.
+fn change_to_node(change: &TrackedChange) -> LexicalNode {
+    LexicalNode::Change {
+        change_id: change.id.clone(),
+        kind: change.kind,
+        author: change.author.clone(),
+        date: change.date.clone(),
+        children: change.content.iter().map(block_to_node).collect(),
+        version: 1,
+    }
+}
+
 #[cfg(test)]
 #[path = "to_lexical_tests.rs"]
 mod tests;
@@ -43,6 +43,61 @@ fn heading_becomes_heading_style_with_tag() {
     }
 }
 
+#[test]
+fn headings_carry_outline_numbers() {
+    use common_core::{OutlineLevel, OutlineStyle};
+
+    let heading = |level| Block::Heading {
+        level,
+        style_name: None,
+        attrs: None,
+        content: vec![],
+    };
+    let mut doc = Document::new();
+    doc.blocks = vec![
+        heading(1),
+        Block::Blockquote {
+            content: vec![heading(2)],
+        },
+        heading(3),
+    ];
+    doc.outline_style = Some(OutlineStyle {
+        levels: vec![
+            OutlineLevel {
+                level: 1,
+                num_format: Some("1".to_string()),
+                ..OutlineLevel::default()
+            },
+            OutlineLevel {
+                level: 2,
+                num_format: Some("a".to_string()),
+                display_levels: 2,
+                ..OutlineLevel::default()
+            },
+        ],
+        ..OutlineStyle::default()
+    });
+
+    let lex = to_lexical(&doc);
+    let number = |node: &LexicalNode| match node {
+        LexicalNode::HeadingStyle { number, .. } => number.clone(),
+        LexicalNode::Quote { children, .. } => match &children[0] {
+            LexicalNode::HeadingStyle { number, .. } => number.clone(),
+            other => panic!("expected HeadingStyle, got {other:?}"),
+        },
+        other => panic!("expected HeadingStyle, got {other:?}"),
+    };
+    let numbers: Vec<Option<String>> = lex.root.children.iter().map(number).collect();
+    assert_eq!(
+        numbers,
+        vec![Some("1".to_string()), Some("1.a".to_string()), None]
+    );
+
+    // The numbers are not part of the heading text.
+    let back = crate::lexical::from_lexical(lex, HashMap::new(), common_core::Metadata::default());
+    assert_eq!(back.blocks, doc.blocks);
+}
+
 #[test]
 fn text_with_bold_italic_sets_format() {
     let inlines = vec![Inline::Text {
@@ -85,6 +140,17 @@ fn line_break_becomes_linebreak_node() {
     assert!(matches!(nodes[0], LexicalNode::LineBreak { .. }));
 }
 
+#[test]
+fn tab_becomes_tab_node_and_space_run_becomes_text() {
+    let nodes = inlines_to_nodes(&[Inline::Tab, Inline::Space { count: 3 }]);
+    assert!(matches!(nodes[0], LexicalNode::Tab { .. }));
+    if let LexicalNode::Text { text, .. } = &nodes[1] {
+        assert_eq!(text, "   ");
+    } else {
+        panic!("expected Text");
+    }
+}
+
 #[test]
 fn page_break_becomes_page_break_node() {
     let node = block_to_node(&Block::PageBreak);
@@ -111,8 +177,11 @@ fn paragraph_with_break_before_style_gets_preceding_page_break_node() {
             text_transform: None,
             outline_level: None,
             autocomplete: None,
+            master_page_name: None,
             font_colour: None,
             background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
         },
     );
 
@@ -124,6 +193,9 @@ fn paragraph_with_break_before_style_gets_preceding_page_break_node() {
         }],
         styles,
         metadata: common_core::Metadata::default(),
+        track_changes: false,
+        outline_style: None,
+        page_setup: None,
         font_face_decls: None,
         automatic_styles: None,
         master_styles: None,
@@ -161,8 +233,11 @@ fn explicit_page_break_before_break_before_paragraph_no_double_insertion() {
             text_transform: None,
             outline_level: None,
             autocomplete: None,
+            master_page_name: None,
             font_colour: None,
             background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
         },
     );
 
@@ -177,6 +252,9 @@ fn explicit_page_break_before_break_before_paragraph_no_double_insertion() {
         ],
         styles,
         metadata: common_core::Metadata::default(),
+        track_changes: false,
+        outline_style: None,
+        page_setup: None,
         font_face_decls: None,
         automatic_styles: None,
         master_styles: None,
@@ -203,6 +281,9 @@ fn page_break_style_paragraph_not_preceded_by_extra_node() {
         blocks: vec![Block::PageBreak],
         styles: HashMap::new(),
         metadata: common_core::Metadata::default(),
+        track_changes: false,
+        outline_style: None,
+        page_setup: None,
         font_face_decls: None,
         automatic_styles: None,
         master_styles: None,
@@ -215,3 +296,89 @@ fn page_break_style_paragraph_not_preceded_by_extra_node() {
         LexicalNode::PageBreak { .. }
     ));
 }
+
+#[test]
+fn comment_becomes_comment_node_with_replies_and_end() {
+    let comment = Comment {
+        id: Some("c1".to_string()),
+        author: Some("Ada".to_string()),
+        replies: vec![Comment {
+            author: Some("Grace".to_string()),
+            ..Comment::default()
+        }],
+        ..Comment::default()
+    };
+    let nodes = inlines_to_nodes(&[
+        Inline::Comment(comment),
+        Inline::CommentEnd {
+            id: "c1".to_string(),
+        },
+    ]);
+    let json = serde_json::to_value(&nodes).unwrap();
+    assert_eq!(json[0]["type"], "comment");
+    assert_eq!(json[0]["commentId"], "c1");
+    assert_eq!(json[0]["replies"][0]["author"], "Grace");
+    assert_eq!(json[1]["type"], "comment-end");
+}
+
+#[test]
+fn section_becomes_section_node_with_columns() {
+    let block = Block::Section {
+        name: "Columns".to_string(),
+        attrs: Some(SectionAttrs {
+            column_count: Some(2),
+            column_gap: None,
+        }),
+        protected: true,
+        content: vec![Block::PageBreak],
+    };
+    let json = serde_json::to_value(block_to_node(&block)).unwrap();
+    assert_eq!(json["type"], "section");
+    assert_eq!(json["name"], "Columns");
+    assert_eq!(json["columnCount"], 2);
+    assert!(json.get("columnGap").is_none());
+    assert_eq!(json["protected"], true);
+    assert_eq!(json["children"][0]["type"], "page-break");
+}
+
+#[test]
+fn field_becomes_field_node_and_back() {
+    let field = Inline::Field {
+        kind: common_core::FieldKind::Date,
+        value: "1 May 2024".to_string(),
+        fixed: true,
+        date_value: Some("2024-05-01".to_string()),
+        data_style_name: Some("N37".to_string()),
+    };
+    let nodes = inlines_to_nodes(std::slice::from_ref(&field));
+    let json = serde_json::to_value(&nodes[0]).unwrap();
+    assert_eq!(json["type"], "field");
+    assert_eq!(json["kind"], "date");
+    assert_eq!(json["value"], "1 May 2024");
+    assert_eq!(json["fixed"], true);
+    assert_eq!(json["dateValue"], "2024-05-01");
+    assert_eq!(json["dataStyleName"], "N37");
+    let back = crate::lexical::from_lexical::node_to_inlines(nodes[0].clone());
+    assert_eq!(back, vec![field]);
+}
+
+#[test]
+fn table_of_contents_becomes_toc_node_and_back() {
+    let block = Block::TableOfContents(common_core::TableOfContents {
+        outline_level: 3,
+        entries: vec![common_core::TocEntry {
+            level: 1,
+            text: "Intro".to_string(),
+            target: Some("__RefHeading___Toc1".to_string()),
+            page: Some("1".to_string()),
+        }],
+        ..Default::default()
+    });
+    let node = block_to_node(&block);
+    let json = serde_json::to_value(&node).unwrap();
+    assert_eq!(json["type"], "table-of-contents");
+    assert_eq!(json["outlineLevel"], 3);
+    assert_eq!(json["entries"][0]["target"], "__RefHeading___Toc1");
+    let back = crate::lexical::from_lexical::node_to_block(node);
+    assert_eq!(back, Some(block));
+}
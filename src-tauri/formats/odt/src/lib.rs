@@ -12,6 +12,11 @@
 //!                            │
 //!                            ▼
 //!                         writer ──► content.xml / styles.xml / meta.xml / FODT
+//!                            │
+//!                            ▼
+//!                        pictures ──► Pictures/ + META-INF/manifest.xml
+//!
+//! package::OdtPackage ◄──► .odt ZIP (mimetype, manifest, parts, Pictures/, …)
 //! ```
 //!
 //! # Examples
@@ -38,16 +43,25 @@
 //!
 //! let lex: LexicalDocument = serde_json::from_str("{}").unwrap();
 //! let doc = from_lexical(lex, HashMap::new(), Metadata::default());
-//! let xml = to_xml(&doc.blocks, &doc.styles, &doc.metadata,
-//!                  &doc.font_face_decls, &doc.automatic_styles, &doc.master_styles).unwrap();
+//! let xml = to_xml(&doc.blocks, doc.track_changes, &doc.styles, doc.outline_style.as_ref(),
+//!                  &doc.metadata, &doc.font_face_decls, &doc.automatic_styles,
+//!                  &doc.master_styles).unwrap();
 //! ```
 
+pub mod changes;
+pub mod cleanup;
+pub mod compare;
 pub mod document;
 pub mod lexical;
+pub mod list_styles;
 pub mod loki_ext;
 pub mod namespaces;
+pub mod package;
 pub mod parser;
+pub mod pictures;
+pub mod template;
 pub mod tiptap;
 pub mod writer;
 
 pub use document::Document;
+pub use package::OdtPackage;
@@ -0,0 +1,10 @@
+//! Names of the default list styles.
+//!
+//! Lists created in the editor reference one of these styles, which the
+//! writer adds to `office:styles`; the parser recognises them by name when
+//! the styles themselves are not at hand.
+
+/// Default list style for bullet lists without an imported style.
+pub const DEFAULT_BULLET_STYLE: &str = "LokiBullet";
+/// Default list style for numbered lists without an imported style.
+pub const DEFAULT_NUMBER_STYLE: &str = "LokiNumbering";
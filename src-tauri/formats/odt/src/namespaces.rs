@@ -34,6 +34,10 @@ pub struct Ns {
     pub table: &'static str,
     /// `http://www.w3.org/1999/xlink`
     pub xlink: &'static str,
+    /// `urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0`
+    pub svg: &'static str,
+    /// `urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0`
+    pub loext: &'static str,
     /// `https://appthere.com/loki/ns`
     pub loki: &'static str,
 }
@@ -51,6 +55,8 @@ impl Default for Ns {
             draw: "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0",
             table: "urn:oasis:names:tc:opendocument:xmlns:table:1.0",
             xlink: "http://www.w3.org/1999/xlink",
+            svg: "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
+            loext: "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
             loki: "https://appthere.com/loki/ns",
         }
     }
@@ -75,6 +81,11 @@ pub fn ns_prefix(ns: &str) -> &'static str {
         "urn:oasis:names:tc:opendocument:xmlns:style:1.0" => "style:",
         "urn:oasis:names:tc:opendocument:xmlns:text:1.0" => "text:",
         "https://appthere.com/loki/ns" => "loki:",
+        "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" => "draw:",
+        "urn:oasis:names:tc:opendocument:xmlns:table:1.0" => "table:",
+        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0" => "svg:",
+        "http://www.w3.org/1999/xlink" => "xlink:",
+        "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0" => "loext:",
         _ => "",
     }
 }
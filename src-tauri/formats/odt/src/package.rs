@@ -0,0 +1,457 @@
+//! The ODT ZIP package.
+//!
+//! An [`OdtPackage`] holds every entry of a `.odt` file: the `mimetype`, the
+//! manifest, the `content.xml`, `styles.xml`, `meta.xml` and `settings.xml`
+//! parts, embedded pictures, thumbnails and any other entries, which are
+//! written back unchanged. [`OdtPackage::to_document`] and
+//! [`OdtPackage::from_document`] convert between the package and a
+//! [`Document`]; [`OdtPackage::update`] replaces the document parts of an
+//! existing package while keeping everything else.
+//!
+//! # Examples
+//!
+//! ```
+//! use odt_format::{Document, OdtPackage};
+//!
+//! let package = OdtPackage::from_document(&Document::new(), None).unwrap();
+//! let bytes = package.to_bytes().unwrap();
+//! let reopened = OdtPackage::from_bytes(&bytes).unwrap();
+//! assert!(reopened.to_document().unwrap().blocks.is_empty());
+//! ```
+
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::Path;
+
+use common_core::Metadata;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::pictures::{self, Picture, PICTURES_DIR};
+use crate::Document;
+
+/// The media type of an ODF text document, stored in the `mimetype` entry.
+pub const MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
+
+/// The package folder holding preview thumbnails.
+pub const THUMBNAILS_DIR: &str = "Thumbnails/";
+
+const MANIFEST_PATH: &str = "META-INF/manifest.xml";
+
+/// Entries larger than this are rejected when reading a package.
+const MAX_ENTRY_SIZE: u64 = 256 * 1024 * 1024;
+
+/// A file inside the package, stored as raw bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageEntry {
+    /// The path inside the package.
+    pub path: String,
+    /// The entry bytes.
+    pub data: Vec<u8>,
+}
+
+/// The entries of an ODT ZIP package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OdtPackage {
+    /// The `mimetype` entry.
+    pub mimetype: String,
+    /// The `META-INF/manifest.xml` read from the package, if any. It is
+    /// regenerated on write to list the entries actually written.
+    pub manifest: Option<String>,
+    /// The `content.xml` part.
+    pub content: String,
+    /// The `styles.xml` part.
+    pub styles: Option<String>,
+    /// The `meta.xml` part.
+    pub meta: Option<String>,
+    /// The `settings.xml` part.
+    pub settings: Option<String>,
+    /// Images in the `Pictures/` folder.
+    pub pictures: Vec<Picture>,
+    /// Previews in the `Thumbnails/` folder.
+    pub thumbnails: Vec<PackageEntry>,
+    /// Every other entry, kept in package order.
+    pub other: Vec<PackageEntry>,
+}
+
+impl OdtPackage {
+    /// Builds a package for a document, moving its data-URI and local-file
+    /// images into `Pictures/`. Relative image paths are resolved against
+    /// `base_dir`, the folder the document is saved in.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if XML writing fails.
+    pub fn from_document(doc: &Document, base_dir: Option<&Path>) -> Result<Self, String> {
+        let mut doc = doc.clone();
+        let pictures = pictures::extract_document_pictures(&mut doc, base_dir);
+        Ok(Self {
+            mimetype: MIMETYPE.to_string(),
+            manifest: None,
+            content: doc.to_content_xml()?,
+            styles: Some(doc.styles_to_xml()?),
+            meta: Some(doc.to_meta_xml()?),
+            settings: None,
+            pictures,
+            thumbnails: Vec::new(),
+            other: Vec::new(),
+        })
+    }
+
+    /// Reads a package from a `.odt` file on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if the file cannot be read or is not a valid
+    /// ODT package.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+        Self::read(std::io::BufReader::new(file))
+    }
+
+    /// Reads a package from the bytes of a `.odt` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if the bytes are not a valid ODT package.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        Self::read(Cursor::new(bytes))
+    }
+
+    /// Reads a package from a ZIP archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if the archive cannot be read, an entry is
+    /// too large, or `content.xml` is missing.
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, String> {
+        let mut archive = ZipArchive::new(reader).map_err(|e| e.to_string())?;
+        let mut entries = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let file = archive.by_index(i).map_err(|e| e.to_string())?;
+            if file.is_dir() {
+                continue;
+            }
+            if file.size() > MAX_ENTRY_SIZE {
+                return Err(format!("Package entry {} is too large", file.name()));
+            }
+            let path = file.name().to_string();
+            let mut data = Vec::new();
+            file.take(MAX_ENTRY_SIZE)
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {path}: {e}"))?;
+            entries.push(PackageEntry { path, data });
+        }
+        Self::from_entries(entries)
+    }
+
+    /// Sorts raw entries into the package parts.
+    fn from_entries(entries: Vec<PackageEntry>) -> Result<Self, String> {
+        let text = |entry: PackageEntry| {
+            String::from_utf8(entry.data).map_err(|e| format!("{} is not UTF-8: {e}", entry.path))
+        };
+        let mut package = Self {
+            mimetype: MIMETYPE.to_string(),
+            manifest: None,
+            content: String::new(),
+            styles: None,
+            meta: None,
+            settings: None,
+            pictures: Vec::new(),
+            thumbnails: Vec::new(),
+            other: Vec::new(),
+        };
+        let mut content = None;
+        let mut picture_entries = Vec::new();
+        for entry in entries {
+            match entry.path.as_str() {
+                "mimetype" => package.mimetype = text(entry)?.trim().to_string(),
+                MANIFEST_PATH => package.manifest = Some(text(entry)?),
+                "content.xml" => content = Some(text(entry)?),
+                "styles.xml" => package.styles = Some(text(entry)?),
+                "meta.xml" => package.meta = Some(text(entry)?),
+                "settings.xml" => package.settings = Some(text(entry)?),
+                path if path.starts_with(PICTURES_DIR) => picture_entries.push(entry),
+                path if path.starts_with(THUMBNAILS_DIR) => package.thumbnails.push(entry),
+                _ => package.other.push(entry),
+            }
+        }
+        package.content = content.ok_or("content.xml not found in ODT package")?;
+
+        let listed = package
+            .manifest
+            .as_deref()
+            .and_then(|m| pictures::manifest_entries(m).ok())
+            .unwrap_or_default();
+        package.pictures = picture_entries
+            .into_iter()
+            .map(|entry| {
+                let media_type = listed
+                    .iter()
+                    .find(|(path, _)| *path == entry.path)
+                    .map(|(_, media_type)| media_type.as_str())
+                    .filter(|media_type| !media_type.is_empty())
+                    .or_else(|| {
+                        entry
+                            .path
+                            .rsplit_once('.')
+                            .and_then(|(_, ext)| pictures::media_type_for(ext))
+                    })
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                Picture {
+                    path: entry.path,
+                    media_type,
+                    data: entry.data,
+                }
+            })
+            .collect();
+        Ok(package)
+    }
+
+    /// Parses the package into a [`Document`].
+    ///
+    /// `content.xml` is parsed with the named styles of `styles.xml`,
+    /// metadata is merged in, and pictures are inlined as data URIs.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if `content.xml` or `styles.xml` cannot be
+    /// parsed.
+    pub fn to_document(&self) -> Result<Document, String> {
+        let mut doc = match &self.styles {
+            Some(styles) => Document::from_xml_with_styles(&self.content, styles)?,
+            None => Document::from_xml(&self.content)?,
+        };
+        if let Some(meta_doc) = self
+            .meta
+            .as_deref()
+            .and_then(|m| Document::from_xml(m).ok())
+        {
+            merge_metadata(&mut doc.metadata, meta_doc.metadata);
+        }
+        pictures::inline_document_pictures(&mut doc, |path| {
+            self.pictures
+                .iter()
+                .find(|p| p.path == path)
+                .map(|p| p.data.clone())
+        });
+        Ok(doc)
+    }
+
+    /// Replaces the document parts of this package with `doc`.
+    ///
+    /// `content.xml` and `styles.xml` are updated in place so their
+    /// preserved sections survive, `meta.xml` is regenerated, and the
+    /// pictures are replaced by the ones extracted from `doc`, with relative
+    /// image paths resolved against `base_dir`. Stored
+    /// pictures that the written parts still refer to outside the document
+    /// content, such as style background and bullet images, are kept along
+    /// with settings, thumbnails and other entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if XML writing fails.
+    pub fn update(&mut self, doc: &Document, base_dir: Option<&Path>) -> Result<(), String> {
+        let mut doc = doc.clone();
+        let mut pictures = pictures::extract_document_pictures(&mut doc, base_dir);
+        self.content = doc
+            .update_fodt(&self.content)
+            .or_else(|_| doc.to_content_xml())?;
+        self.styles = Some(match &self.styles {
+            Some(old) => doc
+                .update_styles_xml(old)
+                .or_else(|_| doc.styles_to_xml())?,
+            None => doc.styles_to_xml()?,
+        });
+        self.meta = Some(doc.to_meta_xml()?);
+
+        for old in std::mem::take(&mut self.pictures) {
+            let referenced = self.content.contains(&old.path)
+                || self
+                    .styles
+                    .as_deref()
+                    .is_some_and(|s| s.contains(&old.path));
+            if referenced && !pictures.iter().any(|p| p.path == old.path) {
+                pictures.push(old);
+            }
+        }
+        self.pictures = pictures;
+        Ok(())
+    }
+
+    /// Generates the manifest for the entries this package writes.
+    ///
+    /// Entries listed in the manifest read from the package are kept, except
+    /// for pictures, which are listed from [`Self::pictures`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if XML writing fails.
+    pub fn manifest_xml(&self) -> Result<String, String> {
+        let mut entries = self
+            .manifest
+            .as_deref()
+            .and_then(|m| pictures::manifest_entries(m).ok())
+            .unwrap_or_else(|| vec![("/".to_string(), self.mimetype.clone())]);
+        entries.retain(|(path, _)| !path.starts_with(PICTURES_DIR));
+        self.list_parts(&mut entries);
+        entries.extend(
+            self.pictures
+                .iter()
+                .map(|p| (p.path.clone(), p.media_type.clone())),
+        );
+        pictures::write_manifest(&entries)
+    }
+
+    /// Adds manifest entries for the parts written but not yet listed.
+    fn list_parts(&self, entries: &mut Vec<(String, String)>) {
+        let xml_parts = [
+            ("content.xml", true),
+            ("styles.xml", self.styles.is_some()),
+            ("meta.xml", self.meta.is_some()),
+            ("settings.xml", self.settings.is_some()),
+        ];
+        let parts = xml_parts
+            .into_iter()
+            .filter(|(_, present)| *present)
+            .map(|(path, _)| (path.to_string(), "text/xml"))
+            .chain(
+                self.thumbnails
+                    .iter()
+                    .map(|t| (t.path.clone(), thumbnail_media_type(&t.path))),
+            )
+            .chain(
+                self.other
+                    .iter()
+                    .filter(|e| !e.path.starts_with("META-INF/"))
+                    .map(|e| (e.path.clone(), "")),
+            )
+            .collect::<Vec<_>>();
+        for (path, media_type) in parts {
+            if !entries.iter().any(|(p, _)| *p == path) {
+                entries.push((path, media_type.to_string()));
+            }
+        }
+    }
+
+    /// Writes the package as a ZIP archive.
+    ///
+    /// The `mimetype` entry comes first and uncompressed, as the ODF
+    /// specification requires. Pictures are stored uncompressed too, since
+    /// image formats are already compressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if ZIP or XML writing fails.
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<(), String> {
+        let mut zip = ZipWriter::new(writer);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let manifest = self.manifest_xml()?;
+        let mut files: Vec<(&str, &[u8], SimpleFileOptions)> = vec![
+            ("mimetype", self.mimetype.as_bytes(), stored),
+            (MANIFEST_PATH, manifest.as_bytes(), deflated),
+            ("content.xml", self.content.as_bytes(), deflated),
+        ];
+        for (path, part) in [
+            ("styles.xml", &self.styles),
+            ("meta.xml", &self.meta),
+            ("settings.xml", &self.settings),
+        ] {
+            if let Some(part) = part {
+                files.push((path, part.as_bytes(), deflated));
+            }
+        }
+        files.extend(
+            self.pictures
+                .iter()
+                .map(|p| (p.path.as_str(), p.data.as_slice(), stored)),
+        );
+        files.extend(
+            self.thumbnails
+                .iter()
+                .chain(&self.other)
+                .map(|e| (e.path.as_str(), e.data.as_slice(), deflated)),
+        );
+
+        for (path, data, options) in files {
+            zip.start_file(path, options).map_err(|e| e.to_string())?;
+            zip.write_all(data).map_err(|e| e.to_string())?;
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Writes the package to the bytes of a `.odt` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if ZIP or XML writing fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut buffer = Cursor::new(Vec::new());
+        self.write(&mut buffer)?;
+        Ok(buffer.into_inner())
+    }
+
+    /// Writes the package to a `.odt` file on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if ZIP or XML writing or the file write fails.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes()?).map_err(|e| e.to_string())
+    }
+}
+
+fn thumbnail_media_type(path: &str) -> &'static str {
+    path.rsplit_once('.')
+        .and_then(|(_, ext)| pictures::media_type_for(ext))
+        .unwrap_or("")
+}
+
+/// Copies the fields set in `from` into `into`.
+fn merge_metadata(into: &mut Metadata, from: Metadata) {
+    let Metadata {
+        identifier,
+        title,
+        language,
+        description,
+        subject,
+        creator,
+        creation_date,
+        generator,
+        keywords,
+        date,
+        editing_cycles,
+        editing_duration,
+        initial_creator,
+        user_defined,
+        statistics,
+    } = from;
+    for (field, value) in [
+        (&mut into.identifier, identifier),
+        (&mut into.title, title),
+        (&mut into.language, language),
+        (&mut into.description, description),
+        (&mut into.subject, subject),
+        (&mut into.creator, creator),
+        (&mut into.creation_date, creation_date),
+        (&mut into.generator, generator),
+        (&mut into.date, date),
+        (&mut into.editing_duration, editing_duration),
+        (&mut into.initial_creator, initial_creator),
+    ] {
+        if value.is_some() {
+            *field = value;
+        }
+    }
+    if !keywords.is_empty() {
+        into.keywords = keywords;
+    }
+    if !user_defined.is_empty() {
+        into.user_defined = user_defined;
+    }
+    into.editing_cycles = editing_cycles.or(into.editing_cycles);
+    into.statistics = statistics.or(into.statistics);
+}
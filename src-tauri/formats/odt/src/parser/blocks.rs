@@ -1,14 +1,17 @@
 //! ODT block content parser.
 //!
-//! Parses `text:p`, `text:h`, `text:list`, and `table:table` elements from
-//! an ODT XML body node into [`Block`] values.
+//! Parses `text:p`, `text:h`, `text:list`, `text:section`,
+//! `text:table-of-content` and `table:table` elements from an ODT XML body
+//! node into [`Block`] values.
 
-use std::collections::HashMap;
-
-use common_core::block::CellAttrs;
-use common_core::{Block, TiptapMark};
+use common_core::{Block, BlockAttrs};
 
+use crate::parser::images::parse_image_frame;
 use crate::parser::inlines::parse_inlines;
+use crate::parser::lists::resolve_list;
+use crate::parser::styles::StyleMap;
+use crate::parser::tables::{cell_attrs, table_columns, table_rows};
+use crate::parser::toc::parse_toc;
 
 /// Maximum nesting depth for lists and tables before recursion is cut off.
 ///
@@ -26,14 +29,14 @@ const MAX_NESTING_DEPTH: usize = 64;
 /// * `ns_table` - The `table:` namespace URI.
 /// * `ns_draw` - The `draw:` namespace URI.
 /// * `ns_xlink` - The `xlink:` namespace URI.
-/// * `style_map` - Map from style name to `(family, marks)` for inline formatting.
+/// * `style_map` - Named and automatic styles, for resolving formatting.
 pub fn parse_blocks(
     node: roxmltree::Node,
     ns_text: &str,
     ns_table: &str,
     ns_draw: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
 ) -> Vec<Block> {
     parse_blocks_depth(node, ns_text, ns_table, ns_draw, ns_xlink, style_map, 0)
 }
@@ -45,7 +48,7 @@ fn parse_blocks_depth(
     ns_table: &str,
     ns_draw: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
     depth: usize,
 ) -> Vec<Block> {
     if depth >= MAX_NESTING_DEPTH {
@@ -68,6 +71,19 @@ fn parse_blocks_depth(
                 depth,
                 &mut blocks,
             );
+        } else if child.has_tag_name((ns_text, "section")) {
+            let content = parse_blocks_depth(
+                child,
+                ns_text,
+                ns_table,
+                ns_draw,
+                ns_xlink,
+                style_map,
+                depth + 1,
+            );
+            blocks.push(parse_section(&child, ns_text, style_map, content));
+        } else if child.has_tag_name((ns_text, "table-of-content")) {
+            blocks.push(parse_toc(&child, ns_text, ns_xlink, style_map));
         } else if child.has_tag_name((ns_table, "table")) {
             parse_table(
                 &child,
@@ -90,25 +106,16 @@ fn parse_paragraph(
     ns_text: &str,
     ns_draw: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
     blocks: &mut Vec<Block>,
 ) {
     // Check for embedded image frame
-    if let Some(img) = child
+    if let Some(image) = child
         .children()
-        .find(|n| n.has_tag_name((ns_draw, "frame")))
-        .and_then(|frame| {
-            frame
-                .children()
-                .find(|n| n.has_tag_name((ns_draw, "image")))
-        })
+        .filter(|n| n.has_tag_name((ns_draw, "frame")))
+        .find_map(|frame| parse_image_frame(frame, ns_draw, ns_xlink, style_map))
     {
-        let href = img.attribute((ns_xlink, "href")).unwrap_or("").to_string();
-        blocks.push(Block::Image {
-            src: href,
-            alt: None,
-            title: None,
-        });
+        blocks.push(image);
         return;
     }
 
@@ -121,20 +128,43 @@ fn parse_paragraph(
         return;
     }
 
+    let (style_name, attrs) = resolve_paragraph_style(style_name, style_map, blocks);
     let content = parse_inlines(*child, ns_text, ns_xlink, style_map);
     blocks.push(Block::Paragraph {
         style_name,
-        attrs: None,
+        attrs,
         content,
     });
 }
 
+/// Resolves an automatic paragraph style into its named parent style and
+/// direct formatting.
+///
+/// A `fo:break-before="page"` on the automatic style is emitted as a
+/// [`Block::PageBreak`] before the paragraph. Named styles are returned
+/// unchanged.
+fn resolve_paragraph_style(
+    style_name: Option<String>,
+    style_map: &StyleMap,
+    blocks: &mut Vec<Block>,
+) -> (Option<String>, Option<BlockAttrs>) {
+    match style_name.as_deref().and_then(|n| style_map.get(n)) {
+        Some(info) if info.automatic => {
+            if info.break_before {
+                blocks.push(Block::PageBreak);
+            }
+            (info.parent.clone(), info.block_attrs.clone())
+        }
+        _ => (style_name, None),
+    }
+}
+
 /// Parses a `text:h` element (heading).
 fn parse_heading(
     child: &roxmltree::Node,
     ns_text: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
     blocks: &mut Vec<Block>,
 ) {
     let level = child
@@ -144,16 +174,20 @@ fn parse_heading(
     let style_name = child
         .attribute((ns_text, "style-name"))
         .map(|s| s.to_string());
+    let (style_name, attrs) = resolve_paragraph_style(style_name, style_map, blocks);
     let content = parse_inlines(*child, ns_text, ns_xlink, style_map);
     blocks.push(Block::Heading {
         level,
         style_name,
-        attrs: None,
+        attrs,
         content,
     });
 }
 
-/// Parses a `text:list` element into a `BulletList`.
+/// Parses a `text:list` element into a `BulletList` or `OrderedList`.
+///
+/// The list kind and numbering attributes come from the resolved
+/// `text:list-style`; lists without a numbered style become bullet lists.
 #[allow(clippy::too_many_arguments)]
 fn parse_list(
     child: &roxmltree::Node,
@@ -161,7 +195,7 @@ fn parse_list(
     ns_table: &str,
     ns_draw: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
     depth: usize,
     blocks: &mut Vec<Block>,
 ) {
@@ -181,10 +215,45 @@ fn parse_list(
         );
         items.push(Block::ListItem { content });
     }
-    blocks.push(Block::BulletList { content: items });
+    match resolve_list(*child, ns_text, style_map) {
+        (true, attrs) => blocks.push(Block::OrderedList {
+            attrs,
+            content: items,
+        }),
+        (false, attrs) => blocks.push(Block::BulletList {
+            attrs,
+            content: items,
+        }),
+    }
+}
+
+/// Builds a [`Block::Section`] from a `text:section` element and its parsed
+/// content. The column layout comes from the section's style.
+fn parse_section(
+    child: &roxmltree::Node,
+    ns_text: &str,
+    style_map: &StyleMap,
+    content: Vec<Block>,
+) -> Block {
+    let attrs = child
+        .attribute((ns_text, "style-name"))
+        .and_then(|n| style_map.get(n))
+        .and_then(|info| info.section.clone());
+    Block::Section {
+        name: child
+            .attribute((ns_text, "name"))
+            .unwrap_or_default()
+            .to_string(),
+        attrs,
+        protected: child.attribute((ns_text, "protected")) == Some("true"),
+        content,
+    }
 }
 
 /// Parses a `table:table` element.
+///
+/// Rows in `table:table-header-rows` produce [`Block::TableHeader`] cells.
+/// Column widths and cell styles are resolved into [`CellAttrs`](common_core::CellAttrs).
 #[allow(clippy::too_many_arguments)]
 fn parse_table(
     child: &roxmltree::Node,
@@ -192,18 +261,20 @@ fn parse_table(
     ns_table: &str,
     ns_draw: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
     depth: usize,
     blocks: &mut Vec<Block>,
 ) {
+    let columns = table_columns(*child, ns_table, style_map);
     let mut rows = Vec::new();
-    for row in child
-        .children()
-        .filter(|n| n.has_tag_name((ns_table, "table-row")))
-    {
+    for (row, header) in table_rows(*child, ns_table) {
         let mut cells = Vec::new();
+        let mut column = 0;
         for cell in row.children() {
-            if cell.has_tag_name((ns_table, "table-cell")) {
+            // Covered cells hold the place of columns spanned from elsewhere
+            if cell.has_tag_name((ns_table, "covered-table-cell")) {
+                column += 1;
+            } else if cell.has_tag_name((ns_table, "table-cell")) {
                 let content = parse_blocks_depth(
                     cell,
                     ns_text,
@@ -213,24 +284,13 @@ fn parse_table(
                     style_map,
                     depth + 1,
                 );
-                let col_span = cell
-                    .attribute((ns_table, "number-columns-spanned"))
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .filter(|&v| v > 1);
-                let row_span = cell
-                    .attribute((ns_table, "number-rows-spanned"))
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .filter(|&v| v > 1);
-                let attrs = if col_span.is_some() || row_span.is_some() {
-                    Some(CellAttrs {
-                        colspan: col_span,
-                        rowspan: row_span,
-                        colwidth: None,
-                    })
+                let attrs = cell_attrs(cell, column, &columns, ns_table, style_map);
+                column += attrs.as_ref().and_then(|a| a.colspan).unwrap_or(1) as usize;
+                cells.push(if header {
+                    Block::TableHeader { attrs, content }
                 } else {
-                    None
-                };
-                cells.push(Block::TableCell { attrs, content });
+                    Block::TableCell { attrs, content }
+                });
             }
         }
         rows.push(Block::TableRow { content: cells });
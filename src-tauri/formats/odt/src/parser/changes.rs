@@ -0,0 +1,101 @@
+//! ODT tracked-change parser.
+//!
+//! [`parse_inlines`](super::inlines::parse_inlines) turns `text:change-start`,
+//! `text:change-end` and `text:change` into markers that carry only the
+//! change id. This module reads the `text:changed-region` elements of
+//! `text:tracked-changes` and fills in each marker's kind, author, date and,
+//! for deletions, the removed content.
+
+use std::collections::HashMap;
+
+use common_core::{Block, ChangeKind, Inline, TrackedChange};
+
+use crate::changes::for_each_inlines_mut;
+use crate::namespaces::Ns;
+use crate::parser::blocks::parse_blocks;
+use crate::parser::styles::StyleMap;
+
+/// The namespace of the `xml:` prefix.
+const NS_XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Parses the `text:tracked-changes` child of `office_text`, keyed by id.
+pub fn parse_tracked_changes(
+    office_text: roxmltree::Node,
+    style_map: &StyleMap,
+) -> HashMap<String, TrackedChange> {
+    let ns = Ns::default();
+    office_text
+        .children()
+        .filter(|n| n.has_tag_name((ns.text, "tracked-changes")))
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((ns.text, "changed-region")))
+        .filter_map(|region| parse_changed_region(region, style_map))
+        .map(|change| (change.id.clone(), change))
+        .collect()
+}
+
+/// Returns `true` if `office_text` records changes.
+///
+/// Recording is switched on by a `text:tracked-changes` element whose
+/// `text:track-changes` attribute is not `"false"`; the attribute defaults
+/// to `true`.
+pub fn is_tracking_changes(office_text: roxmltree::Node) -> bool {
+    let ns = Ns::default();
+    office_text
+        .children()
+        .find(|n| n.has_tag_name((ns.text, "tracked-changes")))
+        .is_some_and(|n| n.attribute((ns.text, "track-changes")) != Some("false"))
+}
+
+fn parse_changed_region(region: roxmltree::Node, style_map: &StyleMap) -> Option<TrackedChange> {
+    let ns = Ns::default();
+    let id = region
+        .attribute((ns.text, "id"))
+        .or_else(|| region.attribute((NS_XML, "id")))?;
+    let (element, kind) = region.children().find_map(|n| {
+        (n.tag_name().namespace() == Some(ns.text))
+            .then(|| ChangeKind::from_odf_str(n.tag_name().name()))
+            .flatten()
+            .map(|kind| (n, kind))
+    })?;
+    let info = element
+        .children()
+        .find(|n| n.has_tag_name((ns.office, "change-info")));
+    let dc_text = |name: &str| {
+        info?
+            .children()
+            .find(|n| n.has_tag_name((ns.dc, name)))
+            .and_then(|n| n.text())
+            .map(str::to_string)
+    };
+    let content = if kind == ChangeKind::Deletion {
+        parse_blocks(element, ns.text, ns.table, ns.draw, ns.xlink, style_map)
+    } else {
+        Vec::new()
+    };
+    Some(TrackedChange {
+        id: id.to_string(),
+        kind,
+        author: dc_text("creator"),
+        date: dc_text("date"),
+        content,
+    })
+}
+
+/// Replaces the id-only change markers in `blocks` with the full changes.
+///
+/// Markers without a matching region are left as they are.
+pub fn attach_tracked_changes(blocks: &mut [Block], changes: &HashMap<String, TrackedChange>) {
+    if changes.is_empty() {
+        return;
+    }
+    for_each_inlines_mut(blocks, &mut |inlines| {
+        for inline in inlines.iter_mut() {
+            if let Inline::ChangeStart(marker) | Inline::Change(marker) = inline {
+                if let Some(change) = changes.get(&marker.id) {
+                    *marker = change.clone();
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,132 @@
+//! ODT image frame parser.
+//!
+//! Converts a `draw:frame` holding a `draw:image` into a [`Block::Image`],
+//! reading its size, anchor, wrapping, title, description and caption.
+
+use common_core::units::{length_to_cm, PX_PER_CM};
+use common_core::{Block, ImageAnchor, ImageFrame};
+
+use crate::namespaces::Ns;
+use crate::parser::styles::StyleMap;
+use crate::pictures::PICTURES_DIR;
+
+/// Parses a `draw:frame` into an image block, or `None` if it holds no image.
+///
+/// LibreOffice writes captioned images as an outer frame with a
+/// `draw:text-box` whose paragraph holds the image frame and the caption
+/// text. The outer frame then supplies the anchor and wrapping, and the inner
+/// frame the image size.
+pub fn parse_image_frame(
+    frame: roxmltree::Node,
+    ns_draw: &str,
+    ns_xlink: &str,
+    style_map: &StyleMap,
+) -> Option<Block> {
+    if let Some(text_box) = frame
+        .children()
+        .find(|n| n.has_tag_name((ns_draw, "text-box")))
+    {
+        let inner = text_box
+            .descendants()
+            .find(|n| n.has_tag_name((ns_draw, "frame")) && image_node(*n, ns_draw).is_some())?;
+        let caption = inner
+            .ancestors()
+            .find(|n| n.parent() == Some(text_box))
+            .map(|para| caption_text(para, ns_draw))
+            .filter(|c| !c.is_empty());
+        let outer = frame_geometry(frame, ns_draw, style_map);
+        let inner_geometry = frame_geometry(inner, ns_draw, style_map);
+        let geometry = ImageFrame {
+            width: inner_geometry.width.or(outer.width),
+            height: inner_geometry.height,
+            ..outer
+        };
+        return Some(image_block(inner, ns_draw, ns_xlink, geometry, caption));
+    }
+    image_node(frame, ns_draw)?;
+    let geometry = frame_geometry(frame, ns_draw, style_map);
+    Some(image_block(frame, ns_draw, ns_xlink, geometry, None))
+}
+
+fn image_node<'a, 'input>(
+    frame: roxmltree::Node<'a, 'input>,
+    ns_draw: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    frame
+        .children()
+        .find(|n| n.has_tag_name((ns_draw, "image")))
+}
+
+fn image_block(
+    frame: roxmltree::Node,
+    ns_draw: &str,
+    ns_xlink: &str,
+    mut geometry: ImageFrame,
+    caption: Option<String>,
+) -> Block {
+    let ns = Ns::default();
+    let child_text = |name: &str| {
+        frame
+            .children()
+            .find(|n| n.has_tag_name((ns.svg, name)))
+            .and_then(|n| n.text())
+            .map(str::to_string)
+    };
+    let src = image_node(frame, ns_draw)
+        .and_then(|img| img.attribute((ns_xlink, "href")))
+        .unwrap_or("")
+        .to_string();
+    // An image outside the package is a link to that file.
+    geometry.linked = !src.is_empty() && !is_embedded_src(&src);
+    Block::Image {
+        src,
+        alt: child_text("desc"),
+        title: child_text("title"),
+        frame: (!geometry.is_empty()).then_some(geometry),
+        caption,
+    }
+}
+
+/// Whether an image `src` is stored in the document: a package picture or a
+/// data URI.
+fn is_embedded_src(src: &str) -> bool {
+    src.starts_with("data:") || src.trim_start_matches("./").starts_with(PICTURES_DIR)
+}
+
+/// Reads the size, anchor and wrapping of a `draw:frame`.
+fn frame_geometry(frame: roxmltree::Node, ns_draw: &str, style_map: &StyleMap) -> ImageFrame {
+    let ns = Ns::default();
+    let length = |name: &str| {
+        frame
+            .attribute((ns.svg, name))
+            .and_then(length_to_cm)
+            .map(|cm| (cm * PX_PER_CM * 100.0).round() / 100.0)
+    };
+    ImageFrame {
+        width: length("width"),
+        height: length("height"),
+        anchor: frame
+            .attribute((ns.text, "anchor-type"))
+            .and_then(ImageAnchor::from_odf_str),
+        wrap: frame
+            .attribute((ns_draw, "style-name"))
+            .and_then(|s| style_map.get(s))
+            .and_then(|info| info.wrap),
+        linked: false,
+    }
+}
+
+/// Returns the text of a caption paragraph, leaving out the image frame.
+fn caption_text(para: roxmltree::Node, ns_draw: &str) -> String {
+    let text: String = para
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter(|n| {
+            !n.ancestors()
+                .take_while(|a| *a != para)
+                .any(|a| a.has_tag_name((ns_draw, "frame")))
+        })
+        .filter_map(|n| n.text())
+        .collect();
+    text.trim().to_string()
+}
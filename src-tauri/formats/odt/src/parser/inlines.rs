@@ -1,80 +1,342 @@
 //! ODT inline content parser.
 //!
-//! Parses `text:span`, `text:a`, `text:line-break`, and plain text nodes
-//! from an ODT XML element into [`Inline`] values.
-
-use std::collections::HashMap;
+//! Parses `text:span`, `text:a`, `text:line-break`, `text:s`, `text:tab`,
+//! `text:note`, `office:annotation`, `office:annotation-end`, the
+//! tracked-change markers, text fields, bookmarks, cross-references, and
+//! plain text nodes from an ODT XML element into [`Inline`] values.
 
 use common_core::marks::LinkAttrs;
-use common_core::{Inline, TiptapMark};
+use common_core::{
+    Comment, CrossReference, FieldKind, Inline, NoteClass, ReferenceKind, TiptapMark, TrackedChange,
+};
+
+use crate::namespaces::Ns;
+use crate::parser::blocks::parse_blocks;
+use crate::parser::styles::StyleMap;
+
+/// Largest `text:c` count accepted on a `text:s` element.
+///
+/// Spaces are expanded into strings by the editor and export converters, so
+/// an adversarial count would allocate without bound.  No legitimate
+/// document needs more consecutive spaces than this.
+const MAX_SPACE_COUNT: u32 = 4096;
+
+/// Formatting inherited from enclosing `text:span` and `text:a` elements.
+#[derive(Debug, Clone, Default)]
+struct RunFormat {
+    /// Style name of the innermost enclosing span.
+    style_name: Option<String>,
+    /// Union of the marks of all enclosing spans and links.
+    marks: Vec<TiptapMark>,
+}
+
+impl RunFormat {
+    /// Returns a copy with `mark` added, unless it is already present; a
+    /// `TextStyle` mark is merged into an enclosing one, the inner properties
+    /// winning.
+    fn with_mark(&self, mark: TiptapMark) -> Self {
+        let mut next = self.clone();
+        if let TiptapMark::TextStyle { attrs } = &mark {
+            if let Some(TiptapMark::TextStyle { attrs: outer }) = next
+                .marks
+                .iter_mut()
+                .find(|m| matches!(m, TiptapMark::TextStyle { .. }))
+            {
+                outer.merge(attrs);
+                return next;
+            }
+        }
+        if !next.marks.contains(&mark) {
+            next.marks.push(mark);
+        }
+        next
+    }
+}
 
 /// Parses inline content from an ODT XML node.
 ///
 /// Walks the children of `node` and converts text nodes, spans, line breaks,
-/// and hyperlinks into [`Inline`] values.
+/// spaces, tabs, hyperlinks, footnotes/endnotes, comments, tracked-change
+/// markers, text fields, bookmarks, and cross-references into [`Inline`]
+/// values.
+///
+/// Nested `text:span` and `text:a` elements are flattened into text runs
+/// that carry the combined marks of every enclosing element; the style name
+/// of the innermost named span style wins. Automatic styles, including the
+/// automatic paragraph style of `node` itself, contribute only their marks
+/// (and their parent's style name).
 ///
 /// # Arguments
 ///
 /// * `node` - The parent XML node whose children will be parsed.
 /// * `ns_text` - The `text:` namespace URI.
 /// * `ns_xlink` - The `xlink:` namespace URI.
-/// * `style_map` - Map from ODT style name to `(family, marks)` pairs.
+/// * `style_map` - Named and automatic styles, for resolving formatting.
 pub fn parse_inlines(
     node: roxmltree::Node,
     ns_text: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
+    style_map: &StyleMap,
 ) -> Vec<Inline> {
     let mut inlines = Vec::new();
+    let mut base = RunFormat::default();
+    if let Some(info) = node
+        .attribute((ns_text, "style-name"))
+        .and_then(|n| style_map.get(n))
+        .filter(|info| info.automatic)
+    {
+        for mark in &info.marks {
+            base = base.with_mark(mark.clone());
+        }
+    }
+    parse_runs(node, ns_text, ns_xlink, style_map, &base, &mut inlines);
+    inlines
+}
+
+/// Recursively converts the children of `node`, applying `format` to text.
+fn parse_runs(
+    node: roxmltree::Node,
+    ns_text: &str,
+    ns_xlink: &str,
+    style_map: &StyleMap,
+    format: &RunFormat,
+    inlines: &mut Vec<Inline>,
+) {
+    let ns = Ns::default();
     for child in node.children() {
         if child.is_text() {
             inlines.push(Inline::Text {
                 text: child.text().unwrap_or("").to_string(),
-                style_name: None,
-                marks: Vec::new(),
+                style_name: format.style_name.clone(),
+                marks: format.marks.clone(),
             });
         } else if child.has_tag_name((ns_text, "span")) {
-            let s_name = child.attribute((ns_text, "style-name"));
-            let marks = s_name
-                .and_then(|s| style_map.get(s))
-                .map(|(_, m)| m.clone())
-                .unwrap_or_default();
-            inlines.push(Inline::Text {
-                text: child.text().unwrap_or("").to_string(),
-                style_name: s_name.map(|s| s.to_string()),
-                marks,
+            let inner = span_format(child, ns_text, style_map, format);
+            parse_runs(child, ns_text, ns_xlink, style_map, &inner, inlines);
+        } else if child.has_tag_name((ns_text, "a")) {
+            let inner = format.with_mark(TiptapMark::Link {
+                attrs: LinkAttrs {
+                    href: child
+                        .attribute((ns_xlink, "href"))
+                        .unwrap_or("")
+                        .to_string(),
+                    target: Some("_blank".to_string()),
+                },
             });
+            parse_runs(child, ns_text, ns_xlink, style_map, &inner, inlines);
         } else if child.has_tag_name((ns_text, "line-break")) {
             inlines.push(Inline::LineBreak);
-        } else if child.has_tag_name((ns_text, "a")) {
-            parse_hyperlink(child, ns_text, ns_xlink, style_map, &mut inlines);
+        } else if child.has_tag_name((ns_text, "s")) {
+            inlines.push(Inline::Space {
+                count: space_count(child, ns_text),
+            });
+        } else if child.has_tag_name((ns_text, "tab")) {
+            inlines.push(Inline::Tab);
+        } else if child.has_tag_name((ns_text, "note")) {
+            inlines.push(parse_note(child, ns_text, ns_xlink, style_map));
+        } else if child.has_tag_name((ns.office, "annotation")) {
+            parse_annotation(child, ns_text, ns_xlink, style_map, inlines);
+        } else if child.has_tag_name((ns.office, "annotation-end")) {
+            if let Some(name) = child.attribute((ns.office, "name")) {
+                inlines.push(Inline::CommentEnd {
+                    id: name.to_string(),
+                });
+            }
+        } else if let Some(kind) = field_kind(child, ns_text) {
+            inlines.push(Inline::Field {
+                kind,
+                value: element_text(child),
+                fixed: child.attribute((ns_text, "fixed")) == Some("true"),
+                date_value: child.attribute((ns_text, "date-value")).map(str::to_string),
+                data_style_name: child
+                    .attribute((ns.style, "data-style-name"))
+                    .map(str::to_string),
+            });
+        } else if let Some(inline) = parse_bookmark(child, ns_text) {
+            inlines.push(inline);
+        } else if let Some(id) = child
+            .attribute((ns_text, "change-id"))
+            .filter(|_| child.tag_name().namespace() == Some(ns_text))
+        {
+            // Change details are filled in from text:tracked-changes later.
+            let change = TrackedChange {
+                id: id.to_string(),
+                ..TrackedChange::default()
+            };
+            match child.tag_name().name() {
+                "change-start" => inlines.push(Inline::ChangeStart(change)),
+                "change-end" => inlines.push(Inline::ChangeEnd { id: change.id }),
+                "change" => inlines.push(Inline::Change(change)),
+                _ => {}
+            }
         }
     }
-    inlines
 }
 
-/// Parses a `text:a` hyperlink element, attaching a `Link` mark to each inline.
-fn parse_hyperlink(
+/// The number of spaces a `text:s` element stands for, clamped to
+/// `1..=MAX_SPACE_COUNT`.
+pub(crate) fn space_count(node: roxmltree::Node, ns_text: &str) -> u32 {
+    node.attribute((ns_text, "c"))
+        .and_then(|c| c.parse::<u32>().ok())
+        .unwrap_or(1)
+        .clamp(1, MAX_SPACE_COUNT)
+}
+
+/// Parses a bookmark or cross-reference element.
+fn parse_bookmark(node: roxmltree::Node, ns_text: &str) -> Option<Inline> {
+    if node.tag_name().namespace() != Some(ns_text) {
+        return None;
+    }
+    let local = node.tag_name().name();
+    if let Some(kind) = ReferenceKind::from_odf_str(local) {
+        return Some(Inline::Reference(CrossReference {
+            kind,
+            name: node.attribute((ns_text, "ref-name"))?.to_string(),
+            format: node
+                .attribute((ns_text, "reference-format"))
+                .map(str::to_string),
+            value: element_text(node),
+        }));
+    }
+    let name = node.attribute((ns_text, "name"))?.to_string();
+    match local {
+        "bookmark" => Some(Inline::Bookmark { name }),
+        "bookmark-start" => Some(Inline::BookmarkStart { name }),
+        "bookmark-end" => Some(Inline::BookmarkEnd { name }),
+        _ => None,
+    }
+}
+
+/// The text content of an element and its descendants.
+fn element_text(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+/// Returns the kind of a supported `text:` field element.
+fn field_kind(node: roxmltree::Node, ns_text: &str) -> Option<FieldKind> {
+    if node.tag_name().namespace() != Some(ns_text) {
+        return None;
+    }
+    FieldKind::from_odf_str(node.tag_name().name())
+}
+
+/// Computes the run format inside a `text:span`.
+///
+/// A run keeps one style name, that of its innermost named span style, but
+/// the marks of every enclosing style are combined: text in a bold span
+/// nested in an italic one is both bold and italic.
+fn span_format(
+    span: roxmltree::Node,
+    ns_text: &str,
+    style_map: &StyleMap,
+    outer: &RunFormat,
+) -> RunFormat {
+    let Some(name) = span.attribute((ns_text, "style-name")) else {
+        return outer.clone();
+    };
+    let mut inner = outer.clone();
+    let (named, direct) = match style_map.get(name) {
+        Some(info) if info.automatic => (info.parent.as_deref(), info.marks.as_slice()),
+        _ => (Some(name), &[][..]),
+    };
+    // The enclosing marks stay; the span's named style and then its direct
+    // formatting are layered on top, so the innermost properties win.
+    if let Some(named) = named {
+        inner.style_name = Some(named.to_string());
+        if let Some(info) = style_map.get(named) {
+            for mark in &info.marks {
+                inner = inner.with_mark(mark.clone());
+            }
+        }
+    }
+    for mark in direct {
+        inner = inner.with_mark(mark.clone());
+    }
+    inner
+}
+
+/// Parses a `text:note` element into an [`Inline::Note`].
+///
+/// The note body (`text:note-body`) is parsed as ordinary block content.
+fn parse_note(
     child: roxmltree::Node,
     ns_text: &str,
     ns_xlink: &str,
-    style_map: &HashMap<String, (String, Vec<TiptapMark>)>,
-    inlines: &mut Vec<Inline>,
-) {
-    let href = child
-        .attribute((ns_xlink, "href"))
+    style_map: &StyleMap,
+) -> Inline {
+    let ns = Ns::default();
+    let citation = child
+        .children()
+        .find(|n| n.has_tag_name((ns_text, "note-citation")))
+        .and_then(|n| n.text())
         .unwrap_or("")
         .to_string();
-    let inner = parse_inlines(child, ns_text, ns_xlink, style_map);
-    for mut inline in inner {
-        if let Inline::Text { ref mut marks, .. } = inline {
-            marks.push(TiptapMark::Link {
-                attrs: LinkAttrs {
-                    href: href.clone(),
-                    target: Some("_blank".to_string()),
-                },
-            });
-        }
-        inlines.push(inline);
+    let content = child
+        .children()
+        .find(|n| n.has_tag_name((ns_text, "note-body")))
+        .map(|body| parse_blocks(body, ns_text, ns.table, ns.draw, ns_xlink, style_map))
+        .unwrap_or_default();
+    Inline::Note {
+        id: child.attribute((ns_text, "id")).map(|s| s.to_string()),
+        note_class: child
+            .attribute((ns_text, "note-class"))
+            .map(NoteClass::from_odf_str)
+            .unwrap_or_default(),
+        citation,
+        content,
+    }
+}
+
+/// Parses an `office:annotation` element into an [`Inline::Comment`].
+///
+/// LibreOffice writes a reply as a separate annotation naming the comment it
+/// answers in `loext:parent-name`; a reply whose parent was parsed earlier in
+/// the same paragraph is attached to it instead of becoming a new comment.
+fn parse_annotation(
+    child: roxmltree::Node,
+    ns_text: &str,
+    ns_xlink: &str,
+    style_map: &StyleMap,
+    inlines: &mut Vec<Inline>,
+) {
+    let ns = Ns::default();
+    let dc_text = |name: &str| {
+        child
+            .children()
+            .find(|n| n.has_tag_name((ns.dc, name)))
+            .and_then(|n| n.text())
+            .map(str::to_string)
+    };
+    let comment = Comment {
+        id: child.attribute((ns.office, "name")).map(str::to_string),
+        author: dc_text("creator"),
+        date: dc_text("date"),
+        resolved: child.attribute((ns.loext, "resolved")) == Some("true"),
+        content: parse_blocks(child, ns_text, ns.table, ns.draw, ns_xlink, style_map),
+        replies: Vec::new(),
+    };
+    let parent = child.attribute((ns.loext, "parent-name")).and_then(|name| {
+        inlines.iter_mut().rev().find_map(|inline| match inline {
+            Inline::Comment(c) => find_comment(c, name),
+            _ => None,
+        })
+    });
+    match parent {
+        Some(parent) => parent.replies.push(comment),
+        None => inlines.push(Inline::Comment(comment)),
+    }
+}
+
+/// Finds the comment named `name` in a comment thread.
+fn find_comment<'a>(comment: &'a mut Comment, name: &str) -> Option<&'a mut Comment> {
+    if comment.id.as_deref() == Some(name) {
+        return Some(comment);
     }
+    comment
+        .replies
+        .iter_mut()
+        .find_map(|reply| find_comment(reply, name))
 }
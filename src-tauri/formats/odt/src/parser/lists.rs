@@ -0,0 +1,95 @@
+//! ODT list style resolution.
+//!
+//! Resolves the `text:list-style` that applies to a `text:list` element and
+//! converts the matching list level into [`ListAttrs`].
+
+use common_core::{ListAttrs, ListLevelKind, ListLevelStyle};
+
+use crate::list_styles::DEFAULT_NUMBER_STYLE;
+use crate::namespaces::Ns;
+use crate::parser::styles::StyleMap;
+
+/// Resolves whether a `text:list` is numbered, and its numbering attributes.
+///
+/// Nested lists without their own `text:style-name` inherit the style of the
+/// nearest enclosing list, and the nesting depth selects the list level.
+/// Styles are looked up in `style_map`, which holds the list styles of the
+/// document being parsed and, for `content.xml`, the named list styles of
+/// `styles.xml`.
+///
+/// # Returns
+///
+/// `(ordered, attrs)`, where `attrs` is `None` when the list carries no
+/// style, start value or continuation flag.
+pub fn resolve_list(
+    list: roxmltree::Node,
+    ns_text: &str,
+    style_map: &StyleMap,
+) -> (bool, Option<ListAttrs>) {
+    let ns = Ns::default();
+    // Lists in table cells and note bodies start a fresh nesting context.
+    let enclosing: Vec<roxmltree::Node> = list
+        .ancestors()
+        .take_while(|n| {
+            !n.has_tag_name((ns.table, "table-cell")) && !n.has_tag_name((ns_text, "note-body"))
+        })
+        .filter(|n| n.has_tag_name((ns_text, "list")))
+        .collect();
+    let level = enclosing.len();
+    let style_name = enclosing
+        .iter()
+        .find_map(|n| n.attribute((ns_text, "style-name")));
+
+    let level_style = style_name
+        .and_then(|name| style_map.get(name))
+        .and_then(|info| find_level_style(&info.list_levels, level));
+    // Without its styles, the writer's default numbering style is still
+    // recognised by name.
+    let ordered = match level_style {
+        Some(ls) => ls.kind == ListLevelKind::Number,
+        None => style_name == Some(DEFAULT_NUMBER_STYLE),
+    };
+
+    let mut attrs = ListAttrs {
+        style_name: style_name.map(str::to_string),
+        continue_numbering: list.attribute((ns_text, "continue-numbering")) == Some("true")
+            || list.attribute((ns_text, "continue-list")).is_some(),
+        ..ListAttrs::default()
+    };
+    let mut start = None;
+    if let Some(ls) = level_style {
+        let attr = |key: &str| ls.attributes.get(key).cloned();
+        if ordered {
+            attrs.num_format = attr("style:num-format");
+            attrs.num_prefix = attr("style:num-prefix");
+            attrs.num_suffix = attr("style:num-suffix");
+            start = attr("text:start-value");
+        } else {
+            attrs.bullet_char = attr("text:bullet-char");
+        }
+    }
+    // A start value on the first item overrides the one from the list style.
+    if let Some(item_start) = list
+        .children()
+        .find(|n| n.has_tag_name((ns_text, "list-item")))
+        .and_then(|item| item.attribute((ns_text, "start-value")))
+    {
+        start = Some(item_start.to_string());
+    }
+    attrs.start = start
+        .and_then(|s| s.parse::<u32>().ok())
+        .filter(|&s| s != 1);
+
+    let attrs = (attrs != ListAttrs::default()).then_some(attrs);
+    (ordered, attrs)
+}
+
+/// Finds the list style level for `level`.
+///
+/// Falls back to the deepest defined level when `level` itself is missing.
+fn find_level_style(levels: &[ListLevelStyle], level: usize) -> Option<&ListLevelStyle> {
+    levels
+        .iter()
+        .filter(|ls| ls.level as usize <= level)
+        .max_by_key(|ls| ls.level)
+}
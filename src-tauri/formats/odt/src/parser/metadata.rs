@@ -3,12 +3,14 @@
 //! Parses the `<office:meta>` section of an ODT XML document into a
 //! [`Metadata`] struct containing Dublin Core and ODF meta fields.
 
-use common_core::Metadata;
+use common_core::{DocumentStatistics, Metadata, UserDefinedField};
 
 /// Parses the `<office:meta>` element from the document root.
 ///
-/// Extracts Dublin Core elements (title, description, creator, etc.) and
-/// ODF meta elements (generator, creation-date) into a [`Metadata`] struct.
+/// Extracts Dublin Core elements (title, description, creator, date, etc.)
+/// and ODF meta elements (generator, creation-date, keywords, editing
+/// cycles and duration, user-defined properties and document statistics)
+/// into a [`Metadata`] struct.
 ///
 /// # Arguments
 ///
@@ -46,9 +48,51 @@ pub fn parse_metadata(
                 metadata.identifier = child.text().map(|s| s.to_string());
             } else if child.has_tag_name((ns_dc, "language")) {
                 metadata.language = child.text().map(|s| s.to_string());
+            } else if child.has_tag_name((ns_dc, "date")) {
+                metadata.date = child.text().map(|s| s.to_string());
+            } else if child.has_tag_name((ns_meta, "keyword")) {
+                metadata
+                    .keywords
+                    .extend(child.text().map(|s| s.to_string()));
+            } else if child.has_tag_name((ns_meta, "initial-creator")) {
+                metadata.initial_creator = child.text().map(|s| s.to_string());
+            } else if child.has_tag_name((ns_meta, "editing-cycles")) {
+                metadata.editing_cycles = child.text().and_then(|s| s.trim().parse().ok());
+            } else if child.has_tag_name((ns_meta, "editing-duration")) {
+                metadata.editing_duration = child.text().map(|s| s.to_string());
+            } else if child.has_tag_name((ns_meta, "user-defined")) {
+                if let Some(name) = child.attribute((ns_meta, "name")) {
+                    metadata.user_defined.push(UserDefinedField {
+                        name: name.to_string(),
+                        value_type: child
+                            .attribute((ns_meta, "value-type"))
+                            .map(|s| s.to_string()),
+                        value: child.text().unwrap_or_default().to_string(),
+                    });
+                }
+            } else if child.has_tag_name((ns_meta, "document-statistic")) {
+                metadata.statistics = Some(parse_statistics(child, ns_meta));
             }
         }
     }
 
     metadata
 }
+
+/// Parses the counts of a `<meta:document-statistic>` element; missing or
+/// invalid counts are zero.
+fn parse_statistics(node: roxmltree::Node, ns_meta: &str) -> DocumentStatistics {
+    let get = |name: &str| {
+        node.attribute((ns_meta, name))
+            .and_then(|v| v.trim().parse::<u32>().ok())
+    };
+    DocumentStatistics {
+        page_count: get("page-count"),
+        paragraph_count: get("paragraph-count").unwrap_or(0),
+        word_count: get("word-count").unwrap_or(0),
+        character_count: get("character-count").unwrap_or(0),
+        non_whitespace_character_count: get("non-whitespace-character-count").unwrap_or(0),
+        table_count: get("table-count").unwrap_or(0),
+        image_count: get("image-count").unwrap_or(0),
+    }
+}
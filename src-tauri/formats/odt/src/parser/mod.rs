@@ -1,7 +1,8 @@
 //! ODT document parser.
 //!
-//! Provides [`parse_document`] and [`add_styles_from_xml`] as the primary
-//! entry points for loading ODT XML content into a [`Document`].
+//! Provides [`parse_document`], [`parse_document_with_styles`] and
+//! [`add_styles_from_xml`] as the primary entry points for loading ODT XML
+//! content into a [`Document`].
 //!
 //! # Supported Formats
 //!
@@ -11,15 +12,25 @@
 //! - **ODT meta.xml** (`office:document-meta`): ZIP-extracted metadata
 
 pub mod blocks;
+pub mod changes;
+pub mod images;
 pub mod inlines;
+pub mod lists;
 pub mod metadata;
+pub mod outline;
+pub mod page;
 pub mod styles;
+pub mod tables;
+pub mod toc;
 
 use crate::document::Document;
 use crate::namespaces::Ns;
 use crate::parser::blocks::parse_blocks;
+use crate::parser::changes::{attach_tracked_changes, is_tracking_changes, parse_tracked_changes};
 use crate::parser::metadata::parse_metadata;
-use crate::parser::styles::{parse_styles, parse_styles_node};
+use crate::parser::outline::parse_outline_style;
+use crate::parser::page::parse_page_setup;
+use crate::parser::styles::{parse_styles, parse_styles_node, StyleMap};
 
 /// Maximum XML element nesting depth accepted before parsing is aborted.
 ///
@@ -108,6 +119,51 @@ fn check_nesting_depth(xml: &str, max: usize) -> Result<(), String> {
 /// println!("Blocks: {}", doc.blocks.len());
 /// ```
 pub fn parse_document(xml: &str) -> Result<Document, String> {
+    parse_document_in(xml, StyleMap::new())
+}
+
+/// Parses a `content.xml` string together with the `styles.xml` of the same
+/// package.
+///
+/// The named styles of `styles.xml` are visible while the content is parsed,
+/// so lists and spans resolve styles defined there, and are then added to
+/// the document as by [`add_styles_from_xml`].
+///
+/// # Errors
+///
+/// Returns a `String` error if either part cannot be parsed; errors in
+/// `styles.xml` are reported as such.
+pub fn parse_document_with_styles(xml: &str, styles_xml: &str) -> Result<Document, String> {
+    let styles_error = |e: String| format!("Failed to parse styles.xml: {e}");
+    let named = named_style_map(styles_xml).map_err(styles_error)?;
+    let mut doc = parse_document_in(xml, named)?;
+    add_styles_from_xml(&mut doc, styles_xml).map_err(styles_error)?;
+    Ok(doc)
+}
+
+/// Returns the style map of the named styles in a `styles.xml` string.
+///
+/// Its automatic styles are left out: they belong to the master pages and
+/// their names may clash with those of `content.xml`.
+fn named_style_map(xml: &str) -> Result<StyleMap, String> {
+    check_nesting_depth(xml, MAX_XML_NESTING_DEPTH)?;
+    let ns = Ns::default();
+    let parsed = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let (_, mut style_map) = parse_styles(
+        parsed.root_element(),
+        ns.office,
+        ns.style,
+        ns.fo,
+        ns.text,
+        ns.loki,
+    );
+    style_map.retain(|_, info| !info.automatic);
+    Ok(style_map)
+}
+
+/// Parses `xml` with the styles of `named` visible to the content parsers;
+/// styles of `xml` itself take precedence.
+fn parse_document_in(xml: &str, mut named: StyleMap) -> Result<Document, String> {
     check_nesting_depth(xml, MAX_XML_NESTING_DEPTH)?;
     let ns = Ns::default();
     let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
@@ -116,10 +172,19 @@ pub fn parse_document(xml: &str) -> Result<Document, String> {
     validate_root(&root, ns.office)?;
 
     let metadata = parse_metadata(root, ns.office, ns.dc, ns.meta);
-    let (style_definitions, style_map) =
+    let (style_definitions, own_styles) =
         parse_styles(root, ns.office, ns.style, ns.fo, ns.text, ns.loki);
+    named.extend(own_styles);
+    let style_map = named;
+
+    let outline_style = root
+        .children()
+        .find(|n| n.has_tag_name((ns.office, "styles")))
+        .and_then(|n| parse_outline_style(n, ns.style, ns.text));
+    let page_setup = parse_page_setup(root, &ns, &style_map);
 
     let is_meta_only = root.has_tag_name((ns.office, "document-meta"));
+    let mut track_changes = false;
     let blocks = if is_meta_only {
         Vec::new()
     } else {
@@ -129,20 +194,27 @@ pub fn parse_document(xml: &str) -> Result<Document, String> {
             .and_then(|n| n.children().find(|c| c.has_tag_name((ns.office, "text"))))
             .ok_or("Could not find office:text")?;
 
-        parse_blocks(
+        let mut blocks = parse_blocks(
             office_text,
             ns.text,
             ns.table,
             ns.draw,
             ns.xlink,
             &style_map,
-        )
+        );
+        let changes = parse_tracked_changes(office_text, &style_map);
+        attach_tracked_changes(&mut blocks, &changes);
+        track_changes = is_tracking_changes(office_text);
+        blocks
     };
 
     Ok(Document {
         blocks,
         styles: style_definitions,
         metadata,
+        track_changes,
+        outline_style,
+        page_setup,
         font_face_decls: None,
         automatic_styles: None,
         master_styles: None,
@@ -179,6 +251,18 @@ pub fn add_styles_from_xml(doc: &mut Document, xml: &str) -> Result<(), String>
         .find(|n| n.has_tag_name((ns.office, "master-styles")))
         .map(|n| xml[n.range()].to_string());
 
+    let (_, style_map) = parse_styles(
+        parsed.root_element(),
+        ns.office,
+        ns.style,
+        ns.fo,
+        ns.text,
+        ns.loki,
+    );
+    if let Some(page_setup) = parse_page_setup(parsed.root_element(), &ns, &style_map) {
+        doc.page_setup = Some(page_setup);
+    }
+
     // Parse styles from the office:styles section
     if let Some(styles_elem) = parsed
         .descendants()
@@ -186,6 +270,9 @@ pub fn add_styles_from_xml(doc: &mut Document, xml: &str) -> Result<(), String>
     {
         let new_styles = parse_styles_node(styles_elem, ns.style, ns.fo, ns.text, ns.loki)?;
         doc.styles.extend(new_styles);
+        if let Some(outline) = parse_outline_style(styles_elem, ns.style, ns.text) {
+            doc.outline_style = Some(outline);
+        }
     }
 
     Ok(())
@@ -284,6 +371,9 @@ mod tests {
             blocks: vec![],
             styles: std::collections::HashMap::new(),
             metadata: common_core::Metadata::default(),
+            track_changes: false,
+            outline_style: None,
+            page_setup: None,
             font_face_decls: None,
             automatic_styles: None,
             master_styles: None,
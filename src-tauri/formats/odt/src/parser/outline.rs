@@ -0,0 +1,58 @@
+//! ODT outline style parser.
+//!
+//! Reads the `text:outline-style` of `office:styles`, which numbers the
+//! headings of the document by outline level.
+
+use common_core::{OutlineLevel, OutlineStyle};
+
+/// Parses the `text:outline-style` child of an `office:styles` element.
+///
+/// Returns `None` when the element has no outline style.
+pub fn parse_outline_style(
+    styles_node: roxmltree::Node,
+    ns_style: &str,
+    ns_text: &str,
+) -> Option<OutlineStyle> {
+    let outline = styles_node
+        .children()
+        .find(|n| n.has_tag_name((ns_text, "outline-style")))?;
+
+    let levels = outline
+        .children()
+        .filter(|n| n.has_tag_name((ns_text, "outline-level-style")))
+        .filter_map(|n| {
+            let defaults = OutlineLevel::default();
+            Some(OutlineLevel {
+                level: n.attribute((ns_text, "level"))?.parse().ok()?,
+                // An empty format leaves the level unnumbered.
+                num_format: n
+                    .attribute((ns_style, "num-format"))
+                    .filter(|f| !f.is_empty())
+                    .map(str::to_string),
+                num_prefix: n
+                    .attribute((ns_style, "num-prefix"))
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_string),
+                num_suffix: n
+                    .attribute((ns_style, "num-suffix"))
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+                start: n
+                    .attribute((ns_text, "start-value"))
+                    .and_then(|s| s.parse::<u32>().ok())
+                    .filter(|&s| s != 1),
+                display_levels: n
+                    .attribute((ns_text, "display-levels"))
+                    .and_then(|d| d.parse().ok())
+                    .unwrap_or(defaults.display_levels),
+            })
+        })
+        .collect();
+
+    Some(OutlineStyle {
+        name: outline
+            .attribute((ns_style, "name"))
+            .map_or_else(|| OutlineStyle::default().name, str::to_string),
+        levels,
+    })
+}
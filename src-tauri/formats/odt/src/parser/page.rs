@@ -0,0 +1,215 @@
+//! ODT page layout parser.
+//!
+//! Reads the `style:page-layout` elements of `office:automatic-styles` and
+//! the `style:master-page` elements of `office:master-styles`, with their
+//! headers and footers, into a [`PageSetup`].
+
+use common_core::units::length_to_cm;
+use common_core::{Block, HeaderFooter, MasterPage, PageLayout, PageOrientation, PageSetup};
+
+use crate::namespaces::Ns;
+use crate::parser::blocks::parse_blocks;
+use crate::parser::styles::StyleMap;
+
+/// Parses the page layouts and master pages below a document root.
+///
+/// Header and footer paragraphs resolve their formatting through
+/// `style_map`. Returns `None` when the document defines neither layouts nor
+/// master pages.
+pub fn parse_page_setup(root: roxmltree::Node, ns: &Ns, style_map: &StyleMap) -> Option<PageSetup> {
+    let section = |name| {
+        root.children()
+            .find(move |n| n.has_tag_name((ns.office, name)))
+    };
+
+    let layouts: Vec<PageLayout> = section("automatic-styles")
+        .into_iter()
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((ns.style, "page-layout")))
+        .filter_map(|n| parse_page_layout(n, ns))
+        .collect();
+
+    let master_pages: Vec<MasterPage> = section("master-styles")
+        .into_iter()
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((ns.style, "master-page")))
+        .filter_map(|n| {
+            Some(MasterPage {
+                name: n.attribute((ns.style, "name"))?.to_string(),
+                display_name: n.attribute((ns.style, "display-name")).map(str::to_string),
+                page_layout: n
+                    .attribute((ns.style, "page-layout-name"))
+                    .unwrap_or_default()
+                    .to_string(),
+                next: n
+                    .attribute((ns.style, "next-style-name"))
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+                header: parse_header_footer(n, "header", ns, style_map),
+                footer: parse_header_footer(n, "footer", ns, style_map),
+            })
+        })
+        .collect();
+
+    if layouts.is_empty() && master_pages.is_empty() {
+        return None;
+    }
+    Some(PageSetup {
+        layouts,
+        master_pages,
+    })
+}
+
+/// Parses `style:header` or `style:footer` (`kind`) of a master page with
+/// its `-left` and `-first` variants. Hidden ones (`style:display="false"`)
+/// are skipped.
+fn parse_header_footer(
+    master: roxmltree::Node,
+    kind: &str,
+    ns: &Ns,
+    style_map: &StyleMap,
+) -> Option<HeaderFooter> {
+    let variant = |name: String| -> Option<Vec<Block>> {
+        let node = master.children().find(|n| {
+            n.has_tag_name((ns.style, name.as_str()))
+                && n.attribute((ns.style, "display")) != Some("false")
+        })?;
+        Some(parse_blocks(
+            node, ns.text, ns.table, ns.draw, ns.xlink, style_map,
+        ))
+    };
+    Some(HeaderFooter {
+        content: variant(kind.to_string())?,
+        left: variant(format!("{kind}-left")),
+        first: variant(format!("{kind}-first")),
+    })
+}
+
+fn parse_page_layout(node: roxmltree::Node, ns: &Ns) -> Option<PageLayout> {
+    let defaults = PageLayout::default();
+    let props = node
+        .children()
+        .find(|n| n.has_tag_name((ns.style, "page-layout-properties")));
+    let length = |name, default| {
+        props
+            .and_then(|p| p.attribute((ns.fo, name)))
+            .and_then(length_to_cm)
+            .map_or(default, cm_to_pt)
+    };
+    // A plain `fo:margin` sets all four sides; margins default to zero.
+    let margin = length("margin", 0.0);
+
+    Some(PageLayout {
+        name: node.attribute((ns.style, "name"))?.to_string(),
+        width: length("page-width", defaults.width),
+        height: length("page-height", defaults.height),
+        margin_top: length("margin-top", margin),
+        margin_bottom: length("margin-bottom", margin),
+        margin_left: length("margin-left", margin),
+        margin_right: length("margin-right", margin),
+        orientation: match props.and_then(|p| p.attribute((ns.style, "print-orientation"))) {
+            Some("landscape") => PageOrientation::Landscape,
+            _ => PageOrientation::Portrait,
+        },
+    })
+}
+
+/// Converts centimetres to points, rounded to a hundredth of a point.
+fn cm_to_pt(cm: f64) -> f64 {
+    (cm * 72.0 / 2.54 * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &str) -> Option<PageSetup> {
+        let xml = format!(
+            r#"<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+                xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+                xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0"
+                xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">{body}</office:document>"#
+        );
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        parse_page_setup(doc.root_element(), &Ns::default(), &StyleMap::new())
+    }
+
+    #[test]
+    fn letter_layout_and_master_page_are_parsed() {
+        let setup = parse(
+            r#"<office:automatic-styles>
+                 <style:page-layout style:name="pm1">
+                   <style:page-layout-properties fo:page-width="8.5in" fo:page-height="11in"
+                     fo:margin-top="1in" fo:margin-bottom="0.5in" fo:margin-left="2.54cm"
+                     fo:margin-right="25.4mm" style:print-orientation="portrait"/>
+                 </style:page-layout>
+               </office:automatic-styles>
+               <office:master-styles>
+                 <style:master-page style:name="Standard" style:page-layout-name="pm1"/>
+               </office:master-styles>"#,
+        )
+        .unwrap();
+        let layout = &setup.layouts[0];
+        assert_eq!((layout.width, layout.height), (612.0, 792.0));
+        assert_eq!(layout.margin_top, 72.0);
+        assert_eq!(layout.margin_bottom, 36.0);
+        assert_eq!(layout.margin_left, 72.0);
+        assert_eq!(layout.margin_right, 72.0);
+        assert_eq!(setup.master_pages[0].page_layout, "pm1");
+    }
+
+    #[test]
+    fn shorthand_margin_and_landscape_are_parsed() {
+        let setup = parse(
+            r#"<office:automatic-styles>
+                 <style:page-layout style:name="pm2">
+                   <style:page-layout-properties fo:page-width="21cm" fo:page-height="14.8cm"
+                     fo:margin="1cm" style:print-orientation="landscape"/>
+                 </style:page-layout>
+               </office:automatic-styles>"#,
+        )
+        .unwrap();
+        let layout = &setup.layouts[0];
+        assert_eq!(layout.orientation, PageOrientation::Landscape);
+        assert_eq!(layout.margin_left, 28.35);
+        assert_eq!(layout.margin_bottom, 28.35);
+        assert!(setup.master_pages.is_empty());
+    }
+
+    #[test]
+    fn headers_and_footers_are_parsed_with_variants() {
+        let setup = parse(
+            r#"<office:master-styles>
+                 <style:master-page style:name="Standard" style:page-layout-name="pm1">
+                   <style:header><text:p>Report</text:p></style:header>
+                   <style:header-first><text:p>Cover</text:p></style:header-first>
+                   <style:footer><text:p>Page <text:page-number>1</text:page-number></text:p></style:footer>
+                   <style:footer-left style:display="false"><text:p>Hidden</text:p></style:footer-left>
+                 </style:master-page>
+               </office:master-styles>"#,
+        )
+        .unwrap();
+        let master = &setup.master_pages[0];
+        let header = master.header.as_ref().unwrap();
+        assert_eq!(header.content.len(), 1);
+        assert!(header.first.is_some());
+        assert!(header.left.is_none());
+        let footer = master.footer.as_ref().unwrap();
+        assert!(footer.left.is_none());
+        let Block::Paragraph { content, .. } = &footer.content[0] else {
+            panic!("expected a paragraph");
+        };
+        assert!(content.iter().any(|i| matches!(
+            i,
+            common_core::Inline::Field {
+                kind: common_core::FieldKind::PageNumber,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn document_without_pages_has_no_setup() {
+        assert_eq!(parse("<office:automatic-styles/>"), None);
+    }
+}
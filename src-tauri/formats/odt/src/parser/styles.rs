@@ -1,28 +1,70 @@
 //! ODT style parser.
 //!
 //! Parses `style:style` and `style:default-style` elements from ODT XML
-//! into [`StyleDefinition`] structs and a style-to-marks lookup map.
+//! into [`StyleDefinition`] structs and a [`StyleMap`] used while parsing
+//! content.
 
 use std::collections::HashMap;
 
-use common_core::{StyleDefinition, StyleFamily, TiptapMark};
+use common_core::{
+    BlockAttrs, CellBorders, ImageWrap, ListLevelStyle, SectionAttrs, StyleDefinition, StyleFamily,
+    TiptapMark,
+};
 
 #[path = "styles_helpers.rs"]
 mod helpers;
-use helpers::{extract_marks_from_style, parse_default_styles, parse_single_style};
+use helpers::{
+    extract_block_attrs, extract_cell_style, extract_column_width, extract_marks_from_style,
+    extract_section_attrs, extract_text_style, extract_wrap, parse_default_styles,
+    parse_list_style, parse_single_style,
+};
 
-/// Return type for [`parse_styles`]: (style definitions, style-name → (odt-name, marks)).
-type StyleParseResult = (
-    HashMap<String, StyleDefinition>,
-    HashMap<String, (String, Vec<TiptapMark>)>,
-);
+/// Formatting of a single style, as seen by the block and inline parsers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleInfo {
+    /// The ODF family string (`"paragraph"`, `"text"`, ...).
+    pub family: String,
+    /// Character marks derived from the style's text properties.
+    pub marks: Vec<TiptapMark>,
+    /// `true` for styles from `office:automatic-styles`.
+    ///
+    /// Automatic styles are direct formatting: the parsers resolve them into
+    /// [`BlockAttrs`] and marks instead of keeping their names.
+    pub automatic: bool,
+    /// The parent style name.
+    pub parent: Option<String>,
+    /// The direct formatting of an automatic paragraph style.
+    pub block_attrs: Option<BlockAttrs>,
+    /// Whether the paragraph properties request a page break before.
+    pub break_before: bool,
+    /// Column width in centimetres, for `table-column` styles.
+    pub column_width: Option<f64>,
+    /// Cell background colour, for `table-cell` styles.
+    pub cell_background: Option<String>,
+    /// Cell borders, for `table-cell` styles.
+    pub cell_border: Option<CellBorders>,
+    /// Text wrapping, for `graphic` styles.
+    pub wrap: Option<ImageWrap>,
+    /// Column layout, for `section` styles.
+    pub section: Option<SectionAttrs>,
+    /// The levels of a `text:list-style`.
+    pub list_levels: Vec<ListLevelStyle>,
+}
+
+/// Style name → [`StyleInfo`] lookup used during block and inline parsing.
+pub type StyleMap = HashMap<String, StyleInfo>;
+
+/// Return type for [`parse_styles`]: (style definitions, style map).
+type StyleParseResult = (HashMap<String, StyleDefinition>, StyleMap);
 
 /// Parses all named styles from an ODT document root.
 ///
 /// Scans `office:styles` and `office:automatic-styles` sections for
-/// `style:style` elements and builds two complementary data structures:
-/// - A `StyleDefinition` map for the frontend
-/// - A lightweight `style_map` used during block/inline parsing
+/// `style:style` and `text:list-style` elements, and `office:styles` for
+/// default styles, and builds two complementary data structures:
+/// - A `StyleDefinition` map of named styles for the frontend
+/// - A [`StyleMap`] of named and automatic styles used during block/inline
+///   parsing
 ///
 /// # Arguments
 ///
@@ -35,8 +77,8 @@ type StyleParseResult = (
 ///
 /// # Returns
 ///
-/// A tuple of `(style_definitions, style_map)` where `style_map` maps
-/// style names to `(family_str, marks)` for use during inline parsing.
+/// A tuple of `(style_definitions, style_map)`. Automatic styles appear only
+/// in `style_map`, since they are regenerated from direct formatting on write.
 pub fn parse_styles(
     root: roxmltree::Node,
     ns_office: &str,
@@ -60,13 +102,43 @@ pub fn parse_styles(
 
     for style_node in style_nodes {
         if let Some(name) = style_node.attribute((ns_style, "name")) {
-            let def = parse_single_style(style_node, name, ns_style, ns_fo, ns_text, ns_loki);
-            let marks = extract_marks_from_style(style_node, ns_style, ns_fo);
-            style_map.insert(
-                name.to_string(),
-                (def.family.to_odf_str().to_string(), marks),
-            );
-            style_definitions.insert(name.to_string(), def);
+            let automatic = style_node
+                .parent()
+                .is_some_and(|p| p.has_tag_name((ns_office, "automatic-styles")));
+            let Some(def) = parse_single_style(style_node, name, ns_style, ns_fo, ns_text, ns_loki)
+            else {
+                continue;
+            };
+            let mut marks = extract_marks_from_style(style_node, ns_style, ns_fo);
+            // Colours and fonts of named styles stay with the style itself.
+            if automatic {
+                marks.extend(extract_text_style(style_node, ns_style, ns_fo));
+            }
+            let (cell_background, cell_border) = extract_cell_style(style_node, ns_style, ns_fo);
+            let info = StyleInfo {
+                family: style_node
+                    .attribute((ns_style, "family"))
+                    .unwrap_or(def.family.to_odf_str())
+                    .to_string(),
+                marks,
+                automatic,
+                parent: def.parent.clone(),
+                block_attrs: automatic
+                    .then(|| extract_block_attrs(style_node, &def, ns_style))
+                    .flatten(),
+                break_before: def.attributes.get("fo:break-before").map(String::as_str)
+                    == Some("page"),
+                column_width: extract_column_width(style_node, ns_style),
+                cell_background,
+                cell_border,
+                wrap: extract_wrap(style_node, ns_style),
+                section: extract_section_attrs(style_node, ns_style, ns_fo),
+                list_levels: Vec::new(),
+            };
+            style_map.insert(name.to_string(), info);
+            if !automatic {
+                style_definitions.insert(name.to_string(), def);
+            }
         }
     }
 
@@ -76,8 +148,34 @@ pub fn parse_styles(
         ns_style,
         ns_fo,
         ns_text,
+        ns_loki,
         &mut style_definitions,
     );
+    let list_styles = root
+        .children()
+        .filter(|n| {
+            n.has_tag_name((ns_office, "styles")) || n.has_tag_name((ns_office, "automatic-styles"))
+        })
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((ns_text, "list-style")));
+    for list_style in list_styles {
+        let Some(def) = parse_list_style(list_style, ns_style, ns_text) else {
+            continue;
+        };
+        let automatic = list_style
+            .parent()
+            .is_some_and(|p| p.has_tag_name((ns_office, "automatic-styles")));
+        let info = StyleInfo {
+            family: "list".to_string(),
+            automatic,
+            list_levels: def.list_levels.clone(),
+            ..StyleInfo::default()
+        };
+        style_map.insert(def.name.clone(), info);
+        if !automatic {
+            style_definitions.insert(def.name.clone(), def);
+        }
+    }
     link_styles_to_defaults(&mut style_definitions);
 
     (style_definitions, style_map)
@@ -86,7 +184,8 @@ pub fn parse_styles(
 /// Parses style properties from `office:styles` in a standalone styles.xml.
 ///
 /// Called when loading a `.odt` ZIP file's `styles.xml`. Populates the
-/// document's `styles` map and preserves raw XML sections.
+/// document's `styles` map with the named, default and list styles of every
+/// family used in text documents.
 ///
 /// # Arguments
 ///
@@ -113,16 +212,28 @@ pub fn parse_styles_node(
         {
             let is_default = style_node.has_tag_name((ns_style, "default-style"));
             let style_name = if is_default {
-                default_style_name(style_node, ns_style)
+                match style_node
+                    .attribute((ns_style, "family"))
+                    .and_then(StyleFamily::from_odf_str)
+                {
+                    Some(family) => family.default_style_name(),
+                    None => continue,
+                }
             } else {
                 style_node
                     .attribute((ns_style, "name"))
                     .ok_or("Style missing style:name attribute")?
                     .to_string()
             };
-            let def =
-                parse_single_style(style_node, &style_name, ns_style, ns_fo, ns_text, ns_loki);
-            styles.insert(style_name, def);
+            if let Some(def) =
+                parse_single_style(style_node, &style_name, ns_style, ns_fo, ns_text, ns_loki)
+            {
+                styles.insert(style_name, def);
+            }
+        } else if style_node.has_tag_name((ns_text, "list-style")) {
+            if let Some(def) = parse_list_style(style_node, ns_style, ns_text) {
+                styles.insert(def.name.clone(), def);
+            }
         }
     }
 
@@ -130,41 +241,36 @@ pub fn parse_styles_node(
 }
 
 /// Builds a default style_map with well-known built-in style mappings.
-fn build_default_style_map() -> HashMap<String, (String, Vec<TiptapMark>)> {
+fn build_default_style_map() -> StyleMap {
+    let text_style = |mark| StyleInfo {
+        family: "text".to_string(),
+        marks: vec![mark],
+        ..StyleInfo::default()
+    };
     let mut m = HashMap::new();
-    m.insert(
-        "Strong".to_string(),
-        ("text".to_string(), vec![TiptapMark::Bold]),
-    );
-    m.insert(
-        "Emphasis".to_string(),
-        ("text".to_string(), vec![TiptapMark::Italic]),
-    );
+    m.insert("Strong".to_string(), text_style(TiptapMark::Bold));
+    m.insert("Emphasis".to_string(), text_style(TiptapMark::Italic));
     m
 }
 
-/// Returns a synthetic name for a `style:default-style` element.
-fn default_style_name(node: roxmltree::Node, ns_style: &str) -> String {
-    match node.attribute((ns_style, "family")).unwrap_or("") {
-        "paragraph" => "_Default_Paragraph".to_string(),
-        "text" => "_Default_Text".to_string(),
-        f => format!("_Default_{f}"),
-    }
-}
-
 /// Links styles that have no parent to the appropriate default style.
+///
+/// Paragraph and text styles are always linked; styles of the other
+/// families only when the document has a default style for the family.
 pub fn link_styles_to_defaults(style_definitions: &mut HashMap<String, StyleDefinition>) {
+    let defaults: Vec<StyleFamily> = style_definitions
+        .values()
+        .filter(|s| s.is_default_style())
+        .map(|s| s.family)
+        .collect();
     for style in style_definitions.values_mut() {
-        if style.parent.is_none() {
-            match style.family {
-                StyleFamily::Paragraph if style.name != "_Default_Paragraph" => {
-                    style.parent = Some("_Default_Paragraph".to_string());
-                }
-                StyleFamily::Text if style.name != "_Default_Text" => {
-                    style.parent = Some("_Default_Text".to_string());
-                }
-                _ => {}
-            }
+        if style.parent.is_some() || style.is_default_style() || style.family == StyleFamily::List {
+            continue;
+        }
+        if matches!(style.family, StyleFamily::Paragraph | StyleFamily::Text)
+            || defaults.contains(&style.family)
+        {
+            style.parent = Some(style.family.default_style_name());
         }
     }
 }
@@ -4,11 +4,18 @@
 
 use std::collections::HashMap;
 
-use common_core::{StyleDefinition, StyleFamily, TiptapMark};
+use common_core::block::INDENT_STEP_CM;
+use common_core::units::{length_to_cm, PX_PER_CM};
+use common_core::{
+    BlockAttrs, CellBorders, ImageWrap, ListLevelKind, ListLevelStyle, PropertyGroup, SectionAttrs,
+    StyleDefinition, StyleFamily, TextStyleAttrs, TiptapMark,
+};
 
 use crate::namespaces::ns_prefix;
 
 /// Parses a single `style:style` or `style:default-style` into a [`StyleDefinition`].
+///
+/// Returns `None` for families that do not occur in text documents.
 pub(super) fn parse_single_style(
     style_node: roxmltree::Node,
     name: &str,
@@ -16,15 +23,12 @@ pub(super) fn parse_single_style(
     ns_fo: &str,
     ns_text: &str,
     ns_loki: &str,
-) -> StyleDefinition {
-    let family_str = style_node
-        .attribute((ns_style, "family"))
-        .unwrap_or("paragraph");
-    let family = match family_str {
-        "paragraph" => StyleFamily::Paragraph,
-        "text" => StyleFamily::Text,
-        _ => StyleFamily::Text,
-    };
+) -> Option<StyleDefinition> {
+    let family = StyleFamily::from_odf_str(
+        style_node
+            .attribute((ns_style, "family"))
+            .unwrap_or("paragraph"),
+    )?;
     let parent = style_node
         .attribute((ns_style, "parent-style-name"))
         .map(|s| s.to_string());
@@ -40,6 +44,11 @@ pub(super) fn parse_single_style(
     let autocomplete = style_node
         .attribute((ns_loki, "autocomplete"))
         .map(|s| s == "true");
+    // An empty name only ends the previous master page's run.
+    let master_page_name = style_node
+        .attribute((ns_style, "master-page-name"))
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
 
     let attributes = collect_style_attributes(style_node, ns_style, ns_fo, ns_text);
     let text_transform = attributes.get("fo:text-transform").cloned();
@@ -54,7 +63,7 @@ pub(super) fn parse_single_style(
         .get("fo:background-color")
         .and_then(|s| crate::loki_ext::parse_colour_str(s));
 
-    StyleDefinition {
+    Some(StyleDefinition {
         name: name.to_string(),
         family,
         parent,
@@ -64,23 +73,26 @@ pub(super) fn parse_single_style(
         text_transform,
         outline_level,
         autocomplete,
+        master_page_name,
         font_colour,
         background_colour,
-    }
+        properties: collect_grouped_properties(style_node, ns_style),
+        list_levels: Vec::new(),
+    })
 }
 
-/// Collects all `fo:`, `style:`, `text:`, and `loki:` attributes from style
-/// property nodes.
+/// Collects the attributes of a style's paragraph and text properties.
 ///
 /// The `loki:colour` attribute on `style:text-properties` is captured so that
-/// non-RGB colour values survive a write → read round-trip.
+/// non-RGB colour values survive a write → read round-trip. Other property
+/// groups are collected by [`collect_grouped_properties`].
 pub(super) fn collect_style_attributes(
     style_node: roxmltree::Node,
     ns_style: &str,
     ns_fo: &str,
     ns_text: &str,
 ) -> HashMap<String, String> {
-    use crate::loki_ext::LOKI_NS;
+    let _ = (ns_fo, ns_text); // used by ns_prefix transitively
 
     let mut attrs = HashMap::new();
     for prop_node in style_node.children() {
@@ -93,20 +105,100 @@ pub(super) fn collect_style_attributes(
                 attrs.insert(key, attr.value().to_string());
             }
         }
-        // Also collect from all other property children for legacy compat
-        for attr in prop_node.attributes() {
-            if let Some(ns) = attr.namespace() {
-                if ns == ns_fo || ns == ns_style || ns == ns_text || ns == LOKI_NS {
-                    let prefix = ns_prefix(ns);
-                    let key = format!("{}{}", prefix, attr.name());
-                    attrs.entry(key).or_insert_with(|| attr.value().to_string());
-                }
-            }
-        }
     }
     attrs
 }
 
+/// Collects the property groups other than paragraph and text, such as
+/// `style:graphic-properties`, keyed by [`PropertyGroup`].
+///
+/// Attributes in namespaces the writers do not declare are dropped.
+pub(super) fn collect_grouped_properties(
+    style_node: roxmltree::Node,
+    ns_style: &str,
+) -> HashMap<PropertyGroup, HashMap<String, String>> {
+    let mut groups = HashMap::new();
+    for prop_node in style_node.children().filter(|n| n.is_element()) {
+        if prop_node.tag_name().namespace() != Some(ns_style) {
+            continue;
+        }
+        if let Some(group) = PropertyGroup::from_local_name(prop_node.tag_name().name()) {
+            groups.insert(group, prefixed_attributes(prop_node));
+        }
+    }
+    groups
+}
+
+/// Returns the attributes of `node` keyed by their prefixed names.
+fn prefixed_attributes(node: roxmltree::Node) -> HashMap<String, String> {
+    node.attributes()
+        .filter_map(|attr| {
+            let prefix = ns_prefix(attr.namespace()?);
+            (!prefix.is_empty())
+                .then(|| (format!("{prefix}{}", attr.name()), attr.value().to_string()))
+        })
+        .collect()
+}
+
+/// Parses a named `text:list-style` into a [`StyleFamily::List`] definition.
+pub(super) fn parse_list_style(
+    list_style: roxmltree::Node,
+    ns_style: &str,
+    ns_text: &str,
+) -> Option<StyleDefinition> {
+    let name = list_style.attribute((ns_style, "name"))?;
+    let list_levels = list_style
+        .children()
+        .filter(|n| n.is_element() && n.tag_name().namespace() == Some(ns_text))
+        .filter_map(|level| {
+            let kind = match level.tag_name().name() {
+                "list-level-style-number" => ListLevelKind::Number,
+                "list-level-style-bullet" => ListLevelKind::Bullet,
+                "list-level-style-image" => ListLevelKind::Image,
+                _ => return None,
+            };
+            let mut attributes = prefixed_attributes(level);
+            let number = attributes.remove("text:level")?.parse().ok()?;
+            let child = |local: &str| level.children().find(|n| n.has_tag_name((ns_style, local)));
+            let level_props = child("list-level-properties");
+            Some(ListLevelStyle {
+                level: number,
+                kind,
+                attributes,
+                properties: level_props.map(prefixed_attributes).unwrap_or_default(),
+                label_alignment: level_props
+                    .and_then(|p| {
+                        p.children()
+                            .find(|n| n.has_tag_name((ns_style, "list-level-label-alignment")))
+                    })
+                    .map(prefixed_attributes)
+                    .unwrap_or_default(),
+                text_properties: child("text-properties")
+                    .map(prefixed_attributes)
+                    .unwrap_or_default(),
+            })
+        })
+        .collect();
+    Some(StyleDefinition {
+        name: name.to_string(),
+        family: StyleFamily::List,
+        parent: None,
+        next: None,
+        display_name: list_style
+            .attribute((ns_style, "display-name"))
+            .map(str::to_string),
+        attributes: HashMap::new(),
+        text_transform: None,
+        outline_level: None,
+        autocomplete: None,
+        master_page_name: None,
+        font_colour: None,
+        background_colour: None,
+        properties: HashMap::new(),
+        list_levels,
+    })
+}
+
 /// Extracts TiptapMark values from a style's text-properties.
 pub(super) fn extract_marks_from_style(
     style_node: roxmltree::Node,
@@ -128,18 +220,206 @@ pub(super) fn extract_marks_from_style(
             {
                 marks.push(TiptapMark::Underline);
             }
+            if prop_node
+                .attribute((ns_style, "text-line-through-style"))
+                .is_some_and(|s| s != "none")
+            {
+                marks.push(TiptapMark::Strike);
+            }
+            // `style:text-position` is "super"/"sub" or a signed percentage,
+            // optionally followed by the relative font size.
+            match prop_node
+                .attribute((ns_style, "text-position"))
+                .and_then(|p| p.split_whitespace().next())
+            {
+                Some("super") => marks.push(TiptapMark::Superscript),
+                Some("sub") => marks.push(TiptapMark::Subscript),
+                Some(p) => match p.trim_end_matches('%').parse::<f64>() {
+                    Ok(v) if v > 0.0 => marks.push(TiptapMark::Superscript),
+                    Ok(v) if v < 0.0 => marks.push(TiptapMark::Subscript),
+                    _ => {}
+                },
+                None => {}
+            }
         }
     }
     marks
 }
 
+/// Extracts a `TextStyle` mark from a style's text-properties: colours, font
+/// family and size.
+///
+/// A `loki:colour` attribute takes precedence over `fo:color`. Transparent
+/// backgrounds and relative font sizes are ignored.
+pub(super) fn extract_text_style(
+    style_node: roxmltree::Node,
+    ns_style: &str,
+    ns_fo: &str,
+) -> Option<TiptapMark> {
+    use crate::loki_ext::{colour_from_attr, parse_colour_str, LOKI_NS};
+
+    let props = style_node
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "text-properties")))?;
+    let attrs = TextStyleAttrs {
+        colour: props
+            .attribute((LOKI_NS, "colour"))
+            .and_then(colour_from_attr)
+            .or_else(|| props.attribute((ns_fo, "color")).and_then(parse_colour_str)),
+        background_colour: props
+            .attribute((ns_fo, "background-color"))
+            .and_then(parse_colour_str)
+            .filter(|c| !c.is_transparent()),
+        font_family: props
+            .attribute((ns_fo, "font-family"))
+            .or_else(|| props.attribute((ns_style, "font-name")))
+            .map(|f| f.trim_matches(|c| c == '\'' || c == '"').to_string()),
+        font_size: props
+            .attribute((ns_fo, "font-size"))
+            .and_then(length_to_cm)
+            .map(|cm| ((cm * 72.0 / 2.54) * 10.0).round() as f32 / 10.0),
+    };
+    (!attrs.is_empty()).then_some(TiptapMark::TextStyle { attrs })
+}
+
+/// Reads `style:column-width` from a column style, in centimetres.
+pub(super) fn extract_column_width(style_node: roxmltree::Node, ns_style: &str) -> Option<f64> {
+    style_node
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "table-column-properties")))?
+        .attribute((ns_style, "column-width"))
+        .and_then(length_to_cm)
+}
+
+/// Reads `style:wrap` from a graphic style.
+pub(super) fn extract_wrap(style_node: roxmltree::Node, ns_style: &str) -> Option<ImageWrap> {
+    style_node
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "graphic-properties")))?
+        .attribute((ns_style, "wrap"))
+        .and_then(ImageWrap::from_odf_str)
+}
+
+/// Reads the `style:columns` of a section style. Single-column layouts
+/// without a gap give `None`.
+pub(super) fn extract_section_attrs(
+    style_node: roxmltree::Node,
+    ns_style: &str,
+    ns_fo: &str,
+) -> Option<SectionAttrs> {
+    let columns = style_node
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "section-properties")))?
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "columns")))?;
+    let attrs = SectionAttrs {
+        column_count: columns
+            .attribute((ns_fo, "column-count"))
+            .and_then(|c| c.parse().ok())
+            .filter(|&c: &u32| c > 1),
+        column_gap: columns
+            .attribute((ns_fo, "column-gap"))
+            .and_then(length_to_cm)
+            .map(|cm| (cm * PX_PER_CM * 100.0).round() / 100.0),
+    };
+    (!attrs.is_empty()).then_some(attrs)
+}
+
+/// Reads the background colour and borders of a cell style.
+///
+/// `fo:border` applies to every side and is overridden by the per-side
+/// attributes. Transparent backgrounds and `none` borders are dropped.
+pub(super) fn extract_cell_style(
+    style_node: roxmltree::Node,
+    ns_style: &str,
+    ns_fo: &str,
+) -> (Option<String>, Option<CellBorders>) {
+    let Some(props) = style_node
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "table-cell-properties")))
+    else {
+        return (None, None);
+    };
+    let background = props
+        .attribute((ns_fo, "background-color"))
+        .filter(|c| *c != "transparent")
+        .map(str::to_string);
+    let side = |name: &str| {
+        props
+            .attribute((ns_fo, name))
+            .or_else(|| props.attribute((ns_fo, "border")))
+            .filter(|b| *b != "none")
+            .map(str::to_string)
+    };
+    let borders = CellBorders {
+        top: side("border-top"),
+        right: side("border-right"),
+        bottom: side("border-bottom"),
+        left: side("border-left"),
+    };
+    (background, (!borders.is_empty()).then_some(borders))
+}
+
+/// Derives the direct formatting of an automatic paragraph style.
+///
+/// An `fo:margin-left` that is a whole number of [`INDENT_STEP_CM`] steps
+/// becomes an indent level; it and every other paragraph property are
+/// otherwise kept in [`BlockAttrs::properties`], except a page break before,
+/// which the block parser writes as a page break block. Text properties
+/// become marks of the paragraph's runs instead. Returns `None` when the
+/// style has no paragraph formatting.
+pub(super) fn extract_block_attrs(
+    style_node: roxmltree::Node,
+    def: &StyleDefinition,
+    ns_style: &str,
+) -> Option<BlockAttrs> {
+    let paragraph_properties = style_node
+        .children()
+        .find(|n| n.has_tag_name((ns_style, "paragraph-properties")));
+    let mut properties = paragraph_properties
+        .map(prefixed_attributes)
+        .unwrap_or_default();
+    let text_align = properties.remove("fo:text-align");
+    let indent = properties
+        .get("fo:margin-left")
+        .and_then(|m| length_to_cm(m))
+        .map(|cm| cm / INDENT_STEP_CM)
+        .filter(|steps| *steps >= 0.5 && (steps - steps.round()).abs() < 0.001)
+        .map(|steps| steps.round() as u32);
+    if indent.is_some() {
+        properties.remove("fo:margin-left");
+    }
+    if properties.get("fo:break-before").map(String::as_str) == Some("page") {
+        properties.remove("fo:break-before");
+    }
+    let tab_stops = paragraph_properties
+        .into_iter()
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((ns_style, "tab-stops")))
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((ns_style, "tab-stop")))
+        .map(prefixed_attributes)
+        .collect();
+    let attrs = BlockAttrs {
+        text_align,
+        indent,
+        properties,
+        tab_stops,
+        master_page_name: def.master_page_name.clone(),
+    };
+    (attrs != BlockAttrs::default()).then_some(attrs)
+}
+
 /// Parses `style:default-style` elements from `office:styles`.
+///
+/// Each default style is stored under [`StyleFamily::default_style_name`].
 pub(super) fn parse_default_styles(
     root: roxmltree::Node,
     ns_office: &str,
     ns_style: &str,
     ns_fo: &str,
     ns_text: &str,
+    ns_loki: &str,
     style_definitions: &mut HashMap<String, StyleDefinition>,
 ) {
     let default_style_nodes = root
@@ -149,42 +429,17 @@ pub(super) fn parse_default_styles(
         .filter(|n| n.has_tag_name((ns_style, "default-style")));
 
     for ds_node in default_style_nodes {
-        let family_str = ds_node.attribute((ns_style, "family")).unwrap_or("");
-        let (family, name) = match family_str {
-            "paragraph" => (StyleFamily::Paragraph, "_Default_Paragraph"),
-            "text" => (StyleFamily::Text, "_Default_Text"),
-            _ => continue,
+        let Some(family) = ds_node
+            .attribute((ns_style, "family"))
+            .and_then(StyleFamily::from_odf_str)
+        else {
+            continue;
         };
-
-        let mut attrs = HashMap::new();
-        for prop_node in ds_node.children() {
-            if prop_node.has_tag_name((ns_style, "text-properties"))
-                || prop_node.has_tag_name((ns_style, "paragraph-properties"))
-            {
-                for attr in prop_node.attributes() {
-                    let prefix = attr.namespace().map(ns_prefix).unwrap_or("");
-                    let key = format!("{}{}", prefix, attr.name());
-                    attrs.insert(key, attr.value().to_string());
-                }
-            }
+        let name = family.default_style_name();
+        if let Some(mut def) = parse_single_style(ds_node, &name, ns_style, ns_fo, ns_text, ns_loki)
+        {
+            def.display_name = Some("Default".to_string());
+            style_definitions.insert(name, def);
         }
-        let _ = (ns_fo, ns_text); // used by ns_prefix transitively
-
-        style_definitions.insert(
-            name.to_string(),
-            StyleDefinition {
-                name: name.to_string(),
-                family,
-                parent: None,
-                next: None,
-                display_name: Some("Default".to_string()),
-                attributes: attrs,
-                text_transform: None,
-                outline_level: None,
-                autocomplete: None,
-                font_colour: None,
-                background_colour: None,
-            },
-        );
     }
 }
@@ -0,0 +1,122 @@
+//! ODT table structure helpers.
+//!
+//! Resolves the column widths, header rows and cell styles of a
+//! `table:table` element. Cell content is parsed by [`super::blocks`].
+
+use common_core::units::PX_PER_CM;
+use common_core::CellAttrs;
+
+use crate::parser::styles::StyleMap;
+
+/// Upper bound on columns read from `table:number-columns-repeated`.
+///
+/// Spreadsheet-style tables repeat an empty column thousands of times; only
+/// columns that can hold cells matter here.
+const MAX_COLUMNS: usize = 1024;
+
+/// Returns the width in pixels of each column of `table`, `None` where the
+/// column has no width.
+///
+/// Reads `table:table-column` elements, including those grouped in
+/// `table:table-header-columns`, `table:table-columns` and
+/// `table:table-column-group`.
+pub fn table_columns(
+    table: roxmltree::Node,
+    ns_table: &str,
+    style_map: &StyleMap,
+) -> Vec<Option<u32>> {
+    let mut columns = Vec::new();
+    collect_columns(table, ns_table, style_map, &mut columns);
+    columns
+}
+
+fn collect_columns(
+    parent: roxmltree::Node,
+    ns_table: &str,
+    style_map: &StyleMap,
+    columns: &mut Vec<Option<u32>>,
+) {
+    for node in parent.children().filter(|n| n.is_element()) {
+        if node.has_tag_name((ns_table, "table-column")) {
+            let width = node
+                .attribute((ns_table, "style-name"))
+                .and_then(|s| style_map.get(s))
+                .and_then(|info| info.column_width)
+                .map(|cm| (cm * PX_PER_CM).round() as u32);
+            let repeat = node
+                .attribute((ns_table, "number-columns-repeated"))
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(1);
+            let room = MAX_COLUMNS.saturating_sub(columns.len());
+            columns.extend(std::iter::repeat_n(width, repeat.min(room)));
+        } else if node.has_tag_name((ns_table, "table-header-columns"))
+            || node.has_tag_name((ns_table, "table-columns"))
+            || node.has_tag_name((ns_table, "table-column-group"))
+        {
+            collect_columns(node, ns_table, style_map, columns);
+        }
+    }
+}
+
+/// Returns the rows of `table` in document order, each flagged `true` when
+/// it belongs to `table:table-header-rows`.
+pub fn table_rows<'a, 'input>(
+    table: roxmltree::Node<'a, 'input>,
+    ns_table: &str,
+) -> Vec<(roxmltree::Node<'a, 'input>, bool)> {
+    let mut rows = Vec::new();
+    collect_rows(table, ns_table, false, &mut rows);
+    rows
+}
+
+fn collect_rows<'a, 'input>(
+    parent: roxmltree::Node<'a, 'input>,
+    ns_table: &str,
+    header: bool,
+    rows: &mut Vec<(roxmltree::Node<'a, 'input>, bool)>,
+) {
+    for node in parent.children().filter(|n| n.is_element()) {
+        if node.has_tag_name((ns_table, "table-row")) {
+            rows.push((node, header));
+        } else if node.has_tag_name((ns_table, "table-header-rows")) {
+            collect_rows(node, ns_table, true, rows);
+        } else if node.has_tag_name((ns_table, "table-rows"))
+            || node.has_tag_name((ns_table, "table-row-group"))
+        {
+            collect_rows(node, ns_table, header, rows);
+        }
+    }
+}
+
+/// Builds the [`CellAttrs`] of a `table:table-cell` starting at column
+/// `column`, or `None` when the cell has no spans, widths or style.
+pub fn cell_attrs(
+    cell: roxmltree::Node,
+    column: usize,
+    columns: &[Option<u32>],
+    ns_table: &str,
+    style_map: &StyleMap,
+) -> Option<CellAttrs> {
+    let span = |name: &str| {
+        cell.attribute((ns_table, name))
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|&v| v > 1)
+    };
+    let colspan = span("number-columns-spanned");
+    let rowspan = span("number-rows-spanned");
+    let spanned = colspan.unwrap_or(1) as usize;
+    let colwidth = columns
+        .get(column..column + spanned)
+        .and_then(|widths| widths.iter().copied().collect::<Option<Vec<u32>>>());
+    let style = cell
+        .attribute((ns_table, "style-name"))
+        .and_then(|s| style_map.get(s));
+    let attrs = CellAttrs {
+        colspan,
+        rowspan,
+        colwidth,
+        background_color: style.and_then(|s| s.cell_background.clone()),
+        border: style.and_then(|s| s.cell_border.clone()),
+    };
+    (attrs != CellAttrs::default()).then_some(attrs)
+}
@@ -0,0 +1,148 @@
+//! ODT table of contents parser.
+//!
+//! Reads a `text:table-of-content` element into a
+//! [`Block::TableOfContents`]: its settings from
+//! `text:table-of-content-source` and its entries from the paragraphs of
+//! `text:index-body`.
+
+use std::collections::HashMap;
+
+use common_core::{Block, TableOfContents, TocEntry};
+
+use crate::parser::inlines::space_count;
+use crate::parser::styles::StyleMap;
+
+/// Parses a `text:table-of-content` element.
+pub fn parse_toc(
+    node: &roxmltree::Node,
+    ns_text: &str,
+    ns_xlink: &str,
+    style_map: &StyleMap,
+) -> Block {
+    let source = node
+        .children()
+        .find(|n| n.has_tag_name((ns_text, "table-of-content-source")));
+    let body = node
+        .children()
+        .find(|n| n.has_tag_name((ns_text, "index-body")));
+
+    // Entry templates name the paragraph style used for each level.
+    let mut template_levels: HashMap<&str, u32> = HashMap::new();
+    for template in source.iter().flat_map(|s| s.children()) {
+        if !template.has_tag_name((ns_text, "table-of-content-entry-template")) {
+            continue;
+        }
+        if let (Some(style), Some(level)) = (
+            template.attribute((ns_text, "style-name")),
+            template
+                .attribute((ns_text, "outline-level"))
+                .and_then(|l| l.parse().ok()),
+        ) {
+            template_levels.insert(style, level);
+        }
+    }
+
+    let title = source
+        .iter()
+        .flat_map(|s| s.children())
+        .find(|n| n.has_tag_name((ns_text, "index-title-template")))
+        .map(|t| t.text().unwrap_or_default().to_string())
+        .or_else(|| {
+            body.iter()
+                .flat_map(|b| b.children())
+                .find(|n| n.has_tag_name((ns_text, "index-title")))
+                .map(|t| entry_segments(&t, ns_text).join(" ").trim().to_string())
+        })
+        .filter(|t| !t.is_empty());
+
+    let entries = body
+        .iter()
+        .flat_map(|b| b.children())
+        .filter(|n| n.has_tag_name((ns_text, "p")))
+        .filter_map(|p| parse_entry(&p, ns_text, ns_xlink, &template_levels, style_map))
+        .collect();
+
+    let defaults = TableOfContents::default();
+    Block::TableOfContents(TableOfContents {
+        name: node
+            .attribute((ns_text, "name"))
+            .unwrap_or_default()
+            .to_string(),
+        title,
+        outline_level: source
+            .and_then(|s| s.attribute((ns_text, "outline-level")))
+            .and_then(|l| l.parse().ok())
+            .unwrap_or(defaults.outline_level),
+        entries,
+    })
+}
+
+/// Parses one entry paragraph: the heading text, then a tab and the page
+/// number, usually all inside a link to the heading's bookmark.
+fn parse_entry(
+    p: &roxmltree::Node,
+    ns_text: &str,
+    ns_xlink: &str,
+    template_levels: &HashMap<&str, u32>,
+    style_map: &StyleMap,
+) -> Option<TocEntry> {
+    let mut segments = entry_segments(p, ns_text);
+    let page = if segments.len() > 1 {
+        segments.pop().map(|s| s.trim().to_string())
+    } else {
+        None
+    };
+    let text = segments.join(" ").trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    let target = p
+        .descendants()
+        .find(|n| n.has_tag_name((ns_text, "a")))
+        .and_then(|a| a.attribute((ns_xlink, "href")))
+        .and_then(|href| href.strip_prefix('#'))
+        .map(str::to_string);
+    let level = p
+        .attribute((ns_text, "style-name"))
+        .map_or(1, |style| entry_level(style, template_levels, style_map));
+    Some(TocEntry {
+        level,
+        text,
+        target,
+        page: page.filter(|p| !p.is_empty()),
+    })
+}
+
+/// The outline level of an entry paragraph style: the level of the entry
+/// template using the style or its parent, or else the number at the end
+/// of its name (`Contents_20_2`).
+fn entry_level(style: &str, template_levels: &HashMap<&str, u32>, style_map: &StyleMap) -> u32 {
+    let mut name = Some(style);
+    for _ in 0..4 {
+        let Some(current) = name else { break };
+        if let Some(level) = template_levels.get(current) {
+            return *level;
+        }
+        name = style_map.get(current).and_then(|s| s.parent.as_deref());
+    }
+    let digits = style.len() - style.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    style[style.len() - digits..].parse().unwrap_or(1).max(1)
+}
+
+/// The text of an element split at its `text:tab` elements.
+fn entry_segments(node: &roxmltree::Node, ns_text: &str) -> Vec<String> {
+    let mut segments = vec![String::new()];
+    for n in node.descendants().skip(1) {
+        let current = segments.last_mut().expect("segments is never empty");
+        if n.is_text() {
+            current.push_str(n.text().unwrap_or_default());
+        } else if n.has_tag_name((ns_text, "s")) {
+            current.push_str(&" ".repeat(space_count(n, ns_text) as usize));
+        } else if n.has_tag_name((ns_text, "line-break")) {
+            current.push(' ');
+        } else if n.has_tag_name((ns_text, "tab")) {
+            segments.push(String::new());
+        }
+    }
+    segments
+}
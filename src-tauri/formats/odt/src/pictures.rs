@@ -0,0 +1,567 @@
+//! Pictures embedded in an ODT package.
+//!
+//! The editor hands images over as data URIs or local file paths. When a
+//! document is saved as a ZIP package, [`extract_pictures`] moves those images
+//! into the package's `Pictures/` folder, naming each file after the SHA-256
+//! of its bytes so an image used twice is stored once, and [`manifest_xml`]
+//! registers them in `META-INF/manifest.xml`. On load, [`inline_pictures`]
+//! turns the package references back into data URIs for the editor.
+//!
+//! Images whose frame is marked [`linked`](common_core::ImageFrame::linked),
+//! such as those a loaded document refers to outside its package, stay
+//! links and are never read.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::Block;
+//! use odt_format::pictures::{extract_pictures, inline_pictures};
+//!
+//! let mut blocks = vec![Block::Image {
+//!     src: "data:image/png;base64,iVBORw0KGgo=".to_string(),
+//!     alt: None,
+//!     title: None,
+//!     frame: None,
+//!     caption: None,
+//! }];
+//! let pictures = extract_pictures(&mut blocks, None);
+//! assert_eq!(pictures.len(), 1);
+//! assert!(pictures[0].path.starts_with("Pictures/"));
+//!
+//! inline_pictures(&mut blocks, |path| {
+//!     pictures.iter().find(|p| p.path == path).map(|p| p.data.clone())
+//! });
+//! assert!(matches!(&blocks[0], Block::Image { src, .. } if src.starts_with("data:image/png")));
+//! ```
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common_core::{Block, Comment, Inline};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use quick_xml::Writer;
+use sha2::{Digest, Sha256};
+
+use crate::package::MIMETYPE;
+use crate::Document;
+
+/// The package folder holding embedded pictures.
+pub const PICTURES_DIR: &str = "Pictures/";
+
+const MANIFEST_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:manifest:1.0";
+
+/// Image file extensions and their media types. The first extension listed
+/// for a media type is the one used when naming extracted pictures.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("svg", "image/svg+xml"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("wmf", "image/x-wmf"),
+    ("emf", "image/x-emf"),
+];
+
+/// An image file stored in the package's `Pictures/` folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    /// The path inside the package, e.g. `Pictures/<sha256>.png`.
+    pub path: String,
+    /// The image media type, e.g. `image/png`.
+    pub media_type: String,
+    /// The image bytes.
+    pub data: Vec<u8>,
+}
+
+/// Moves data-URI and local-file images into package pictures.
+///
+/// Each image `src` that can be loaded is rewritten to the picture's package
+/// path. Identical images share one picture. Remote URLs, existing package
+/// paths and sources that cannot be read are left unchanged. Local files are
+/// only read when their extension names an image type; relative paths are
+/// resolved against `base_dir`, the folder of the document, and left
+/// unchanged without one.
+pub fn extract_pictures(blocks: &mut [Block], base_dir: Option<&Path>) -> Vec<Picture> {
+    let mut pictures: Vec<Picture> = Vec::new();
+    for_each_image_src(blocks, &mut |src| extract_src(src, base_dir, &mut pictures));
+    pictures
+}
+
+/// Like [`extract_pictures`], for the body and the running headers and
+/// footers of `doc`.
+pub fn extract_document_pictures(doc: &mut Document, base_dir: Option<&Path>) -> Vec<Picture> {
+    let mut pictures: Vec<Picture> = Vec::new();
+    for_each_document_image_src(doc, &mut |src| extract_src(src, base_dir, &mut pictures));
+    pictures
+}
+
+/// Replaces package picture references with data URIs.
+///
+/// `read` returns the bytes of a package entry, or `None` if it is missing,
+/// in which case the reference is left unchanged.
+pub fn inline_pictures(blocks: &mut [Block], mut read: impl FnMut(&str) -> Option<Vec<u8>>) {
+    for_each_image_src(blocks, &mut |src| inline_src(src, &mut read));
+}
+
+/// Like [`inline_pictures`], for the body and the running headers and
+/// footers of `doc`.
+pub fn inline_document_pictures(doc: &mut Document, mut read: impl FnMut(&str) -> Option<Vec<u8>>) {
+    for_each_document_image_src(doc, &mut |src| inline_src(src, &mut read));
+}
+
+/// Rewrites one image source to a package picture, adding the picture to
+/// `pictures` unless an identical one is there.
+fn extract_src(src: &mut String, base_dir: Option<&Path>, pictures: &mut Vec<Picture>) {
+    let Some((media_type, data)) = load_image(src, base_dir) else {
+        return;
+    };
+    let path = format!(
+        "{PICTURES_DIR}{}.{}",
+        sha256_hex(&data),
+        extension_for(&media_type)
+    );
+    if !pictures.iter().any(|p| p.path == path) {
+        pictures.push(Picture {
+            path: path.clone(),
+            media_type,
+            data,
+        });
+    }
+    *src = path;
+}
+
+/// Rewrites one package picture reference to a data URI.
+fn inline_src(src: &mut String, read: &mut impl FnMut(&str) -> Option<Vec<u8>>) {
+    let path = src.trim_start_matches("./");
+    if !path.starts_with(PICTURES_DIR) {
+        return;
+    }
+    let Some(data) = read(path) else {
+        return;
+    };
+    let media_type = path
+        .rsplit_once('.')
+        .and_then(|(_, ext)| media_type_for(ext))
+        .unwrap_or("application/octet-stream");
+    *src = format!("data:{media_type};base64,{}", STANDARD.encode(data));
+}
+
+/// Generates a `META-INF/manifest.xml` listing the document parts and the
+/// given pictures.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn manifest_xml(pictures: &[Picture]) -> Result<String, String> {
+    let mut entries = vec![
+        ("/".to_string(), MIMETYPE.to_string()),
+        ("content.xml".to_string(), "text/xml".to_string()),
+        ("styles.xml".to_string(), "text/xml".to_string()),
+        ("meta.xml".to_string(), "text/xml".to_string()),
+    ];
+    entries.extend(
+        pictures
+            .iter()
+            .map(|p| (p.path.clone(), p.media_type.clone())),
+    );
+    write_manifest(&entries)
+}
+
+/// Rewrites an existing `META-INF/manifest.xml` so that it lists the given
+/// pictures in place of the old `Pictures/` entries. Every other entry is
+/// kept.
+///
+/// # Errors
+///
+/// Returns a `String` error if the old manifest cannot be parsed or XML
+/// writing fails.
+pub fn update_manifest(old_xml: &str, pictures: &[Picture]) -> Result<String, String> {
+    let mut entries = manifest_entries(old_xml)?;
+    entries.retain(|(path, _)| !path.starts_with(PICTURES_DIR));
+    entries.extend(
+        pictures
+            .iter()
+            .map(|p| (p.path.clone(), p.media_type.clone())),
+    );
+    write_manifest(&entries)
+}
+
+/// Reads the `(full-path, media-type)` pairs of a `META-INF/manifest.xml`.
+///
+/// # Errors
+///
+/// Returns a `String` error if the manifest cannot be parsed.
+pub(crate) fn manifest_entries(xml: &str) -> Result<Vec<(String, String)>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    Ok(doc
+        .descendants()
+        .filter(|n| n.has_tag_name((MANIFEST_NS, "file-entry")))
+        .filter_map(|n| {
+            let path = n.attribute((MANIFEST_NS, "full-path"))?;
+            let media_type = n.attribute((MANIFEST_NS, "media-type")).unwrap_or("");
+            Some((path.to_string(), media_type.to_string()))
+        })
+        .collect())
+}
+
+/// Returns the media type for an image file extension.
+#[must_use]
+pub fn media_type_for(extension: &str) -> Option<&'static str> {
+    let extension = extension.to_ascii_lowercase();
+    IMAGE_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, media_type)| *media_type)
+}
+
+fn extension_for(media_type: &str) -> &'static str {
+    IMAGE_TYPES
+        .iter()
+        .find(|(_, mt)| *mt == media_type)
+        .map_or("bin", |(ext, _)| *ext)
+}
+
+/// Writes a `META-INF/manifest.xml` from `(full-path, media-type)` pairs.
+pub(crate) fn write_manifest(entries: &[(String, String)]) -> Result<String, String> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 1);
+    writer
+        .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+        .map_err(|e| e.to_string())?;
+    let mut root = BytesStart::new("manifest:manifest");
+    root.push_attribute(("xmlns:manifest", MANIFEST_NS));
+    root.push_attribute(("manifest:version", "1.3"));
+    writer
+        .write_event(Event::Start(root))
+        .map_err(|e| e.to_string())?;
+    for (path, media_type) in entries {
+        let mut entry = BytesStart::new("manifest:file-entry");
+        entry.push_attribute(("manifest:full-path", path.as_str()));
+        if path == "/" {
+            entry.push_attribute(("manifest:version", "1.3"));
+        }
+        entry.push_attribute(("manifest:media-type", media_type.as_str()));
+        writer
+            .write_event(Event::Empty(entry))
+            .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("manifest:manifest")))
+        .map_err(|e| e.to_string())?;
+    String::from_utf8(writer.into_inner().into_inner()).map_err(|e| e.to_string())
+}
+
+/// Calls `f` with the `src` of every image that is not linked, including
+/// images inside lists, tables, quotations, notes, comments and tracked
+/// deletions.
+fn for_each_image_src(blocks: &mut [Block], f: &mut dyn FnMut(&mut String)) {
+    for block in blocks {
+        match block {
+            Block::Image { src, frame, .. } => {
+                if !frame.as_ref().is_some_and(|frame| frame.linked) {
+                    f(src);
+                }
+            }
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+                for inline in content {
+                    match inline {
+                        Inline::Note { content, .. } => for_each_image_src(content, f),
+                        Inline::Comment(comment) => for_each_comment_image_src(comment, f),
+                        Inline::Change(change) => for_each_image_src(&mut change.content, f),
+                        _ => {}
+                    }
+                }
+            }
+            Block::BulletList { content, .. }
+            | Block::OrderedList { content, .. }
+            | Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. }
+            | Block::Table { content }
+            | Block::TableRow { content }
+            | Block::TableHeader { content, .. }
+            | Block::TableCell { content, .. } => for_each_image_src(content, f),
+            Block::HorizontalRule | Block::PageBreak | Block::TableOfContents(_) => {}
+        }
+    }
+}
+
+/// Calls `f` with the `src` of every image in the body and in the headers
+/// and footers of `doc`.
+fn for_each_document_image_src(doc: &mut Document, f: &mut dyn FnMut(&mut String)) {
+    for_each_image_src(&mut doc.blocks, f);
+    if let Some(setup) = doc.page_setup.as_mut() {
+        for region in setup.header_footer_blocks_mut() {
+            for_each_image_src(region, f);
+        }
+    }
+}
+
+fn for_each_comment_image_src(comment: &mut Comment, f: &mut dyn FnMut(&mut String)) {
+    for_each_image_src(&mut comment.content, f);
+    for reply in &mut comment.replies {
+        for_each_comment_image_src(reply, f);
+    }
+}
+
+/// Loads an image from a data URI or a local file, returning its media type
+/// and bytes. Relative paths are resolved against `base_dir`.
+fn load_image(src: &str, base_dir: Option<&Path>) -> Option<(String, Vec<u8>)> {
+    if let Some(uri) = src.strip_prefix("data:") {
+        return decode_data_uri(uri);
+    }
+    let path = match src.strip_prefix("file://") {
+        Some(path) => PathBuf::from(String::from_utf8(percent_decode(path)).ok()?),
+        None if src.contains("://") || src.starts_with(PICTURES_DIR) => return None,
+        None => PathBuf::from(src),
+    };
+    let path = if path.is_absolute() {
+        path
+    } else {
+        base_dir?.join(path)
+    };
+    let media_type = media_type_for(path.extension()?.to_str()?)?;
+    let data = std::fs::read(&path).ok()?;
+    Some((media_type.to_string(), data))
+}
+
+/// Decodes the part of a data URI after `data:`. Only image media types are
+/// accepted.
+fn decode_data_uri(uri: &str) -> Option<(String, Vec<u8>)> {
+    let (header, payload) = uri.split_once(',')?;
+    let mut params = header.split(';');
+    let media_type = params.next()?.trim().to_ascii_lowercase();
+    if !media_type.starts_with("image/") {
+        return None;
+    }
+    let data = if params.any(|p| p.eq_ignore_ascii_case("base64")) {
+        let payload: String = payload.split_whitespace().collect();
+        STANDARD.decode(payload).ok()?
+    } else {
+        percent_decode(payload)
+    };
+    Some((media_type, data))
+}
+
+/// Decodes `%XX` escapes, leaving malformed escapes as they are.
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_core::{ImageFrame, NoteClass};
+
+    fn image(src: &str) -> Block {
+        Block::Image {
+            src: src.to_string(),
+            alt: None,
+            title: None,
+            frame: None,
+            caption: None,
+        }
+    }
+
+    fn src(block: &Block) -> &str {
+        match block {
+            Block::Image { src, .. } => src,
+            _ => panic!("expected an image"),
+        }
+    }
+
+    #[test]
+    fn identical_data_uris_share_one_picture() {
+        let uri = format!("data:image/png;base64,{}", STANDARD.encode(b"png bytes"));
+        let mut blocks = vec![image(&uri), image(&uri)];
+        let pictures = extract_pictures(&mut blocks, None);
+        assert_eq!(pictures.len(), 1);
+        assert_eq!(pictures[0].media_type, "image/png");
+        assert_eq!(pictures[0].data, b"png bytes");
+        assert_eq!(
+            pictures[0].path,
+            format!("Pictures/{}.png", sha256_hex(b"png bytes"))
+        );
+        assert_eq!(src(&blocks[0]), pictures[0].path);
+        assert_eq!(src(&blocks[1]), pictures[0].path);
+    }
+
+    #[test]
+    fn percent_encoded_svg_data_uri_is_decoded() {
+        let mut blocks = vec![image("data:image/svg+xml,%3Csvg%2F%3E")];
+        let pictures = extract_pictures(&mut blocks, None);
+        assert_eq!(pictures[0].data, b"<svg/>");
+        assert!(pictures[0].path.ends_with(".svg"));
+    }
+
+    #[test]
+    fn local_image_files_are_embedded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.JPG");
+        std::fs::write(&path, b"jpeg bytes").unwrap();
+        let mut blocks = vec![
+            image(path.to_str().unwrap()),
+            image(&format!("file://{}", path.display())),
+        ];
+        let pictures = extract_pictures(&mut blocks, None);
+        assert_eq!(pictures.len(), 1);
+        assert_eq!(pictures[0].media_type, "image/jpeg");
+        assert!(pictures[0].path.ends_with(".jpg"));
+    }
+
+    #[test]
+    fn relative_paths_are_resolved_against_the_document_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("img")).unwrap();
+        std::fs::write(dir.path().join("img/logo.png"), b"png bytes").unwrap();
+
+        let mut blocks = vec![image("img/logo.png")];
+        assert!(extract_pictures(&mut blocks, None).is_empty());
+        assert_eq!(src(&blocks[0]), "img/logo.png");
+
+        let pictures = extract_pictures(&mut blocks, Some(dir.path()));
+        assert_eq!(pictures.len(), 1);
+        assert_eq!(pictures[0].data, b"png bytes");
+    }
+
+    #[test]
+    fn linked_images_are_left_as_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.png");
+        std::fs::write(&path, b"png bytes").unwrap();
+        let mut blocks = vec![Block::Image {
+            src: path.display().to_string(),
+            alt: None,
+            title: None,
+            frame: Some(ImageFrame {
+                linked: true,
+                ..ImageFrame::default()
+            }),
+            caption: None,
+        }];
+        assert!(extract_pictures(&mut blocks, Some(dir.path())).is_empty());
+        assert_eq!(src(&blocks[0]), path.display().to_string());
+    }
+
+    #[test]
+    fn remote_non_image_and_missing_sources_are_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, b"secret").unwrap();
+        let sources = [
+            "https://example.com/a.png".to_string(),
+            "data:text/plain;base64,aGk=".to_string(),
+            notes.display().to_string(),
+            dir.path().join("missing.png").display().to_string(),
+        ];
+        let mut blocks: Vec<Block> = sources.iter().map(|s| image(s)).collect();
+        assert!(extract_pictures(&mut blocks, None).is_empty());
+        for (block, source) in blocks.iter().zip(&sources) {
+            assert_eq!(src(block), source);
+        }
+    }
+
+    #[test]
+    fn images_in_nested_blocks_and_notes_are_found() {
+        let uri = format!("data:image/gif;base64,{}", STANDARD.encode(b"gif"));
+        let mut blocks = vec![
+            Block::Table {
+                content: vec![Block::TableRow {
+                    content: vec![Block::TableCell {
+                        attrs: None,
+                        content: vec![image(&uri)],
+                    }],
+                }],
+            },
+            Block::Paragraph {
+                style_name: None,
+                attrs: None,
+                content: vec![Inline::Note {
+                    id: None,
+                    note_class: NoteClass::Footnote,
+                    citation: "1".to_string(),
+                    content: vec![image("data:image/png;base64,cG5n")],
+                }],
+            },
+        ];
+        assert_eq!(extract_pictures(&mut blocks, None).len(), 2);
+    }
+
+    #[test]
+    fn pictures_are_inlined_as_data_uris() {
+        let mut blocks = vec![image("Pictures/logo.png"), image("Pictures/gone.png")];
+        inline_pictures(&mut blocks, |path| {
+            (path == "Pictures/logo.png").then(|| b"png".to_vec())
+        });
+        assert_eq!(src(&blocks[0]), "data:image/png;base64,cG5n");
+        assert_eq!(src(&blocks[1]), "Pictures/gone.png");
+    }
+
+    #[test]
+    fn manifest_lists_pictures() {
+        let pictures = vec![Picture {
+            path: "Pictures/abc.png".to_string(),
+            media_type: "image/png".to_string(),
+            data: vec![],
+        }];
+        let xml = manifest_xml(&pictures).unwrap();
+        assert!(xml.contains(r#"manifest:full-path="content.xml""#));
+        assert!(xml
+            .contains(r#"manifest:full-path="Pictures/abc.png" manifest:media-type="image/png""#));
+    }
+
+    #[test]
+    fn updated_manifest_keeps_other_entries_and_replaces_pictures() {
+        let old = manifest_xml(&[Picture {
+            path: "Pictures/old.png".to_string(),
+            media_type: "image/png".to_string(),
+            data: vec![],
+        }])
+        .unwrap()
+        .replace(
+            "</manifest:manifest>",
+            r#"<manifest:file-entry manifest:full-path="settings.xml" manifest:media-type="text/xml"/></manifest:manifest>"#,
+        );
+        let pictures = vec![Picture {
+            path: "Pictures/new.gif".to_string(),
+            media_type: "image/gif".to_string(),
+            data: vec![],
+        }];
+        let xml = update_manifest(&old, &pictures).unwrap();
+        assert!(xml.contains("settings.xml"));
+        assert!(xml.contains("Pictures/new.gif"));
+        assert!(!xml.contains("Pictures/old.png"));
+        assert!(xml.contains(r#"manifest:full-path="/" manifest:version="1.3""#));
+    }
+}
@@ -0,0 +1,257 @@
+//! Templates and style import.
+//!
+//! An ODF text template (`.ott`, or a flat `.fott`) is an ordinary text
+//! document with its own media type. [`open_template`] reads one as a new
+//! document: the content, styles, heading numbering and page layouts are
+//! kept, while the metadata that identifies the template file itself is
+//! cleared so the result saves as a fresh document.
+//!
+//! [`import_styles`] brings the named styles of another document into a
+//! style map, either merging them with a [`StyleConflictPolicy`] for names
+//! both maps define, or replacing the map outright.
+//!
+//! # Examples
+//!
+//! ```
+//! use common_core::{StyleDefinition, StyleFamily};
+//! use odt_format::template::{
+//!     import_styles, StyleConflictPolicy, StyleImportMode, StyleImportOptions,
+//! };
+//! use std::collections::HashMap;
+//!
+//! let style = |size: &str| StyleDefinition {
+//!     name: "Body".to_string(),
+//!     family: StyleFamily::Paragraph,
+//!     parent: None,
+//!     next: None,
+//!     display_name: None,
+//!     attributes: HashMap::from([("fo:font-size".to_string(), size.to_string())]),
+//!     text_transform: None,
+//!     outline_level: None,
+//!     autocomplete: None,
+//!     master_page_name: None,
+//!     font_colour: None,
+//!     background_colour: None,
+//!     properties: Default::default(),
+//!     list_levels: Vec::new(),
+//! };
+//! let mut styles = HashMap::from([("Body".to_string(), style("12pt"))]);
+//! let house = HashMap::from([("Body".to_string(), style("11pt"))]);
+//!
+//! let report = import_styles(
+//!     &mut styles,
+//!     &house,
+//!     StyleImportOptions {
+//!         mode: StyleImportMode::Merge,
+//!         conflict: StyleConflictPolicy::Rename,
+//!     },
+//! );
+//! assert_eq!(report.renamed, vec![("Body".to_string(), "Body_20_2".to_string())]);
+//! assert_eq!(styles["Body_20_2"].display_name.as_deref(), Some("Body 2"));
+//! assert_eq!(styles["Body_20_2"].attributes["fo:font-size"], "11pt");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use common_core::style::encode_style_name;
+use common_core::StyleDefinition;
+use serde::{Deserialize, Serialize};
+
+use crate::{Document, OdtPackage};
+
+/// The media type of an ODF text template.
+pub const TEMPLATE_MIMETYPE: &str = "application/vnd.oasis.opendocument.text-template";
+
+/// How [`import_styles`] combines the two style maps.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StyleImportMode {
+    /// Add the imported styles to the existing ones, resolving names both
+    /// define with the [`StyleConflictPolicy`].
+    #[default]
+    Merge,
+    /// Discard the existing styles and use the imported ones.
+    Replace,
+}
+
+/// What [`import_styles`] does when both maps define a style name with
+/// different definitions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StyleConflictPolicy {
+    /// Keep the existing style and skip the imported one.
+    #[default]
+    Keep,
+    /// Replace the existing style with the imported one.
+    Overwrite,
+    /// Import the style under a new name, such as `"Body_20_2"` shown as
+    /// `"Body 2"`. Default styles cannot be renamed and are kept.
+    Rename,
+}
+
+/// Options for [`import_styles`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleImportOptions {
+    /// Whether to merge with or replace the existing styles.
+    pub mode: StyleImportMode,
+    /// How to resolve names both maps define when merging.
+    pub conflict: StyleConflictPolicy,
+}
+
+/// What [`import_styles`] did, with each list sorted by style name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleImportReport {
+    /// Styles that did not exist before.
+    pub added: Vec<String>,
+    /// Existing styles replaced by the imported definition.
+    pub overwritten: Vec<String>,
+    /// Existing styles left as they were, either because the imported
+    /// definition is identical or because the policy kept them.
+    pub kept: Vec<String>,
+    /// Imported styles added under a new name, as `(imported, new)` pairs.
+    pub renamed: Vec<(String, String)>,
+    /// Existing styles dropped by [`StyleImportMode::Replace`].
+    pub removed: Vec<String>,
+}
+
+/// Reads a template from the bytes of an `.ott` package or a flat XML
+/// file as a new document.
+///
+/// The document identifier, creation date and generator are cleared, since
+/// they describe the template rather than the document made from it.
+/// Ordinary documents are accepted too, so any file can serve as a
+/// template.
+///
+/// # Errors
+///
+/// Returns a `String` error if the bytes are neither a readable ODF package
+/// nor UTF-8 ODF XML.
+pub fn open_template(bytes: &[u8]) -> Result<Document, String> {
+    let mut doc = if bytes.starts_with(b"PK") {
+        OdtPackage::from_bytes(bytes)?.to_document()?
+    } else {
+        let xml = std::str::from_utf8(bytes)
+            .map_err(|e| format!("Template is not a package or UTF-8 XML: {e}"))?;
+        Document::from_xml(xml)?
+    };
+    doc.metadata.identifier = None;
+    doc.metadata.creation_date = None;
+    doc.metadata.generator = None;
+    Ok(doc)
+}
+
+/// Imports the styles of `source` into `target`.
+///
+/// Identical definitions are never a conflict. When merging with
+/// [`StyleConflictPolicy::Rename`], the `parent` and `next` references of
+/// the imported styles follow the new names, so an imported hierarchy stays
+/// intact.
+pub fn import_styles(
+    target: &mut HashMap<String, StyleDefinition>,
+    source: &HashMap<String, StyleDefinition>,
+    options: StyleImportOptions,
+) -> StyleImportReport {
+    let mut report = StyleImportReport::default();
+    let mut names: Vec<&String> = source.keys().collect();
+    names.sort();
+
+    if options.mode == StyleImportMode::Replace {
+        report.removed = target
+            .keys()
+            .filter(|name| !source.contains_key(*name))
+            .cloned()
+            .collect();
+        report.removed.sort();
+        for name in names {
+            match target.get(name) {
+                None => report.added.push(name.clone()),
+                Some(existing) if existing == &source[name] => report.kept.push(name.clone()),
+                Some(_) => report.overwritten.push(name.clone()),
+            }
+        }
+        *target = source.clone();
+        return report;
+    }
+
+    let conflicts: Vec<&String> = names
+        .iter()
+        .copied()
+        .filter(|name| target.get(*name).is_some_and(|t| t != &source[*name]))
+        .collect();
+    let renames = match options.conflict {
+        StyleConflictPolicy::Rename => rename_map(&conflicts, target, source),
+        _ => HashMap::new(),
+    };
+    let rename = |name: &String| {
+        renames
+            .get(name)
+            .map_or_else(|| name.clone(), |(new, _)| new.clone())
+    };
+
+    for name in names {
+        let imported = &source[name];
+        let new_name = rename(name);
+        match target.get(&new_name) {
+            Some(existing) if existing == imported => {
+                report.kept.push(name.clone());
+                continue;
+            }
+            Some(_) if options.conflict != StyleConflictPolicy::Overwrite => {
+                report.kept.push(name.clone());
+                continue;
+            }
+            Some(_) => report.overwritten.push(name.clone()),
+            None if new_name != *name => report.renamed.push((name.clone(), new_name.clone())),
+            None => report.added.push(name.clone()),
+        }
+        let mut style = imported.clone();
+        style.parent = style.parent.as_ref().map(rename);
+        style.next = style.next.as_ref().map(rename);
+        if let Some((_, display_name)) = renames.get(name) {
+            style.display_name = Some(display_name.clone());
+            style.name = new_name.clone();
+        }
+        target.insert(new_name, style);
+    }
+    report
+}
+
+/// Picks a free display name of the form `"{display name} {n}"` for each
+/// conflicting style, skipping default styles, and returns it with the
+/// style name encoded from it.
+fn rename_map(
+    conflicts: &[&String],
+    target: &HashMap<String, StyleDefinition>,
+    source: &HashMap<String, StyleDefinition>,
+) -> HashMap<String, (String, String)> {
+    let mut taken: HashSet<String> = target
+        .values()
+        .chain(source.values())
+        .flat_map(|style| [Some(&style.name), style.display_name.as_ref()])
+        .flatten()
+        .cloned()
+        .collect();
+    let mut renames = HashMap::new();
+    for name in conflicts {
+        let style = &source[*name];
+        if style.is_default_style() {
+            continue;
+        }
+        let display = style.display_name.as_deref().unwrap_or(name.as_str());
+        let Some((new_name, display_name)) = (2..)
+            .map(|n| format!("{display} {n}"))
+            .map(|display_name| (encode_style_name(&display_name), display_name))
+            .find(|(new_name, display_name)| {
+                !taken.contains(new_name) && !taken.contains(display_name)
+            })
+        else {
+            continue;
+        };
+        taken.insert(new_name.clone());
+        taken.insert(display_name.clone());
+        renames.insert((*name).clone(), (new_name, display_name));
+    }
+    renames
+}
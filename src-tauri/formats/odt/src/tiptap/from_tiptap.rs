@@ -47,6 +47,9 @@ pub fn tiptap_to_document(
         blocks,
         styles,
         metadata,
+        track_changes: false,
+        outline_style: None,
+        page_setup: None,
         font_face_decls: None,
         automatic_styles: None,
         master_styles: None,
@@ -63,6 +66,7 @@ pub fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
             let block_attrs = attrs.map(|a| BlockAttrs {
                 text_align: a.text_align,
                 indent: a.indent,
+                ..BlockAttrs::default()
             });
             let inlines = tiptap_content_to_inlines(content.unwrap_or_default());
             Some(Block::Paragraph {
@@ -77,6 +81,7 @@ pub fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
             let block_attrs = attrs.map(|a| BlockAttrs {
                 text_align: a.text_align,
                 indent: a.indent,
+                ..BlockAttrs::default()
             });
             let inlines = tiptap_content_to_inlines(content.unwrap_or_default());
             Some(Block::Heading {
@@ -90,14 +95,18 @@ pub fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
             src: attrs.src,
             alt: attrs.alt,
             title: attrs.title,
+            frame: attrs.frame,
+            caption: attrs.caption,
         }),
-        TiptapNode::BulletList { content } => Some(Block::BulletList {
+        TiptapNode::BulletList { attrs, content } => Some(Block::BulletList {
+            attrs,
             content: content
                 .into_iter()
                 .filter_map(tiptap_node_to_block)
                 .collect(),
         }),
-        TiptapNode::OrderedList { content } => Some(Block::OrderedList {
+        TiptapNode::OrderedList { attrs, content } => Some(Block::OrderedList {
+            attrs,
             content: content
                 .into_iter()
                 .filter_map(tiptap_node_to_block)
@@ -115,6 +124,15 @@ pub fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
                 .filter_map(tiptap_node_to_block)
                 .collect(),
         }),
+        TiptapNode::Section { attrs, content } => Some(Block::Section {
+            name: attrs.name,
+            attrs: Some(attrs.columns).filter(|c| !c.is_empty()),
+            protected: attrs.protected,
+            content: content
+                .into_iter()
+                .filter_map(tiptap_node_to_block)
+                .collect(),
+        }),
         TiptapNode::Table { content } => Some(Block::Table {
             content: content
                 .into_iter()
@@ -143,6 +161,7 @@ pub fn tiptap_node_to_block(node: TiptapNode) -> Option<Block> {
         }),
         TiptapNode::HorizontalRule => Some(Block::HorizontalRule),
         TiptapNode::PageBreak => Some(Block::PageBreak),
+        TiptapNode::TableOfContents { attrs } => Some(Block::TableOfContents(attrs)),
         _ => None,
     }
 }
@@ -158,6 +177,24 @@ fn tiptap_content_to_inlines(nodes: Vec<TiptapNode>) -> Vec<Inline> {
                 marks: marks.unwrap_or_default(),
             }),
             TiptapNode::HardBreak => Some(Inline::LineBreak),
+            TiptapNode::Note { attrs, content } => Some(Inline::Note {
+                id: attrs.id,
+                note_class: attrs.note_class,
+                citation: attrs.citation,
+                content: content
+                    .into_iter()
+                    .filter_map(tiptap_node_to_block)
+                    .collect(),
+            }),
+            TiptapNode::Field { attrs } => Some(Inline::Field {
+                kind: attrs.kind,
+                value: attrs.value,
+                fixed: false,
+                date_value: None,
+                data_style_name: None,
+            }),
+            TiptapNode::Bookmark { attrs } => Some(Inline::Bookmark { name: attrs.name }),
+            TiptapNode::Reference { attrs } => Some(Inline::Reference(attrs)),
             _ => None,
         })
         .collect()
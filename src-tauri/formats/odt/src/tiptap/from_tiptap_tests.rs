@@ -89,6 +89,8 @@ fn tiptap_node_to_block_image() {
             src: "img.png".to_string(),
             alt: Some("alt".to_string()),
             title: None,
+            frame: None,
+            caption: None,
         },
     };
     let block = tiptap_node_to_block(node).unwrap();
@@ -129,10 +131,11 @@ fn tiptap_node_to_block_page_break() {
 #[test]
 fn tiptap_node_to_block_bullet_list() {
     let node = TiptapNode::BulletList {
+        attrs: None,
         content: vec![TiptapNode::ListItem { content: vec![] }],
     };
     let block = tiptap_node_to_block(node).unwrap();
-    if let Block::BulletList { content } = block {
+    if let Block::BulletList { content, .. } = block {
         assert_eq!(content.len(), 1);
     } else {
         panic!("expected BulletList");
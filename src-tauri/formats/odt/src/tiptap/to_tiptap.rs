@@ -3,7 +3,9 @@
 //! Provides [`document_to_tiptap`] which transforms the parsed document
 //! into a [`TiptapNode::Doc`] tree suitable for sending to the frontend.
 
-use common_core::tiptap::{ImageAttrs, TiptapAttrs};
+use common_core::tiptap::{
+    BookmarkAttrs, FieldAttrs, ImageAttrs, NoteAttrs, SectionNodeAttrs, TiptapAttrs,
+};
 use common_core::{Block, Inline, TiptapNode};
 
 /// Converts a slice of blocks to a `TiptapNode::Doc`.
@@ -54,17 +56,27 @@ pub fn block_to_tiptap(block: &Block) -> TiptapNode {
             }),
             content: Some(inlines_to_tiptap(content)),
         },
-        Block::Image { src, alt, title } => TiptapNode::Image {
+        Block::Image {
+            src,
+            alt,
+            title,
+            frame,
+            caption,
+        } => TiptapNode::Image {
             attrs: ImageAttrs {
                 src: src.clone(),
                 alt: alt.clone(),
                 title: title.clone(),
+                frame: frame.clone(),
+                caption: caption.clone(),
             },
         },
-        Block::BulletList { content } => TiptapNode::BulletList {
+        Block::BulletList { attrs, content } => TiptapNode::BulletList {
+            attrs: attrs.clone(),
             content: content.iter().map(block_to_tiptap).collect(),
         },
-        Block::OrderedList { content } => TiptapNode::OrderedList {
+        Block::OrderedList { attrs, content } => TiptapNode::OrderedList {
+            attrs: attrs.clone(),
             content: content.iter().map(block_to_tiptap).collect(),
         },
         Block::ListItem { content } => TiptapNode::ListItem {
@@ -73,6 +85,19 @@ pub fn block_to_tiptap(block: &Block) -> TiptapNode {
         Block::Blockquote { content } => TiptapNode::Blockquote {
             content: content.iter().map(block_to_tiptap).collect(),
         },
+        Block::Section {
+            name,
+            attrs,
+            protected,
+            content,
+        } => TiptapNode::Section {
+            attrs: SectionNodeAttrs {
+                name: name.clone(),
+                columns: attrs.clone().unwrap_or_default(),
+                protected: *protected,
+            },
+            content: content.iter().map(block_to_tiptap).collect(),
+        },
         Block::Table { content } => TiptapNode::Table {
             content: content.iter().map(block_to_tiptap).collect(),
         },
@@ -89,19 +114,66 @@ pub fn block_to_tiptap(block: &Block) -> TiptapNode {
         },
         Block::HorizontalRule => TiptapNode::HorizontalRule,
         Block::PageBreak => TiptapNode::PageBreak,
+        Block::TableOfContents(toc) => TiptapNode::TableOfContents { attrs: toc.clone() },
     }
 }
 
-/// Converts a slice of [`Inline`] values to Tiptap text/break nodes.
+/// Converts a slice of [`Inline`] values to Tiptap text/break/note nodes.
+///
+/// Tiptap has no comment nodes, so comments are left out.
 pub fn inlines_to_tiptap(inlines: &[Inline]) -> Vec<TiptapNode> {
     inlines
         .iter()
-        .map(|inline| match inline {
-            Inline::Text { text, marks, .. } => TiptapNode::Text {
+        .filter_map(|inline| match inline {
+            Inline::Text { text, marks, .. } => Some(TiptapNode::Text {
                 text: text.clone(),
                 marks: Some(marks.clone()),
-            },
-            Inline::LineBreak => TiptapNode::HardBreak,
+            }),
+            Inline::LineBreak => Some(TiptapNode::HardBreak),
+            // Tiptap has no whitespace nodes; the writer restores `text:s`
+            // and `text:tab` from the literal characters.
+            Inline::Space { count } => Some(TiptapNode::Text {
+                text: " ".repeat(*count as usize),
+                marks: None,
+            }),
+            Inline::Tab => Some(TiptapNode::Text {
+                text: "\t".to_string(),
+                marks: None,
+            }),
+            Inline::Note {
+                id,
+                note_class,
+                citation,
+                content,
+            } => Some(TiptapNode::Note {
+                attrs: NoteAttrs {
+                    id: id.clone(),
+                    note_class: *note_class,
+                    citation: citation.clone(),
+                },
+                content: content.iter().map(block_to_tiptap).collect(),
+            }),
+            Inline::Field { kind, value, .. } => Some(TiptapNode::Field {
+                attrs: FieldAttrs {
+                    kind: *kind,
+                    value: value.clone(),
+                },
+            }),
+            // A bookmarked range is reduced to its start.
+            Inline::Bookmark { name } | Inline::BookmarkStart { name } => {
+                Some(TiptapNode::Bookmark {
+                    attrs: BookmarkAttrs { name: name.clone() },
+                })
+            }
+            Inline::Reference(reference) => Some(TiptapNode::Reference {
+                attrs: reference.clone(),
+            }),
+            Inline::BookmarkEnd { .. }
+            | Inline::Comment(_)
+            | Inline::CommentEnd { .. }
+            | Inline::ChangeStart(_)
+            | Inline::ChangeEnd { .. }
+            | Inline::Change(_) => None,
         })
         .collect()
 }
@@ -139,6 +211,7 @@ mod tests {
             attrs: Some(BlockAttrs {
                 text_align: Some("right".to_string()),
                 indent: None,
+                ..BlockAttrs::default()
             }),
             content: vec![],
         };
@@ -174,6 +247,8 @@ mod tests {
             src: "hero.jpg".to_string(),
             alt: None,
             title: Some("Hero".to_string()),
+            frame: None,
+            caption: None,
         };
         let node = block_to_tiptap(&block);
         if let TiptapNode::Image { attrs } = node {
@@ -224,10 +299,11 @@ mod tests {
     #[test]
     fn block_to_tiptap_bullet_list() {
         let block = Block::BulletList {
+            attrs: None,
             content: vec![Block::ListItem { content: vec![] }],
         };
         let node = block_to_tiptap(&block);
-        if let TiptapNode::BulletList { content } = node {
+        if let TiptapNode::BulletList { content, .. } = node {
             assert_eq!(content.len(), 1);
         } else {
             panic!("expected BulletList");
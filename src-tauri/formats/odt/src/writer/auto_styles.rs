@@ -0,0 +1,718 @@
+//! Automatic style generation for direct formatting.
+//!
+//! Paragraph alignment/indentation and character marks are not named styles
+//! in the document model. On write they are turned into deduplicated
+//! automatic styles (`P1`, `P2`, … for paragraphs and `T1`, `T2`, … for text
+//! runs) that derive from the block's or run's named style. Table column
+//! widths and cell backgrounds and borders likewise become `co1`, … and
+//! `ce1`, … styles, image wrapping becomes `fr1`, … graphic styles and
+//! section columns become `Sect1`, … section styles.
+
+use common_core::units::PX_PER_CM;
+use common_core::{
+    Block, BlockAttrs, CellAttrs, CellBorders, ImageWrap, Inline, SectionAttrs, TextStyleAttrs,
+    TiptapMark,
+};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+
+use crate::loki_ext::{colour_to_attr, colour_to_odf_string, needs_loki_attr, LOKI_COLOUR_KEY};
+
+use super::inlines::XmlWriter;
+use super::tables::column_widths;
+
+/// A paragraph automatic style: parent style plus direct formatting.
+#[derive(Debug, PartialEq)]
+struct ParagraphKey {
+    parent: Option<String>,
+    attrs: BlockAttrs,
+}
+
+/// A text automatic style: parent span style plus formatting marks.
+#[derive(Debug, PartialEq)]
+struct TextKey {
+    parent: Option<String>,
+    marks: Vec<TiptapMark>,
+}
+
+/// A table cell automatic style: background and borders.
+#[derive(Debug, PartialEq)]
+struct CellKey {
+    background: Option<String>,
+    border: Option<CellBorders>,
+}
+
+impl CellKey {
+    fn from_attrs(attrs: Option<&CellAttrs>) -> Option<Self> {
+        let attrs = attrs?;
+        let key = Self {
+            background: attrs.background_color.clone(),
+            border: attrs.border.clone().filter(|b| !b.is_empty()),
+        };
+        (key.background.is_some() || key.border.is_some()).then_some(key)
+    }
+}
+
+/// The automatic styles needed to write a block tree.
+///
+/// Build with [`AutoStyles::collect`], write the definitions with
+/// [`AutoStyles::write`], and look names up while writing the body.
+#[derive(Debug, Default)]
+pub struct AutoStyles {
+    paragraph: Vec<(ParagraphKey, String)>,
+    text: Vec<(TextKey, String)>,
+    /// Column widths in pixels.
+    column: Vec<(u32, String)>,
+    cell: Vec<(CellKey, String)>,
+    graphic: Vec<(ImageWrap, String)>,
+    section: Vec<(SectionAttrs, String)>,
+    reserved: Vec<String>,
+    /// Put before every generated name.
+    prefix: String,
+}
+
+impl AutoStyles {
+    /// Collects the automatic styles used by `blocks`.
+    ///
+    /// Generated names skip those in `reserved`, e.g. the names of preserved
+    /// automatic styles that will be written alongside.
+    #[must_use]
+    pub fn collect(blocks: &[Block], reserved: &[&str]) -> Self {
+        Self::collect_prefixed(blocks, "", reserved)
+    }
+
+    /// Collects the automatic styles used by `blocks`, with `prefix` put
+    /// before every generated name (`MP1`, `MT1`, … for `M`).
+    #[must_use]
+    pub fn collect_prefixed(blocks: &[Block], prefix: &str, reserved: &[&str]) -> Self {
+        let mut styles = Self {
+            reserved: reserved.iter().map(|s| s.to_string()).collect(),
+            prefix: prefix.to_string(),
+            ..Self::default()
+        };
+        styles.collect_blocks(blocks);
+        styles
+    }
+
+    /// Returns whether `name` looks generated with `prefix`, e.g. `MP3` or
+    /// `MSect1` for `M`.
+    #[must_use]
+    pub fn is_generated_name(name: &str, prefix: &str) -> bool {
+        let Some(rest) = name.strip_prefix(prefix) else {
+            return false;
+        };
+        ["P", "T", "co", "ce", "fr", "Sect"].iter().any(|kind| {
+            rest.strip_prefix(kind)
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+    }
+
+    /// Returns the automatic paragraph style for a block, if it needs one.
+    #[must_use]
+    pub fn paragraph_style(
+        &self,
+        parent: Option<&str>,
+        attrs: Option<&BlockAttrs>,
+    ) -> Option<&str> {
+        let attrs = normalize_block_attrs(attrs?)?;
+        self.paragraph
+            .iter()
+            .find(|(k, _)| k.parent.as_deref() == parent && k.attrs == attrs)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the automatic text style for a run, if it needs one.
+    #[must_use]
+    pub fn text_style(&self, parent: Option<&str>, marks: &[TiptapMark]) -> Option<&str> {
+        let marks = formatting_marks(marks);
+        if marks.is_empty() {
+            return None;
+        }
+        self.text
+            .iter()
+            .find(|(k, _)| k.parent.as_deref() == parent && k.marks == marks)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the automatic column style for a width in pixels.
+    #[must_use]
+    pub fn column_style(&self, width: u32) -> Option<&str> {
+        self.column
+            .iter()
+            .find(|(w, _)| *w == width)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the automatic cell style for a cell's attributes, if it needs
+    /// one.
+    #[must_use]
+    pub fn cell_style(&self, attrs: Option<&CellAttrs>) -> Option<&str> {
+        let key = CellKey::from_attrs(attrs)?;
+        self.cell
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the automatic graphic style for an image frame's wrapping.
+    #[must_use]
+    pub fn graphic_style(&self, wrap: ImageWrap) -> Option<&str> {
+        self.graphic
+            .iter()
+            .find(|(w, _)| *w == wrap)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the automatic section style for a section's column layout,
+    /// if it needs one.
+    #[must_use]
+    pub fn section_style(&self, attrs: Option<&SectionAttrs>) -> Option<&str> {
+        let attrs = attrs.filter(|a| !a.is_empty())?;
+        self.section
+            .iter()
+            .find(|(a, _)| a == attrs)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Writes all collected automatic styles as `style:style` elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `String` error if XML writing fails.
+    pub fn write(&self, writer: &mut XmlWriter) -> Result<(), String> {
+        for (key, name) in &self.paragraph {
+            write_paragraph_style(name, key, writer)?;
+        }
+        for (key, name) in &self.text {
+            let mut props = BytesStart::new("style:text-properties");
+            for mark in &key.marks {
+                push_mark_properties(mark, &mut props);
+            }
+            write_style(name, "text", key.parent.as_deref(), props, writer)?;
+        }
+        for (width, name) in &self.column {
+            let mut props = BytesStart::new("style:table-column-properties");
+            let cm = format!("{:.3}cm", f64::from(*width) / PX_PER_CM);
+            props.push_attribute(("style:column-width", cm.as_str()));
+            write_style(name, "table-column", None, props, writer)?;
+        }
+        for (key, name) in &self.cell {
+            let mut props = BytesStart::new("style:table-cell-properties");
+            if let Some(background) = &key.background {
+                props.push_attribute(("fo:background-color", background.as_str()));
+            }
+            if let Some(border) = &key.border {
+                push_border_properties(border, &mut props);
+            }
+            write_style(name, "table-cell", None, props, writer)?;
+        }
+        for (wrap, name) in &self.graphic {
+            let mut props = BytesStart::new("style:graphic-properties");
+            props.push_attribute(("style:wrap", wrap.to_odf_str()));
+            write_style(name, "graphic", None, props, writer)?;
+        }
+        for (attrs, name) in &self.section {
+            write_section_style(name, attrs, writer)?;
+        }
+        Ok(())
+    }
+
+    fn collect_blocks(&mut self, blocks: &[Block]) {
+        for block in blocks {
+            match block {
+                Block::Paragraph {
+                    style_name,
+                    attrs,
+                    content,
+                }
+                | Block::Heading {
+                    style_name,
+                    attrs,
+                    content,
+                    ..
+                } => {
+                    self.add_paragraph(style_name.as_deref(), attrs.as_ref());
+                    self.collect_inlines(content);
+                }
+                Block::Table { content } => {
+                    for width in column_widths(content).into_iter().flatten() {
+                        self.add_column(width);
+                    }
+                    self.collect_blocks(content);
+                }
+                Block::TableCell { attrs, content } | Block::TableHeader { attrs, content } => {
+                    self.add_cell(attrs.as_ref());
+                    self.collect_blocks(content);
+                }
+                Block::Section { attrs, content, .. } => {
+                    self.add_section(attrs.as_ref());
+                    self.collect_blocks(content);
+                }
+                Block::BulletList { content, .. }
+                | Block::OrderedList { content, .. }
+                | Block::ListItem { content }
+                | Block::Blockquote { content }
+                | Block::TableRow { content } => self.collect_blocks(content),
+                Block::Image { frame, .. } => {
+                    if let Some(wrap) = frame.as_ref().and_then(|f| f.wrap) {
+                        self.add_graphic(wrap);
+                    }
+                }
+                Block::HorizontalRule | Block::PageBreak | Block::TableOfContents(_) => {}
+            }
+        }
+    }
+
+    fn collect_inlines(&mut self, inlines: &[Inline]) {
+        for inline in inlines {
+            match inline {
+                Inline::Text {
+                    style_name, marks, ..
+                } => self.add_text(run_parent_style(style_name.as_deref(), marks), marks),
+                Inline::Note { content, .. } => self.collect_blocks(content),
+                Inline::Comment(comment) => {
+                    for c in comment.thread() {
+                        self.collect_blocks(&c.content);
+                    }
+                }
+                Inline::Change(change) => self.collect_blocks(&change.content),
+                Inline::LineBreak
+                | Inline::Space { .. }
+                | Inline::Tab
+                | Inline::CommentEnd { .. }
+                | Inline::ChangeStart(_)
+                | Inline::ChangeEnd { .. }
+                | Inline::Field { .. }
+                | Inline::Bookmark { .. }
+                | Inline::BookmarkStart { .. }
+                | Inline::BookmarkEnd { .. }
+                | Inline::Reference(_) => {}
+            }
+        }
+    }
+
+    fn add_paragraph(&mut self, parent: Option<&str>, attrs: Option<&BlockAttrs>) {
+        let Some(attrs) = attrs.and_then(normalize_block_attrs) else {
+            return;
+        };
+        if self.paragraph_style(parent, Some(&attrs)).is_some() {
+            return;
+        }
+        let name = self.next_name("P", self.paragraph.len());
+        let key = ParagraphKey {
+            parent: parent.map(str::to_string),
+            attrs,
+        };
+        self.paragraph.push((key, name));
+    }
+
+    fn add_text(&mut self, parent: Option<&str>, marks: &[TiptapMark]) {
+        if formatting_marks(marks).is_empty() || self.text_style(parent, marks).is_some() {
+            return;
+        }
+        let name = self.next_name("T", self.text.len());
+        let key = TextKey {
+            parent: parent.map(str::to_string),
+            marks: formatting_marks(marks),
+        };
+        self.text.push((key, name));
+    }
+
+    fn add_column(&mut self, width: u32) {
+        if self.column_style(width).is_some() {
+            return;
+        }
+        let name = self.next_name("co", self.column.len());
+        self.column.push((width, name));
+    }
+
+    fn add_cell(&mut self, attrs: Option<&CellAttrs>) {
+        let Some(key) = CellKey::from_attrs(attrs) else {
+            return;
+        };
+        if self.cell_style(attrs).is_some() {
+            return;
+        }
+        let name = self.next_name("ce", self.cell.len());
+        self.cell.push((key, name));
+    }
+
+    fn add_graphic(&mut self, wrap: ImageWrap) {
+        if self.graphic_style(wrap).is_none() {
+            let name = self.next_name("fr", self.graphic.len());
+            self.graphic.push((wrap, name));
+        }
+    }
+
+    fn add_section(&mut self, attrs: Option<&SectionAttrs>) {
+        let Some(attrs) = attrs.filter(|a| !a.is_empty()) else {
+            return;
+        };
+        if self.section_style(Some(attrs)).is_none() {
+            let name = self.next_name("Sect", self.section.len());
+            self.section.push((attrs.clone(), name));
+        }
+    }
+
+    /// Returns the first `{prefix}{n}` name, counting from `count + 1`, that
+    /// is neither reserved nor already generated.
+    fn next_name(&self, prefix: &str, count: usize) -> String {
+        (count + 1..)
+            .map(|n| format!("{}{prefix}{n}", self.prefix))
+            .find(|name| {
+                !self.reserved.contains(name)
+                    && !self.paragraph.iter().any(|(_, n)| n == name)
+                    && !self.text.iter().any(|(_, n)| n == name)
+                    && !self.column.iter().any(|(_, n)| n == name)
+                    && !self.cell.iter().any(|(_, n)| n == name)
+                    && !self.graphic.iter().any(|(_, n)| n == name)
+                    && !self.section.iter().any(|(_, n)| n == name)
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the named span style a run derives from: its `NamedSpanStyle`
+/// mark if present, otherwise its inline style name.
+#[must_use]
+pub fn run_parent_style<'a>(
+    style_name: Option<&'a str>,
+    marks: &'a [TiptapMark],
+) -> Option<&'a str> {
+    marks
+        .iter()
+        .find_map(|m| match m {
+            TiptapMark::NamedSpanStyle { attrs } => attrs.style_name.as_deref(),
+            _ => None,
+        })
+        .or(style_name)
+}
+
+/// Drops empty alignment and zero indentation; `None` if nothing is left.
+pub(crate) fn normalize_block_attrs(attrs: &BlockAttrs) -> Option<BlockAttrs> {
+    let text_align = attrs.text_align.clone().filter(|a| {
+        matches!(
+            a.as_str(),
+            "start" | "end" | "left" | "right" | "center" | "justify"
+        )
+    });
+    let attrs = BlockAttrs {
+        text_align,
+        indent: attrs.indent.filter(|&i| i > 0),
+        ..attrs.clone()
+    };
+    (attrs != BlockAttrs::default()).then_some(attrs)
+}
+
+/// Returns the marks that become text properties, in a canonical order.
+///
+/// Several `TextStyle` marks are merged into one, later marks winning.
+pub(crate) fn formatting_marks(marks: &[TiptapMark]) -> Vec<TiptapMark> {
+    let mut out: Vec<TiptapMark> = Vec::new();
+    for mark in marks {
+        if mark_rank(mark).is_none() || out.contains(mark) {
+            continue;
+        }
+        match (mark, out.iter_mut().find(|m| mark_rank(m) == Some(6))) {
+            (TiptapMark::TextStyle { attrs }, Some(TiptapMark::TextStyle { attrs: merged })) => {
+                merged.merge(attrs);
+            }
+            _ => out.push(mark.clone()),
+        }
+    }
+    out.sort_by_key(mark_rank);
+    out
+}
+
+fn mark_rank(mark: &TiptapMark) -> Option<u8> {
+    match mark {
+        TiptapMark::Bold => Some(0),
+        TiptapMark::Italic => Some(1),
+        TiptapMark::Underline => Some(2),
+        TiptapMark::Strike => Some(3),
+        TiptapMark::Superscript => Some(4),
+        TiptapMark::Subscript => Some(5),
+        TiptapMark::TextStyle { attrs } if !attrs.is_empty() => Some(6),
+        TiptapMark::NamedSpanStyle { .. }
+        | TiptapMark::Link { .. }
+        | TiptapMark::TextStyle { .. } => None,
+    }
+}
+
+fn push_mark_properties(mark: &TiptapMark, props: &mut BytesStart) {
+    for (key, value) in mark_properties(mark) {
+        props.push_attribute((key, value.as_str()));
+    }
+}
+
+/// Returns the text properties a formatting mark stands for.
+pub(crate) fn mark_properties(mark: &TiptapMark) -> Vec<(&'static str, String)> {
+    let fixed = |props: &[(&'static str, &str)]| {
+        props
+            .iter()
+            .map(|(key, value)| (*key, value.to_string()))
+            .collect()
+    };
+    match mark {
+        TiptapMark::Bold => fixed(&[("fo:font-weight", "bold")]),
+        TiptapMark::Italic => fixed(&[("fo:font-style", "italic")]),
+        TiptapMark::Underline => fixed(&[
+            ("style:text-underline-style", "solid"),
+            ("style:text-underline-width", "auto"),
+            ("style:text-underline-color", "font-color"),
+        ]),
+        TiptapMark::Strike => fixed(&[("style:text-line-through-style", "solid")]),
+        TiptapMark::Superscript => fixed(&[("style:text-position", "super 58%")]),
+        TiptapMark::Subscript => fixed(&[("style:text-position", "sub 58%")]),
+        TiptapMark::TextStyle { attrs } => text_style_properties(attrs),
+        TiptapMark::NamedSpanStyle { .. } | TiptapMark::Link { .. } => Vec::new(),
+    }
+}
+
+/// Returns colours, font family and size. Non-RGB colours also get a
+/// `loki:colour` attribute so they survive a round-trip.
+fn text_style_properties(attrs: &TextStyleAttrs) -> Vec<(&'static str, String)> {
+    let mut props = Vec::new();
+    if let Some(colour) = &attrs.colour {
+        props.push(("fo:color", colour_to_odf_string(colour)));
+        if needs_loki_attr(colour) {
+            if let Some(json) = colour_to_attr(colour) {
+                props.push((LOKI_COLOUR_KEY, json));
+            }
+        }
+    }
+    if let Some(colour) = &attrs.background_colour {
+        props.push(("fo:background-color", colour_to_odf_string(colour)));
+    }
+    if let Some(family) = &attrs.font_family {
+        props.push(("fo:font-family", family.clone()));
+    }
+    if let Some(size) = attrs.font_size {
+        props.push(("fo:font-size", format!("{size}pt")));
+    }
+    props
+}
+
+/// Writes `fo:border` when all sides match, otherwise one attribute per side.
+fn push_border_properties(border: &CellBorders, props: &mut BytesStart) {
+    if let Some(all) = border.uniform() {
+        props.push_attribute(("fo:border", all));
+        return;
+    }
+    let sides = [
+        ("fo:border-top", &border.top),
+        ("fo:border-right", &border.right),
+        ("fo:border-bottom", &border.bottom),
+        ("fo:border-left", &border.left),
+    ];
+    for (key, side) in sides {
+        props.push_attribute((key, side.as_deref().unwrap_or("none")));
+    }
+}
+
+fn write_style(
+    name: &str,
+    family: &str,
+    parent: Option<&str>,
+    props: BytesStart,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut style = BytesStart::new("style:style");
+    style.push_attribute(("style:name", name));
+    style.push_attribute(("style:family", family));
+    if let Some(parent) = parent {
+        style.push_attribute(("style:parent-style-name", parent));
+    }
+    writer
+        .write_event(Event::Start(style))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Empty(props))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("style:style")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a paragraph style with its paragraph properties and tab stops.
+fn write_paragraph_style(
+    name: &str,
+    key: &ParagraphKey,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut style = BytesStart::new("style:style");
+    style.push_attribute(("style:name", name));
+    style.push_attribute(("style:family", "paragraph"));
+    if let Some(parent) = &key.parent {
+        style.push_attribute(("style:parent-style-name", parent.as_str()));
+    }
+    if let Some(master) = &key.attrs.master_page_name {
+        style.push_attribute(("style:master-page-name", master.as_str()));
+    }
+    writer
+        .write_event(Event::Start(style))
+        .map_err(|e| e.to_string())?;
+
+    let properties = key.attrs.to_odf_attributes();
+    let mut keys: Vec<&String> = properties.keys().collect();
+    keys.sort();
+    let mut props = BytesStart::new("style:paragraph-properties");
+    for key in keys {
+        props.push_attribute((key.as_str(), properties[key].as_str()));
+    }
+    if key.attrs.tab_stops.is_empty() {
+        writer
+            .write_event(Event::Empty(props))
+            .map_err(|e| e.to_string())?;
+    } else {
+        writer
+            .write_event(Event::Start(props))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_event(Event::Start(BytesStart::new("style:tab-stops")))
+            .map_err(|e| e.to_string())?;
+        for stop in &key.attrs.tab_stops {
+            let mut keys: Vec<&String> = stop.keys().collect();
+            keys.sort();
+            let mut elem = BytesStart::new("style:tab-stop");
+            for key in keys {
+                elem.push_attribute((key.as_str(), stop[key].as_str()));
+            }
+            writer
+                .write_event(Event::Empty(elem))
+                .map_err(|e| e.to_string())?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("style:tab-stops")))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_event(Event::End(BytesEnd::new("style:paragraph-properties")))
+            .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("style:style")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a section style with its `style:columns`.
+fn write_section_style(
+    name: &str,
+    attrs: &SectionAttrs,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut style = BytesStart::new("style:style");
+    style.push_attribute(("style:name", name));
+    style.push_attribute(("style:family", "section"));
+    writer
+        .write_event(Event::Start(style))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Start(BytesStart::new("style:section-properties")))
+        .map_err(|e| e.to_string())?;
+    let mut columns = BytesStart::new("style:columns");
+    columns.push_attribute(("fo:column-count", attrs.columns().to_string().as_str()));
+    let gap = format!("{:.3}cm", attrs.column_gap.unwrap_or(0.0) / PX_PER_CM);
+    columns.push_attribute(("fo:column-gap", gap.as_str()));
+    writer
+        .write_event(Event::Empty(columns))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("style:section-properties")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("style:style")))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(marks: Vec<TiptapMark>) -> Inline {
+        Inline::Text {
+            text: "x".to_string(),
+            style_name: None,
+            marks,
+        }
+    }
+
+    #[test]
+    fn identical_formatting_shares_one_style() {
+        let blocks = vec![
+            Block::Paragraph {
+                style_name: Some("Standard".to_string()),
+                attrs: Some(BlockAttrs {
+                    text_align: Some("center".to_string()),
+                    indent: None,
+                    ..BlockAttrs::default()
+                }),
+                content: vec![
+                    text(vec![TiptapMark::Bold, TiptapMark::Italic]),
+                    text(vec![TiptapMark::Italic, TiptapMark::Bold]),
+                ],
+            },
+            Block::Paragraph {
+                style_name: Some("Standard".to_string()),
+                attrs: Some(BlockAttrs {
+                    text_align: Some("center".to_string()),
+                    indent: Some(0),
+                    ..BlockAttrs::default()
+                }),
+                content: vec![text(vec![TiptapMark::Underline])],
+            },
+        ];
+        let styles = AutoStyles::collect(&blocks, &["T1"]);
+        assert_eq!(styles.paragraph.len(), 1);
+        assert_eq!(styles.text.len(), 2);
+        assert_eq!(
+            styles.text_style(None, &[TiptapMark::Italic, TiptapMark::Bold]),
+            Some("T2")
+        );
+        assert_eq!(styles.text_style(None, &[]), None);
+    }
+
+    #[test]
+    fn prefixed_names_are_recognized() {
+        let blocks = vec![Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![text(vec![TiptapMark::Bold])],
+        }];
+        let styles = AutoStyles::collect_prefixed(&blocks, "M", &["MT1"]);
+        assert_eq!(styles.text_style(None, &[TiptapMark::Bold]), Some("MT2"));
+        assert!(AutoStyles::is_generated_name("MT2", "M"));
+        assert!(AutoStyles::is_generated_name("MSect10", "M"));
+        assert!(!AutoStyles::is_generated_name("MT", "M"));
+        assert!(!AutoStyles::is_generated_name("P1", "M"));
+        assert!(!AutoStyles::is_generated_name("MPx", "M"));
+    }
+
+    #[test]
+    fn text_style_writes_colour_and_font() {
+        use common_core::colour_management::Colour;
+        use quick_xml::Writer;
+        use std::io::Cursor;
+
+        let mark = TiptapMark::TextStyle {
+            attrs: TextStyleAttrs {
+                colour: Some(Colour::from_u8_rgb(255, 0, 0)),
+                background_colour: Some(Colour::from_u8_rgb(255, 255, 0)),
+                font_family: Some("Liberation Sans".to_string()),
+                font_size: Some(14.0),
+            },
+        };
+        let blocks = vec![Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![text(vec![mark])],
+        }];
+        let mut writer = Writer::new(Cursor::new(Vec::new()));
+        AutoStyles::collect(&blocks, &[])
+            .write(&mut writer)
+            .unwrap();
+        let xml = String::from_utf8(writer.into_inner().into_inner()).unwrap();
+        assert!(xml.contains(r##"fo:color="#ff0000""##));
+        assert!(xml.contains(r##"fo:background-color="#ffff00""##));
+        assert!(xml.contains(r#"fo:font-family="Liberation Sans""#));
+        assert!(xml.contains(r#"fo:font-size="14pt""#));
+    }
+}
@@ -4,52 +4,121 @@
 //! the document's block tree. Inline content is delegated to
 //! [`super::inlines`].
 
-use common_core::{Block, Inline};
+use common_core::{Block, BlockAttrs, Inline, ListAttrs};
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 
+use super::auto_styles::AutoStyles;
+use super::images::write_image;
+use super::lists::{list_item_start_tag, list_start_tag};
+use super::sections::write_section;
+use super::tables::{write_cell, write_lone_row, write_table};
+use super::toc::write_toc;
+
 pub use super::inlines::{write_inlines_with_marks, write_inlines_with_style, XmlWriter};
 
 /// Writes a slice of blocks as ODF XML.
 ///
 /// Handles all block types: paragraphs, headings, lists, tables, images,
-/// page breaks, etc.
+/// page breaks, etc. Direct formatting is written as references to the
+/// automatic styles in `autos`.
 ///
 /// # Errors
 ///
 /// Returns a `String` error if any XML writing operation fails.
-pub fn write_blocks(blocks: &[Block], writer: &mut XmlWriter) -> Result<(), String> {
+pub fn write_blocks(
+    blocks: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
     for block in blocks {
-        write_single_block(block, writer)?;
+        write_single_block(block, autos, writer)?;
     }
     Ok(())
 }
 
-fn write_single_block(block: &Block, writer: &mut XmlWriter) -> Result<(), String> {
+fn write_single_block(
+    block: &Block,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
     match block {
         Block::Paragraph {
             style_name,
+            attrs,
+            content,
+        } => write_paragraph(
+            style_name.as_deref(),
+            attrs.as_ref(),
             content,
-            ..
-        } => write_paragraph(style_name.as_deref(), content, writer),
+            autos,
+            writer,
+        ),
         Block::Heading {
             level,
             style_name,
+            attrs,
             content,
-            ..
-        } => write_heading(*level, style_name.as_deref(), content, writer),
+        } => write_heading(
+            *level,
+            style_name.as_deref(),
+            attrs.as_ref(),
+            content,
+            autos,
+            writer,
+        ),
         Block::PageBreak => write_page_break(writer),
-        Block::BulletList { content } | Block::OrderedList { content } => {
-            write_list(content, writer)
+        Block::BulletList { attrs, content } => {
+            write_list(false, attrs.as_ref(), content, autos, writer)
+        }
+        Block::OrderedList { attrs, content } => {
+            write_list(true, attrs.as_ref(), content, autos, writer)
+        }
+        Block::ListItem { content } => write_list_item(content, autos, writer),
+        Block::Table { content } => write_table(content, autos, writer, write_blocks),
+        Block::TableRow { content } => write_lone_row(content, autos, writer, write_blocks),
+        Block::TableCell { attrs, content } | Block::TableHeader { attrs, content } => {
+            write_cell(attrs.as_ref(), content, autos, writer, write_blocks)
         }
-        Block::ListItem { content } => write_list_item(content, writer),
-        Block::Table { content } => write_table(content, writer),
-        Block::TableRow { content } => write_table_row(content, writer),
-        Block::TableCell { content, .. } => write_table_cell("table:table-cell", content, writer),
-        Block::TableHeader { content, .. } => {
-            write_table_cell("table:table-header-cell", content, writer)
+        Block::Image {
+            src,
+            alt,
+            title,
+            frame,
+            caption,
+        } => {
+            // ODF stores images inside a text:p paragraph wrapper.
+            writer
+                .write_event(Event::Start(BytesStart::new("text:p")))
+                .map_err(|e| e.to_string())?;
+            write_image(
+                src,
+                alt.as_deref(),
+                title.as_deref(),
+                frame.as_ref(),
+                caption.as_deref(),
+                autos,
+                writer,
+            )?;
+            writer
+                .write_event(Event::End(BytesEnd::new("text:p")))
+                .map_err(|e| e.to_string())
         }
-        Block::Image { src, .. } => write_image(src, writer),
-        Block::Blockquote { content } => write_blocks(content, writer),
+        Block::Blockquote { content } => write_blocks(content, autos, writer),
+        Block::Section {
+            name,
+            attrs,
+            protected,
+            content,
+        } => write_section(
+            name,
+            attrs.as_ref(),
+            *protected,
+            content,
+            autos,
+            writer,
+            write_blocks,
+        ),
+        Block::TableOfContents(toc) => write_toc(toc, writer),
         Block::HorizontalRule => writer
             .write_event(Event::Empty(BytesStart::new("text:p")))
             .map_err(|e| e.to_string()),
@@ -58,17 +127,19 @@ fn write_single_block(block: &Block, writer: &mut XmlWriter) -> Result<(), Strin
 
 fn write_paragraph(
     style_name: Option<&str>,
+    attrs: Option<&BlockAttrs>,
     content: &[Inline],
+    autos: &AutoStyles,
     writer: &mut XmlWriter,
 ) -> Result<(), String> {
     let mut p = BytesStart::new("text:p");
-    if let Some(s) = style_name {
+    if let Some(s) = autos.paragraph_style(style_name, attrs).or(style_name) {
         p.push_attribute(("text:style-name", s));
     }
     writer
         .write_event(Event::Start(p))
         .map_err(|e| e.to_string())?;
-    write_inlines_with_style(content, writer)?;
+    write_inlines_with_style(content, autos, writer)?;
     writer
         .write_event(Event::End(BytesEnd::new("text:p")))
         .map_err(|e| e.to_string())
@@ -77,18 +148,20 @@ fn write_paragraph(
 fn write_heading(
     level: u32,
     style_name: Option<&str>,
+    attrs: Option<&BlockAttrs>,
     content: &[Inline],
+    autos: &AutoStyles,
     writer: &mut XmlWriter,
 ) -> Result<(), String> {
     let mut h = BytesStart::new("text:h");
-    if let Some(s) = style_name {
+    if let Some(s) = autos.paragraph_style(style_name, attrs).or(style_name) {
         h.push_attribute(("text:style-name", s));
     }
     h.push_attribute(("text:outline-level", level.to_string().as_str()));
     writer
         .write_event(Event::Start(h))
         .map_err(|e| e.to_string())?;
-    write_inlines_with_style(content, writer)?;
+    write_inlines_with_style(content, autos, writer)?;
     writer
         .write_event(Event::End(BytesEnd::new("text:h")))
         .map_err(|e| e.to_string())
@@ -102,77 +175,49 @@ fn write_page_break(writer: &mut XmlWriter) -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
-fn write_list(items: &[Block], writer: &mut XmlWriter) -> Result<(), String> {
+fn write_list(
+    ordered: bool,
+    attrs: Option<&ListAttrs>,
+    items: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
     writer
-        .write_event(Event::Start(BytesStart::new("text:list")))
+        .write_event(Event::Start(list_start_tag(ordered, attrs)))
         .map_err(|e| e.to_string())?;
-    for item in items {
-        write_single_block(item, writer)?;
+    let start = attrs.and_then(|a| a.start);
+    for (i, item) in items.iter().enumerate() {
+        match item {
+            Block::ListItem { content } if i == 0 && start.is_some() => {
+                write_list_item_tag(list_item_start_tag(start), content, autos, writer)?
+            }
+            _ => write_single_block(item, autos, writer)?,
+        }
     }
     writer
         .write_event(Event::End(BytesEnd::new("text:list")))
         .map_err(|e| e.to_string())
 }
 
-fn write_list_item(content: &[Block], writer: &mut XmlWriter) -> Result<(), String> {
-    writer
-        .write_event(Event::Start(BytesStart::new("text:list-item")))
-        .map_err(|e| e.to_string())?;
-    write_blocks(content, writer)?;
-    writer
-        .write_event(Event::End(BytesEnd::new("text:list-item")))
-        .map_err(|e| e.to_string())
-}
-
-fn write_table(content: &[Block], writer: &mut XmlWriter) -> Result<(), String> {
-    writer
-        .write_event(Event::Start(BytesStart::new("table:table")))
-        .map_err(|e| e.to_string())?;
-    write_blocks(content, writer)?;
-    writer
-        .write_event(Event::End(BytesEnd::new("table:table")))
-        .map_err(|e| e.to_string())
-}
-
-fn write_table_row(content: &[Block], writer: &mut XmlWriter) -> Result<(), String> {
-    writer
-        .write_event(Event::Start(BytesStart::new("table:table-row")))
-        .map_err(|e| e.to_string())?;
-    write_blocks(content, writer)?;
-    writer
-        .write_event(Event::End(BytesEnd::new("table:table-row")))
-        .map_err(|e| e.to_string())
-}
-
-fn write_table_cell(
-    tag: &'static str,
+fn write_list_item(
     content: &[Block],
+    autos: &AutoStyles,
     writer: &mut XmlWriter,
 ) -> Result<(), String> {
-    writer
-        .write_event(Event::Start(BytesStart::new(tag)))
-        .map_err(|e| e.to_string())?;
-    write_blocks(content, writer)?;
-    writer
-        .write_event(Event::End(BytesEnd::new(tag)))
-        .map_err(|e| e.to_string())
+    write_list_item_tag(list_item_start_tag(None), content, autos, writer)
 }
 
-pub fn write_image(src: &str, writer: &mut XmlWriter) -> Result<(), String> {
-    let mut frame = BytesStart::new("draw:frame");
-    frame.push_attribute(("draw:name", "Image"));
-    writer
-        .write_event(Event::Start(frame))
-        .map_err(|e| e.to_string())?;
-    let mut img = BytesStart::new("draw:image");
-    img.push_attribute(("xlink:href", src));
-    img.push_attribute(("xlink:type", "simple"));
-    img.push_attribute(("xlink:show", "embed"));
-    img.push_attribute(("xlink:actuate", "onLoad"));
+fn write_list_item_tag(
+    tag: BytesStart<'static>,
+    content: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
     writer
-        .write_event(Event::Empty(img))
+        .write_event(Event::Start(tag))
         .map_err(|e| e.to_string())?;
+    write_blocks(content, autos, writer)?;
     writer
-        .write_event(Event::End(BytesEnd::new("draw:frame")))
+        .write_event(Event::End(BytesEnd::new("text:list-item")))
         .map_err(|e| e.to_string())
 }
@@ -0,0 +1,91 @@
+//! ODT tracked-changes writer.
+//!
+//! Writes the `text:tracked-changes` section at the start of `office:text`,
+//! with one `text:changed-region` for each change marker in the document.
+//! The markers themselves are written by the inline writers.
+
+use common_core::field::now;
+use common_core::{Block, ChangeKind};
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+
+use super::auto_styles::AutoStyles;
+use super::inlines::XmlWriter;
+use crate::changes::tracked_changes;
+
+/// Writes `text:tracked-changes` for the changes in `blocks`, if any, or
+/// an empty one to record that `track_changes` is on.
+///
+/// `write_body` writes the removed content of deletions. ODF requires a
+/// date on every change, so changes without one are dated now.
+/// `text:track-changes` defaults to `true`, so it is always written.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn write_tracked_changes(
+    blocks: &[Block],
+    track_changes: bool,
+    write_body: fn(&[Block], &AutoStyles, &mut XmlWriter) -> Result<(), String>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let changes = tracked_changes(blocks);
+    if changes.is_empty() && !track_changes {
+        return Ok(());
+    }
+    let mut section = BytesStart::new("text:tracked-changes");
+    section.push_attribute((
+        "text:track-changes",
+        if track_changes { "true" } else { "false" },
+    ));
+    writer
+        .write_event(Event::Start(section))
+        .map_err(|e| e.to_string())?;
+    for change in changes {
+        let mut region = BytesStart::new("text:changed-region");
+        region.push_attribute(("xml:id", change.id.as_str()));
+        region.push_attribute(("text:id", change.id.as_str()));
+        writer
+            .write_event(Event::Start(region))
+            .map_err(|e| e.to_string())?;
+        let element = format!("text:{}", change.kind.to_odf_str());
+        writer
+            .write_event(Event::Start(BytesStart::new(element.as_str())))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_event(Event::Start(BytesStart::new("office:change-info")))
+            .map_err(|e| e.to_string())?;
+        let date = change.date.clone().unwrap_or_else(now);
+        for (element, text) in [
+            ("dc:creator", change.author.as_ref()),
+            ("dc:date", Some(&date)),
+        ] {
+            if let Some(text) = text {
+                writer
+                    .write_event(Event::Start(BytesStart::new(element)))
+                    .map_err(|e| e.to_string())?;
+                writer
+                    .write_event(Event::Text(BytesText::new(text)))
+                    .map_err(|e| e.to_string())?;
+                writer
+                    .write_event(Event::End(BytesEnd::new(element)))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("office:change-info")))
+            .map_err(|e| e.to_string())?;
+        if change.kind == ChangeKind::Deletion {
+            write_body(&change.content, autos, writer)?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new(element.as_str())))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_event(Event::End(BytesEnd::new("text:changed-region")))
+            .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("text:tracked-changes")))
+        .map_err(|e| e.to_string())
+}
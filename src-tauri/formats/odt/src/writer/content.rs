@@ -10,24 +10,32 @@ use quick_xml::Writer;
 
 use common_core::{Block, Inline};
 
-use crate::writer::blocks::write_image;
+use crate::writer::auto_styles::AutoStyles;
+use crate::writer::changes::write_tracked_changes;
+use crate::writer::images::write_image;
 use crate::writer::inlines::write_inlines_with_marks;
+use crate::writer::lists::{list_item_start_tag, list_start_tag, write_list_styles};
 use crate::writer::namespaces::push_content_ns;
+use crate::writer::sections::write_section;
+use crate::writer::tables::{write_cell, write_lone_row, write_table};
+use crate::writer::toc::write_toc;
 
 /// Generates the `content.xml` string for a ZIP-format ODT file.
 ///
-/// This output contains only the document body (`office:body` /
-/// `office:text`) and an empty `office:automatic-styles` placeholder.
-/// Named styles live in `styles.xml`.
+/// This output contains the document body (`office:body` / `office:text`)
+/// and an `office:automatic-styles` section with the automatic paragraph,
+/// text and table styles for direct formatting and the list styles used by
+/// imported lists. Named styles live in `styles.xml`.
 ///
 /// # Arguments
 ///
 /// * `blocks` - The document's block content.
+/// * `track_changes` - Whether changes are being recorded.
 ///
 /// # Errors
 ///
 /// Returns a `String` error if XML writing fails.
-pub fn to_content_xml(blocks: &[Block]) -> Result<String, String> {
+pub fn to_content_xml(blocks: &[Block], track_changes: bool) -> Result<String, String> {
     let mut writer = Writer::new(Cursor::new(Vec::new()));
     writer
         .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
@@ -39,10 +47,14 @@ pub fn to_content_xml(blocks: &[Block]) -> Result<String, String> {
         .write_event(Event::Start(document))
         .map_err(|e| e.to_string())?;
 
-    // Empty automatic-styles — named styles are in styles.xml
+    // Automatic styles hold direct formatting and the imported list styles;
+    // named styles are in styles.xml
+    let autos = AutoStyles::collect(blocks, &[]);
     writer
         .write_event(Event::Start(BytesStart::new("office:automatic-styles")))
         .map_err(|e| e.to_string())?;
+    autos.write(&mut writer)?;
+    write_list_styles(blocks, &[], &mut writer)?;
     writer
         .write_event(Event::End(BytesEnd::new("office:automatic-styles")))
         .map_err(|e| e.to_string())?;
@@ -54,7 +66,14 @@ pub fn to_content_xml(blocks: &[Block]) -> Result<String, String> {
         .write_event(Event::Start(BytesStart::new("office:text")))
         .map_err(|e| e.to_string())?;
 
-    write_blocks_content(blocks, &mut writer)?;
+    write_tracked_changes(
+        blocks,
+        track_changes,
+        write_blocks_content,
+        &autos,
+        &mut writer,
+    )?;
+    write_blocks_content(blocks, &autos, &mut writer)?;
 
     writer
         .write_event(Event::End(BytesEnd::new("office:text")))
@@ -74,31 +93,40 @@ pub fn to_content_xml(blocks: &[Block]) -> Result<String, String> {
 ///
 /// Unlike the FODT writer, `content.xml` uses mark-based spans rather than
 /// style-name spans for inline content.
-fn write_blocks_content(
+pub(crate) fn write_blocks_content(
     blocks: &[Block],
+    autos: &AutoStyles,
     writer: &mut Writer<Cursor<Vec<u8>>>,
 ) -> Result<(), String> {
     for block in blocks {
-        write_block_content(block, writer)?;
+        write_block_content(block, autos, writer)?;
     }
     Ok(())
 }
 
-fn write_block_content(block: &Block, writer: &mut Writer<Cursor<Vec<u8>>>) -> Result<(), String> {
+fn write_block_content(
+    block: &Block,
+    autos: &AutoStyles,
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+) -> Result<(), String> {
     match block {
         Block::Paragraph {
             style_name,
+            attrs,
             content,
-            ..
         } => {
             let mut p = BytesStart::new("text:p");
-            if let Some(s) = style_name {
-                p.push_attribute(("text:style-name", s.as_str()));
+            let style_name = style_name.as_deref();
+            if let Some(s) = autos
+                .paragraph_style(style_name, attrs.as_ref())
+                .or(style_name)
+            {
+                p.push_attribute(("text:style-name", s));
             }
             writer
                 .write_event(Event::Start(p))
                 .map_err(|e| e.to_string())?;
-            write_inlines_content(content, writer)?;
+            write_inlines_content(content, autos, writer)?;
             writer
                 .write_event(Event::End(BytesEnd::new("text:p")))
                 .map_err(|e| e.to_string())?;
@@ -106,18 +134,22 @@ fn write_block_content(block: &Block, writer: &mut Writer<Cursor<Vec<u8>>>) -> R
         Block::Heading {
             level,
             style_name,
+            attrs,
             content,
-            ..
         } => {
             let mut h = BytesStart::new("text:h");
-            if let Some(s) = style_name {
-                h.push_attribute(("text:style-name", s.as_str()));
+            let style_name = style_name.as_deref();
+            if let Some(s) = autos
+                .paragraph_style(style_name, attrs.as_ref())
+                .or(style_name)
+            {
+                h.push_attribute(("text:style-name", s));
             }
             h.push_attribute(("text:outline-level", level.to_string().as_str()));
             writer
                 .write_event(Event::Start(h))
                 .map_err(|e| e.to_string())?;
-            write_inlines_content(content, writer)?;
+            write_inlines_content(content, autos, writer)?;
             writer
                 .write_event(Event::End(BytesEnd::new("text:h")))
                 .map_err(|e| e.to_string())?;
@@ -129,12 +161,25 @@ fn write_block_content(block: &Block, writer: &mut Writer<Cursor<Vec<u8>>>) -> R
                 .write_event(Event::Empty(p))
                 .map_err(|e| e.to_string())?;
         }
-        Block::BulletList { content } | Block::OrderedList { content } => {
+        Block::BulletList { attrs, content } | Block::OrderedList { attrs, content } => {
+            let ordered = matches!(block, Block::OrderedList { .. });
             writer
-                .write_event(Event::Start(BytesStart::new("text:list")))
+                .write_event(Event::Start(list_start_tag(ordered, attrs.as_ref())))
                 .map_err(|e| e.to_string())?;
-            for item in content {
-                write_block_content(item, writer)?;
+            let start = attrs.as_ref().and_then(|a| a.start);
+            for (i, item) in content.iter().enumerate() {
+                match item {
+                    Block::ListItem { content } if i == 0 && start.is_some() => {
+                        writer
+                            .write_event(Event::Start(list_item_start_tag(start)))
+                            .map_err(|e| e.to_string())?;
+                        write_blocks_content(content, autos, writer)?;
+                        writer
+                            .write_event(Event::End(BytesEnd::new("text:list-item")))
+                            .map_err(|e| e.to_string())?;
+                    }
+                    _ => write_block_content(item, autos, writer)?,
+                }
             }
             writer
                 .write_event(Event::End(BytesEnd::new("text:list")))
@@ -144,68 +189,59 @@ fn write_block_content(block: &Block, writer: &mut Writer<Cursor<Vec<u8>>>) -> R
             writer
                 .write_event(Event::Start(BytesStart::new("text:list-item")))
                 .map_err(|e| e.to_string())?;
-            write_blocks_content(content, writer)?;
+            write_blocks_content(content, autos, writer)?;
             writer
                 .write_event(Event::End(BytesEnd::new("text:list-item")))
                 .map_err(|e| e.to_string())?;
         }
-        Block::Table { content } => {
-            writer
-                .write_event(Event::Start(BytesStart::new("table:table")))
-                .map_err(|e| e.to_string())?;
-            write_blocks_content(content, writer)?;
-            writer
-                .write_event(Event::End(BytesEnd::new("table:table")))
-                .map_err(|e| e.to_string())?;
-        }
+        Block::Table { content } => write_table(content, autos, writer, write_blocks_content)?,
         Block::TableRow { content } => {
-            writer
-                .write_event(Event::Start(BytesStart::new("table:table-row")))
-                .map_err(|e| e.to_string())?;
-            write_blocks_content(content, writer)?;
-            writer
-                .write_event(Event::End(BytesEnd::new("table:table-row")))
-                .map_err(|e| e.to_string())?;
+            write_lone_row(content, autos, writer, write_blocks_content)?
         }
-        Block::TableCell { attrs, content } => {
-            let mut cell = BytesStart::new("table:table-cell");
-            if let Some(a) = attrs {
-                if let Some(n) = a.colspan.filter(|&v| v > 1) {
-                    cell.push_attribute(("table:number-columns-spanned", n.to_string().as_str()));
-                }
-                if let Some(n) = a.rowspan.filter(|&v| v > 1) {
-                    cell.push_attribute(("table:number-rows-spanned", n.to_string().as_str()));
-                }
-            }
-            writer
-                .write_event(Event::Start(cell))
-                .map_err(|e| e.to_string())?;
-            write_blocks_content(content, writer)?;
-            writer
-                .write_event(Event::End(BytesEnd::new("table:table-cell")))
-                .map_err(|e| e.to_string())?;
+        Block::TableCell { attrs, content } | Block::TableHeader { attrs, content } => {
+            write_cell(attrs.as_ref(), content, autos, writer, write_blocks_content)?
         }
-        Block::TableHeader { content, .. } => {
-            writer
-                .write_event(Event::Start(BytesStart::new("table:table-cell")))
-                .map_err(|e| e.to_string())?;
-            write_blocks_content(content, writer)?;
-            writer
-                .write_event(Event::End(BytesEnd::new("table:table-cell")))
-                .map_err(|e| e.to_string())?;
-        }
-        Block::Blockquote { content } => write_blocks_content(content, writer)?,
+        Block::Blockquote { content } => write_blocks_content(content, autos, writer)?,
+        Block::Section {
+            name,
+            attrs,
+            protected,
+            content,
+        } => write_section(
+            name,
+            attrs.as_ref(),
+            *protected,
+            content,
+            autos,
+            writer,
+            write_blocks_content,
+        )?,
+        Block::TableOfContents(toc) => write_toc(toc, writer)?,
         Block::HorizontalRule => {
             writer
                 .write_event(Event::Empty(BytesStart::new("text:p")))
                 .map_err(|e| e.to_string())?;
         }
-        Block::Image { src, .. } => {
+        Block::Image {
+            src,
+            alt,
+            title,
+            frame,
+            caption,
+        } => {
             // ODF stores images inside a text:p paragraph wrapper.
             writer
                 .write_event(Event::Start(BytesStart::new("text:p")))
                 .map_err(|e| e.to_string())?;
-            write_image(src, writer)?;
+            write_image(
+                src,
+                alt.as_deref(),
+                title.as_deref(),
+                frame.as_ref(),
+                caption.as_deref(),
+                autos,
+                writer,
+            )?;
             writer
                 .write_event(Event::End(BytesEnd::new("text:p")))
                 .map_err(|e| e.to_string())?;
@@ -217,7 +253,8 @@ fn write_block_content(block: &Block, writer: &mut Writer<Cursor<Vec<u8>>>) -> R
 /// Writes inlines for content.xml using marks (not style names).
 fn write_inlines_content(
     inlines: &[Inline],
+    autos: &AutoStyles,
     writer: &mut Writer<Cursor<Vec<u8>>>,
 ) -> Result<(), String> {
-    write_inlines_with_marks(inlines, writer)
+    write_inlines_with_marks(inlines, autos, writer)
 }
@@ -3,18 +3,22 @@
 //! Provides [`to_xml`] to generate a complete single-file FODT document, and
 //! [`update_fodt`] to update the body, meta, and styles sections of an
 //! existing FODT file while preserving all other content verbatim.
+//! [`update_styles`] updates only the styles section of a `styles.xml`.
 
 use std::io::Cursor;
 
-use common_core::StyleDefinition;
 use common_core::{Block, Metadata};
+use common_core::{OutlineStyle, StyleDefinition, StyleFamily};
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::{Reader, Writer};
 use std::collections::HashMap;
 
+use crate::writer::auto_styles::AutoStyles;
 use crate::writer::blocks::write_blocks;
+use crate::writer::changes::write_tracked_changes;
+use crate::writer::lists::write_list_styles;
 use crate::writer::meta::write_meta_elements;
-use crate::writer::namespaces::push_fodt_ns;
+use crate::writer::namespaces::{push_fodt_ns, with_extension_ns};
 use crate::writer::styles_writer::write_styles_section;
 
 /// Generates a complete FODT (flat XML ODT) document string.
@@ -25,9 +29,12 @@ use crate::writer::styles_writer::write_styles_section;
 /// # Errors
 ///
 /// Returns a `String` error if XML writing fails.
+#[allow(clippy::too_many_arguments)]
 pub fn to_xml(
     blocks: &[Block],
+    track_changes: bool,
     styles: &HashMap<String, StyleDefinition>,
+    outline_style: Option<&OutlineStyle>,
     metadata: &Metadata,
     font_face_decls: &Option<String>,
     automatic_styles: &Option<String>,
@@ -57,10 +64,10 @@ pub fn to_xml(
     write_preserved(&mut writer, font_face_decls)?;
 
     // Write <office:styles>
-    write_styles_section(&mut writer, styles)?;
+    write_styles_section(&mut writer, styles, outline_style)?;
 
-    // Write preserved <office:automatic-styles>
-    write_preserved_or_empty(&mut writer, automatic_styles, "office:automatic-styles")?;
+    // Write preserved <office:automatic-styles> plus the generated styles
+    let autos = write_automatic_styles(&mut writer, automatic_styles, blocks, styles)?;
 
     // Write preserved <office:master-styles>
     write_preserved(&mut writer, master_styles)?;
@@ -73,7 +80,8 @@ pub fn to_xml(
         .write_event(Event::Start(BytesStart::new("office:text")))
         .map_err(|e| e.to_string())?;
 
-    write_blocks(blocks, &mut writer)?;
+    write_tracked_changes(blocks, track_changes, write_blocks, &autos, &mut writer)?;
+    write_blocks(blocks, &autos, &mut writer)?;
 
     writer
         .write_event(Event::End(BytesEnd::new("office:text")))
@@ -95,6 +103,11 @@ pub fn to_xml(
 /// `office:styles` content with freshly generated content while preserving
 /// all other XML verbatim.
 ///
+/// In `office:automatic-styles`, the paragraph, text, table column, table
+/// cell, graphic and list styles are replaced by those of `content_xml`; styles
+/// referenced from `office:master-styles` (headers and footers) and all
+/// other families are kept.
+///
 /// # Errors
 ///
 /// Returns a `String` error if XML parsing or writing fails.
@@ -112,9 +125,80 @@ pub fn update_fodt(
     let mut buf = Vec::new();
     let mut skip_depth = 0;
     let mut in_styles = false;
+    let keep = master_style_references(old_xml);
+    let mut in_auto = false;
+    let mut seen_auto = false;
+    let mut drop_depth = 0;
+    let mut seen_root = false;
 
     loop {
         match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+
+            // The root may predate extension attributes the writers now use.
+            Ok(Event::Start(ref e)) if !seen_root => {
+                writer
+                    .write_event(Event::Start(with_extension_ns(e)))
+                    .map_err(|err| err.to_string())?;
+                seen_root = true;
+            }
+
+            // Regenerated automatic styles are dropped from the old section.
+            Ok(Event::Start(_)) if drop_depth > 0 => drop_depth += 1,
+            Ok(Event::End(_)) if drop_depth > 0 => drop_depth -= 1,
+            Ok(_) if drop_depth > 0 => {}
+            Ok(Event::Start(ref e)) if in_auto && is_regenerated_style(e, &keep) => {
+                drop_depth = 1;
+            }
+            Ok(Event::Empty(ref e)) if in_auto && is_regenerated_style(e, &keep) => {}
+            Ok(Event::Start(ref e))
+                if e.name().as_ref() == b"office:automatic-styles" && skip_depth == 0 =>
+            {
+                writer
+                    .write_event(Event::Start(e.clone()))
+                    .map_err(|err| err.to_string())?;
+                in_auto = true;
+                seen_auto = true;
+            }
+            Ok(Event::End(ref e)) if in_auto && e.name().as_ref() == b"office:automatic-styles" => {
+                inject_automatic_styles(&mut writer, content_xml, styles)?;
+                writer
+                    .write_event(Event::End(e.clone()))
+                    .map_err(|err| err.to_string())?;
+                in_auto = false;
+            }
+            Ok(Event::Empty(ref e))
+                if e.name().as_ref() == b"office:automatic-styles" && skip_depth == 0 =>
+            {
+                writer
+                    .write_event(Event::Start(e.clone()))
+                    .map_err(|err| err.to_string())?;
+                inject_automatic_styles(&mut writer, content_xml, styles)?;
+                writer
+                    .write_event(Event::End(BytesEnd::new("office:automatic-styles")))
+                    .map_err(|err| err.to_string())?;
+                seen_auto = true;
+            }
+            // A file without automatic styles gets a section before the
+            // master styles or the body, whichever comes first.
+            Ok(Event::Start(ref e))
+                if !seen_auto
+                    && skip_depth == 0
+                    && matches!(e.name().as_ref(), b"office:master-styles" | b"office:body") =>
+            {
+                writer
+                    .write_event(Event::Start(BytesStart::new("office:automatic-styles")))
+                    .map_err(|err| err.to_string())?;
+                inject_automatic_styles(&mut writer, content_xml, styles)?;
+                writer
+                    .write_event(Event::End(BytesEnd::new("office:automatic-styles")))
+                    .map_err(|err| err.to_string())?;
+                writer
+                    .write_event(Event::Start(e.clone()))
+                    .map_err(|err| err.to_string())?;
+                seen_auto = true;
+            }
+
             Ok(Event::Start(ref e)) if e.name().as_ref() == b"office:text" && skip_depth == 0 => {
                 writer
                     .write_event(Event::Start(e.clone()))
@@ -193,7 +277,6 @@ pub fn update_fodt(
                     in_styles = false;
                 }
             }
-            Ok(Event::Eof) => break,
             Ok(e) => {
                 if skip_depth == 0 {
                     writer.write_event(e).map_err(|err| err.to_string())?;
@@ -209,6 +292,162 @@ pub fn update_fodt(
     String::from_utf8(result).map_err(|e| e.to_string())
 }
 
+/// Updates the `office:styles` section of an existing `styles.xml`.
+///
+/// Everything else, including the automatic styles used by the page layouts
+/// and the headers and footers, is kept verbatim: the automatic styles of the
+/// content belong in `content.xml`.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML parsing or writing fails.
+pub fn update_styles(old_xml: &str, styles_xml: &str) -> Result<String, String> {
+    let mut reader = Reader::from_str(old_xml);
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let mut buf = Vec::new();
+    let mut skip_depth = 0;
+    let mut seen_root = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(ref e)) if !seen_root => {
+                writer
+                    .write_event(Event::Start(with_extension_ns(e)))
+                    .map_err(|err| err.to_string())?;
+                seen_root = true;
+            }
+            Ok(Event::Start(_)) if skip_depth > 0 => skip_depth += 1,
+            Ok(Event::End(e)) if skip_depth > 0 => {
+                skip_depth -= 1;
+                if skip_depth == 0 {
+                    writer
+                        .write_event(Event::End(e))
+                        .map_err(|err| err.to_string())?;
+                }
+            }
+            Ok(_) if skip_depth > 0 => {}
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"office:styles" => {
+                writer
+                    .write_event(Event::Start(e.clone()))
+                    .map_err(|err| err.to_string())?;
+                inject_inner_xml(
+                    &mut writer,
+                    styles_xml,
+                    "<office:styles>",
+                    "</office:styles>",
+                )?;
+                skip_depth = 1;
+            }
+            Ok(Event::Empty(ref e)) if e.name().as_ref() == b"office:styles" => {
+                writer
+                    .write_event(Event::Start(e.clone()))
+                    .map_err(|err| err.to_string())?;
+                inject_inner_xml(
+                    &mut writer,
+                    styles_xml,
+                    "<office:styles>",
+                    "</office:styles>",
+                )?;
+                writer
+                    .write_event(Event::End(BytesEnd::new("office:styles")))
+                    .map_err(|err| err.to_string())?;
+            }
+            Ok(event) => writer.write_event(event).map_err(|err| err.to_string())?,
+            Err(e) => return Err(e.to_string()),
+        }
+        buf.clear();
+    }
+
+    let result = writer.into_inner().into_inner();
+    String::from_utf8(result).map_err(|e| e.to_string())
+}
+
+/// Streams the automatic styles generated for `content.xml`.
+///
+/// List styles that `styles` defines as named list styles are left out:
+/// they are written to `office:styles` instead.
+fn inject_automatic_styles(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    content_xml: &str,
+    styles: &HashMap<String, StyleDefinition>,
+) -> Result<(), String> {
+    const OPEN: &str = "<office:automatic-styles>";
+    const CLOSE: &str = "</office:automatic-styles>";
+    let (Some(start), Some(end)) = (content_xml.find(OPEN), content_xml.rfind(CLOSE)) else {
+        return Ok(());
+    };
+    let mut reader = Reader::from_str(&content_xml[start + OPEN.len()..end]);
+    let mut buf = Vec::new();
+    let mut drop_depth = 0;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(_)) if drop_depth > 0 => drop_depth += 1,
+            Ok(Event::End(_)) if drop_depth > 0 => drop_depth -= 1,
+            Ok(_) if drop_depth > 0 => {}
+            Ok(Event::Start(ref e)) if is_named_list_style(e, styles) => drop_depth = 1,
+            Ok(Event::Empty(ref e)) if is_named_list_style(e, styles) => {}
+            Ok(event) => writer.write_event(event).map_err(|e| e.to_string())?,
+            Err(e) => return Err(e.to_string()),
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+/// Whether `e` starts a `text:list-style` that `styles` defines as a named
+/// list style.
+fn is_named_list_style(e: &BytesStart, styles: &HashMap<String, StyleDefinition>) -> bool {
+    e.name().as_ref() == b"text:list-style"
+        && e.attributes()
+            .filter_map(|a| a.ok())
+            .find(|a| a.key.as_ref() == b"style:name")
+            .and_then(|a| a.unescape_value().ok())
+            .is_some_and(|name| {
+                styles
+                    .get(name.as_ref())
+                    .is_some_and(|s| s.family == StyleFamily::List)
+            })
+}
+
+/// Returns the style names referenced from `office:master-styles`.
+fn master_style_references(xml: &str) -> Vec<String> {
+    let Some(start) = xml.find("<office:master-styles") else {
+        return Vec::new();
+    };
+    let end = xml[start..]
+        .find("</office:master-styles>")
+        .map_or(xml.len(), |i| start + i);
+    xml[start..end]
+        .split("style-name=\"")
+        .skip(1)
+        .filter_map(|s| s.split('"').next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether an automatic style is regenerated from the document model: a
+/// paragraph, text, table column, table cell or graphic style, or a list
+/// style, not used by master pages.
+fn is_regenerated_style(e: &BytesStart, keep: &[String]) -> bool {
+    let attr = |key: &[u8]| {
+        e.attributes()
+            .filter_map(|a| a.ok())
+            .find(|a| a.key.as_ref() == key)
+            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+    };
+    let regenerated = match e.name().as_ref() {
+        b"text:list-style" => true,
+        b"style:style" => matches!(
+            attr(b"style:family").as_deref(),
+            Some("paragraph" | "text" | "table-column" | "table-cell" | "graphic")
+        ),
+        _ => false,
+    };
+    regenerated && attr(b"style:name").is_none_or(|name| !keep.contains(&name))
+}
+
 /// Extracts the inner XML between `open_tag` and `close_tag` and streams it.
 fn inject_inner_xml(
     writer: &mut Writer<Cursor<Vec<u8>>>,
@@ -247,23 +486,96 @@ fn write_preserved(
     Ok(())
 }
 
-/// Writes a preserved raw XML section, or an empty element if absent.
-fn write_preserved_or_empty(
+/// Writes `office:automatic-styles`: the styles of the preserved section, if
+/// any, followed by the automatic styles and list styles used by `blocks`.
+///
+/// Generated names avoid those defined in the preserved XML, and list styles
+/// already defined there or among the named list styles of `styles` are not
+/// written again.
+///
+/// # Returns
+///
+/// The automatic styles to reference from the body.
+fn write_automatic_styles(
     writer: &mut Writer<Cursor<Vec<u8>>>,
-    content: &Option<String>,
-    tag: &str,
-) -> Result<(), String> {
-    if let Some(ref xml) = content {
-        writer
-            .write_event(Event::Text(BytesText::from_escaped(xml)))
-            .map_err(|e| e.to_string())?;
-    } else {
-        writer
-            .write_event(Event::Start(BytesStart::new(tag)))
-            .map_err(|e| e.to_string())?;
-        writer
-            .write_event(Event::End(BytesEnd::new(tag)))
+    preserved: &Option<String>,
+    blocks: &[Block],
+    styles: &HashMap<String, StyleDefinition>,
+) -> Result<AutoStyles, String> {
+    const TAG: &str = "office:automatic-styles";
+    let xml = preserved.as_deref().unwrap_or("");
+    let existing = preserved_style_names(xml)?;
+    let mut skip: Vec<&str> = existing.iter().map(String::as_str).collect();
+    let autos = AutoStyles::collect(blocks, &skip);
+
+    writer
+        .write_event(Event::Start(BytesStart::new(TAG)))
+        .map_err(|e| e.to_string())?;
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(ref e) | Event::Empty(ref e))
+                if e.name().as_ref() == TAG.as_bytes() => {}
+            Ok(Event::End(ref e)) if e.name().as_ref() == TAG.as_bytes() => {}
+            Ok(event) => writer.write_event(event).map_err(|e| e.to_string())?,
+            Err(e) => return Err(e.to_string()),
+        }
+        buf.clear();
+    }
+    autos.write(writer)?;
+    skip.extend(
+        styles
+            .values()
+            .filter(|s| s.family == StyleFamily::List)
+            .map(|s| s.name.as_str()),
+    );
+    write_list_styles(blocks, &skip, writer)?;
+    writer
+        .write_event(Event::End(BytesEnd::new(TAG)))
+        .map_err(|e| e.to_string())?;
+    Ok(autos)
+}
+
+/// Returns the `style:name` of every style in a preserved
+/// `office:automatic-styles` section.
+fn preserved_style_names(xml: &str) -> Result<Vec<String>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
             .map_err(|e| e.to_string())?;
+        let (e, opens) = match &event {
+            Event::Eof => break,
+            Event::Start(e) => (e, true),
+            Event::Empty(e) => (e, false),
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                buf.clear();
+                continue;
+            }
+            _ => {
+                buf.clear();
+                continue;
+            }
+        };
+        // The styles are the children of the section element.
+        if depth == 1 {
+            let name = e
+                .attributes()
+                .filter_map(|a| a.ok())
+                .find(|a| a.key.as_ref() == b"style:name")
+                .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()));
+            names.extend(name);
+        }
+        if opens {
+            depth += 1;
+        }
+        buf.clear();
     }
-    Ok(())
+    Ok(names)
 }
@@ -0,0 +1,126 @@
+//! ODT image frame writer.
+//!
+//! Writes a [`Block::Image`](common_core::Block::Image) as a `draw:frame`
+//! with its size, anchor, wrapping, title and description. Captioned images
+//! use LibreOffice's layout: an outer frame whose `draw:text-box` paragraph
+//! holds the image frame followed by the caption text.
+
+use common_core::units::PX_PER_CM;
+use common_core::{ImageAnchor, ImageFrame};
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+
+use super::auto_styles::AutoStyles;
+use super::inlines::XmlWriter;
+
+/// Writes an image frame.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn write_image(
+    src: &str,
+    alt: Option<&str>,
+    title: Option<&str>,
+    frame: Option<&ImageFrame>,
+    caption: Option<&str>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let geometry = frame.cloned().unwrap_or_default();
+    let Some(caption) = caption else {
+        let tag = frame_tag("Image", &geometry, autos);
+        return write_image_frame(tag, src, alt, title, writer);
+    };
+
+    let outer = ImageFrame {
+        height: None,
+        ..geometry.clone()
+    };
+    writer
+        .write_event(Event::Start(frame_tag("Frame", &outer, autos)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Start(BytesStart::new("draw:text-box")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Start(BytesStart::new("text:p")))
+        .map_err(|e| e.to_string())?;
+    let inner = ImageFrame {
+        anchor: Some(ImageAnchor::AsChar),
+        wrap: None,
+        ..geometry
+    };
+    write_image_frame(frame_tag("Image", &inner, autos), src, alt, title, writer)?;
+    writer
+        .write_event(Event::Empty(BytesStart::new("text:line-break")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Text(BytesText::new(caption)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("text:p")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("draw:text-box")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("draw:frame")))
+        .map_err(|e| e.to_string())
+}
+
+/// Builds a `draw:frame` start tag with the frame's style, anchor and size.
+fn frame_tag(name: &str, geometry: &ImageFrame, autos: &AutoStyles) -> BytesStart<'static> {
+    let mut frame = BytesStart::new("draw:frame");
+    if let Some(style) = geometry.wrap.and_then(|w| autos.graphic_style(w)) {
+        frame.push_attribute(("draw:style-name", style));
+    }
+    frame.push_attribute(("draw:name", name));
+    if let Some(anchor) = geometry.anchor {
+        frame.push_attribute(("text:anchor-type", anchor.to_odf_str()));
+    }
+    for (key, px) in [
+        ("svg:width", geometry.width),
+        ("svg:height", geometry.height),
+    ] {
+        if let Some(px) = px {
+            frame.push_attribute((key, format!("{:.4}cm", px / PX_PER_CM).as_str()));
+        }
+    }
+    frame
+}
+
+fn write_image_frame(
+    tag: BytesStart,
+    src: &str,
+    alt: Option<&str>,
+    title: Option<&str>,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    writer
+        .write_event(Event::Start(tag))
+        .map_err(|e| e.to_string())?;
+    let mut img = BytesStart::new("draw:image");
+    img.push_attribute(("xlink:href", src));
+    img.push_attribute(("xlink:type", "simple"));
+    img.push_attribute(("xlink:show", "embed"));
+    img.push_attribute(("xlink:actuate", "onLoad"));
+    writer
+        .write_event(Event::Empty(img))
+        .map_err(|e| e.to_string())?;
+    for (element, text) in [("svg:title", title), ("svg:desc", alt)] {
+        if let Some(text) = text {
+            writer
+                .write_event(Event::Start(BytesStart::new(element)))
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_event(Event::Text(BytesText::new(text)))
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_event(Event::End(BytesEnd::new(element)))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("draw:frame")))
+        .map_err(|e| e.to_string())
+}
@@ -4,48 +4,103 @@
 //!
 //! - [`write_inlines_with_style`]: uses named `text:span` style names (FODT / styles.xml path)
 //! - [`write_inlines_with_marks`]: uses mark-derived style names (content.xml path)
+//!
+//! In both flavours a run's formatting marks are written as a single span
+//! referencing its automatic text style from [`AutoStyles`]; links become
+//! `text:a` around that span.
+//!
+//! Text fields and cross-references are written as their ODF element around
+//! the value last shown.
+//!
+//! Both flavours write whitespace in canonical ODF form: the first space after
+//! a non-space character is literal, further spaces become `text:s`, and tab
+//! characters become `text:tab`.
 
 use std::io::Cursor;
 
 use common_core::marks::TiptapMark;
-use common_core::Inline;
+use common_core::{Block, Comment, CrossReference, FieldKind, Inline, NoteClass};
 use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Writer;
 
+use super::auto_styles::{run_parent_style, AutoStyles};
+
 /// Shared XML writer type used by all ODT writer modules.
 pub type XmlWriter = Writer<Cursor<Vec<u8>>>;
 
 /// Writes inline content using style names (FODT / styles.xml path).
 ///
 /// Wraps styled text runs in `text:span` elements with the ODT style name.
-pub fn write_inlines_with_style(inlines: &[Inline], writer: &mut XmlWriter) -> Result<(), String> {
+pub fn write_inlines_with_style(
+    inlines: &[Inline],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut at_space = true;
     for inline in inlines {
         match inline {
             Inline::Text {
-                text, style_name, ..
-            } => {
-                if let Some(s) = style_name {
-                    let mut span = BytesStart::new("text:span");
-                    span.push_attribute(("text:style-name", s.as_str()));
-                    writer
-                        .write_event(Event::Start(span))
-                        .map_err(|e| e.to_string())?;
-                    writer
-                        .write_event(Event::Text(BytesText::new(text)))
-                        .map_err(|e| e.to_string())?;
-                    writer
-                        .write_event(Event::End(BytesEnd::new("text:span")))
-                        .map_err(|e| e.to_string())?;
-                } else {
-                    writer
-                        .write_event(Event::Text(BytesText::new(text)))
-                        .map_err(|e| e.to_string())?;
-                }
-            }
+                text,
+                style_name,
+                marks,
+            } => write_run(
+                text,
+                style_name.as_deref(),
+                marks,
+                autos,
+                &mut at_space,
+                writer,
+            )?,
             Inline::LineBreak => {
                 writer
                     .write_event(Event::Empty(BytesStart::new("text:line-break")))
                     .map_err(|e| e.to_string())?;
+                at_space = true;
+            }
+            Inline::Space { count } => {
+                write_spaces(*count, writer)?;
+                at_space = true;
+            }
+            Inline::Tab => {
+                write_tab(writer)?;
+                at_space = false;
+            }
+            Inline::Note {
+                id,
+                note_class,
+                citation,
+                content,
+            } => {
+                write_note(
+                    id.as_deref(),
+                    *note_class,
+                    citation,
+                    content,
+                    super::blocks::write_blocks,
+                    autos,
+                    writer,
+                )?;
+                at_space = false;
+            }
+            Inline::Comment(comment) => {
+                write_comment(comment, None, super::blocks::write_blocks, autos, writer)?
+            }
+            Inline::CommentEnd { id } => write_comment_end(id, writer)?,
+            Inline::ChangeStart(change) => {
+                write_change_marker("text:change-start", &change.id, writer)?
+            }
+            Inline::ChangeEnd { id } => write_change_marker("text:change-end", id, writer)?,
+            Inline::Change(change) => write_change_marker("text:change", &change.id, writer)?,
+            Inline::Field { value, .. } => {
+                write_field(inline, writer)?;
+                at_space &= value.is_empty();
+            }
+            Inline::Bookmark { name } => write_bookmark("text:bookmark", name, writer)?,
+            Inline::BookmarkStart { name } => write_bookmark("text:bookmark-start", name, writer)?,
+            Inline::BookmarkEnd { name } => write_bookmark("text:bookmark-end", name, writer)?,
+            Inline::Reference(reference) => {
+                write_reference(reference, writer)?;
+                at_space &= reference.value.is_empty();
             }
         }
     }
@@ -54,22 +109,82 @@ pub fn write_inlines_with_style(inlines: &[Inline], writer: &mut XmlWriter) -> R
 
 /// Writes inline content using marks (content.xml path).
 ///
-/// Wraps text runs that carry marks in `text:span` / `text:a` elements,
-/// opening tags first, then the text, then closing tags in reverse order.
-pub fn write_inlines_with_marks(inlines: &[Inline], writer: &mut XmlWriter) -> Result<(), String> {
+/// Wraps text runs that carry marks in `text:span` / `text:a` elements.
+/// A `NamedSpanStyle` mark takes precedence over the run's style name.
+pub fn write_inlines_with_marks(
+    inlines: &[Inline],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut at_space = true;
     for inline in inlines {
         match inline {
-            Inline::Text { text, marks, .. } => {
-                write_mark_open_tags(marks, writer)?;
-                writer
-                    .write_event(Event::Text(BytesText::new(text)))
-                    .map_err(|e| e.to_string())?;
-                write_mark_close_tags(marks, writer)?;
-            }
+            Inline::Text {
+                text,
+                style_name,
+                marks,
+            } => write_run(
+                text,
+                style_name.as_deref(),
+                marks,
+                autos,
+                &mut at_space,
+                writer,
+            )?,
             Inline::LineBreak => {
                 writer
                     .write_event(Event::Empty(BytesStart::new("text:line-break")))
                     .map_err(|e| e.to_string())?;
+                at_space = true;
+            }
+            Inline::Space { count } => {
+                write_spaces(*count, writer)?;
+                at_space = true;
+            }
+            Inline::Tab => {
+                write_tab(writer)?;
+                at_space = false;
+            }
+            Inline::Note {
+                id,
+                note_class,
+                citation,
+                content,
+            } => {
+                write_note(
+                    id.as_deref(),
+                    *note_class,
+                    citation,
+                    content,
+                    super::content::write_blocks_content,
+                    autos,
+                    writer,
+                )?;
+                at_space = false;
+            }
+            Inline::Comment(comment) => write_comment(
+                comment,
+                None,
+                super::content::write_blocks_content,
+                autos,
+                writer,
+            )?,
+            Inline::CommentEnd { id } => write_comment_end(id, writer)?,
+            Inline::ChangeStart(change) => {
+                write_change_marker("text:change-start", &change.id, writer)?
+            }
+            Inline::ChangeEnd { id } => write_change_marker("text:change-end", id, writer)?,
+            Inline::Change(change) => write_change_marker("text:change", &change.id, writer)?,
+            Inline::Field { value, .. } => {
+                write_field(inline, writer)?;
+                at_space &= value.is_empty();
+            }
+            Inline::Bookmark { name } => write_bookmark("text:bookmark", name, writer)?,
+            Inline::BookmarkStart { name } => write_bookmark("text:bookmark-start", name, writer)?,
+            Inline::BookmarkEnd { name } => write_bookmark("text:bookmark-end", name, writer)?,
+            Inline::Reference(reference) => {
+                write_reference(reference, writer)?;
+                at_space &= reference.value.is_empty();
             }
         }
     }
@@ -78,65 +193,290 @@ pub fn write_inlines_with_marks(inlines: &[Inline], writer: &mut XmlWriter) -> R
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
-fn write_mark_open_tags(marks: &[TiptapMark], writer: &mut XmlWriter) -> Result<(), String> {
-    for mark in marks {
-        match mark {
-            TiptapMark::Bold => open_span("Strong", writer)?,
-            TiptapMark::Italic => open_span("Emphasis", writer)?,
-            TiptapMark::Underline => open_span("Underline", writer)?,
-            TiptapMark::Strike => open_span("Strike", writer)?,
-            TiptapMark::Superscript => open_span("Superscript", writer)?,
-            TiptapMark::Subscript => open_span("Subscript", writer)?,
-            TiptapMark::NamedSpanStyle { attrs } => {
-                if let Some(name) = &attrs.style_name {
-                    open_span(name, writer)?;
-                }
-            }
-            TiptapMark::Link { attrs } => {
-                let mut a = BytesStart::new("text:a");
-                a.push_attribute(("xlink:type", "simple"));
-                a.push_attribute(("xlink:href", attrs.href.as_str()));
-                if let Some(t) = &attrs.target {
-                    a.push_attribute(("office:target-frame-name", t.as_str()));
-                }
-                writer
-                    .write_event(Event::Start(a))
-                    .map_err(|e| e.to_string())?;
-            }
+/// Writes an empty bookmark element named `name`.
+fn write_bookmark(tag: &str, name: &str, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut el = BytesStart::new(tag);
+    el.push_attribute(("text:name", name));
+    writer
+        .write_event(Event::Empty(el))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a cross-reference element holding its last shown value.
+fn write_reference(reference: &CrossReference, writer: &mut XmlWriter) -> Result<(), String> {
+    let tag = format!("text:{}", reference.kind.to_odf_str());
+    let mut el = BytesStart::new(tag.as_str());
+    el.push_attribute(("text:ref-name", reference.name.as_str()));
+    if let Some(format) = &reference.format {
+        el.push_attribute(("text:reference-format", format.as_str()));
+    }
+    writer
+        .write_event(Event::Start(el))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Text(BytesText::new(&reference.value)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new(tag.as_str())))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a text field element holding its last shown value.
+fn write_field(field: &Inline, writer: &mut XmlWriter) -> Result<(), String> {
+    let Inline::Field {
+        kind,
+        value,
+        fixed,
+        date_value,
+        data_style_name,
+    } = field
+    else {
+        return Ok(());
+    };
+    let tag = format!("text:{}", kind.to_odf_str());
+    let mut el = BytesStart::new(tag.as_str());
+    if *kind == FieldKind::PageNumber {
+        el.push_attribute(("text:select-page", "current"));
+    }
+    if let Some(style) = data_style_name {
+        el.push_attribute(("style:data-style-name", style.as_str()));
+    }
+    if let Some(date) = date_value {
+        el.push_attribute(("text:date-value", date.as_str()));
+    }
+    if *fixed {
+        el.push_attribute(("text:fixed", "true"));
+    }
+    writer
+        .write_event(Event::Start(el))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Text(BytesText::new(value)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new(tag.as_str())))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes one text run: an optional `text:a` for its link, around an
+/// optional `text:span` naming its automatic or named style.
+fn write_run(
+    text: &str,
+    style_name: Option<&str>,
+    marks: &[TiptapMark],
+    autos: &AutoStyles,
+    at_space: &mut bool,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let link = marks.iter().find_map(|m| match m {
+        TiptapMark::Link { attrs } => Some(attrs),
+        _ => None,
+    });
+    if let Some(attrs) = link {
+        let mut a = BytesStart::new("text:a");
+        a.push_attribute(("xlink:type", "simple"));
+        a.push_attribute(("xlink:href", attrs.href.as_str()));
+        if let Some(t) = &attrs.target {
+            a.push_attribute(("office:target-frame-name", t.as_str()));
         }
+        writer
+            .write_event(Event::Start(a))
+            .map_err(|e| e.to_string())?;
+    }
+    let parent = run_parent_style(style_name, marks);
+    let span = autos.text_style(parent, marks).or(parent);
+    if let Some(name) = span {
+        let mut el = BytesStart::new("text:span");
+        el.push_attribute(("text:style-name", name));
+        writer
+            .write_event(Event::Start(el))
+            .map_err(|e| e.to_string())?;
+    }
+    write_text(text, at_space, writer)?;
+    if span.is_some() {
+        writer
+            .write_event(Event::End(BytesEnd::new("text:span")))
+            .map_err(|e| e.to_string())?;
+    }
+    if link.is_some() {
+        writer
+            .write_event(Event::End(BytesEnd::new("text:a")))
+            .map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
-fn write_mark_close_tags(marks: &[TiptapMark], writer: &mut XmlWriter) -> Result<(), String> {
-    for mark in marks.iter().rev() {
-        match mark {
-            TiptapMark::Link { .. } => {
-                writer
-                    .write_event(Event::End(BytesEnd::new("text:a")))
-                    .map_err(|e| e.to_string())?;
+/// Writes `text` in canonical ODF whitespace form.
+///
+/// `at_space` tracks whether the previous character in the paragraph was a
+/// space (or the paragraph start), in which case a literal space would be
+/// collapsed by a reader and must be written as `text:s` instead.
+fn write_text(text: &str, at_space: &mut bool, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut literal = String::new();
+    let mut spaces = 0u32;
+    for c in text.chars() {
+        match c {
+            // Newlines are not significant in ODF text; readers treat them
+            // as spaces.
+            ' ' | '\n' | '\r' if *at_space => spaces += 1,
+            ' ' | '\n' | '\r' => {
+                literal.push(' ');
+                *at_space = true;
             }
-            TiptapMark::NamedSpanStyle { attrs } => {
-                if attrs.style_name.is_some() {
-                    writer
-                        .write_event(Event::End(BytesEnd::new("text:span")))
-                        .map_err(|e| e.to_string())?;
+            '\t' => {
+                flush_literal(&mut literal, writer)?;
+                if spaces > 0 {
+                    write_spaces(spaces, writer)?;
+                    spaces = 0;
                 }
+                write_tab(writer)?;
+                *at_space = false;
             }
             _ => {
-                writer
-                    .write_event(Event::End(BytesEnd::new("text:span")))
-                    .map_err(|e| e.to_string())?;
+                if spaces > 0 {
+                    flush_literal(&mut literal, writer)?;
+                    write_spaces(spaces, writer)?;
+                    spaces = 0;
+                }
+                literal.push(c);
+                *at_space = false;
             }
         }
     }
+    flush_literal(&mut literal, writer)?;
+    if spaces > 0 {
+        write_spaces(spaces, writer)?;
+    }
+    Ok(())
+}
+
+fn flush_literal(literal: &mut String, writer: &mut XmlWriter) -> Result<(), String> {
+    if literal.is_empty() {
+        return Ok(());
+    }
+    writer
+        .write_event(Event::Text(BytesText::new(literal)))
+        .map_err(|e| e.to_string())?;
+    literal.clear();
     Ok(())
 }
 
-fn open_span(style: &str, writer: &mut XmlWriter) -> Result<(), String> {
-    let mut span = BytesStart::new("text:span");
-    span.push_attribute(("text:style-name", style));
+/// Writes `text:s`, with `text:c` only when more than one space is needed.
+fn write_spaces(count: u32, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut s = BytesStart::new("text:s");
+    if count > 1 {
+        s.push_attribute(("text:c", count.to_string().as_str()));
+    }
+    writer
+        .write_event(Event::Empty(s))
+        .map_err(|e| e.to_string())
+}
+
+fn write_tab(writer: &mut XmlWriter) -> Result<(), String> {
+    writer
+        .write_event(Event::Empty(BytesStart::new("text:tab")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a `text:note` element; the body is emitted with `write_body` so
+/// each writer flavour keeps its own block serialization.
+fn write_note(
+    id: Option<&str>,
+    note_class: NoteClass,
+    citation: &str,
+    content: &[Block],
+    write_body: fn(&[Block], &AutoStyles, &mut XmlWriter) -> Result<(), String>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut note = BytesStart::new("text:note");
+    if let Some(id) = id {
+        note.push_attribute(("text:id", id));
+    }
+    note.push_attribute(("text:note-class", note_class.to_odf_str()));
+    writer
+        .write_event(Event::Start(note))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Start(BytesStart::new("text:note-citation")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Text(BytesText::new(citation)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("text:note-citation")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Start(BytesStart::new("text:note-body")))
+        .map_err(|e| e.to_string())?;
+    write_body(content, autos, writer)?;
+    writer
+        .write_event(Event::End(BytesEnd::new("text:note-body")))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("text:note")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes an `office:annotation` element followed by its replies, each
+/// naming the comment it answers in `loext:parent-name`.
+fn write_comment(
+    comment: &Comment,
+    parent: Option<&str>,
+    write_body: fn(&[Block], &AutoStyles, &mut XmlWriter) -> Result<(), String>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut annotation = BytesStart::new("office:annotation");
+    if let Some(id) = &comment.id {
+        annotation.push_attribute(("office:name", id.as_str()));
+    }
+    if let Some(parent) = parent {
+        annotation.push_attribute(("loext:parent-name", parent));
+    }
+    if comment.resolved {
+        annotation.push_attribute(("loext:resolved", "true"));
+    }
+    writer
+        .write_event(Event::Start(annotation))
+        .map_err(|e| e.to_string())?;
+    for (element, text) in [("dc:creator", &comment.author), ("dc:date", &comment.date)] {
+        if let Some(text) = text {
+            writer
+                .write_event(Event::Start(BytesStart::new(element)))
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_event(Event::Text(BytesText::new(text)))
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_event(Event::End(BytesEnd::new(element)))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    write_body(&comment.content, autos, writer)?;
+    writer
+        .write_event(Event::End(BytesEnd::new("office:annotation")))
+        .map_err(|e| e.to_string())?;
+    for reply in &comment.replies {
+        write_comment(reply, comment.id.as_deref(), write_body, autos, writer)?;
+    }
+    Ok(())
+}
+
+fn write_comment_end(id: &str, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut end = BytesStart::new("office:annotation-end");
+    end.push_attribute(("office:name", id));
+    writer
+        .write_event(Event::Empty(end))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a `text:change-start`, `text:change-end` or `text:change` marker;
+/// the change itself is written in `text:tracked-changes`.
+fn write_change_marker(element: &str, id: &str, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut marker = BytesStart::new(element);
+    marker.push_attribute(("text:change-id", id));
     writer
-        .write_event(Event::Start(span))
+        .write_event(Event::Empty(marker))
         .map_err(|e| e.to_string())
 }
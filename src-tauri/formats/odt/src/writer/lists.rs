@@ -0,0 +1,335 @@
+//! ODT list style writers.
+//!
+//! Lists reference a `text:list-style` by name. Lists imported from ODT keep
+//! their original style name, and the style itself is regenerated from the
+//! [`ListAttrs`] of the lists that use it. Lists created in the editor use
+//! one of the two default styles written into `office:styles`, next to the
+//! named list styles of the document's style definitions.
+
+use std::collections::HashMap;
+
+use common_core::walk::{for_each_block, for_each_inline};
+use common_core::{Block, Inline, ListAttrs, StyleDefinition};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+
+use super::inlines::XmlWriter;
+use crate::list_styles::{DEFAULT_BULLET_STYLE, DEFAULT_NUMBER_STYLE};
+
+/// Number of levels in an ODF list style.
+const LIST_LEVELS: usize = 10;
+/// Indentation step per list level, in centimetres.
+const LEVEL_INDENT_CM: f64 = 0.635;
+
+/// Returns the list style name written for a list block.
+///
+/// An imported style name is reused only when it was imported for the same
+/// kind of list; a list switched between bullets and numbers in the editor
+/// falls back to the matching default style.
+#[must_use]
+pub fn list_style_name(ordered: bool, attrs: Option<&ListAttrs>) -> &str {
+    match attrs {
+        Some(ListAttrs {
+            style_name: Some(name),
+            num_format,
+            bullet_char,
+            ..
+        }) if num_format.is_some() == ordered && (ordered || bullet_char.is_some()) => name,
+        _ if ordered => DEFAULT_NUMBER_STYLE,
+        _ => DEFAULT_BULLET_STYLE,
+    }
+}
+
+/// Builds the `text:list` start tag for a list block.
+pub(crate) fn list_start_tag(ordered: bool, attrs: Option<&ListAttrs>) -> BytesStart<'static> {
+    let mut list = BytesStart::new("text:list");
+    list.push_attribute(("text:style-name", list_style_name(ordered, attrs)));
+    if attrs.is_some_and(|a| a.continue_numbering) {
+        list.push_attribute(("text:continue-numbering", "true"));
+    }
+    list
+}
+
+/// Builds the `text:list-item` start tag, with a start value if given.
+pub(crate) fn list_item_start_tag(start_value: Option<u32>) -> BytesStart<'static> {
+    let mut item = BytesStart::new("text:list-item");
+    if let Some(n) = start_value {
+        item.push_attribute(("text:start-value", n.to_string().as_str()));
+    }
+    item
+}
+
+/// A list style collected from the block tree, with one entry per level.
+struct ListStyleSpec {
+    name: String,
+    levels: Vec<Option<(bool, ListAttrs)>>,
+}
+
+/// Writes a `text:list-style` for every imported list style used in `blocks`.
+///
+/// Intended for `office:automatic-styles`. Names listed in `skip` (for example
+/// styles already present in preserved XML) are not written again.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn write_list_styles(
+    blocks: &[Block],
+    skip: &[&str],
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut specs = Vec::new();
+    collect_list_styles(blocks, &mut specs);
+    for spec in specs.iter().filter(|s| !skip.contains(&s.name.as_str())) {
+        write_list_style(spec, writer)?;
+    }
+    Ok(())
+}
+
+/// Writes the two default list styles for `office:styles`.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn write_default_list_styles(writer: &mut XmlWriter) -> Result<(), String> {
+    for (name, ordered) in [(DEFAULT_BULLET_STYLE, false), (DEFAULT_NUMBER_STYLE, true)] {
+        let spec = ListStyleSpec {
+            name: name.to_string(),
+            levels: vec![Some((ordered, ListAttrs::default())); LIST_LEVELS],
+        };
+        write_list_style(&spec, writer)?;
+    }
+    Ok(())
+}
+
+/// Collects the list styles of `blocks` in document order. Tables, notes,
+/// comments and deletions start a fresh list nesting context.
+fn collect_list_styles(blocks: &[Block], specs: &mut Vec<ListStyleSpec>) {
+    collect_list_levels(blocks, 0, specs);
+    for_each_block(blocks, &mut |block| {
+        if let Block::TableCell { content, .. } | Block::TableHeader { content, .. } = block {
+            collect_list_levels(content, 0, specs);
+        }
+    });
+    for_each_inline(blocks, &mut |inline| match inline {
+        Inline::Note { content, .. } => collect_list_levels(content, 0, specs),
+        Inline::Comment(comment) => {
+            for c in comment.thread() {
+                collect_list_styles(&c.content, specs);
+            }
+        }
+        Inline::Change(change) => collect_list_styles(&change.content, specs),
+        _ => {}
+    });
+}
+
+/// Records the lists of one nesting context, `depth` levels deep.
+fn collect_list_levels(blocks: &[Block], depth: usize, specs: &mut Vec<ListStyleSpec>) {
+    for block in blocks {
+        match block {
+            Block::BulletList { attrs, content } | Block::OrderedList { attrs, content } => {
+                let ordered = matches!(block, Block::OrderedList { .. });
+                let name = list_style_name(ordered, attrs.as_ref());
+                if name != DEFAULT_BULLET_STYLE && name != DEFAULT_NUMBER_STYLE {
+                    let index = match specs.iter().position(|s| s.name == name) {
+                        Some(i) => i,
+                        None => {
+                            specs.push(ListStyleSpec {
+                                name: name.to_string(),
+                                levels: vec![None; LIST_LEVELS],
+                            });
+                            specs.len() - 1
+                        }
+                    };
+                    let level = &mut specs[index].levels[depth.min(LIST_LEVELS - 1)];
+                    if level.is_none() {
+                        *level = Some((ordered, attrs.clone().unwrap_or_default()));
+                    }
+                }
+                collect_list_levels(content, depth + 1, specs);
+            }
+            Block::ListItem { content }
+            | Block::Blockquote { content }
+            | Block::Section { content, .. } => collect_list_levels(content, depth, specs),
+            _ => {}
+        }
+    }
+}
+
+fn write_list_style(spec: &ListStyleSpec, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut style = BytesStart::new("text:list-style");
+    style.push_attribute(("style:name", spec.name.as_str()));
+    writer
+        .write_event(Event::Start(style))
+        .map_err(|e| e.to_string())?;
+
+    // Levels that no list used take the kind of the first used level.
+    let fallback = spec
+        .levels
+        .iter()
+        .flatten()
+        .next()
+        .map(|(ordered, _)| (*ordered, ListAttrs::default()))
+        .unwrap_or_default();
+    for (i, level) in spec.levels.iter().enumerate() {
+        let (ordered, attrs) = level.as_ref().unwrap_or(&fallback);
+        write_list_level(i + 1, *ordered, attrs, writer)?;
+    }
+
+    writer
+        .write_event(Event::End(BytesEnd::new("text:list-style")))
+        .map_err(|e| e.to_string())
+}
+
+fn write_list_level(
+    level: usize,
+    ordered: bool,
+    attrs: &ListAttrs,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let tag = if ordered {
+        "text:list-level-style-number"
+    } else {
+        "text:list-level-style-bullet"
+    };
+    let mut el = BytesStart::new(tag);
+    el.push_attribute(("text:level", level.to_string().as_str()));
+    if ordered {
+        if let Some(prefix) = &attrs.num_prefix {
+            el.push_attribute(("style:num-prefix", prefix.as_str()));
+        }
+        // Lists without an imported format get the familiar "1." numbering.
+        let suffix = match &attrs.num_format {
+            Some(_) => attrs.num_suffix.as_deref(),
+            None => Some("."),
+        };
+        if let Some(suffix) = suffix {
+            el.push_attribute(("style:num-suffix", suffix));
+        }
+        el.push_attribute((
+            "style:num-format",
+            attrs.num_format.as_deref().unwrap_or("1"),
+        ));
+    } else {
+        el.push_attribute((
+            "text:bullet-char",
+            attrs.bullet_char.as_deref().unwrap_or("\u{2022}"),
+        ));
+    }
+    writer
+        .write_event(Event::Start(el))
+        .map_err(|e| e.to_string())?;
+
+    let mut props = BytesStart::new("style:list-level-properties");
+    props.push_attribute(("text:list-level-position-and-space-mode", "label-alignment"));
+    writer
+        .write_event(Event::Start(props))
+        .map_err(|e| e.to_string())?;
+    let indent = format!("{:.3}cm", LEVEL_INDENT_CM * (level + 1) as f64);
+    let mut align = BytesStart::new("style:list-level-label-alignment");
+    align.push_attribute(("text:label-followed-by", "listtab"));
+    align.push_attribute(("text:list-tab-stop-position", indent.as_str()));
+    align.push_attribute(("fo:text-indent", format!("-{LEVEL_INDENT_CM}cm").as_str()));
+    align.push_attribute(("fo:margin-left", indent.as_str()));
+    writer
+        .write_event(Event::Empty(align))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("style:list-level-properties")))
+        .map_err(|e| e.to_string())?;
+
+    writer
+        .write_event(Event::End(BytesEnd::new(tag)))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a named list style from its [`StyleDefinition`] levels, for
+/// `office:styles`.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn write_named_list_style(
+    name: &str,
+    def: &StyleDefinition,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut style = BytesStart::new("text:list-style");
+    style.push_attribute(("style:name", name));
+    if let Some(display_name) = &def.display_name {
+        style.push_attribute(("style:display-name", display_name.as_str()));
+    }
+    writer
+        .write_event(Event::Start(style))
+        .map_err(|e| e.to_string())?;
+
+    for level in &def.list_levels {
+        let tag = level.kind.element_name();
+        let mut el = BytesStart::new(tag);
+        el.push_attribute(("text:level", level.level.to_string().as_str()));
+        push_sorted(&mut el, &level.attributes);
+        writer
+            .write_event(Event::Start(el))
+            .map_err(|e| e.to_string())?;
+
+        let mut props = BytesStart::new("style:list-level-properties");
+        push_sorted(&mut props, &level.properties);
+        if level.label_alignment.is_empty() {
+            writer
+                .write_event(Event::Empty(props))
+                .map_err(|e| e.to_string())?;
+        } else {
+            writer
+                .write_event(Event::Start(props))
+                .map_err(|e| e.to_string())?;
+            let mut align = BytesStart::new("style:list-level-label-alignment");
+            push_sorted(&mut align, &level.label_alignment);
+            writer
+                .write_event(Event::Empty(align))
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_event(Event::End(BytesEnd::new("style:list-level-properties")))
+                .map_err(|e| e.to_string())?;
+        }
+        if !level.text_properties.is_empty() {
+            let mut text = BytesStart::new("style:text-properties");
+            push_sorted(&mut text, &level.text_properties);
+            writer
+                .write_event(Event::Empty(text))
+                .map_err(|e| e.to_string())?;
+        }
+
+        writer
+            .write_event(Event::End(BytesEnd::new(tag)))
+            .map_err(|e| e.to_string())?;
+    }
+
+    writer
+        .write_event(Event::End(BytesEnd::new("text:list-style")))
+        .map_err(|e| e.to_string())
+}
+
+/// Adds the attributes of `map` to `el`, sorted by name.
+fn push_sorted(el: &mut BytesStart, map: &HashMap<String, String>) {
+    let mut attrs: Vec<(&String, &String)> = map.iter().collect();
+    attrs.sort();
+    for (key, value) in attrs {
+        el.push_attribute((key.as_str(), value.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imported_style_name_kept_for_same_kind() {
+        let attrs = ListAttrs {
+            style_name: Some("L1".to_string()),
+            num_format: Some("a".to_string()),
+            ..ListAttrs::default()
+        };
+        assert_eq!(list_style_name(true, Some(&attrs)), "L1");
+        assert_eq!(list_style_name(false, Some(&attrs)), DEFAULT_BULLET_STYLE);
+        assert_eq!(list_style_name(true, None), DEFAULT_NUMBER_STYLE);
+    }
+}
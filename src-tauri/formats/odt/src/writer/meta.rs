@@ -5,7 +5,7 @@
 
 use std::io::Cursor;
 
-use common_core::Metadata;
+use common_core::{DocumentStatistics, Metadata};
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Writer;
 
@@ -88,10 +88,61 @@ pub fn write_meta_elements(
     if let Some(lang) = &metadata.language {
         write_text_element(writer, "dc:language", lang)?;
     }
+    for keyword in &metadata.keywords {
+        write_text_element(writer, "meta:keyword", keyword)?;
+    }
+    if let Some(creator) = &metadata.initial_creator {
+        write_text_element(writer, "meta:initial-creator", creator)?;
+    }
+    if let Some(date) = &metadata.date {
+        write_text_element(writer, "dc:date", date)?;
+    }
+    if let Some(cycles) = metadata.editing_cycles {
+        write_text_element(writer, "meta:editing-cycles", &cycles.to_string())?;
+    }
+    if let Some(duration) = &metadata.editing_duration {
+        write_text_element(writer, "meta:editing-duration", duration)?;
+    }
+    if let Some(stats) = &metadata.statistics {
+        write_statistics(writer, stats)?;
+    }
+    for field in &metadata.user_defined {
+        let mut start = BytesStart::new("meta:user-defined");
+        start.push_attribute(("meta:name", field.name.as_str()));
+        if let Some(value_type) = &field.value_type {
+            start.push_attribute(("meta:value-type", value_type.as_str()));
+        }
+        writer
+            .write_event(Event::Start(start))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_event(Event::Text(BytesText::new(&field.value)))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_event(Event::End(BytesEnd::new("meta:user-defined")))
+            .map_err(|e| e.to_string())?;
+    }
 
     Ok(())
 }
 
+/// Writes `<meta:document-statistic/>` with the counts as attributes.
+fn write_statistics(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    stats: &DocumentStatistics,
+) -> Result<(), String> {
+    let mut start = BytesStart::new("meta:document-statistic");
+    if let Some(pages) = stats.page_count {
+        start.push_attribute(("meta:page-count", pages.to_string().as_str()));
+    }
+    for (name, value) in stats.odf_counts() {
+        start.push_attribute((format!("meta:{name}").as_str(), value.to_string().as_str()));
+    }
+    writer
+        .write_event(Event::Empty(start))
+        .map_err(|e| e.to_string())
+}
+
 /// Writes a single XML element with a text node: `<tag>text</tag>`.
 fn write_text_element(
     writer: &mut Writer<Cursor<Vec<u8>>>,
@@ -171,6 +222,21 @@ mod tests {
             creation_date: Some("2024-01-01T00:00:00Z".to_string()),
             identifier: Some("doc-123".to_string()),
             generator: Some("MyApp".to_string()),
+            keywords: vec!["alpha".to_string(), "beta".to_string()],
+            date: Some("2024-02-01T10:00:00".to_string()),
+            editing_cycles: Some(4),
+            editing_duration: Some("PT1H5M".to_string()),
+            initial_creator: Some("Carol".to_string()),
+            user_defined: vec![common_core::UserDefinedField {
+                name: "Client".to_string(),
+                value_type: Some("string".to_string()),
+                value: "ACME & Co".to_string(),
+            }],
+            statistics: Some(DocumentStatistics {
+                page_count: Some(2),
+                word_count: 42,
+                ..DocumentStatistics::default()
+            }),
         };
         let xml = to_meta_xml(&meta).unwrap();
         assert!(xml.contains("Title"));
@@ -181,5 +247,15 @@ mod tests {
         assert!(xml.contains("2024-01-01"));
         assert!(xml.contains("doc-123"));
         assert!(xml.contains("MyApp"));
+        assert!(xml.contains("<meta:keyword>beta</meta:keyword>"));
+        assert!(xml.contains("<dc:date>2024-02-01T10:00:00</dc:date>"));
+        assert!(xml.contains("<meta:editing-cycles>4</meta:editing-cycles>"));
+        assert!(xml.contains("<meta:editing-duration>PT1H5M</meta:editing-duration>"));
+        assert!(xml.contains("<meta:initial-creator>Carol</meta:initial-creator>"));
+        assert!(xml.contains(
+            "<meta:user-defined meta:name=\"Client\" meta:value-type=\"string\">ACME &amp; Co</meta:user-defined>"
+        ));
+        assert!(xml.contains("meta:page-count=\"2\""));
+        assert!(xml.contains("meta:word-count=\"42\""));
     }
 }
@@ -6,15 +6,32 @@
 //! - [`fodt`]: generates complete FODT flat XML documents and in-place updates
 //! - [`meta`]: generates `meta.xml` for ZIP-format ODT files
 //! - [`styles_writer`]: generates `styles.xml` for ZIP-format ODT files
+//! - [`auto_styles`]: automatic styles for direct formatting
 //! - [`blocks`]: shared block XML writers
+//! - [`changes`]: tracked-changes section writer
+//! - [`images`]: image frame writers
 //! - [`inlines`]: shared inline XML writers
+//! - [`lists`]: list style writers
+//! - [`outline`]: heading outline numbering writer
+//! - [`sections`]: section writers
+//! - [`tables`]: table writers
+//! - [`toc`]: table of contents writers
 //! - [`namespaces`]: ODF namespace attribute helpers
 
+pub mod auto_styles;
 pub mod blocks;
+pub mod changes;
 pub mod content;
 pub mod fodt;
+pub mod images;
 pub mod inlines;
+pub mod lists;
 pub mod meta;
 pub mod namespaces;
+pub mod outline;
+pub mod page;
+pub mod sections;
 pub mod styles_utils;
 pub mod styles_writer;
+pub mod tables;
+pub mod toc;
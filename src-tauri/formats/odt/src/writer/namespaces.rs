@@ -131,6 +131,29 @@ pub fn push_styles_doc_ns(elem: &mut BytesStart) {
     elem.push_attribute(("office:version", "1.3"));
 }
 
+/// Returns a copy of a document root element that also declares the
+/// `loext:` and `loki:` namespaces, for updating files written by other
+/// applications or by older versions of the writers.
+pub fn with_extension_ns(root: &BytesStart) -> BytesStart<'static> {
+    let mut elem = root.to_owned();
+    for (prefix, uri) in [
+        (
+            "xmlns:loext",
+            "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
+        ),
+        ("xmlns:loki", "https://appthere.com/loki/ns"),
+    ] {
+        let declared = root
+            .attributes()
+            .flatten()
+            .any(|a| a.key.as_ref() == prefix.as_bytes());
+        if !declared {
+            elem.push_attribute((prefix, uri));
+        }
+    }
+    elem
+}
+
 /// Pushes the core ODF namespace declarations shared by all document types.
 fn push_common_ns(elem: &mut BytesStart) {
     elem.push_attribute((
@@ -155,4 +178,8 @@ fn push_common_ns(elem: &mut BytesStart) {
         "xmlns:meta",
         "urn:oasis:names:tc:opendocument:xmlns:meta:1.0",
     ));
+    elem.push_attribute((
+        "xmlns:loext",
+        "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
+    ));
 }
@@ -0,0 +1,75 @@
+//! ODT outline style writer.
+//!
+//! Writes the document's heading numbering as the `text:outline-style` of
+//! `office:styles`. Levels without a number format get an empty
+//! `style:num-format`, which is how ODF marks an unnumbered level.
+
+use common_core::{OutlineLevel, OutlineStyle};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+
+use super::inlines::XmlWriter;
+
+/// Writes a `text:outline-style` element.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub fn write_outline_style(outline: &OutlineStyle, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut style = BytesStart::new("text:outline-style");
+    style.push_attribute(("style:name", outline.name.as_str()));
+    writer
+        .write_event(Event::Start(style))
+        .map_err(|e| e.to_string())?;
+    for level in &outline.levels {
+        write_outline_level(level, writer)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("text:outline-style")))
+        .map_err(|e| e.to_string())
+}
+
+fn write_outline_level(level: &OutlineLevel, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut el = BytesStart::new("text:outline-level-style");
+    el.push_attribute(("text:level", level.level.to_string().as_str()));
+    el.push_attribute((
+        "style:num-format",
+        level.num_format.as_deref().unwrap_or_default(),
+    ));
+    if let Some(prefix) = &level.num_prefix {
+        el.push_attribute(("style:num-prefix", prefix.as_str()));
+    }
+    if let Some(suffix) = &level.num_suffix {
+        el.push_attribute(("style:num-suffix", suffix.as_str()));
+    }
+    if let Some(start) = level.start {
+        el.push_attribute(("text:start-value", start.to_string().as_str()));
+    }
+    if level.display_levels > 1 {
+        el.push_attribute((
+            "text:display-levels",
+            level.display_levels.to_string().as_str(),
+        ));
+    }
+    writer
+        .write_event(Event::Start(el))
+        .map_err(|e| e.to_string())?;
+
+    // The number is followed by a space, as the exporters show it.
+    let mut props = BytesStart::new("style:list-level-properties");
+    props.push_attribute(("text:list-level-position-and-space-mode", "label-alignment"));
+    writer
+        .write_event(Event::Start(props))
+        .map_err(|e| e.to_string())?;
+    let mut align = BytesStart::new("style:list-level-label-alignment");
+    align.push_attribute(("text:label-followed-by", "space"));
+    writer
+        .write_event(Event::Empty(align))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(BytesEnd::new("style:list-level-properties")))
+        .map_err(|e| e.to_string())?;
+
+    writer
+        .write_event(Event::End(BytesEnd::new("text:outline-level-style")))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,609 @@
+//! ODT page layout writer.
+//!
+//! Regenerates the `style:page-layout` elements of `office:automatic-styles`
+//! and the `style:master-page` elements of `office:master-styles` from a
+//! [`PageSetup`]. Layouts and master pages already in the XML keep the
+//! attributes the model does not cover and their other children (header and
+//! footer styles, drawings); size, margins, orientation, the master page
+//! links and the headers and footers are rewritten.
+//!
+//! Direct formatting in headers and footers becomes automatic styles named
+//! `MP1`, `MT1`, … next to the page layouts; old styles with such names are
+//! dropped.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use common_core::units::length_to_cm;
+use common_core::{Block, HeaderFooter, MasterPage, PageLayout, PageSetup};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+use super::auto_styles::AutoStyles;
+use super::blocks::write_blocks;
+use super::inlines::XmlWriter;
+
+const AUTOMATIC_STYLES: &[u8] = b"office:automatic-styles";
+const MASTER_STYLES: &[u8] = b"office:master-styles";
+const PAGE_LAYOUT: &[u8] = b"style:page-layout";
+const PAGE_LAYOUT_PROPERTIES: &[u8] = b"style:page-layout-properties";
+const MASTER_PAGE: &[u8] = b"style:master-page";
+const STYLE: &[u8] = b"style:style";
+
+/// The prefix of the automatic styles used by headers and footers.
+const MASTER_STYLE_PREFIX: &str = "M";
+
+/// What an element set aside while streaming is.
+#[derive(Clone, Copy, PartialEq)]
+enum Kept {
+    Layout,
+    Master,
+    /// An old header or footer automatic style, dropped.
+    Dropped,
+}
+
+/// The `style:page-layout-properties` attributes written from the model.
+const LAYOUT_ATTRIBUTES: [&str; 8] = [
+    "fo:page-width",
+    "fo:page-height",
+    "fo:margin",
+    "fo:margin-top",
+    "fo:margin-bottom",
+    "fo:margin-left",
+    "fo:margin-right",
+    "style:print-orientation",
+];
+
+/// The `style:master-page` attributes written from the model.
+const MASTER_ATTRIBUTES: [&str; 4] = [
+    "style:name",
+    "style:display-name",
+    "style:page-layout-name",
+    "style:next-style-name",
+];
+
+/// Rewrites the page layouts and master pages of a flat ODT document or a
+/// `styles.xml` so they match `setup`. A `content.xml` is returned as is.
+///
+/// A missing `office:automatic-styles` is added before the master styles or
+/// the body, and a missing `office:master-styles` before the body or the end
+/// of the document.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML parsing or writing fails.
+pub fn write_page_setup(xml: &str, setup: &PageSetup) -> Result<String, String> {
+    let autos = header_footer_styles(xml, setup);
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let mut layouts: HashMap<String, Vec<Event>> = HashMap::new();
+    let mut masters: HashMap<String, Vec<Event>> = HashMap::new();
+    // The element being set aside: what it is, its name, its events and the
+    // depth reached inside it.
+    let mut kept: Option<(Kept, String, Vec<Event>, usize)> = None;
+    let mut in_automatic = false;
+    let mut seen_automatic = false;
+    let mut seen_master = false;
+    let mut depth = 0usize;
+
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+
+        if let Some((_, _, events, level)) = kept.as_mut() {
+            match &event {
+                Event::Start(_) => *level += 1,
+                Event::End(_) => *level -= 1,
+                _ => {}
+            }
+            events.push(event);
+            if *level == 0 {
+                match kept.take() {
+                    Some((Kept::Layout, name, events, _)) => {
+                        layouts.insert(name, events);
+                    }
+                    Some((Kept::Master, name, events, _)) => {
+                        masters.insert(name, events);
+                    }
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        match &event {
+            Event::Eof => break,
+            // Pages are defined in `styles.xml`, not in `content.xml`.
+            Event::Start(e) if depth == 0 && e.name().as_ref() == b"office:document-content" => {
+                return Ok(xml.to_string());
+            }
+            Event::Start(e) | Event::Empty(e)
+                if matches!(e.name().as_ref(), PAGE_LAYOUT | MASTER_PAGE) =>
+            {
+                let kind = if e.name().as_ref() == PAGE_LAYOUT {
+                    Kept::Layout
+                } else {
+                    Kept::Master
+                };
+                let name = attribute(e, "style:name").unwrap_or_default();
+                if matches!(event, Event::Start(_)) {
+                    kept = Some((kind, name, vec![event], 1));
+                } else if kind == Kept::Layout {
+                    layouts.insert(name, vec![event]);
+                } else {
+                    masters.insert(name, vec![event]);
+                }
+                continue;
+            }
+            Event::Start(e) | Event::Empty(e)
+                if in_automatic
+                    && e.name().as_ref() == STYLE
+                    && attribute(e, "style:name").is_some_and(|name| {
+                        AutoStyles::is_generated_name(&name, MASTER_STYLE_PREFIX)
+                    }) =>
+            {
+                if matches!(event, Event::Start(_)) {
+                    kept = Some((Kept::Dropped, String::new(), Vec::new(), 1));
+                }
+                continue;
+            }
+            Event::Start(e) if e.name().as_ref() == AUTOMATIC_STYLES => {
+                seen_automatic = true;
+                in_automatic = true;
+            }
+            Event::Start(e) if e.name().as_ref() == MASTER_STYLES => {
+                if !seen_automatic {
+                    write_automatic_section(setup, &layouts, &autos, &mut writer)?;
+                    seen_automatic = true;
+                }
+                seen_master = true;
+            }
+            Event::Empty(e) if e.name().as_ref() == AUTOMATIC_STYLES => {
+                write_automatic_section(setup, &layouts, &autos, &mut writer)?;
+                seen_automatic = true;
+                continue;
+            }
+            Event::Empty(e) if e.name().as_ref() == MASTER_STYLES => {
+                write_master_section(setup, &masters, &autos, &mut writer)?;
+                seen_master = true;
+                continue;
+            }
+            Event::End(e) if e.name().as_ref() == AUTOMATIC_STYLES => {
+                in_automatic = false;
+                autos.write(&mut writer)?;
+                for layout in &setup.layouts {
+                    write_page_layout(layout, layouts.get(&layout.name), &mut writer)?;
+                }
+            }
+            Event::End(e) if e.name().as_ref() == MASTER_STYLES => {
+                for master in &setup.master_pages {
+                    write_master_page(master, masters.get(&master.name), &autos, &mut writer)?;
+                }
+            }
+            // The body, or the end of the root element.
+            Event::Start(e) if e.name().as_ref() == b"office:body" => {
+                if !seen_automatic {
+                    write_automatic_section(setup, &layouts, &autos, &mut writer)?;
+                    seen_automatic = true;
+                }
+                if !seen_master {
+                    write_master_section(setup, &masters, &autos, &mut writer)?;
+                    seen_master = true;
+                }
+            }
+            Event::End(_) if depth == 1 && !seen_master => {
+                if !seen_automatic {
+                    write_automatic_section(setup, &layouts, &autos, &mut writer)?;
+                }
+                write_master_section(setup, &masters, &autos, &mut writer)?;
+                seen_master = true;
+            }
+            _ => {}
+        }
+
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+        writer.write_event(event).map_err(|e| e.to_string())?;
+    }
+
+    String::from_utf8(writer.into_inner().into_inner()).map_err(|e| e.to_string())
+}
+
+/// Collects the automatic styles of every header and footer, avoiding the
+/// names defined in `xml` other than old header and footer styles.
+fn header_footer_styles(xml: &str, setup: &PageSetup) -> AutoStyles {
+    let reserved: Vec<&str> = xml
+        .split("style:name=\"")
+        .skip(1)
+        .filter_map(|s| s.split('"').next())
+        .filter(|name| !AutoStyles::is_generated_name(name, MASTER_STYLE_PREFIX))
+        .collect();
+    let blocks: Vec<Block> = setup.header_footer_blocks().flatten().cloned().collect();
+    AutoStyles::collect_prefixed(&blocks, MASTER_STYLE_PREFIX, &reserved)
+}
+
+/// Writes an `office:automatic-styles` section holding the header and
+/// footer styles and the layouts.
+fn write_automatic_section(
+    setup: &PageSetup,
+    kept: &HashMap<String, Vec<Event>>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    writer
+        .write_event(Event::Start(BytesStart::new("office:automatic-styles")))
+        .map_err(|e| e.to_string())?;
+    autos.write(writer)?;
+    for layout in &setup.layouts {
+        write_page_layout(layout, kept.get(&layout.name), writer)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("office:automatic-styles")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes an `office:master-styles` section holding the master pages.
+fn write_master_section(
+    setup: &PageSetup,
+    kept: &HashMap<String, Vec<Event>>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    writer
+        .write_event(Event::Start(BytesStart::new("office:master-styles")))
+        .map_err(|e| e.to_string())?;
+    for master in &setup.master_pages {
+        write_master_page(master, kept.get(&master.name), autos, writer)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("office:master-styles")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a `style:page-layout`, rewriting the element `kept` from the old
+/// XML when there is one.
+fn write_page_layout(
+    layout: &PageLayout,
+    kept: Option<&Vec<Event>>,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let events = kept.map(Vec::as_slice).unwrap_or_default();
+    let old = match events.first() {
+        Some(Event::Start(e) | Event::Empty(e)) => Some(e),
+        _ => None,
+    };
+    let mut start = BytesStart::new("style:page-layout");
+    start.push_attribute(("style:name", layout.name.as_str()));
+    if let Some(old) = old {
+        start.extend_attributes(
+            old.attributes()
+                .filter_map(Result::ok)
+                .filter(|a| a.key.as_ref() != b"style:name"),
+        );
+    }
+    writer
+        .write_event(Event::Start(start))
+        .map_err(|e| e.to_string())?;
+
+    let children = match events.first() {
+        Some(Event::Start(_)) => &events[1..events.len() - 1],
+        _ => &[],
+    };
+    let is_props = |ev: &Event| matches!(ev, Event::Start(e) | Event::Empty(e) if e.name().as_ref() == PAGE_LAYOUT_PROPERTIES);
+    if !children.iter().any(is_props) {
+        writer
+            .write_event(Event::Empty(layout_properties(layout, None)))
+            .map_err(|e| e.to_string())?;
+    }
+    for event in children {
+        let event = match event {
+            Event::Start(e) if is_props(event) => Event::Start(layout_properties(layout, Some(e))),
+            Event::Empty(e) if is_props(event) => Event::Empty(layout_properties(layout, Some(e))),
+            other => other.clone(),
+        };
+        writer.write_event(event).map_err(|e| e.to_string())?;
+    }
+
+    writer
+        .write_event(Event::End(BytesEnd::new("style:page-layout")))
+        .map_err(|e| e.to_string())
+}
+
+/// Builds `style:page-layout-properties`, keeping the attributes of `old`
+/// that the model does not cover.
+///
+/// Lengths of `old` that still match the model are written back unchanged,
+/// so they are not rounded through points. Margins that `old` does not set
+/// are only written when they are not zero, the ODF default.
+fn layout_properties(layout: &PageLayout, old: Option<&BytesStart>) -> BytesStart<'static> {
+    let old_value = |key: &str| old.and_then(|e| attribute(e, key));
+    let length = |key: &str, pt: f64| match old_value(key) {
+        Some(value) if same_length(&value, pt) => value,
+        _ => format!("{:.4}cm", pt * 2.54 / 72.0),
+    };
+    let mut props = BytesStart::new("style:page-layout-properties");
+    props.push_attribute((
+        "fo:page-width",
+        length("fo:page-width", layout.width).as_str(),
+    ));
+    props.push_attribute((
+        "fo:page-height",
+        length("fo:page-height", layout.height).as_str(),
+    ));
+
+    let margins = [
+        ("fo:margin-top", layout.margin_top),
+        ("fo:margin-bottom", layout.margin_bottom),
+        ("fo:margin-left", layout.margin_left),
+        ("fo:margin-right", layout.margin_right),
+    ];
+    let shorthand = old_value("fo:margin")
+        .filter(|value| margins.iter().all(|(_, pt)| same_length(value, *pt)));
+    if let Some(shorthand) = &shorthand {
+        props.push_attribute(("fo:margin", shorthand.as_str()));
+    }
+    for (key, pt) in margins {
+        let present = old_value(key).is_some() || (shorthand.is_none() && pt != 0.0);
+        if present {
+            props.push_attribute((key, length(key, pt).as_str()));
+        }
+    }
+    props.push_attribute(("style:print-orientation", layout.orientation.to_odf_str()));
+    if let Some(old) = old {
+        for attr in old.attributes().filter_map(Result::ok) {
+            let key = String::from_utf8_lossy(attr.key.as_ref());
+            if !LAYOUT_ATTRIBUTES.contains(&key.as_ref()) {
+                props.push_attribute(attr);
+            }
+        }
+    }
+    props
+}
+
+/// Writes a `style:master-page` with its headers and footers, keeping the
+/// other attributes and children of the element `kept` from the old XML when
+/// there is one.
+fn write_master_page(
+    master: &MasterPage,
+    kept: Option<&Vec<Event>>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut start = BytesStart::new("style:master-page");
+    start.push_attribute(("style:name", master.name.as_str()));
+    if let Some(display_name) = &master.display_name {
+        start.push_attribute(("style:display-name", display_name.as_str()));
+    }
+    start.push_attribute(("style:page-layout-name", master.page_layout.as_str()));
+    if let Some(next) = &master.next {
+        start.push_attribute(("style:next-style-name", next.as_str()));
+    }
+
+    let (old, children) = match kept.map(Vec::as_slice) {
+        Some([Event::Start(old), rest @ .., _]) => (Some(old), rest),
+        Some([Event::Empty(old)]) => (Some(old), &[][..]),
+        _ => (None, &[][..]),
+    };
+    if let Some(old) = old {
+        for attr in old.attributes().filter_map(Result::ok) {
+            let key = String::from_utf8_lossy(attr.key.as_ref());
+            if !MASTER_ATTRIBUTES.contains(&key.as_ref()) {
+                start.push_attribute(attr);
+            }
+        }
+    }
+    if master.header.is_none() && master.footer.is_none() && children.is_empty() {
+        return writer
+            .write_event(Event::Empty(start))
+            .map_err(|e| e.to_string());
+    }
+    writer
+        .write_event(Event::Start(start))
+        .map_err(|e| e.to_string())?;
+
+    for (kind, region) in [("header", &master.header), ("footer", &master.footer)] {
+        if let Some(region) = region {
+            write_header_footer(kind, region, autos, writer)?;
+        }
+    }
+
+    // The old children other than headers and footers.
+    let mut skipped = 0usize;
+    for event in children {
+        if skipped > 0 {
+            match event {
+                Event::Start(_) => skipped += 1,
+                Event::End(_) => skipped -= 1,
+                _ => {}
+            }
+            continue;
+        }
+        if let Event::Start(e) | Event::Empty(e) = event {
+            let name = e.name();
+            if name.as_ref().starts_with(b"style:header")
+                || name.as_ref().starts_with(b"style:footer")
+            {
+                skipped = usize::from(matches!(event, Event::Start(_)));
+                continue;
+            }
+        }
+        writer
+            .write_event(event.clone())
+            .map_err(|e| e.to_string())?;
+    }
+
+    writer
+        .write_event(Event::End(BytesEnd::new("style:master-page")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes `style:header` or `style:footer` (`kind`) with its `-left` and
+/// `-first` variants.
+fn write_header_footer(
+    kind: &str,
+    region: &HeaderFooter,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let variants = [
+        (format!("style:{kind}"), Some(&region.content)),
+        (format!("style:{kind}-left"), region.left.as_ref()),
+        (format!("style:{kind}-first"), region.first.as_ref()),
+    ];
+    for (tag, blocks) in variants {
+        let Some(blocks) = blocks else {
+            continue;
+        };
+        writer
+            .write_event(Event::Start(BytesStart::new(tag.as_str())))
+            .map_err(|e| e.to_string())?;
+        write_blocks(blocks, autos, writer)?;
+        writer
+            .write_event(Event::End(BytesEnd::new(tag.as_str())))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether an ODF length string is `pt` points, to within the hundredth of a
+/// point that page lengths are parsed to.
+fn same_length(value: &str, pt: f64) -> bool {
+    length_to_cm(value).is_some_and(|cm| (cm * 72.0 / 2.54 - pt).abs() < 0.006)
+}
+
+fn attribute(e: &BytesStart, key: &str) -> Option<String> {
+    e.attributes()
+        .filter_map(Result::ok)
+        .find(|a| a.key.as_ref() == key.as_bytes())
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_core::PageOrientation;
+
+    fn letter() -> PageSetup {
+        PageSetup {
+            layouts: vec![PageLayout {
+                name: "pm1".to_string(),
+                width: 612.0,
+                height: 792.0,
+                orientation: PageOrientation::Portrait,
+                ..PageLayout::default()
+            }],
+            master_pages: vec![MasterPage {
+                name: "Standard".to_string(),
+                page_layout: "pm1".to_string(),
+                ..MasterPage::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn existing_layout_keeps_its_other_attributes_and_children() {
+        let xml = r#"<office:document-styles><office:automatic-styles><style:page-layout style:name="pm1" style:page-usage="mirrored"><style:page-layout-properties fo:page-width="21cm" fo:margin="2cm" style:num-format="1"/><style:header-style/></style:page-layout><style:page-layout style:name="unused"/></office:automatic-styles><office:master-styles><style:master-page style:name="Standard" style:page-layout-name="old" draw:style-name="dp1"><style:header><text:p>Title</text:p></style:header><draw:frame draw:name="Logo"/></style:master-page></office:master-styles></office:document-styles>"#;
+        let out = write_page_setup(xml, &letter()).unwrap();
+
+        assert!(out.contains(r#"style:page-usage="mirrored""#), "{out}");
+        assert!(out.contains(r#"fo:page-width="21.5900cm""#), "{out}");
+        assert!(!out.contains(r#"fo:margin="2cm""#), "{out}");
+        assert!(out.contains(r#"style:num-format="1""#), "{out}");
+        assert!(out.contains("<style:header-style/>"), "{out}");
+        assert!(!out.contains("unused"), "{out}");
+        assert!(
+            out.contains(r#"style:page-layout-name="pm1" draw:style-name="dp1""#),
+            "{out}"
+        );
+        // The model has no header; other children are kept.
+        assert!(!out.contains("Title"), "{out}");
+        assert!(
+            out.contains(r#"<draw:frame draw:name="Logo"/></style:master-page>"#),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn layouts_without_margins_are_written_without_them() {
+        let xml = r#"<office:document-styles><office:automatic-styles><style:page-layout style:name="pm1"><style:page-layout-properties fo:page-width="8.5in" fo:page-height="11in"/></style:page-layout></office:automatic-styles></office:document-styles>"#;
+        let mut setup = letter();
+        let layout = &mut setup.layouts[0];
+        layout.margin_top = 0.0;
+        layout.margin_bottom = 0.0;
+        layout.margin_left = 0.0;
+        layout.margin_right = 0.0;
+        let out = write_page_setup(xml, &setup).unwrap();
+
+        assert!(
+            out.contains(r#"fo:page-width="8.5in" fo:page-height="11in""#),
+            "{out}"
+        );
+        assert!(!out.contains("fo:margin"), "{out}");
+    }
+
+    #[test]
+    fn headers_and_footers_are_written_with_their_styles() {
+        use common_core::{HeaderFooter, Inline, TiptapMark};
+
+        let paragraph = |text: &str, marks| Block::Paragraph {
+            style_name: Some("Header".to_string()),
+            attrs: None,
+            content: vec![Inline::Text {
+                text: text.to_string(),
+                style_name: None,
+                marks,
+            }],
+        };
+        let mut setup = letter();
+        setup.master_pages[0].header = Some(HeaderFooter {
+            content: vec![paragraph("Report", vec![TiptapMark::Bold])],
+            first: Some(vec![]),
+            ..HeaderFooter::default()
+        });
+        setup.master_pages[0].footer = Some(HeaderFooter {
+            left: Some(vec![paragraph("Left", vec![])]),
+            ..HeaderFooter::default()
+        });
+        let xml = r#"<office:document-styles><office:automatic-styles><style:style style:name="MT1" style:family="text"/><style:style style:name="MP1" style:family="paragraph"><style:paragraph-properties/></style:style><style:style style:name="T1" style:family="text"/></office:automatic-styles><office:master-styles><style:master-page style:name="Standard" style:page-layout-name="pm1"><style:header><text:p text:style-name="MP1">Old</text:p></style:header><style:footer-left style:display="false"/></style:master-page></office:master-styles></office:document-styles>"#;
+        let out = write_page_setup(xml, &setup).unwrap();
+
+        // Old header styles are replaced; other automatic styles are kept.
+        assert!(!out.contains(r#""MP1""#), "{out}");
+        assert!(out.contains(r#"<style:style style:name="T1""#), "{out}");
+        assert!(
+            out.contains(r#"<style:style style:name="MT1" style:family="text""#),
+            "{out}"
+        );
+        assert!(!out.contains("Old"), "{out}");
+        assert!(
+            out.contains(r#"<style:header><text:p text:style-name="Header"><text:span text:style-name="MT1">Report</text:span></text:p></style:header><style:header-first></style:header-first>"#),
+            "{out}"
+        );
+        assert!(
+            out.contains(r#"<style:footer></style:footer><style:footer-left><text:p text:style-name="Header">Left</text:p></style:footer-left></style:master-page>"#),
+            "{out}"
+        );
+
+        // Writing again gives the same result.
+        assert_eq!(write_page_setup(&out, &setup).unwrap(), out);
+    }
+
+    #[test]
+    fn missing_sections_are_added() {
+        let xml = "<office:document-styles><office:styles/></office:document-styles>";
+        let out = write_page_setup(xml, &letter()).unwrap();
+        let auto = out
+            .find("<office:automatic-styles><style:page-layout")
+            .unwrap();
+        let master = out
+            .find(r#"<office:master-styles><style:master-page style:name="Standard""#)
+            .unwrap();
+        assert!(auto < master, "{out}");
+        assert!(
+            out.ends_with("</office:master-styles></office:document-styles>"),
+            "{out}"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! ODT section writer.
+//!
+//! Shared by the `content.xml` and FODT writers. The column layout of a
+//! section is written as a reference to its automatic section style.
+
+use common_core::{Block, SectionAttrs};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+
+use super::auto_styles::AutoStyles;
+use super::inlines::XmlWriter;
+use super::tables::WriteContent;
+
+/// Writes a `text:section` element around `content`.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub(crate) fn write_section(
+    name: &str,
+    attrs: Option<&SectionAttrs>,
+    protected: bool,
+    content: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+    write_content: WriteContent,
+) -> Result<(), String> {
+    let mut section = BytesStart::new("text:section");
+    if let Some(style) = autos.section_style(attrs) {
+        section.push_attribute(("text:style-name", style));
+    }
+    section.push_attribute(("text:name", name));
+    if protected {
+        section.push_attribute(("text:protected", "true"));
+    }
+    writer
+        .write_event(Event::Start(section))
+        .map_err(|e| e.to_string())?;
+    write_content(content, autos, writer)?;
+    writer
+        .write_event(Event::End(BytesEnd::new("text:section")))
+        .map_err(|e| e.to_string())
+}
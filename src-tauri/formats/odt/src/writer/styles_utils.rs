@@ -14,6 +14,31 @@
 
 //! Utilities for ODT style writing.
 
+/// Paragraph properties without a common prefix with the editor's ones.
+const PARAGRAPH_PROPERTIES: &[&str] = &[
+    "fo:keep-together",
+    "fo:text-align-last",
+    "fo:hyphenation-ladder-count",
+    "style:tab-stop-distance",
+    "style:writing-mode",
+    "style:line-break",
+    "style:punctuation-wrap",
+    "style:text-autospace",
+    "style:snap-to-layout-grid",
+    "style:register-true",
+    "style:page-number",
+    "style:vertical-align",
+    "style:justify-single-word",
+    "style:auto-text-indent",
+    "style:line-height-at-least",
+    "style:line-spacing",
+    "style:font-independent-line-spacing",
+    "style:join-border",
+    "style:shadow",
+    "text:number-lines",
+    "text:line-number",
+];
+
 /// Returns `true` if `key` is a paragraph-property attribute.
 pub fn is_paragraph_property(key: &str) -> bool {
     key.starts_with("fo:margin")
@@ -24,19 +49,42 @@ pub fn is_paragraph_property(key: &str) -> bool {
         || key.starts_with("fo:hyphenate")
         || key.starts_with("fo:break-")
         || key.starts_with("style:break-")
+        || key.starts_with("fo:padding")
+        || key.starts_with("fo:border")
+        || key.starts_with("style:border-line-width")
         || key == "fo:line-height"
         || key == "fo:keep-with-next"
         || key == "style:contextual-spacing"
+        || PARAGRAPH_PROPERTIES.contains(&key)
 }
 
 /// Returns `true` if `key` is a text-property attribute.
+///
+/// Never `true` for a key that [`is_paragraph_property`] accepts.
 pub fn is_text_property(key: &str) -> bool {
+    if is_paragraph_property(key) {
+        return false;
+    }
     key.starts_with("fo:font")
         || key.starts_with("fo:color")
         || key.starts_with("fo:font-size")
         || key.starts_with("fo:font-weight")
         || key.starts_with("fo:font-style")
         || key.starts_with("fo:text-transform")
+        || key.starts_with("fo:hyphenation-")
+        || key.starts_with("style:font-")
+        || key.starts_with("style:text-")
+        || key.starts_with("style:language-")
+        || key.starts_with("style:country-")
+        || key.starts_with("style:script-")
+        || key.starts_with("style:rfc-language-tag")
+        || key == "fo:language"
+        || key == "fo:country"
+        || key == "fo:script"
+        || key == "fo:letter-spacing"
+        || key == "fo:text-shadow"
+        || key == "style:letter-kerning"
+        || key == "style:use-window-font-color"
         || key == "fo:background-color"
         || key == "loki:colour"
 }
@@ -80,4 +128,16 @@ mod tests {
     fn style_contextual_spacing_is_paragraph_property() {
         assert!(is_paragraph_property("style:contextual-spacing"));
     }
+
+    #[test]
+    fn template_default_properties_are_classified() {
+        assert!(is_paragraph_property("style:tab-stop-distance"));
+        assert!(is_text_property("style:font-name"));
+        assert!(is_text_property("fo:language"));
+        assert!(is_text_property("style:text-underline-style"));
+        // Paragraph properties that look like text ones stay paragraph ones.
+        assert!(is_paragraph_property("style:text-autospace"));
+        assert!(!is_text_property("style:text-autospace"));
+        assert!(!is_text_property("fo:hyphenation-ladder-count"));
+    }
 }
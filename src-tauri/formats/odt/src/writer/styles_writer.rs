@@ -7,11 +7,15 @@ use std::collections::HashMap;
 use std::io::Cursor;
 
 use common_core::colour_management::Colour;
-use common_core::{StyleDefinition, StyleFamily};
+use common_core::style::is_default_style_name;
+use common_core::{OutlineStyle, PropertyGroup, StyleDefinition, StyleFamily};
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
 
+use crate::list_styles::{DEFAULT_BULLET_STYLE, DEFAULT_NUMBER_STYLE};
+use crate::writer::lists::{write_default_list_styles, write_named_list_style};
 use crate::writer::namespaces::push_styles_doc_ns;
+use crate::writer::outline::write_outline_style;
 use crate::writer::styles_utils::{coerce_line_height, is_paragraph_property, is_text_property};
 
 /// Generates a standalone `styles.xml` document string.
@@ -21,6 +25,7 @@ use crate::writer::styles_utils::{coerce_line_height, is_paragraph_property, is_
 /// Returns a `String` error if XML writing fails.
 pub fn styles_to_xml(
     styles: &HashMap<String, StyleDefinition>,
+    outline_style: Option<&OutlineStyle>,
     font_face_decls: &Option<String>,
     automatic_styles: &Option<String>,
     master_styles: &Option<String>,
@@ -37,7 +42,7 @@ pub fn styles_to_xml(
         .map_err(|e| e.to_string())?;
 
     write_preserved_section(&mut writer, font_face_decls)?;
-    write_styles_section(&mut writer, styles)?;
+    write_styles_section(&mut writer, styles, outline_style)?;
     write_preserved_section(&mut writer, automatic_styles)?;
     write_preserved_section(&mut writer, master_styles)?;
 
@@ -49,12 +54,17 @@ pub fn styles_to_xml(
     String::from_utf8(result).map_err(|e| e.to_string())
 }
 
-/// Writes the `<office:styles>` section with all named style definitions.
+/// Writes the `<office:styles>` section with all named style definitions
+/// and the outline style.
+///
+/// Default styles are written as `style:default-style` and list styles as
+/// `text:list-style`.
 ///
 /// Called by both [`styles_to_xml`] and the FODT writer.
 pub fn write_styles_section(
     writer: &mut Writer<Cursor<Vec<u8>>>,
     styles: &HashMap<String, StyleDefinition>,
+    outline_style: Option<&OutlineStyle>,
 ) -> Result<(), String> {
     writer
         .write_event(Event::Start(BytesStart::new("office:styles")))
@@ -69,12 +79,22 @@ pub fn write_styles_section(
             && style_name != "Strike"
             && style_name != "Superscript"
             && style_name != "Subscript"
+            && style_name != DEFAULT_BULLET_STYLE
+            && style_name != DEFAULT_NUMBER_STYLE
         {
-            write_style_definition(writer, style_name, style_def)?;
+            if style_def.family == StyleFamily::List {
+                write_named_list_style(style_name, style_def, writer)?;
+            } else {
+                write_style_definition(writer, style_name, style_def)?;
+            }
         }
     }
 
     write_builtin_styles(writer)?;
+    write_default_list_styles(writer)?;
+    if let Some(outline) = outline_style {
+        write_outline_style(outline, writer)?;
+    }
 
     writer
         .write_event(Event::End(BytesEnd::new("office:styles")))
@@ -86,22 +106,39 @@ fn write_style_definition(
     style_name: &str,
     style_def: &StyleDefinition,
 ) -> Result<(), String> {
-    let mut style_elem = BytesStart::new("style:style");
-    style_elem.push_attribute(("style:name", style_name));
+    // Default styles carry only their family and properties.
+    let is_default = is_default_style_name(style_name);
+    let tag = if is_default {
+        "style:default-style"
+    } else {
+        "style:style"
+    };
+    let mut style_elem = BytesStart::new(tag);
+    if !is_default {
+        style_elem.push_attribute(("style:name", style_name));
+    }
     style_elem.push_attribute(("style:family", style_def.family.to_odf_str()));
 
-    if let Some(ref parent) = style_def.parent {
-        style_elem.push_attribute(("style:parent-style-name", parent.as_str()));
+    // Parents linked to a default style on import have no XML counterpart.
+    let parent = style_def
+        .parent
+        .as_deref()
+        .filter(|p| !is_default_style_name(p));
+    if let Some(parent) = parent {
+        style_elem.push_attribute(("style:parent-style-name", parent));
     }
     if let Some(ref next) = style_def.next {
         style_elem.push_attribute(("style:next-style-name", next.as_str()));
     }
-    if let Some(ref display_name) = style_def.display_name {
-        style_elem.push_attribute(("style:display-name", display_name.as_str()));
+    if let Some(display_name) = style_def.display_name.as_deref().filter(|_| !is_default) {
+        style_elem.push_attribute(("style:display-name", display_name));
     }
     if let Some(level) = style_def.outline_level {
         style_elem.push_attribute(("style:outline-level", level.to_string().as_str()));
     }
+    if let Some(ref master) = style_def.master_page_name {
+        style_elem.push_attribute(("style:master-page-name", master.as_str()));
+    }
     if style_def.autocomplete == Some(true) {
         style_elem.push_attribute(("loki:autocomplete", "true"));
     }
@@ -110,7 +147,20 @@ fn write_style_definition(
         .write_event(Event::Start(style_elem))
         .map_err(|e| e.to_string())?;
 
-    if style_def.family == StyleFamily::Paragraph {
+    for group in PropertyGroup::ALL {
+        if let Some(props) = style_def.properties.get(&group) {
+            write_group_properties(writer, group, props)?;
+        }
+    }
+    // Paragraph styles always get paragraph properties; other families only
+    // when they have some, e.g. a table cell's alignment.
+    if style_def.family == StyleFamily::Paragraph
+        || (style_def.family != StyleFamily::Text
+            && style_def
+                .attributes
+                .keys()
+                .any(|k| is_paragraph_property(k)))
+    {
         write_paragraph_properties(writer, &style_def.attributes)?;
     }
     write_text_properties(
@@ -122,7 +172,24 @@ fn write_style_definition(
     )?;
 
     writer
-        .write_event(Event::End(BytesEnd::new("style:style")))
+        .write_event(Event::End(BytesEnd::new(tag)))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes the properties element of a group other than paragraph and text.
+fn write_group_properties(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    group: PropertyGroup,
+    props: &HashMap<String, String>,
+) -> Result<(), String> {
+    let mut keys: Vec<&String> = props.keys().collect();
+    keys.sort();
+    let mut elem = BytesStart::new(group.element_name());
+    for key in keys {
+        elem.push_attribute((key.as_str(), props[key].as_str()));
+    }
+    writer
+        .write_event(Event::Empty(elem))
         .map_err(|e| e.to_string())
 }
 
@@ -181,6 +248,9 @@ fn write_text_properties(
             if typed_bg.is_some() && key == "fo:background-color" {
                 continue;
             }
+            if text_transform.is_some() && key == "fo:text-transform" {
+                continue;
+            }
             text_props.push_attribute((key.as_str(), value.as_str()));
             has_props = true;
         }
@@ -0,0 +1,319 @@
+//! ODT table writers.
+//!
+//! Shared by the `content.xml` and FODT writers, which differ only in how
+//! cell content is written. Leading rows of header cells go into
+//! `table:table-header-rows`, column widths are written as
+//! `table:table-column` elements, and the columns a spanning cell covers are
+//! filled with `table:covered-table-cell` placeholders.
+
+use common_core::{Block, CellAttrs};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+
+use super::auto_styles::AutoStyles;
+use super::inlines::XmlWriter;
+
+/// Writes the blocks inside a table cell.
+pub(crate) type WriteContent = fn(&[Block], &AutoStyles, &mut XmlWriter) -> Result<(), String>;
+
+/// The grid position of the cells of one row.
+struct RowLayout {
+    /// The first column of each cell.
+    starts: Vec<usize>,
+    /// The number of columns the row occupies, including cells spanning
+    /// down from rows above.
+    width: usize,
+}
+
+/// Returns the width in pixels of each column of a table, `None` where no
+/// cell gives one.
+#[must_use]
+pub fn column_widths(rows: &[Block]) -> Vec<Option<u32>> {
+    let layout = layout(rows);
+    let mut widths = vec![None; layout.iter().map(|r| r.width).max().unwrap_or(0)];
+    for (row, row_layout) in rows.iter().zip(&layout) {
+        for (cell, &start) in row_cells(row).iter().zip(&row_layout.starts) {
+            let Some(cw) = cell_attrs(cell).and_then(|a| a.colwidth.as_ref()) else {
+                continue;
+            };
+            if cw.len() != spans(cell).0 {
+                continue;
+            }
+            for (slot, &w) in widths[start..].iter_mut().zip(cw) {
+                slot.get_or_insert(w);
+            }
+        }
+    }
+    widths
+}
+
+/// Writes a `table:table` element with its columns, header rows and rows.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub(crate) fn write_table(
+    rows: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+    write_content: WriteContent,
+) -> Result<(), String> {
+    writer
+        .write_event(Event::Start(BytesStart::new("table:table")))
+        .map_err(|e| e.to_string())?;
+    write_columns(&column_widths(rows), autos, writer)?;
+
+    let layout = layout(rows);
+    let header_end = rows.iter().take_while(|r| is_header_row(r)).count();
+    if header_end > 0 {
+        writer
+            .write_event(Event::Start(BytesStart::new("table:table-header-rows")))
+            .map_err(|e| e.to_string())?;
+    }
+    for (i, (row, row_layout)) in rows.iter().zip(&layout).enumerate() {
+        if i == header_end && header_end > 0 {
+            writer
+                .write_event(Event::End(BytesEnd::new("table:table-header-rows")))
+                .map_err(|e| e.to_string())?;
+        }
+        write_row(
+            row_cells(row),
+            Some(row_layout),
+            autos,
+            writer,
+            write_content,
+        )?;
+    }
+    if header_end == rows.len() && header_end > 0 {
+        writer
+            .write_event(Event::End(BytesEnd::new("table:table-header-rows")))
+            .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("table:table")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a `table:table-row` outside of a table, without covered cells.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub(crate) fn write_lone_row(
+    cells: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+    write_content: WriteContent,
+) -> Result<(), String> {
+    write_row(cells, None, autos, writer, write_content)
+}
+
+/// Writes a `table:table-cell` for a cell or header cell.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub(crate) fn write_cell(
+    attrs: Option<&CellAttrs>,
+    content: &[Block],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+    write_content: WriteContent,
+) -> Result<(), String> {
+    let mut cell = BytesStart::new("table:table-cell");
+    if let Some(style) = autos.cell_style(attrs) {
+        cell.push_attribute(("table:style-name", style));
+    }
+    if let Some(a) = attrs {
+        if let Some(n) = a.colspan.filter(|&v| v > 1) {
+            cell.push_attribute(("table:number-columns-spanned", n.to_string().as_str()));
+        }
+        if let Some(n) = a.rowspan.filter(|&v| v > 1) {
+            cell.push_attribute(("table:number-rows-spanned", n.to_string().as_str()));
+        }
+    }
+    writer
+        .write_event(Event::Start(cell))
+        .map_err(|e| e.to_string())?;
+    write_content(content, autos, writer)?;
+    writer
+        .write_event(Event::End(BytesEnd::new("table:table-cell")))
+        .map_err(|e| e.to_string())
+}
+
+/// Returns `true` when every cell in a row is a header cell.
+fn is_header_row(row: &Block) -> bool {
+    let cells = row_cells(row);
+    !cells.is_empty() && cells.iter().all(|c| matches!(c, Block::TableHeader { .. }))
+}
+
+fn write_columns(
+    widths: &[Option<u32>],
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+) -> Result<(), String> {
+    let mut i = 0;
+    while i < widths.len() {
+        let repeat = widths[i..].iter().take_while(|&&w| w == widths[i]).count();
+        let mut column = BytesStart::new("table:table-column");
+        if let Some(style) = widths[i].and_then(|w| autos.column_style(w)) {
+            column.push_attribute(("table:style-name", style));
+        }
+        if repeat > 1 {
+            column.push_attribute(("table:number-columns-repeated", repeat.to_string().as_str()));
+        }
+        writer
+            .write_event(Event::Empty(column))
+            .map_err(|e| e.to_string())?;
+        i += repeat;
+    }
+    Ok(())
+}
+
+fn write_row(
+    cells: &[Block],
+    layout: Option<&RowLayout>,
+    autos: &AutoStyles,
+    writer: &mut XmlWriter,
+    write_content: WriteContent,
+) -> Result<(), String> {
+    writer
+        .write_event(Event::Start(BytesStart::new("table:table-row")))
+        .map_err(|e| e.to_string())?;
+    let mut column = 0;
+    for (i, cell) in cells.iter().enumerate() {
+        let start = layout
+            .and_then(|l| l.starts.get(i).copied())
+            .unwrap_or(column);
+        write_covered_cells(start.saturating_sub(column), writer)?;
+        match cell {
+            Block::TableCell { attrs, content } | Block::TableHeader { attrs, content } => {
+                write_cell(attrs.as_ref(), content, autos, writer, write_content)?
+            }
+            other => write_content(std::slice::from_ref(other), autos, writer)?,
+        }
+        let span = spans(cell).0;
+        write_covered_cells(span - 1, writer)?;
+        column = start + span;
+    }
+    if let Some(l) = layout {
+        write_covered_cells(l.width.saturating_sub(column), writer)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("table:table-row")))
+        .map_err(|e| e.to_string())
+}
+
+fn write_covered_cells(count: usize, writer: &mut XmlWriter) -> Result<(), String> {
+    for _ in 0..count {
+        writer
+            .write_event(Event::Empty(BytesStart::new("table:covered-table-cell")))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Places the cells of every row on the table grid, skipping the columns
+/// covered by cells that span down from rows above.
+fn layout(rows: &[Block]) -> Vec<RowLayout> {
+    // Rows still to be covered below, per column
+    let mut covered: Vec<usize> = Vec::new();
+    rows.iter()
+        .map(|row| {
+            let occupied: Vec<bool> = covered.iter().map(|&n| n > 0).collect();
+            for n in &mut covered {
+                *n = n.saturating_sub(1);
+            }
+            let mut starts = Vec::new();
+            let mut column = 0;
+            for cell in row_cells(row) {
+                while occupied.get(column) == Some(&true) {
+                    column += 1;
+                }
+                let (colspan, rowspan) = spans(cell);
+                if covered.len() < column + colspan {
+                    covered.resize(column + colspan, 0);
+                }
+                for n in &mut covered[column..column + colspan] {
+                    *n = rowspan - 1;
+                }
+                starts.push(column);
+                column += colspan;
+            }
+            let width = occupied
+                .iter()
+                .rposition(|&o| o)
+                .map_or(column, |last| column.max(last + 1));
+            RowLayout { starts, width }
+        })
+        .collect()
+}
+
+fn row_cells(row: &Block) -> &[Block] {
+    match row {
+        Block::TableRow { content } => content,
+        _ => &[],
+    }
+}
+
+fn cell_attrs(cell: &Block) -> Option<&CellAttrs> {
+    match cell {
+        Block::TableCell { attrs, .. } | Block::TableHeader { attrs, .. } => attrs.as_ref(),
+        _ => None,
+    }
+}
+
+/// Returns the `(columns, rows)` a cell spans, each at least 1.
+fn spans(cell: &Block) -> (usize, usize) {
+    let attrs = cell_attrs(cell);
+    let span = |n: Option<u32>| n.map_or(1, |n| n.max(1) as usize);
+    (
+        span(attrs.and_then(|a| a.colspan)),
+        span(attrs.and_then(|a| a.rowspan)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(colspan: Option<u32>, rowspan: Option<u32>, colwidth: Option<Vec<u32>>) -> Block {
+        Block::TableCell {
+            attrs: Some(CellAttrs {
+                colspan,
+                rowspan,
+                colwidth,
+                ..CellAttrs::default()
+            }),
+            content: vec![],
+        }
+    }
+
+    #[test]
+    fn layout_skips_columns_covered_from_above() {
+        let rows = vec![
+            Block::TableRow {
+                content: vec![cell(None, Some(2), None), cell(Some(2), None, None)],
+            },
+            Block::TableRow {
+                content: vec![cell(None, None, None), cell(None, None, None)],
+            },
+        ];
+        let layout = layout(&rows);
+        assert_eq!(layout[0].starts, vec![0, 1]);
+        assert_eq!(layout[1].starts, vec![1, 2]);
+        assert_eq!(layout[1].width, 3);
+    }
+
+    #[test]
+    fn column_widths_come_from_spanning_and_plain_cells() {
+        let rows = vec![
+            Block::TableRow {
+                content: vec![cell(Some(2), None, Some(vec![50, 70]))],
+            },
+            Block::TableRow {
+                content: vec![cell(None, None, None), cell(None, None, None)],
+            },
+        ];
+        assert_eq!(column_widths(&rows), vec![Some(50), Some(70)]);
+    }
+}
@@ -0,0 +1,155 @@
+//! ODT table of contents writer.
+//!
+//! Shared by the `content.xml` and FODT writers. The entries are written as
+//! LibreOffice writes them: one `Contents N` paragraph per heading, holding
+//! a link to the heading's bookmark with the text, a tab and the page
+//! number.
+
+use common_core::TableOfContents;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+
+use super::inlines::XmlWriter;
+
+/// The paragraph style of the entries at `level`.
+fn entry_style(level: u32) -> String {
+    format!("Contents_20_{level}")
+}
+
+/// Writes a `text:table-of-content` element.
+///
+/// # Errors
+///
+/// Returns a `String` error if XML writing fails.
+pub(crate) fn write_toc(toc: &TableOfContents, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut start = BytesStart::new("text:table-of-content");
+    start.push_attribute(("text:name", toc.name.as_str()));
+    start.push_attribute(("text:protected", "true"));
+    writer
+        .write_event(Event::Start(start))
+        .map_err(|e| e.to_string())?;
+
+    let mut source = BytesStart::new("text:table-of-content-source");
+    source.push_attribute(("text:outline-level", toc.outline_level.to_string().as_str()));
+    writer
+        .write_event(Event::Start(source))
+        .map_err(|e| e.to_string())?;
+    if let Some(title) = &toc.title {
+        let mut template = BytesStart::new("text:index-title-template");
+        template.push_attribute(("text:style-name", "Contents_20_Heading"));
+        write_text_element(template, title, writer)?;
+    }
+    for level in 1..=toc.outline_level {
+        write_entry_template(level, writer)?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("text:table-of-content-source")))
+        .map_err(|e| e.to_string())?;
+
+    writer
+        .write_event(Event::Start(BytesStart::new("text:index-body")))
+        .map_err(|e| e.to_string())?;
+    if let Some(title) = &toc.title {
+        let mut index_title = BytesStart::new("text:index-title");
+        index_title.push_attribute(("text:name", format!("{}_Head", toc.name).as_str()));
+        writer
+            .write_event(Event::Start(index_title))
+            .map_err(|e| e.to_string())?;
+        let mut p = BytesStart::new("text:p");
+        p.push_attribute(("text:style-name", "Contents_20_Heading"));
+        write_text_element(p, title, writer)?;
+        writer
+            .write_event(Event::End(BytesEnd::new("text:index-title")))
+            .map_err(|e| e.to_string())?;
+    }
+    for entry in &toc.entries {
+        let mut p = BytesStart::new("text:p");
+        p.push_attribute(("text:style-name", entry_style(entry.level).as_str()));
+        writer
+            .write_event(Event::Start(p))
+            .map_err(|e| e.to_string())?;
+        if let Some(target) = &entry.target {
+            let mut a = BytesStart::new("text:a");
+            a.push_attribute(("xlink:type", "simple"));
+            a.push_attribute(("xlink:href", format!("#{target}").as_str()));
+            writer
+                .write_event(Event::Start(a))
+                .map_err(|e| e.to_string())?;
+        }
+        writer
+            .write_event(Event::Text(BytesText::new(&entry.text)))
+            .map_err(|e| e.to_string())?;
+        if let Some(page) = &entry.page {
+            writer
+                .write_event(Event::Empty(BytesStart::new("text:tab")))
+                .map_err(|e| e.to_string())?;
+            writer
+                .write_event(Event::Text(BytesText::new(page)))
+                .map_err(|e| e.to_string())?;
+        }
+        if entry.target.is_some() {
+            writer
+                .write_event(Event::End(BytesEnd::new("text:a")))
+                .map_err(|e| e.to_string())?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new("text:p")))
+            .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new("text:index-body")))
+        .map_err(|e| e.to_string())?;
+
+    writer
+        .write_event(Event::End(BytesEnd::new("text:table-of-content")))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes the entry template of `level`: a link around the chapter number,
+/// the heading text, a dotted right tab and the page number.
+fn write_entry_template(level: u32, writer: &mut XmlWriter) -> Result<(), String> {
+    let mut template = BytesStart::new("text:table-of-content-entry-template");
+    template.push_attribute(("text:outline-level", level.to_string().as_str()));
+    template.push_attribute(("text:style-name", entry_style(level).as_str()));
+    writer
+        .write_event(Event::Start(template))
+        .map_err(|e| e.to_string())?;
+    for name in [
+        "text:index-entry-link-start",
+        "text:index-entry-chapter",
+        "text:index-entry-text",
+    ] {
+        writer
+            .write_event(Event::Empty(BytesStart::new(name)))
+            .map_err(|e| e.to_string())?;
+    }
+    let mut tab = BytesStart::new("text:index-entry-tab-stop");
+    tab.push_attribute(("style:type", "right"));
+    tab.push_attribute(("style:leader-char", "."));
+    writer
+        .write_event(Event::Empty(tab))
+        .map_err(|e| e.to_string())?;
+    for name in ["text:index-entry-page-number", "text:index-entry-link-end"] {
+        writer
+            .write_event(Event::Empty(BytesStart::new(name)))
+            .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new(
+            "text:table-of-content-entry-template",
+        )))
+        .map_err(|e| e.to_string())
+}
+
+/// Writes `start` with `text` as its only content.
+fn write_text_element(start: BytesStart, text: &str, writer: &mut XmlWriter) -> Result<(), String> {
+    let end = start.to_end().into_owned();
+    writer
+        .write_event(Event::Start(start))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::Text(BytesText::new(text)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_event(Event::End(end))
+        .map_err(|e| e.to_string())
+}
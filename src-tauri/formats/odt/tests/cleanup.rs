@@ -0,0 +1,351 @@
+//! Style cleanup integration tests.
+//!
+//! Direct formatting is grouped into proposals, applied, and the rewritten
+//! document is written and parsed back.
+
+use std::collections::HashMap;
+
+use common_core::style::encode_style_name;
+use common_core::{
+    Block, BlockAttrs, Inline, LinkAttrs, StyleDefinition, StyleFamily, TiptapAttrsInline,
+    TiptapMark,
+};
+use odt_format::cleanup::{
+    analyze_direct_formatting, apply_style_proposals, CleanupOptions, DirectFormatting,
+};
+use odt_format::Document;
+
+fn run(text: &str, marks: Vec<TiptapMark>) -> Inline {
+    Inline::Text {
+        text: text.to_string(),
+        style_name: None,
+        marks,
+    }
+}
+
+fn paragraph(style: &str, align: Option<&str>, indent: Option<u32>, content: Vec<Inline>) -> Block {
+    Block::Paragraph {
+        style_name: Some(style.to_string()),
+        attrs: Some(BlockAttrs {
+            text_align: align.map(str::to_string),
+            indent,
+            ..BlockAttrs::default()
+        }),
+        content,
+    }
+}
+
+fn link() -> TiptapMark {
+    TiptapMark::Link {
+        attrs: LinkAttrs {
+            href: "https://example.com".to_string(),
+            target: None,
+        },
+    }
+}
+
+fn document() -> Document {
+    let mut doc = Document::new();
+    doc.blocks = vec![
+        paragraph(
+            "Text Body",
+            Some("center"),
+            Some(1),
+            vec![run("a", vec![TiptapMark::Bold, TiptapMark::Italic])],
+        ),
+        Block::BulletList {
+            attrs: None,
+            content: vec![Block::ListItem {
+                content: vec![paragraph(
+                    "Text Body",
+                    Some("center"),
+                    Some(1),
+                    vec![run("b", vec![TiptapMark::Italic, TiptapMark::Bold, link()])],
+                )],
+            }],
+        },
+        paragraph("Text Body", Some("end"), None, vec![run("c", vec![])]),
+        paragraph("Text Body", None, Some(0), vec![]),
+    ];
+    doc
+}
+
+#[test]
+fn identical_formatting_is_grouped_into_proposals() {
+    let proposals = analyze_direct_formatting(&document(), &CleanupOptions::default());
+    assert_eq!(proposals.len(), 2, "single uses are below the threshold");
+
+    let para = proposals
+        .iter()
+        .find(|p| matches!(p.formatting, DirectFormatting::Paragraph(_)))
+        .unwrap();
+    assert_eq!(para.uses, 2);
+    assert_eq!(
+        para.style.name,
+        "Text_20_Body_20__28_Centered_2c__20_Indent_20_1_29_"
+    );
+    assert_eq!(
+        para.style.display_name.as_deref(),
+        Some("Text Body (Centered, Indent 1)")
+    );
+    assert_eq!(para.style.parent.as_deref(), Some("Text Body"));
+    assert_eq!(para.style.attributes["fo:text-align"], "center");
+    assert_eq!(para.style.attributes["fo:margin-left"], "1.270cm");
+    assert!(!para.existing);
+
+    let text = proposals
+        .iter()
+        .find(|p| matches!(p.formatting, DirectFormatting::Text(_)))
+        .unwrap();
+    assert_eq!(text.uses, 2, "mark order and links do not matter");
+    assert_eq!(
+        text.style.display_name.as_deref(),
+        Some("Character (Bold, Italic)")
+    );
+    assert_eq!(text.style.family, StyleFamily::Text);
+    assert_eq!(text.style.attributes["fo:font-weight"], "bold");
+    assert_eq!(text.style.attributes["fo:font-style"], "italic");
+
+    let all = analyze_direct_formatting(&document(), &CleanupOptions { min_uses: 1 });
+    assert_eq!(all.len(), 3);
+    assert_eq!(
+        all[2].style.display_name.as_deref(),
+        Some("Text Body (Right)")
+    );
+}
+
+#[test]
+fn identical_existing_styles_are_reused_and_names_do_not_clash() {
+    let mut doc = document();
+    let style =
+        |name: &str, family, parent: Option<&str>, attrs: &[(&str, &str)]| StyleDefinition {
+            name: name.to_string(),
+            family,
+            parent: parent.map(str::to_string),
+            next: None,
+            display_name: None,
+            attributes: attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            text_transform: None,
+            outline_level: None,
+            autocomplete: None,
+            master_page_name: None,
+            font_colour: None,
+            background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
+        };
+    doc.styles.insert(
+        "Strong Emphasis".to_string(),
+        style(
+            "Strong Emphasis",
+            StyleFamily::Text,
+            None,
+            &[("fo:font-weight", "bold"), ("fo:font-style", "italic")],
+        ),
+    );
+    let mut taken = style("Centered", StyleFamily::Paragraph, None, &[]);
+    taken.display_name = Some("Text Body (Centered, Indent 1)".to_string());
+    doc.styles.insert(taken.name.clone(), taken);
+
+    let proposals = analyze_direct_formatting(&doc, &CleanupOptions::default());
+    let text = proposals
+        .iter()
+        .find(|p| p.style.family == StyleFamily::Text)
+        .unwrap();
+    assert!(text.existing);
+    assert_eq!(text.style.name, "Strong Emphasis");
+    let para = proposals
+        .iter()
+        .find(|p| p.style.family == StyleFamily::Paragraph)
+        .unwrap();
+    assert_eq!(
+        para.style.display_name.as_deref(),
+        Some("Text Body (Centered, Indent 1) 2")
+    );
+    assert_eq!(
+        para.style.name,
+        encode_style_name("Text Body (Centered, Indent 1) 2")
+    );
+}
+
+#[test]
+fn applied_proposals_rewrite_blocks_and_survive_a_round_trip() {
+    let mut doc = document();
+    let proposals = analyze_direct_formatting(&doc, &CleanupOptions::default());
+    assert_eq!(apply_style_proposals(&mut doc, &proposals), 4);
+
+    let Block::BulletList { content, .. } = &doc.blocks[1] else {
+        panic!("expected a list");
+    };
+    let Block::ListItem { content } = &content[0] else {
+        panic!("expected a list item");
+    };
+    let Block::Paragraph {
+        style_name,
+        attrs,
+        content,
+    } = &content[0]
+    else {
+        panic!("expected a paragraph");
+    };
+    let para_name = encode_style_name("Text Body (Centered, Indent 1)");
+    let text_name = encode_style_name("Character (Bold, Italic)");
+    assert_eq!(style_name.as_ref(), Some(&para_name));
+    assert!(attrs.is_none());
+    let Inline::Text {
+        style_name, marks, ..
+    } = &content[0]
+    else {
+        panic!("expected text");
+    };
+    assert_eq!(style_name.as_ref(), Some(&text_name));
+    assert_eq!(marks, &vec![link()], "links are kept");
+
+    // Formatting no proposal covers is left alone.
+    assert!(matches!(
+        &doc.blocks[2],
+        Block::Paragraph { attrs: Some(_), .. }
+    ));
+
+    let reparsed = Document::from_xml(&doc.to_xml().unwrap()).unwrap();
+    let style = &reparsed.styles[&para_name];
+    assert_eq!(style.parent.as_deref(), Some("Text Body"));
+    assert_eq!(
+        style.display_name.as_deref(),
+        Some("Text Body (Centered, Indent 1)")
+    );
+    assert_eq!(style.attributes["fo:text-align"], "center");
+    assert_eq!(
+        reparsed.styles[&text_name].attributes["fo:font-weight"],
+        "bold"
+    );
+}
+
+#[test]
+fn named_span_styles_are_kept_on_rewritten_runs() {
+    let emphasis = || TiptapMark::NamedSpanStyle {
+        attrs: TiptapAttrsInline {
+            style_name: Some("Emphasis".to_string()),
+        },
+    };
+    let mut doc = Document::new();
+    doc.blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            run("a", vec![emphasis(), TiptapMark::Bold]),
+            run("b", vec![TiptapMark::Bold, emphasis()]),
+        ],
+    }];
+
+    let proposals = analyze_direct_formatting(&doc, &CleanupOptions::default());
+    assert_eq!(proposals.len(), 1);
+    let style = &proposals[0].style;
+    assert_eq!(style.parent.as_deref(), Some("Emphasis"));
+    assert_eq!(style.display_name.as_deref(), Some("Emphasis (Bold)"));
+    assert_eq!(apply_style_proposals(&mut doc, &proposals), 2);
+
+    let Block::Paragraph { content, .. } = &doc.blocks[0] else {
+        panic!("expected a paragraph");
+    };
+    let Inline::Text { marks, .. } = &content[0] else {
+        panic!("expected text");
+    };
+    assert_eq!(
+        marks,
+        &vec![TiptapMark::NamedSpanStyle {
+            attrs: TiptapAttrsInline {
+                style_name: Some(style.name.clone()),
+            },
+        }]
+    );
+}
+
+#[test]
+fn headings_are_grouped_by_level_apart_from_paragraphs() {
+    let centred = || {
+        Some(BlockAttrs {
+            text_align: Some("center".to_string()),
+            ..BlockAttrs::default()
+        })
+    };
+    let heading = |level| Block::Heading {
+        level,
+        style_name: None,
+        attrs: centred(),
+        content: vec![],
+    };
+    let para = || Block::Paragraph {
+        style_name: None,
+        attrs: centred(),
+        content: vec![],
+    };
+    let mut doc = Document::new();
+    doc.styles.insert(
+        "Heading_20_1".to_string(),
+        StyleDefinition {
+            name: "Heading_20_1".to_string(),
+            family: StyleFamily::Paragraph,
+            parent: None,
+            next: None,
+            display_name: Some("Heading 1".to_string()),
+            attributes: HashMap::new(),
+            text_transform: None,
+            outline_level: Some(1),
+            autocomplete: None,
+            master_page_name: None,
+            font_colour: None,
+            background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
+        },
+    );
+    doc.blocks = vec![
+        para(),
+        heading(1),
+        heading(2),
+        para(),
+        heading(1),
+        heading(2),
+    ];
+
+    let proposals = analyze_direct_formatting(&doc, &CleanupOptions::default());
+    let groups: Vec<(Option<&str>, Option<u32>, usize)> = proposals
+        .iter()
+        .map(|p| (p.base.as_deref(), p.level, p.uses))
+        .collect();
+    assert_eq!(groups.len(), 3);
+    for group in [
+        (None, None, 2),
+        (Some("Heading_20_1"), Some(1), 2),
+        (Some("Heading_20_2"), Some(2), 2),
+    ] {
+        assert!(groups.contains(&group), "{group:?} missing from {groups:?}");
+    }
+    let h1 = proposals.iter().find(|p| p.level == Some(1)).unwrap();
+    assert_eq!(
+        h1.style.display_name.as_deref(),
+        Some("Heading 1 (Centered)")
+    );
+    assert_eq!(h1.style.outline_level, Some(1));
+
+    assert_eq!(apply_style_proposals(&mut doc, &proposals), 6);
+    let names: Vec<(bool, Option<&str>)> = doc
+        .blocks
+        .iter()
+        .map(|block| match block {
+            Block::Paragraph { style_name, .. } => (false, style_name.as_deref()),
+            Block::Heading { style_name, .. } => (true, style_name.as_deref()),
+            other => panic!("unexpected block {other:?}"),
+        })
+        .collect();
+    assert_eq!(names[0], names[3]);
+    assert_eq!(names[1], (true, Some(h1.style.name.as_str())));
+    assert_eq!(names[1], names[4]);
+    assert_eq!(names[2], names[5]);
+    assert_ne!(names[0].1, names[1].1);
+    assert_ne!(names[1].1, names[2].1);
+}
@@ -0,0 +1,107 @@
+//! Document comparison integration tests.
+//!
+//! Each test compares two drafts, writes the redline to FODT and reads it
+//! back:
+//!
+//!   (old, new) → compare_documents → to_xml → parse_document → Document
+//!
+//! Accepting every change in the result must give the new draft's text and
+//! rejecting every change the old draft's.
+
+use common_core::{Block, Inline};
+use odt_format::compare::{compare_documents, CompareOptions};
+use odt_format::{parser::parse_document, Document};
+
+fn para(text: &str) -> Block {
+    Block::Paragraph {
+        style_name: Some("Standard".to_string()),
+        attrs: None,
+        content: vec![Inline::Text {
+            text: text.to_string(),
+            style_name: None,
+            marks: vec![],
+        }],
+    }
+}
+
+fn document(blocks: Vec<Block>) -> Document {
+    let mut doc = Document::new();
+    doc.blocks = blocks;
+    doc
+}
+
+/// The plain text of each paragraph, including those inside lists.
+fn texts(blocks: &[Block]) -> Vec<String> {
+    let mut out = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Paragraph { content, .. } | Block::Heading { content, .. } => out.push(
+                content
+                    .iter()
+                    .filter_map(|inline| match inline {
+                        Inline::Text { text, .. } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            Block::BulletList { content, .. } | Block::ListItem { content } => {
+                out.extend(texts(content))
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[test]
+fn redline_survives_fodt_and_accepts_to_new_and_rejects_to_old() {
+    let old = document(vec![
+        para("Dear editor,"),
+        para("Please find the first draft attached."),
+        para("This paragraph will go."),
+        Block::BulletList {
+            attrs: None,
+            content: vec![Block::ListItem {
+                content: vec![para("One point")],
+            }],
+        },
+        para("Regards"),
+    ]);
+    let new = document(vec![
+        para("Dear editor,"),
+        para("Please find the second draft attached."),
+        Block::BulletList {
+            attrs: None,
+            content: vec![Block::ListItem {
+                content: vec![para("One sharper point")],
+            }],
+        },
+        para("Regards"),
+        para("A postscript."),
+    ]);
+    let options = CompareOptions {
+        author: Some("Compare".to_string()),
+        date: Some("2024-06-01T12:00:00".to_string()),
+    };
+    let redline = compare_documents(&old, &new, &options);
+    let xml = redline.to_xml().expect("to_xml failed");
+    assert!(xml.contains("<text:tracked-changes text:track-changes=\"false\">"));
+
+    let parsed = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(
+        parsed.tracked_changes().len(),
+        redline.tracked_changes().len()
+    );
+    assert!(parsed
+        .tracked_changes()
+        .iter()
+        .all(|c| c.author.as_deref() == Some("Compare")));
+
+    let mut accepted = parsed.clone();
+    accepted.accept_all_changes();
+    assert_eq!(texts(&accepted.blocks), texts(&new.blocks));
+
+    let mut rejected = parsed;
+    rejected.reject_all_changes();
+    assert_eq!(texts(&rejected.blocks), texts(&old.blocks));
+}
@@ -81,9 +81,9 @@ fn test_huge_number_of_styles() {
     xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}"
     xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
     office:version="1.3">
-  <office:automatic-styles>
+  <office:styles>
     {style_defs}
-  </office:automatic-styles>
+  </office:styles>
   <office:body>
     <office:text><text:p text:style-name="S0">Test</text:p></office:text>
   </office:body>
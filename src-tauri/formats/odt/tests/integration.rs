@@ -6,12 +6,13 @@
 
 use std::collections::HashMap;
 
-use common_core::{Block, Inline, Metadata, TiptapNode};
+use common_core::{Block, DocumentStatistics, Inline, Metadata, TiptapNode, UserDefinedField};
 use odt_format::parser::parse_document;
 use odt_format::tiptap::from_tiptap::tiptap_to_document;
 use odt_format::tiptap::to_tiptap::document_to_tiptap;
 use odt_format::writer::fodt::to_xml;
 use odt_format::writer::meta::to_meta_xml;
+use odt_format::Document;
 
 const NS_OFFICE: &str = "urn:oasis:names:tc:opendocument:xmlns:office:1.0";
 const NS_TEXT: &str = "urn:oasis:names:tc:opendocument:xmlns:text:1.0";
@@ -204,7 +205,9 @@ fn full_pipeline_fodt_paragraph() {
 fn to_xml_empty_document_produces_valid_fodt() {
     let xml = to_xml(
         &[],
+        false,
         &HashMap::new(),
+        None,
         &Metadata::default(),
         &None,
         &None,
@@ -228,7 +231,9 @@ fn to_xml_with_paragraph() {
     }];
     let xml = to_xml(
         &blocks,
+        false,
         &HashMap::new(),
+        None,
         &Metadata::default(),
         &None,
         &None,
@@ -239,6 +244,97 @@ fn to_xml_with_paragraph() {
     assert!(xml.contains("text:p"));
 }
 
+#[test]
+fn update_fodt_regenerates_automatic_styles() {
+    let old = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" office:version="1.3">
+  <office:automatic-styles>
+    <style:style style:name="P1" style:family="paragraph">
+      <style:paragraph-properties fo:text-align="center"/>
+    </style:style>
+    <style:style style:name="MP1" style:family="paragraph">
+      <style:paragraph-properties fo:text-align="end"/>
+    </style:style>
+    <style:page-layout style:name="pm1"/>
+  </office:automatic-styles>
+  <office:master-styles>
+    <style:master-page style:name="Standard" style:page-layout-name="pm1">
+      <style:header><text:p text:style-name="MP1">Header</text:p></style:header>
+    </style:master-page>
+  </office:master-styles>
+  <office:body>
+    <office:text><text:p text:style-name="P1">Centred</text:p></office:text>
+  </office:body>
+</office:document>"#
+    );
+    let doc = Document::from_xml(&old).unwrap();
+    let updated = doc.update_fodt(&old).unwrap();
+
+    assert_eq!(updated.matches(r#"style:name="P1""#).count(), 1);
+    assert!(
+        updated.contains(r#"style:name="MP1""#),
+        "header style dropped"
+    );
+    assert!(
+        updated.contains(r#"style:name="pm1""#),
+        "page layout dropped"
+    );
+
+    let reparsed = Document::from_xml(&updated).unwrap();
+    let Block::Paragraph { attrs, .. } = &reparsed.blocks[0] else {
+        panic!("expected Paragraph");
+    };
+    let align = attrs.as_ref().and_then(|a| a.text_align.as_deref());
+    assert_eq!(align, Some("center"));
+}
+
+#[test]
+fn automatic_paragraph_styles_keep_every_property() {
+    let old = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" office:version="1.3">
+  <office:automatic-styles>
+    <style:style style:name="P1" style:family="paragraph" style:parent-style-name="Standard" style:master-page-name="Landscape">
+      <style:paragraph-properties fo:text-align="center" fo:margin-left="1.5cm" fo:margin-top="0.42cm" fo:margin-bottom="0.21cm" fo:line-height="150%" fo:keep-with-next="always">
+        <style:tab-stops><style:tab-stop style:position="8cm" style:type="right"/></style:tab-stops>
+      </style:paragraph-properties>
+    </style:style>
+  </office:automatic-styles>
+  <office:body>
+    <office:text><text:p text:style-name="P1">Spaced</text:p></office:text>
+  </office:body>
+</office:document>"#
+    );
+    let doc = Document::from_xml(&old).unwrap();
+    let Block::Paragraph { attrs, .. } = &doc.blocks[0] else {
+        panic!("expected Paragraph");
+    };
+    let attrs = attrs.as_ref().expect("direct formatting");
+    assert_eq!(attrs.text_align.as_deref(), Some("center"));
+    // 1.5cm is not a whole number of indent steps, so it stays a margin.
+    assert_eq!(attrs.indent, None);
+    assert_eq!(attrs.properties["fo:margin-left"], "1.5cm");
+    assert_eq!(attrs.properties["fo:line-height"], "150%");
+    assert_eq!(attrs.tab_stops.len(), 1);
+    assert_eq!(attrs.master_page_name.as_deref(), Some("Landscape"));
+
+    let updated = doc.update_fodt(&old).unwrap();
+    for expected in [
+        r#"style:master-page-name="Landscape""#,
+        r#"fo:margin-top="0.42cm""#,
+        r#"fo:margin-bottom="0.21cm""#,
+        r#"fo:keep-with-next="always""#,
+        r#"style:position="8cm""#,
+    ] {
+        assert!(updated.contains(expected), "{expected} lost:\n{updated}");
+    }
+    let reparsed = Document::from_xml(&updated).unwrap();
+    assert_eq!(reparsed.blocks, doc.blocks);
+}
+
 #[test]
 fn to_meta_xml_round_trip_title() {
     let meta = Metadata {
@@ -252,3 +348,50 @@ fn to_meta_xml_round_trip_title() {
     assert_eq!(reparsed.metadata.title.as_deref(), Some("Round-trip Title"));
     assert_eq!(reparsed.metadata.creator.as_deref(), Some("Author"));
 }
+
+#[test]
+fn extended_metadata_round_trips_and_statistics_are_recomputed() {
+    let meta = Metadata {
+        keywords: vec!["odf".to_string(), "meta".to_string()],
+        date: Some("2024-03-01T12:00:00".to_string()),
+        editing_cycles: Some(5),
+        editing_duration: Some("PT2H".to_string()),
+        initial_creator: Some("First Author".to_string()),
+        user_defined: vec![UserDefinedField {
+            name: "Reviewed".to_string(),
+            value_type: Some("boolean".to_string()),
+            value: "true".to_string(),
+        }],
+        statistics: Some(DocumentStatistics {
+            page_count: Some(3),
+            word_count: 999,
+            ..DocumentStatistics::default()
+        }),
+        generator: Some("AppThere Loki".to_string()),
+        ..Metadata::default()
+    };
+    let reparsed = parse_document(&to_meta_xml(&meta).unwrap()).unwrap();
+    assert_eq!(reparsed.metadata, meta);
+
+    let mut doc = Document::new();
+    doc.metadata = meta;
+    doc.blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Text {
+            text: "Two words".to_string(),
+            style_name: None,
+            marks: vec![],
+        }],
+    }];
+    let saved = Document::from_xml(&doc.to_xml().unwrap()).unwrap();
+    let stats = saved.metadata.statistics.unwrap();
+    assert_eq!(stats.word_count, 2);
+    assert_eq!(stats.paragraph_count, 1);
+    assert_eq!(stats.page_count, Some(3), "the page count is kept");
+    assert_eq!(saved.metadata.keywords, vec!["odf", "meta"]);
+    assert_eq!(
+        saved.metadata.initial_creator.as_deref(),
+        Some("First Author")
+    );
+}
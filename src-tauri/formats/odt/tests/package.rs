@@ -0,0 +1,309 @@
+//! ODT package integration tests.
+//!
+//! Each test writes an [`OdtPackage`] to ZIP bytes and reads it back:
+//!
+//!   Document → OdtPackage → .odt bytes → OdtPackage → Document
+//!
+//! These check that the package keeps the entries it does not understand and
+//! that embedded pictures survive a save and load.
+
+use std::io::{Cursor, Read, Write};
+
+use common_core::{
+    Block, HeaderFooter, Inline, MasterPage, Metadata, PageLayout, PageSetup, TiptapMark,
+};
+use odt_format::package::PackageEntry;
+use odt_format::{Document, OdtPackage};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+const PNG_URI: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+fn document() -> Document {
+    let mut doc = Document::new();
+    doc.blocks = vec![
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![Inline::Text {
+                text: "Hello".to_string(),
+                style_name: None,
+                marks: vec![],
+            }],
+        },
+        Block::Image {
+            src: PNG_URI.to_string(),
+            alt: Some("dot".to_string()),
+            title: None,
+            frame: None,
+            caption: None,
+        },
+    ];
+    doc.metadata = Metadata {
+        title: Some("Package".to_string()),
+        ..Metadata::default()
+    };
+    doc
+}
+
+fn entry_names(bytes: &[u8]) -> Vec<String> {
+    let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+    archive.file_names().map(str::to_string).collect()
+}
+
+fn read_entry(bytes: &[u8], name: &str) -> String {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut out = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut out)
+        .unwrap();
+    out
+}
+
+#[test]
+fn document_round_trips_through_package_bytes() {
+    let bytes = OdtPackage::from_document(&document(), None)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    let names = entry_names(&bytes);
+    assert_eq!(names[0], "mimetype", "mimetype must be the first entry");
+    let picture = names
+        .iter()
+        .find(|n| n.starts_with("Pictures/"))
+        .expect("image should be embedded");
+    assert!(picture.ends_with(".png"));
+    let manifest = read_entry(&bytes, "META-INF/manifest.xml");
+    assert!(manifest.contains(picture.as_str()));
+
+    let doc = OdtPackage::from_bytes(&bytes)
+        .unwrap()
+        .to_document()
+        .unwrap();
+    assert_eq!(doc.metadata.title.as_deref(), Some("Package"));
+    assert_eq!(doc.blocks.len(), 2);
+    match &doc.blocks[1] {
+        Block::Image { src, alt, .. } => {
+            assert_eq!(src, PNG_URI);
+            assert_eq!(alt.as_deref(), Some("dot"));
+        }
+        other => panic!("expected an image, got {other:?}"),
+    }
+}
+
+#[test]
+fn update_keeps_settings_thumbnails_and_unknown_entries() {
+    let mut package = OdtPackage::from_document(&document(), None).unwrap();
+    package.settings = Some("<office:document-settings/>".to_string());
+    package.thumbnails.push(PackageEntry {
+        path: "Thumbnails/thumbnail.png".to_string(),
+        data: b"thumb".to_vec(),
+    });
+    package.other.push(PackageEntry {
+        path: "Configurations2/accelerator/current.xml".to_string(),
+        data: b"<x/>".to_vec(),
+    });
+    let bytes = package.to_bytes().unwrap();
+
+    let mut reopened = OdtPackage::from_bytes(&bytes).unwrap();
+    let mut doc = reopened.to_document().unwrap();
+    doc.blocks.truncate(1);
+    reopened.update(&doc, None).unwrap();
+    let bytes = reopened.to_bytes().unwrap();
+
+    let names = entry_names(&bytes);
+    assert!(names.contains(&"settings.xml".to_string()));
+    assert!(names.contains(&"Thumbnails/thumbnail.png".to_string()));
+    assert!(names.contains(&"Configurations2/accelerator/current.xml".to_string()));
+    assert!(
+        !names.iter().any(|n| n.starts_with("Pictures/")),
+        "the removed image should no longer be stored"
+    );
+    let manifest = read_entry(&bytes, "META-INF/manifest.xml");
+    assert!(manifest.contains("settings.xml"));
+    assert!(manifest.contains("Thumbnails/thumbnail.png"));
+    assert!(!manifest.contains("Pictures/"));
+}
+
+#[test]
+fn package_without_content_xml_is_rejected() {
+    let mut buffer = Cursor::new(Vec::new());
+    let mut zip = ZipWriter::new(&mut buffer);
+    zip.start_file("mimetype", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(odt_format::package::MIMETYPE.as_bytes())
+        .unwrap();
+    zip.finish().unwrap();
+
+    let err = OdtPackage::from_bytes(buffer.get_ref()).unwrap_err();
+    assert!(err.contains("content.xml"), "unexpected error: {err}");
+}
+
+#[test]
+fn package_files_can_be_saved_and_opened() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("saved.odt");
+    OdtPackage::from_document(&document(), None)
+        .unwrap()
+        .save(&path)
+        .unwrap();
+    let doc = OdtPackage::open(&path).unwrap().to_document().unwrap();
+    assert_eq!(doc.blocks.len(), 2);
+}
+
+#[test]
+fn header_images_are_embedded_and_inlined() {
+    let mut doc = document();
+    doc.blocks.truncate(1);
+    doc.page_setup = Some(PageSetup {
+        layouts: vec![PageLayout::default()],
+        master_pages: vec![MasterPage {
+            name: "Standard".to_string(),
+            page_layout: PageLayout::default().name,
+            header: Some(HeaderFooter {
+                content: vec![Block::Image {
+                    src: PNG_URI.to_string(),
+                    alt: Some("logo".to_string()),
+                    title: None,
+                    frame: None,
+                    caption: None,
+                }],
+                ..HeaderFooter::default()
+            }),
+            ..MasterPage::default()
+        }],
+    });
+
+    let mut package = OdtPackage::from_document(&doc, None).unwrap();
+    assert_eq!(package.pictures.len(), 1);
+    let styles = package.styles.as_deref().unwrap();
+    assert!(styles.contains(package.pictures[0].path.as_str()));
+
+    // Saving again keeps the header picture even though the body has none.
+    let reopened = package.to_document().unwrap();
+    package.update(&reopened, None).unwrap();
+    assert_eq!(package.pictures.len(), 1);
+
+    let doc = package.to_document().unwrap();
+    let header = &doc.page_setup.unwrap().master_pages[0];
+    match &header.header.as_ref().unwrap().content[0] {
+        Block::Image { src, .. } => assert_eq!(src, PNG_URI),
+        other => panic!("expected an image, got {other:?}"),
+    }
+}
+
+#[test]
+fn update_keeps_pictures_referenced_from_styles() {
+    let mut doc = document();
+    doc.page_setup = Some(PageSetup {
+        layouts: vec![PageLayout::default()],
+        master_pages: vec![MasterPage {
+            name: "Standard".to_string(),
+            page_layout: PageLayout::default().name,
+            ..MasterPage::default()
+        }],
+    });
+    let mut package = OdtPackage::from_document(&doc, None).unwrap();
+    let styles = package.styles.take().unwrap();
+    assert!(styles.contains("</style:page-layout>"));
+    // A header background image, referenced from the page layout.
+    package.styles = Some(styles.replacen(
+        "</style:page-layout>",
+        r#"<style:header-style><style:header-footer-properties><style:background-image xlink:href="Pictures/paper.png" xlink:type="simple"/></style:header-footer-properties></style:header-style></style:page-layout>"#,
+        1,
+    ));
+    package.pictures.push(odt_format::pictures::Picture {
+        path: "Pictures/paper.png".to_string(),
+        media_type: "image/png".to_string(),
+        data: b"paper".to_vec(),
+    });
+
+    let mut doc = package.to_document().unwrap();
+    doc.blocks.truncate(1);
+    package.update(&doc, None).unwrap();
+    let paths: Vec<&str> = package.pictures.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(paths, vec!["Pictures/paper.png"]);
+}
+
+#[test]
+fn update_keeps_content_automatic_styles_out_of_styles_xml() {
+    let mut doc = document();
+    doc.blocks[0] = Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Text {
+            text: "Hello".to_string(),
+            style_name: None,
+            marks: vec![TiptapMark::Bold],
+        }],
+    };
+    doc.page_setup = Some(PageSetup {
+        layouts: vec![PageLayout::default()],
+        master_pages: vec![MasterPage {
+            name: "Standard".to_string(),
+            page_layout: PageLayout::default().name,
+            ..MasterPage::default()
+        }],
+    });
+    let mut package = OdtPackage::from_document(&doc, None).unwrap();
+    let doc = package.to_document().unwrap();
+    package.update(&doc, None).unwrap();
+
+    assert!(package.content.contains(r#"style:name="T1""#));
+    let styles = package.styles.as_deref().unwrap();
+    for prefix in ["P", "T"] {
+        assert!(
+            !styles.contains(&format!(r#"style:name="{prefix}1""#)),
+            "styles.xml gained the content style {prefix}1: {styles}"
+        );
+    }
+    assert!(styles.contains("<style:master-page"));
+}
+
+#[test]
+fn content_lists_resolve_named_list_styles_from_styles_xml() {
+    const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0""#;
+    let content = format!(
+        r#"<office:document-content {NS} office:version="1.3"><office:body><office:text><text:list text:style-name="Roman"><text:list-item><text:p>One</text:p></text:list-item></text:list></office:text></office:body></office:document-content>"#
+    );
+    let styles = format!(
+        r#"<office:document-styles {NS} office:version="1.3"><office:styles><text:list-style style:name="Roman"><text:list-level-style-number text:level="1" style:num-format="i" style:num-suffix=")"/></text:list-style></office:styles></office:document-styles>"#
+    );
+    let mut buffer = Cursor::new(Vec::new());
+    let mut zip = ZipWriter::new(&mut buffer);
+    for (name, data) in [
+        ("mimetype", odt_format::package::MIMETYPE),
+        ("content.xml", content.as_str()),
+        ("styles.xml", styles.as_str()),
+    ] {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(data.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let doc = OdtPackage::from_bytes(buffer.get_ref())
+        .unwrap()
+        .to_document()
+        .unwrap();
+    match &doc.blocks[0] {
+        Block::OrderedList { attrs, .. } => {
+            let attrs = attrs.as_ref().unwrap();
+            assert_eq!(attrs.style_name.as_deref(), Some("Roman"));
+            assert_eq!(attrs.num_format.as_deref(), Some("i"));
+            assert_eq!(attrs.num_suffix.as_deref(), Some(")"));
+        }
+        other => panic!("expected a numbered list, got {other:?}"),
+    }
+    assert!(doc.styles.contains_key("Roman"));
+}
+
+#[test]
+fn unreadable_styles_xml_is_an_error() {
+    let mut package = OdtPackage::from_document(&document(), None).unwrap();
+    package.styles = Some("<office:document-styles>".to_string());
+    let err = package.to_document().unwrap_err();
+    assert!(err.contains("styles.xml"), "unexpected error: {err}");
+}
@@ -7,7 +7,11 @@
 //! Content (block count, text, formatting) must be identical before and after.
 //! These tests guard against regressions introduced by parser or writer changes.
 
-use common_core::{Block, Inline};
+use common_core::toc::regenerate_toc;
+use common_core::{
+    Block, ChangeKind, CrossReference, FieldKind, Inline, ListAttrs, NoteClass, ReferenceKind,
+    SectionAttrs, TocEntry,
+};
 use odt_format::{
     lexical::{from_lexical, to_lexical},
     parser::parse_document,
@@ -173,7 +177,8 @@ fn bullet_list_items_preserved() {
     let (b1, b2) = round_trip(&xml);
     assert_eq!(b1.len(), b2.len(), "list block count changed");
 
-    if let (Block::BulletList { content: c1 }, Block::BulletList { content: c2 }) = (&b1[0], &b2[0])
+    if let (Block::BulletList { content: c1, .. }, Block::BulletList { content: c2, .. }) =
+        (&b1[0], &b2[0])
     {
         assert_eq!(c1.len(), c2.len(), "list item count changed");
     } else {
@@ -181,6 +186,50 @@ fn bullet_list_items_preserved() {
     }
 }
 
+#[test]
+fn numbered_list_style_is_resolved() {
+    let xml = fodt(
+        r#"<text:list-style style:name="L1">
+             <text:list-level-style-number text:level="1" style:num-suffix=")"
+                 style:num-format="a"/>
+             <text:list-level-style-bullet text:level="2" text:bullet-char="–"/>
+           </text:list-style>"#,
+        r#"<text:list text:style-name="L1">
+             <text:list-item text:start-value="3"><text:p>Third</text:p>
+               <text:list><text:list-item><text:p>Sub</text:p></text:list-item></text:list>
+             </text:list-item>
+             <text:list-item><text:p>Fourth</text:p></text:list-item>
+           </text:list>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    let expected = ListAttrs {
+        style_name: Some("L1".to_string()),
+        num_format: Some("a".to_string()),
+        num_suffix: Some(")".to_string()),
+        start: Some(3),
+        ..ListAttrs::default()
+    };
+
+    for blocks in [&b1, &b2] {
+        let Block::OrderedList { attrs, content } = &blocks[0] else {
+            panic!("expected OrderedList, got {:?}", blocks[0]);
+        };
+        assert_eq!(attrs.as_ref(), Some(&expected));
+        let Block::ListItem { content: item } = &content[0] else {
+            panic!("expected ListItem");
+        };
+        let Block::BulletList {
+            attrs: Some(nested),
+            ..
+        } = &item[1]
+        else {
+            panic!("expected nested BulletList, got {:?}", item[1]);
+        };
+        assert_eq!(nested.style_name.as_deref(), Some("L1"));
+        assert_eq!(nested.bullet_char.as_deref(), Some("–"));
+    }
+}
+
 // ── Images ────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -211,6 +260,67 @@ fn image_block_src_preserved() {
     } else {
         panic!("expected Image blocks");
     }
+    // An image outside the package is a link, not an embedded picture.
+    match &b2[0] {
+        Block::Image { frame, .. } => {
+            assert!(frame.as_ref().is_some_and(|f| f.linked), "{frame:?}")
+        }
+        other => panic!("expected Image, got {other:?}"),
+    }
+}
+
+#[test]
+fn captioned_image_frame_is_parsed() {
+    use common_core::{ImageAnchor, ImageWrap};
+
+    let ns_draw = "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0";
+    let ns_xlink = "http://www.w3.org/1999/xlink";
+    let ns_svg = "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0";
+
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:draw="{ns_draw}" xmlns:xlink="{ns_xlink}" xmlns:svg="{ns_svg}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" office:version="1.3">
+  <office:automatic-styles>
+    <style:style style:name="fr1" style:family="graphic" style:parent-style-name="Frame">
+      <style:graphic-properties style:wrap="right"/>
+    </style:style>
+  </office:automatic-styles>
+  <office:body>
+    <office:text>
+      <text:p text:style-name="Standard">
+        <draw:frame draw:style-name="fr1" draw:name="Frame1" text:anchor-type="char" svg:width="5cm">
+          <draw:text-box fo:min-height="3cm">
+            <text:p text:style-name="Figure"><draw:frame draw:name="Image1" text:anchor-type="as-char" svg:width="5cm" svg:height="2.54cm"><draw:image xlink:href="Pictures/1.png"/><svg:title>Logo</svg:title><svg:desc>Company logo</svg:desc></draw:frame><text:line-break/>Figure <text:sequence text:name="Figure">1</text:sequence>: Our logo</text:p>
+          </draw:text-box>
+        </draw:frame>
+      </text:p>
+    </office:text>
+  </office:body>
+</office:document>"#
+    );
+
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2, "image changed in the Lexical round-trip");
+    let Block::Image {
+        src,
+        alt,
+        title,
+        frame,
+        caption,
+    } = &b1[0]
+    else {
+        panic!("expected Image, got {:?}", b1[0]);
+    };
+    assert_eq!(src, "Pictures/1.png");
+    assert_eq!(alt.as_deref(), Some("Company logo"));
+    assert_eq!(title.as_deref(), Some("Logo"));
+    assert_eq!(caption.as_deref(), Some("Figure 1: Our logo"));
+    let frame = frame.as_ref().expect("image frame");
+    assert_eq!(frame.height, Some(96.0));
+    assert_eq!(frame.anchor, Some(ImageAnchor::Char));
+    assert_eq!(frame.wrap, Some(ImageWrap::Right));
 }
 
 // ── Page break ────────────────────────────────────────────────────────────────
@@ -234,6 +344,635 @@ fn page_break_block_survives() {
     assert!(has_page_break(&b2), "PageBreak lost after round-trip");
 }
 
+// ── Automatic styles ──────────────────────────────────────────────────────────
+
+#[test]
+fn automatic_styles_become_direct_formatting() {
+    use common_core::marks::TiptapMark;
+
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" office:version="1.3">
+  <office:styles>
+    <style:style style:name="Standard" style:family="paragraph"/>
+  </office:styles>
+  <office:automatic-styles>
+    <style:style style:name="P1" style:family="paragraph" style:parent-style-name="Standard">
+      <style:paragraph-properties fo:text-align="center" fo:margin-left="2.54cm"/>
+    </style:style>
+    <style:style style:name="T1" style:family="text">
+      <style:text-properties fo:font-weight="bold" style:text-line-through-style="solid"/>
+    </style:style>
+  </office:automatic-styles>
+  <office:body>
+    <office:text><text:p text:style-name="P1">Centred <text:span text:style-name="T1">struck</text:span></text:p></office:text>
+  </office:body>
+</office:document>"#
+    );
+
+    let (b1, b2) = round_trip(&xml);
+    for blocks in [&b1, &b2] {
+        let Block::Paragraph {
+            style_name, attrs, ..
+        } = &blocks[0]
+        else {
+            panic!("expected Paragraph, got {:?}", blocks[0]);
+        };
+        assert_eq!(style_name.as_deref(), Some("Standard"));
+        let attrs = attrs.as_ref().expect("paragraph attrs");
+        assert_eq!(attrs.text_align.as_deref(), Some("center"));
+        assert_eq!(attrs.indent, Some(2));
+    }
+
+    let Inline::Text {
+        style_name, marks, ..
+    } = &paragraph_inlines(&b1[0])[1]
+    else {
+        panic!("expected text run");
+    };
+    assert_eq!(style_name, &None, "automatic style name leaked");
+    assert!(marks.contains(&TiptapMark::Bold) && marks.contains(&TiptapMark::Strike));
+}
+
+// ── Tables ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn table_header_rows_widths_and_cell_styles_are_parsed() {
+    let xml = format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" xmlns:table="{NS_TABLE}"
+    office:version="1.3">
+  <office:automatic-styles>
+    <style:style style:name="Table1.A" style:family="table-column">
+      <style:table-column-properties style:column-width="2.54cm"/>
+    </style:style>
+    <style:style style:name="Table1.A1" style:family="table-cell">
+      <style:table-cell-properties fo:background-color="#cccccc"
+          fo:border="0.5pt solid #000000" fo:border-top="none"/>
+    </style:style>
+  </office:automatic-styles>
+  <office:body>
+    <office:text>
+      <table:table table:name="Table1">
+        <table:table-column table:style-name="Table1.A" table:number-columns-repeated="2"/>
+        <table:table-header-rows>
+          <table:table-row>
+            <table:table-cell table:style-name="Table1.A1"><text:p>Name</text:p></table:table-cell>
+            <table:table-cell><text:p>Value</text:p></table:table-cell>
+          </table:table-row>
+        </table:table-header-rows>
+        <table:table-row>
+          <table:table-cell table:number-columns-spanned="2"><text:p>Both</text:p></table:table-cell>
+          <table:covered-table-cell/>
+        </table:table-row>
+      </table:table>
+    </office:text>
+  </office:body>
+</office:document>"##
+    );
+
+    let (b1, b2) = round_trip(&xml);
+    let Block::Table { content: rows } = &b1[0] else {
+        panic!("expected Table, got {:?}", b1[0]);
+    };
+    let Block::TableRow { content: header } = &rows[0] else {
+        panic!("expected TableRow");
+    };
+    let Block::TableHeader {
+        attrs: Some(attrs), ..
+    } = &header[0]
+    else {
+        panic!("expected TableHeader, got {:?}", header[0]);
+    };
+    assert_eq!(attrs.colwidth, Some(vec![96]));
+    assert_eq!(attrs.background_color.as_deref(), Some("#cccccc"));
+    let border = attrs.border.as_ref().expect("cell borders");
+    assert_eq!(border.top, None);
+    assert_eq!(border.left.as_deref(), Some("0.5pt solid #000000"));
+
+    let Block::TableRow { content: body } = &rows[1] else {
+        panic!("expected TableRow");
+    };
+    let Block::TableCell {
+        attrs: Some(attrs), ..
+    } = &body[0]
+    else {
+        panic!("expected TableCell, got {:?}", body[0]);
+    };
+    assert_eq!(attrs.colspan, Some(2));
+    assert_eq!(attrs.colwidth, Some(vec![96, 96]));
+
+    // The Lexical round-trip keeps header rows, widths and backgrounds
+    let Block::Table { content: rows } = &b2[0] else {
+        panic!("expected Table after round-trip");
+    };
+    let Block::TableRow { content: header } = &rows[0] else {
+        panic!("expected TableRow");
+    };
+    let Block::TableHeader {
+        attrs: Some(attrs), ..
+    } = &header[0]
+    else {
+        panic!("expected TableHeader after round-trip, got {:?}", header[0]);
+    };
+    assert_eq!(attrs.colwidth, Some(vec![96]));
+    assert_eq!(attrs.background_color.as_deref(), Some("#cccccc"));
+}
+
+// ── Nested inlines ────────────────────────────────────────────────────────────
+
+#[test]
+fn nested_spans_and_links_combine_marks() {
+    use common_core::marks::{LinkAttrs, TextStyleAttrs, TiptapMark};
+
+    let ns_xlink = "http://www.w3.org/1999/xlink";
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:xlink="{ns_xlink}" xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}"
+    office:version="1.3">
+  <office:styles>
+    <style:style style:name="B" style:family="text">
+      <style:text-properties fo:font-weight="bold"/>
+    </style:style>
+    <style:style style:name="I" style:family="text">
+      <style:text-properties fo:font-style="italic"/>
+    </style:style>
+    <style:style style:name="Big" style:family="text">
+      <style:text-properties fo:font-size="14pt"/>
+    </style:style>
+  </office:styles>
+  <office:automatic-styles>
+    <style:style style:name="T1" style:family="text" style:parent-style-name="Big">
+      <style:text-properties fo:font-size="18pt"/>
+    </style:style>
+  </office:automatic-styles>
+  <office:body>
+    <office:text><text:p>A <text:span text:style-name="B">bold <text:span text:style-name="I">both</text:span><text:line-break/>after</text:span> <text:a xlink:href="https://example.com"><text:span text:style-name="B">link</text:span></text:a> <text:span text:style-name="I"><text:span text:style-name="T1">big</text:span></text:span></text:p></office:text>
+  </office:body>
+</office:document>"#
+    );
+
+    let (b1, b2) = round_trip(&xml);
+    let find = |inlines: &[Inline], needle: &str| {
+        inlines
+            .iter()
+            .find_map(|i| match i {
+                Inline::Text {
+                    text,
+                    style_name,
+                    marks,
+                } if text == needle => Some((style_name.clone(), marks.clone())),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no text inline {needle:?}"))
+    };
+    let link = TiptapMark::Link {
+        attrs: LinkAttrs {
+            href: "https://example.com".to_string(),
+            target: Some("_blank".to_string()),
+        },
+    };
+    let big = TiptapMark::TextStyle {
+        attrs: TextStyleAttrs {
+            font_size: Some(18.0),
+            ..TextStyleAttrs::default()
+        },
+    };
+    let expected = [
+        ("both", "I", vec![TiptapMark::Bold, TiptapMark::Italic]),
+        ("after", "B", vec![TiptapMark::Bold]),
+        ("link", "B", vec![link, TiptapMark::Bold]),
+        ("big", "Big", vec![TiptapMark::Italic, big]),
+    ];
+
+    let inlines = paragraph_inlines(&b1[0]);
+    assert!(
+        inlines.contains(&Inline::LineBreak),
+        "nested line break lost"
+    );
+    for (text, style, marks) in &expected {
+        assert_eq!(
+            find(inlines, text),
+            (Some(style.to_string()), marks.clone()),
+            "{text:?}"
+        );
+    }
+
+    let runs = |inlines: &[Inline]| -> Vec<(String, Option<String>)> {
+        inlines
+            .iter()
+            .filter_map(|i| match i {
+                Inline::Text {
+                    text, style_name, ..
+                } => Some((text.clone(), style_name.clone())),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(
+        runs(inlines),
+        runs(paragraph_inlines(&b2[0])),
+        "runs changed"
+    );
+
+    // The combined marks are written as automatic styles and read back.
+    let written = parse_document(&xml).unwrap().to_xml().unwrap();
+    let reparsed = parse_document(&written).unwrap();
+    let inlines = paragraph_inlines(&reparsed.blocks[0]);
+    for (text, style, marks) in &expected {
+        let (reparsed_style, reparsed_marks) = find(inlines, text);
+        assert_eq!(reparsed_style.as_deref(), Some(*style), "{text:?}");
+        for mark in marks {
+            assert!(reparsed_marks.contains(mark), "{text:?} lost {mark:?}");
+        }
+    }
+}
+
+// ── Whitespace ────────────────────────────────────────────────────────────────
+
+#[test]
+fn space_runs_and_tabs_are_parsed() {
+    let xml = fodt(
+        "",
+        r#"<text:p>a<text:s text:c="4"/>b<text:tab/>c<text:s/></text:p>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    let inlines = paragraph_inlines(&b1[0]);
+    assert_eq!(inlines[1], Inline::Space { count: 4 });
+    assert_eq!(inlines[3], Inline::Tab);
+    assert_eq!(inlines[5], Inline::Space { count: 1 });
+    assert!(paragraph_inlines(&b2[0]).contains(&Inline::Tab));
+}
+
+#[test]
+fn huge_space_counts_are_clamped() {
+    let xml = fodt(
+        "",
+        r##"<text:table-of-content text:name="Table of Contents1">
+             <text:table-of-content-source text:outline-level="1"/>
+             <text:index-body>
+               <text:p text:style-name="Contents_20_1">a<text:s text:c="4000000000"/>b<text:tab/>1</text:p>
+             </text:index-body>
+           </text:table-of-content>
+           <text:p>a<text:s text:c="4000000000"/>b</text:p>"##,
+    );
+    let (b1, _) = round_trip(&xml);
+    let Block::TableOfContents(toc) = &b1[0] else {
+        panic!("expected TableOfContents, got {:?}", b1[0]);
+    };
+    let Inline::Space { count } = paragraph_inlines(&b1[1])[1] else {
+        panic!("expected Space, got {:?}", paragraph_inlines(&b1[1]));
+    };
+    assert!((1..=4096).contains(&count), "count {count}");
+    assert_eq!(toc.entries[0].text.len(), 2 + count as usize);
+}
+
+// ── Notes ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn footnote_survives_round_trip() {
+    let xml = fodt(
+        "",
+        r#"<text:p>Claim<text:note text:id="ftn1" text:note-class="footnote">
+             <text:note-citation>1</text:note-citation>
+             <text:note-body><text:p>Source A</text:p></text:note-body>
+           </text:note> continues</text:p>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2, "note content changed after round-trip");
+
+    let inlines = paragraph_inlines(&b1[0]);
+    if let Inline::Note {
+        id,
+        note_class,
+        citation,
+        content,
+    } = &inlines[1]
+    {
+        assert_eq!(id.as_deref(), Some("ftn1"));
+        assert_eq!(*note_class, NoteClass::Footnote);
+        assert_eq!(citation, "1");
+        assert_inline_text(&paragraph_inlines(&content[0])[0], "Source A");
+    } else {
+        panic!("expected Note inline, got {:?}", inlines[1]);
+    }
+}
+
+#[test]
+fn endnote_class_preserved() {
+    let xml = fodt(
+        "",
+        r#"<text:p>See<text:note text:note-class="endnote">
+             <text:note-citation>i</text:note-citation>
+             <text:note-body><text:p>Later</text:p></text:note-body>
+           </text:note></text:p>"#,
+    );
+    let (_, b2) = round_trip(&xml);
+    assert!(matches!(
+        &paragraph_inlines(&b2[0])[1],
+        Inline::Note {
+            note_class: NoteClass::Endnote,
+            ..
+        }
+    ));
+}
+
+// ── Comments ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn comment_range_and_reply_survive_round_trip() {
+    let xml = fodt(
+        "",
+        r#"<text:p xmlns:dc="http://purl.org/dc/elements/1.1/"
+             xmlns:loext="urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0"
+           >Before <office:annotation office:name="__Annotation__1">
+             <dc:creator>Ada</dc:creator><dc:date>2024-05-01T10:00:00</dc:date>
+             <text:p>Check this</text:p>
+           </office:annotation><office:annotation office:name="__Annotation__2"
+             loext:parent-name="__Annotation__1">
+             <dc:creator>Grace</dc:creator>
+             <text:p>Checked</text:p>
+           </office:annotation>claim<office:annotation-end office:name="__Annotation__1"/> after</text:p>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2, "comment changed after round-trip");
+
+    let inlines = paragraph_inlines(&b1[0]);
+    let Inline::Comment(comment) = &inlines[1] else {
+        panic!("expected Comment inline, got {:?}", inlines[1]);
+    };
+    assert_eq!(comment.id.as_deref(), Some("__Annotation__1"));
+    assert_eq!(comment.author.as_deref(), Some("Ada"));
+    assert_eq!(comment.date.as_deref(), Some("2024-05-01T10:00:00"));
+    assert_inline_text(&paragraph_inlines(&comment.content[0])[0], "Check this");
+    assert_eq!(
+        comment.replies.len(),
+        1,
+        "reply should attach to its parent"
+    );
+    assert_eq!(comment.replies[0].author.as_deref(), Some("Grace"));
+    assert_inline_text(&inlines[2], "claim");
+    assert_eq!(
+        inlines[3],
+        Inline::CommentEnd {
+            id: "__Annotation__1".to_string()
+        }
+    );
+}
+
+// ── Tracked changes ───────────────────────────────────────────────────────────
+
+#[test]
+fn tracked_changes_survive_round_trip_and_can_be_accepted_or_rejected() {
+    let xml = fodt(
+        "",
+        r#"<text:tracked-changes text:track-changes="true" xmlns:dc="http://purl.org/dc/elements/1.1/">
+             <text:changed-region text:id="ct1">
+               <text:insertion><office:change-info>
+                 <dc:creator>Ada</dc:creator><dc:date>2024-05-01T10:00:00</dc:date>
+               </office:change-info></text:insertion>
+             </text:changed-region>
+             <text:changed-region text:id="ct2">
+               <text:deletion><office:change-info>
+                 <dc:creator>Grace</dc:creator><dc:date>2024-05-02T09:30:00</dc:date>
+               </office:change-info><text:p>old </text:p></text:deletion>
+             </text:changed-region>
+           </text:tracked-changes>
+           <text:p>Keep <text:change-start text:change-id="ct1"/>new <text:change-end text:change-id="ct1"/><text:change text:change-id="ct2"/>end</text:p>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2, "tracked changes changed after round-trip");
+
+    let mut doc = parse_document(&xml).unwrap();
+    assert!(doc.track_changes);
+    assert!(doc
+        .to_xml()
+        .unwrap()
+        .contains(r#"<text:tracked-changes text:track-changes="true">"#));
+    let changes = doc.tracked_changes();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].kind, ChangeKind::Insertion);
+    assert_eq!(changes[0].author.as_deref(), Some("Ada"));
+    assert_eq!(changes[1].kind, ChangeKind::Deletion);
+    assert_eq!(changes[1].date.as_deref(), Some("2024-05-02T09:30:00"));
+    assert_inline_text(&paragraph_inlines(&changes[1].content[0])[0], "old ");
+
+    let mut rejected = doc.clone();
+    rejected.reject_all_changes();
+    let texts: Vec<_> = paragraph_inlines(&rejected.blocks[0])
+        .iter()
+        .map(|i| match i {
+            Inline::Text { text, .. } => text.as_str(),
+            other => panic!("unexpected inline {other:?}"),
+        })
+        .collect();
+    assert_eq!(texts.concat(), "Keep old end");
+
+    doc.accept_change("ct1").unwrap();
+    doc.accept_change("ct2").unwrap();
+    assert!(doc.tracked_changes().is_empty());
+    assert_eq!(paragraph_inlines(&doc.blocks[0]).len(), 3);
+}
+
+// ── Sections ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn section_with_columns_survives_round_trip() {
+    let xml = fodt(
+        r#"<style:style style:name="Sect1" style:family="section">
+             <style:section-properties>
+               <style:columns fo:column-count="2" fo:column-gap="0.5cm"/>
+             </style:section-properties>
+           </style:style>"#,
+        r#"<text:p>Before</text:p>
+           <text:section text:style-name="Sect1" text:name="Columns"
+               text:protected="true">
+             <text:p>Left</text:p>
+             <text:section text:name="Inner"><text:p>Nested</text:p></text:section>
+           </text:section>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2);
+    assert_eq!(b1.len(), 2);
+    let Block::Section {
+        name,
+        attrs,
+        protected,
+        content,
+    } = &b1[1]
+    else {
+        panic!("expected Section, got {:?}", b1[1]);
+    };
+    assert_eq!(name, "Columns");
+    assert!(protected);
+    assert_eq!(
+        attrs.as_ref(),
+        Some(&SectionAttrs {
+            column_count: Some(2),
+            column_gap: Some(18.9),
+        })
+    );
+    assert_eq!(content.len(), 2);
+    assert!(matches!(
+        &content[1],
+        Block::Section { name, attrs: None, protected: false, .. } if name == "Inner"
+    ));
+}
+
+// ── Fields ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn text_fields_survive_round_trip() {
+    let xml = fodt(
+        "",
+        r#"<text:p>Page <text:page-number text:select-page="current">3</text:page-number> of <text:page-count>9</text:page-count></text:p>
+           <text:p><text:span><text:title>Report</text:title></text:span> by <text:author-name text:fixed="true">Ada</text:author-name>, <text:date style:data-style-name="N37" text:date-value="2024-05-01" text:fixed="true">05/01/24</text:date>, <text:word-count>120</text:word-count></text:p>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2);
+    let fields: Vec<(FieldKind, &str)> = b1
+        .iter()
+        .flat_map(|b| match b {
+            Block::Paragraph { content, .. } => content.as_slice(),
+            _ => &[],
+        })
+        .filter_map(|i| match i {
+            Inline::Field { kind, value, .. } => Some((*kind, value.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            (FieldKind::PageNumber, "3"),
+            (FieldKind::PageCount, "9"),
+            (FieldKind::Title, "Report"),
+            (FieldKind::Author, "Ada"),
+            (FieldKind::Date, "05/01/24"),
+            (FieldKind::WordCount, "120"),
+        ]
+    );
+
+    // Fixed values and the date's own value and number style are kept.
+    let written = parse_document(&xml).unwrap().to_xml().unwrap();
+    assert!(written.contains(r#"<text:author-name text:fixed="true">Ada</text:author-name>"#));
+    assert!(written.contains(
+        r#"<text:date style:data-style-name="N37" text:date-value="2024-05-01" text:fixed="true">05/01/24</text:date>"#
+    ));
+}
+
+// ── Bookmarks ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn bookmarks_and_references_survive_round_trip() {
+    let xml = fodt(
+        "",
+        r#"<text:h text:outline-level="1"><text:bookmark-start text:name="methods"/>Methods<text:bookmark-end text:name="methods"/></text:h>
+           <text:p><text:bookmark text:name="here"/>See <text:bookmark-ref text:reference-format="text" text:ref-name="methods">Methods</text:bookmark-ref> and <text:reference-ref text:ref-name="fig1">Figure 1</text:reference-ref>.</text:p>"#,
+    );
+    let (b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2);
+    let Block::Heading { content, .. } = &b1[0] else {
+        panic!("expected Heading, got {:?}", b1[0]);
+    };
+    assert_eq!(
+        content.first(),
+        Some(&Inline::BookmarkStart {
+            name: "methods".to_string()
+        })
+    );
+    assert_eq!(
+        content.last(),
+        Some(&Inline::BookmarkEnd {
+            name: "methods".to_string()
+        })
+    );
+    let Block::Paragraph { content, .. } = &b1[1] else {
+        panic!("expected Paragraph, got {:?}", b1[1]);
+    };
+    assert_eq!(
+        content[0],
+        Inline::Bookmark {
+            name: "here".to_string()
+        }
+    );
+    assert_eq!(
+        content[2],
+        Inline::Reference(CrossReference {
+            kind: ReferenceKind::Bookmark,
+            name: "methods".to_string(),
+            format: Some("text".to_string()),
+            value: "Methods".to_string(),
+        })
+    );
+    assert!(matches!(
+        &content[4],
+        Inline::Reference(r) if r.kind == ReferenceKind::ReferenceMark && r.value == "Figure 1"
+    ));
+}
+
+// ── Table of contents ─────────────────────────────────────────────────────────
+
+#[test]
+fn table_of_contents_survives_round_trip_and_regenerates() {
+    let xml = fodt(
+        r#"<style:style style:name="P1" style:family="paragraph" style:parent-style-name="Contents_20_2"/>"#,
+        r##"<text:table-of-content xmlns:xlink="http://www.w3.org/1999/xlink" text:name="Table of Contents1" text:protected="true">
+             <text:table-of-content-source text:outline-level="2">
+               <text:index-title-template text:style-name="Contents_20_Heading">Contents</text:index-title-template>
+               <text:table-of-content-entry-template text:outline-level="1" text:style-name="Contents_20_1"/>
+               <text:table-of-content-entry-template text:outline-level="2" text:style-name="Contents_20_2"/>
+             </text:table-of-content-source>
+             <text:index-body>
+               <text:index-title text:name="Table of Contents1_Head"><text:p text:style-name="Contents_20_Heading">Contents</text:p></text:index-title>
+               <text:p text:style-name="Contents_20_1"><text:a xlink:type="simple" xlink:href="#__RefHeading___Toc1_7">Intro<text:tab/>1</text:a></text:p>
+               <text:p text:style-name="P1"><text:a xlink:type="simple" xlink:href="#__RefHeading___Toc2_7">Old<text:s/>name<text:tab/>2</text:a></text:p>
+             </text:index-body>
+           </text:table-of-content>
+           <text:h text:outline-level="1"><text:bookmark-start text:name="__RefHeading___Toc1_7"/>Intro<text:bookmark-end text:name="__RefHeading___Toc1_7"/></text:h>
+           <text:h text:outline-level="2">Background</text:h>
+           <text:h text:outline-level="3">Details</text:h>"##,
+    );
+    let (mut b1, b2) = round_trip(&xml);
+    assert_eq!(b1, b2);
+    let Block::TableOfContents(toc) = &b1[0] else {
+        panic!("expected TableOfContents, got {:?}", b1[0]);
+    };
+    assert_eq!(toc.name, "Table of Contents1");
+    assert_eq!(toc.title.as_deref(), Some("Contents"));
+    assert_eq!(toc.outline_level, 2);
+    assert_eq!(
+        toc.entries[1],
+        TocEntry {
+            level: 2,
+            text: "Old name".to_string(),
+            target: Some("__RefHeading___Toc2_7".to_string()),
+            page: Some("2".to_string()),
+        }
+    );
+
+    regenerate_toc(&mut b1);
+    let Block::TableOfContents(toc) = &b1[0] else {
+        panic!("expected TableOfContents, got {:?}", b1[0]);
+    };
+    let entries: Vec<(u32, &str, Option<&str>)> = toc
+        .entries
+        .iter()
+        .map(|e| (e.level, e.text.as_str(), e.page.as_deref()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![(1, "Intro", Some("1")), (2, "Background", None)]
+    );
+    assert_eq!(
+        toc.entries[0].target.as_deref(),
+        Some("__RefHeading___Toc1_7")
+    );
+}
+
 // ── Metadata ──────────────────────────────────────────────────────────────────
 
 #[test]
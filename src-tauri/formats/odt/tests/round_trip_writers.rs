@@ -7,7 +7,10 @@
 //! These guard against silent drops or corruption introduced by the
 //! content.xml writer (as distinct from the FODT / Lexical path).
 
-use common_core::{block::CellAttrs, Block, Inline};
+use common_core::{
+    block::CellAttrs, Block, BlockAttrs, CellBorders, ChangeKind, Comment, ImageAnchor, ImageFrame,
+    ImageWrap, Inline, ListAttrs, NoteClass, SectionAttrs, TiptapMark, TrackedChange,
+};
 use odt_format::{parser::parse_document, writer::content::to_content_xml};
 
 // ── Image ─────────────────────────────────────────────────────────────────────
@@ -20,8 +23,10 @@ fn image_round_trips_through_content_xml() {
         src: src.to_string(),
         alt: None,
         title: None,
+        frame: None,
+        caption: None,
     }];
-    let xml = to_content_xml(&blocks).expect("to_content_xml failed");
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
     let doc = parse_document(&xml).expect("parse_document failed");
 
     assert_eq!(doc.blocks.len(), 1, "expected exactly one block");
@@ -33,6 +38,425 @@ fn image_round_trips_through_content_xml() {
     }
 }
 
+/// Frame size, anchor, wrapping, title, description and caption survive
+/// write → parse.
+#[test]
+fn image_frame_and_caption_round_trip_through_content_xml() {
+    let image = |caption: Option<&str>| Block::Image {
+        src: "Pictures/cat.png".to_string(),
+        alt: Some("A sleeping cat".to_string()),
+        title: Some("Cat".to_string()),
+        frame: Some(ImageFrame {
+            width: Some(240.0),
+            height: Some(180.5),
+            anchor: Some(ImageAnchor::Paragraph),
+            wrap: Some(ImageWrap::Parallel),
+            linked: false,
+        }),
+        caption: caption.map(str::to_string),
+    };
+    let blocks = vec![image(None), image(Some("Figure 1: A cat"))];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains(r#"svg:width="6.3500cm""#));
+    assert_eq!(xml.matches(r#"style:family="graphic""#).count(), 1);
+
+    let doc = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(doc.blocks, blocks);
+}
+
+// ── Direct formatting ─────────────────────────────────────────────────────────
+
+/// Alignment and character marks are written as automatic styles, one per
+/// distinct combination, and read back as the same direct formatting.
+#[test]
+fn direct_formatting_round_trips_as_automatic_styles() {
+    let run = |text: &str, marks: Vec<TiptapMark>| Inline::Text {
+        text: text.to_string(),
+        style_name: None,
+        marks,
+    };
+    let blocks = vec![Block::Paragraph {
+        style_name: Some("Standard".to_string()),
+        attrs: Some(BlockAttrs {
+            text_align: Some("right".to_string()),
+            indent: Some(1),
+            ..BlockAttrs::default()
+        }),
+        content: vec![
+            run("one ", vec![TiptapMark::Bold, TiptapMark::Subscript]),
+            run("two ", vec![]),
+            run("three", vec![TiptapMark::Subscript, TiptapMark::Bold]),
+        ],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert_eq!(xml.matches(r#"style:family="text""#).count(), 1);
+    assert_eq!(xml.matches(r#"style:family="paragraph""#).count(), 1);
+
+    let doc = parse_document(&xml).expect("parse_document failed");
+    let Block::Paragraph {
+        style_name,
+        attrs,
+        content,
+    } = &doc.blocks[0]
+    else {
+        panic!("expected Paragraph");
+    };
+    assert_eq!(style_name.as_deref(), Some("Standard"));
+    assert_eq!(
+        attrs,
+        &Some(BlockAttrs {
+            text_align: Some("right".to_string()),
+            indent: Some(1),
+            ..BlockAttrs::default()
+        })
+    );
+    let Inline::Text {
+        marks, style_name, ..
+    } = &content[2]
+    else {
+        panic!("expected text run");
+    };
+    assert_eq!(style_name, &None);
+    assert!(marks.contains(&TiptapMark::Bold) && marks.contains(&TiptapMark::Subscript));
+}
+
+/// Colour, highlight, font family and size survive write → parse, including
+/// a CMYK colour carried in `loki:colour`.
+#[test]
+fn text_style_mark_round_trips_through_content_xml() {
+    use common_core::colour_management::Colour;
+    use common_core::TextStyleAttrs;
+
+    let mark = TiptapMark::TextStyle {
+        attrs: TextStyleAttrs {
+            colour: Some(Colour::Cmyk {
+                c: 0.0,
+                m: 1.0,
+                y: 1.0,
+                k: 0.0,
+                alpha: 1.0,
+            }),
+            background_colour: Some(Colour::from_u8_rgb(255, 255, 0)),
+            font_family: Some("Liberation Serif".to_string()),
+            font_size: Some(18.0),
+        },
+    };
+    let blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Text {
+            text: "highlighted".to_string(),
+            style_name: None,
+            marks: vec![TiptapMark::Italic, mark.clone()],
+        }],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    let doc = parse_document(&xml).expect("parse_document failed");
+    let Block::Paragraph { content, .. } = &doc.blocks[0] else {
+        panic!("expected Paragraph");
+    };
+    let Inline::Text { marks, .. } = &content[0] else {
+        panic!("expected text run");
+    };
+    assert!(marks.contains(&TiptapMark::Italic));
+    assert!(marks.contains(&mark), "text style changed: {marks:?}");
+}
+
+// ── Whitespace ────────────────────────────────────────────────────────────────
+
+/// Repeated spaces, leading spaces and tabs are written as `text:s` /
+/// `text:tab` and read back with the same visible whitespace.
+#[test]
+fn whitespace_written_in_canonical_form() {
+    let blocks = vec![make_para("  indented    code\tcomment")];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(
+        xml.contains(r#"<text:s text:c="2"/>indented <text:s text:c="3"/>code<text:tab/>comment"#)
+    );
+
+    let doc = parse_document(&xml).expect("parse_document failed");
+    let Block::Paragraph { content, .. } = &doc.blocks[0] else {
+        panic!("expected Paragraph");
+    };
+    let visible: String = content
+        .iter()
+        .map(|i| match i {
+            Inline::Text { text, .. } => text.clone(),
+            Inline::Space { count } => " ".repeat(*count as usize),
+            Inline::Tab => "\t".to_string(),
+            other => panic!("unexpected inline {other:?}"),
+        })
+        .collect();
+    assert_eq!(visible, "  indented    code\tcomment");
+}
+
+/// Explicit `Space` and `Tab` inlines survive write → parse unchanged.
+#[test]
+fn space_and_tab_inlines_round_trip() {
+    let blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Tab,
+            Inline::Text {
+                text: "x".to_string(),
+                marks: vec![],
+                style_name: None,
+            },
+            Inline::Space { count: 5 },
+        ],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    let doc = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(doc.blocks, blocks);
+}
+
+// ── Notes ─────────────────────────────────────────────────────────────────────
+
+/// A footnote with a marked-up body must survive write → parse intact.
+#[test]
+fn footnote_round_trips_through_content_xml() {
+    let blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Text {
+                text: "Claim".to_string(),
+                marks: vec![],
+                style_name: None,
+            },
+            Inline::Note {
+                id: Some("ftn1".to_string()),
+                note_class: NoteClass::Footnote,
+                citation: "1".to_string(),
+                content: vec![make_para("Source & notes")],
+            },
+        ],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains(r#"<text:note text:id="ftn1" text:note-class="footnote">"#));
+    let doc = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(doc.blocks, blocks);
+}
+
+// ── Comments ──────────────────────────────────────────────────────────────────
+
+/// A comment range spanning two paragraphs, with a resolved reply thread,
+/// must survive write → parse intact.
+#[test]
+fn comment_range_and_replies_round_trip_through_content_xml() {
+    let comment = Comment {
+        id: Some("c1".to_string()),
+        author: Some("Ada".to_string()),
+        date: Some("2024-05-01T10:00:00".to_string()),
+        resolved: true,
+        content: vec![make_para("Needs a source")],
+        replies: vec![Comment {
+            id: Some("c2".to_string()),
+            author: Some("Grace".to_string()),
+            date: None,
+            resolved: false,
+            content: vec![make_para("Added one")],
+            replies: vec![],
+        }],
+    };
+    let blocks = vec![
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::Comment(comment),
+                Inline::Text {
+                    text: "First".to_string(),
+                    marks: vec![],
+                    style_name: None,
+                },
+            ],
+        },
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::Text {
+                    text: "Second".to_string(),
+                    marks: vec![],
+                    style_name: None,
+                },
+                Inline::CommentEnd {
+                    id: "c1".to_string(),
+                },
+            ],
+        },
+    ];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains(r#"<office:annotation office:name="c1" loext:resolved="true">"#));
+    assert!(xml.contains(r#"<office:annotation office:name="c2" loext:parent-name="c1">"#));
+    assert!(xml.contains(r#"<office:annotation-end office:name="c1"/>"#));
+    let doc = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(doc.blocks, blocks);
+    assert_eq!(doc.comments().len(), 1);
+}
+
+// ── Tracked changes ───────────────────────────────────────────────────────────
+
+/// An insertion spanning two paragraphs and a deletion keeping its removed
+/// paragraph must survive write → parse intact, with the recording switch.
+/// A change without a date is dated when written.
+#[test]
+fn tracked_changes_round_trip_through_content_xml() {
+    let insertion = TrackedChange {
+        id: "ct1".to_string(),
+        kind: ChangeKind::Insertion,
+        author: Some("Ada".to_string()),
+        date: Some("2024-05-01T10:00:00".to_string()),
+        content: vec![],
+    };
+    let deletion = TrackedChange {
+        id: "ct2".to_string(),
+        kind: ChangeKind::Deletion,
+        author: Some("Grace".to_string()),
+        date: None,
+        content: vec![make_para("Removed")],
+    };
+    let blocks = vec![
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::ChangeStart(insertion),
+                Inline::Text {
+                    text: "Inserted".to_string(),
+                    marks: vec![],
+                    style_name: None,
+                },
+            ],
+        },
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::ChangeEnd {
+                    id: "ct1".to_string(),
+                },
+                Inline::Change(deletion),
+            ],
+        },
+    ];
+    let xml = to_content_xml(&blocks, true).expect("to_content_xml failed");
+    assert!(xml.contains(r#"<text:tracked-changes text:track-changes="true">"#));
+    assert!(xml.contains(r#"<text:changed-region xml:id="ct1" text:id="ct1"><text:insertion>"#));
+    assert!(xml.contains(r#"<text:change-start text:change-id="ct1"/>"#));
+    assert!(xml.contains(r#"<text:change text:change-id="ct2"/>"#));
+    assert!(xml.contains("<dc:creator>Grace</dc:creator><dc:date>"));
+    let mut doc = parse_document(&xml).expect("parse_document failed");
+    assert!(doc.track_changes);
+
+    // Apart from the date now on the deletion, nothing changed.
+    let Block::Paragraph { content, .. } = &mut doc.blocks[1] else {
+        panic!("expected a paragraph");
+    };
+    let Inline::Change(written) = &mut content[1] else {
+        panic!("expected the deletion");
+    };
+    assert!(written.date.is_some());
+    written.date = None;
+    assert_eq!(doc.blocks, blocks);
+}
+
+/// Changes kept while recording is off write the switch as `"false"`, and
+/// a section without the switch records changes, as ODF defaults it.
+#[test]
+fn track_changes_switch_is_written_explicitly() {
+    let deletion = TrackedChange {
+        id: "ct1".to_string(),
+        kind: ChangeKind::Deletion,
+        author: None,
+        date: Some("2024-05-01T10:00:00".to_string()),
+        content: vec![make_para("Removed")],
+    };
+    let blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Change(deletion)],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains(r#"<text:tracked-changes text:track-changes="false">"#));
+    assert!(!parse_document(&xml).unwrap().track_changes);
+
+    let implicit = xml.replace(r#" text:track-changes="false""#, "");
+    assert!(parse_document(&implicit).unwrap().track_changes);
+}
+
+// ── Sections ──────────────────────────────────────────────────────────────────
+
+/// Section names, protection and columns survive write → parse; the column
+/// layout is written as an automatic section style.
+#[test]
+fn section_round_trips_through_content_xml() {
+    let blocks = vec![Block::Section {
+        name: "Columns".to_string(),
+        attrs: Some(SectionAttrs {
+            column_count: Some(3),
+            column_gap: Some(24.0),
+        }),
+        protected: true,
+        content: vec![make_para("In three columns")],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains(r#"style:family="section""#));
+    assert!(xml.contains(r#"fo:column-count="3""#));
+    assert!(xml.contains(r#"text:protected="true""#));
+
+    let doc = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(doc.blocks, blocks);
+}
+
+// ── Lists ─────────────────────────────────────────────────────────────────────
+
+/// Numbered lists keep their kind, format and start value, and lists created
+/// in the editor reference the default list styles.
+#[test]
+fn ordered_list_round_trips_through_content_xml() {
+    let attrs = ListAttrs {
+        style_name: Some("L1".to_string()),
+        num_format: Some("a".to_string()),
+        num_suffix: Some(")".to_string()),
+        start: Some(3),
+        ..ListAttrs::default()
+    };
+    let item = |text: &str| Block::ListItem {
+        content: vec![make_para(text)],
+    };
+    let blocks = vec![
+        Block::OrderedList {
+            attrs: Some(attrs.clone()),
+            content: vec![item("c"), item("d")],
+        },
+        Block::OrderedList {
+            attrs: None,
+            content: vec![item("one")],
+        },
+        Block::BulletList {
+            attrs: None,
+            content: vec![item("dot")],
+        },
+    ];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains(r#"<text:list-style style:name="L1">"#));
+    assert!(xml.contains(r#"<text:list-item text:start-value="3">"#));
+
+    let doc = parse_document(&xml).expect("parse_document failed");
+    assert_eq!(
+        doc.blocks[0],
+        Block::OrderedList {
+            attrs: Some(attrs),
+            content: vec![item("c"), item("d")],
+        }
+    );
+    assert!(matches!(doc.blocks[1], Block::OrderedList { .. }));
+    assert!(matches!(doc.blocks[2], Block::BulletList { .. }));
+}
+
 // ── Table ─────────────────────────────────────────────────────────────────────
 
 fn make_para(text: &str) -> Block {
@@ -68,7 +492,7 @@ fn table_2x2_round_trips_through_content_xml() {
         ],
     }];
 
-    let xml = to_content_xml(&blocks).expect("to_content_xml failed");
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
     let doc = parse_document(&xml).expect("parse_document failed");
 
     assert_eq!(doc.blocks.len(), 1, "expected one table block");
@@ -108,8 +532,7 @@ fn table_cell_col_span_preserved_through_content_xml() {
     let spanned_cell = Block::TableCell {
         attrs: Some(CellAttrs {
             colspan: Some(2),
-            rowspan: None,
-            colwidth: None,
+            ..CellAttrs::default()
         }),
         content: vec![make_para("wide")],
     };
@@ -119,7 +542,7 @@ fn table_cell_col_span_preserved_through_content_xml() {
         }],
     }];
 
-    let xml = to_content_xml(&blocks).expect("to_content_xml failed");
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
     let doc = parse_document(&xml).expect("parse_document failed");
 
     let Block::Table { content: rows } = &doc.blocks[0] else {
@@ -134,3 +557,68 @@ fn table_cell_col_span_preserved_through_content_xml() {
     let attrs = attrs.as_ref().expect("expected Some(CellAttrs)");
     assert_eq!(attrs.colspan, Some(2), "colspan not preserved");
 }
+
+/// Header rows, column widths and cell backgrounds and borders survive
+/// write → parse, and spanned columns are filled with covered cells.
+#[test]
+fn table_structure_round_trips_through_content_xml() {
+    let border = "0.06pt solid #000000".to_string();
+    let header = |text: &str| Block::TableHeader {
+        attrs: Some(CellAttrs {
+            background_color: Some("#dddddd".to_string()),
+            border: Some(CellBorders {
+                bottom: Some(border.clone()),
+                ..CellBorders::default()
+            }),
+            ..CellAttrs::default()
+        }),
+        content: vec![make_para(text)],
+    };
+    let wide = Block::TableCell {
+        attrs: Some(CellAttrs {
+            colspan: Some(2),
+            colwidth: Some(vec![120, 60]),
+            ..CellAttrs::default()
+        }),
+        content: vec![make_para("wide")],
+    };
+    let blocks = vec![Block::Table {
+        content: vec![
+            Block::TableRow {
+                content: vec![header("A"), header("B")],
+            },
+            Block::TableRow {
+                content: vec![wide.clone()],
+            },
+        ],
+    }];
+    let xml = to_content_xml(&blocks, false).expect("to_content_xml failed");
+    assert!(xml.contains("<table:table-header-rows>"));
+    assert!(xml.contains("<table:covered-table-cell/>"));
+    assert_eq!(xml.matches(r#"style:family="table-column""#).count(), 2);
+
+    let doc = parse_document(&xml).expect("parse_document failed");
+    let Block::Table { content: rows } = &doc.blocks[0] else {
+        panic!("expected Block::Table");
+    };
+    let Block::TableRow { content: cells } = &rows[0] else {
+        panic!("expected Block::TableRow");
+    };
+    // Header cells take their widths from the columns of the spanning cell
+    let Block::TableHeader { attrs, .. } = &cells[1] else {
+        panic!("expected Block::TableHeader, got {:?}", cells[1]);
+    };
+    let attrs = attrs.as_ref().expect("expected Some(CellAttrs)");
+    assert_eq!(attrs.colwidth, Some(vec![60]));
+    assert_eq!(attrs.background_color.as_deref(), Some("#dddddd"));
+    assert_eq!(
+        attrs.border.as_ref().and_then(|b| b.bottom.as_deref()),
+        Some(border.as_str())
+    );
+    assert_eq!(
+        rows[1],
+        Block::TableRow {
+            content: vec![wide]
+        }
+    );
+}
@@ -280,3 +280,332 @@ fn style_parent_name_preserved() {
 
     assert_eq!(orig.parent, restored.parent, "parent style name changed");
 }
+
+#[test]
+fn outline_style_survives_fodt_and_styles_xml() {
+    use common_core::{OutlineLevel, OutlineStyle};
+
+    let xml = fodt(
+        r#"<text:outline-style style:name="Outline">
+            <text:outline-level-style text:level="1" style:num-format="I" style:num-suffix="."/>
+            <text:outline-level-style text:level="2" style:num-format="1"
+                text:display-levels="2" text:start-value="3"/>
+            <text:outline-level-style text:level="3" style:num-format=""/>
+           </text:outline-style>"#,
+        r#"<text:h text:outline-level="1">Part</text:h>"#,
+    );
+
+    let doc1 = parse_document(&xml).unwrap();
+    assert_eq!(
+        doc1.outline_style,
+        Some(OutlineStyle {
+            name: "Outline".to_string(),
+            levels: vec![
+                OutlineLevel {
+                    level: 1,
+                    num_format: Some("I".to_string()),
+                    num_suffix: Some(".".to_string()),
+                    ..OutlineLevel::default()
+                },
+                OutlineLevel {
+                    level: 2,
+                    num_format: Some("1".to_string()),
+                    start: Some(3),
+                    display_levels: 2,
+                    ..OutlineLevel::default()
+                },
+                OutlineLevel {
+                    level: 3,
+                    ..OutlineLevel::default()
+                },
+            ],
+        })
+    );
+
+    let doc2 = parse_document(&doc1.to_xml().unwrap()).unwrap();
+    assert_eq!(doc2.outline_style, doc1.outline_style);
+
+    let mut doc3 = Document::new();
+    doc3.add_styles_from_xml(&doc1.styles_to_xml().unwrap())
+        .unwrap();
+    assert_eq!(doc3.outline_style, doc1.outline_style);
+}
+
+#[test]
+fn page_setup_survives_fodt_update_and_styles_xml() {
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" office:version="1.3">
+  <office:styles>
+    <style:style style:name="Small Chapter" style:family="paragraph"
+        style:master-page-name="A5"/>
+  </office:styles>
+  <office:automatic-styles>
+    <style:page-layout style:name="pm1">
+      <style:page-layout-properties fo:page-width="8.5in" fo:page-height="11in"
+          fo:margin="1in" style:print-orientation="portrait"/>
+      <style:header-style/>
+    </style:page-layout>
+    <style:page-layout style:name="pm2">
+      <style:page-layout-properties fo:page-width="14.8cm" fo:page-height="21cm"
+          fo:margin-top="2cm" fo:margin-bottom="2cm" fo:margin-left="1.5cm"
+          fo:margin-right="1.5cm"/>
+    </style:page-layout>
+  </office:automatic-styles>
+  <office:master-styles>
+    <style:master-page style:name="Standard" style:page-layout-name="pm1">
+      <style:header><text:p>Running head</text:p></style:header>
+    </style:master-page>
+    <style:master-page style:name="A5" style:page-layout-name="pm2"
+        style:next-style-name="Standard"/>
+  </office:master-styles>
+  <office:body>
+    <office:text><text:p text:style-name="Small Chapter">Text</text:p></office:text>
+  </office:body>
+</office:document>"#
+    );
+
+    let mut doc1 = parse_document(&xml).unwrap();
+    let setup = doc1.page_setup.clone().expect("page setup");
+    assert_eq!(setup.layout_for(None).width, 612.0);
+    assert_eq!(setup.layout_for(None).margin_left, 72.0);
+    assert_eq!(setup.layout_for(Some("A5")).height, 595.28);
+    assert_eq!(setup.next_master("A5"), "Standard");
+    assert_eq!(
+        doc1.styles["Small Chapter"].master_page_name.as_deref(),
+        Some("A5")
+    );
+
+    // Regenerated from the model, in a fresh file and in place.
+    let doc2 = parse_document(&doc1.to_xml().unwrap()).unwrap();
+    assert_eq!(doc2.page_setup.as_ref(), Some(&setup));
+    assert_eq!(
+        doc2.styles["Small Chapter"].master_page_name.as_deref(),
+        Some("A5")
+    );
+
+    let mut wider = setup.clone();
+    wider.layouts[0].orientation = common_core::PageOrientation::Landscape;
+    (wider.layouts[0].width, wider.layouts[0].height) = (792.0, 612.0);
+    doc1.page_setup = Some(wider.clone());
+    let updated = doc1.update_fodt(&xml).unwrap();
+    assert!(updated.contains("Running head"), "header content kept");
+    assert!(
+        updated.contains("<style:header-style/>"),
+        "header style kept"
+    );
+    // Unchanged lengths are written as they were, not rounded through points.
+    for kept in [
+        r#"fo:margin="1in""#,
+        r#"fo:page-height="21cm""#,
+        r#"fo:margin-left="1.5cm""#,
+    ] {
+        assert!(updated.contains(kept), "{kept} rewritten:\n{updated}");
+    }
+    let doc3 = parse_document(&updated).unwrap();
+    assert_eq!(doc3.page_setup, Some(wider));
+
+    let mut doc4 = Document::new();
+    doc4.add_styles_from_xml(&doc2.styles_to_xml().unwrap())
+        .unwrap();
+    assert_eq!(doc4.page_setup, Some(setup));
+}
+
+#[test]
+fn headers_and_footers_round_trip_through_the_model() {
+    use common_core::{Block, FieldKind, Inline, TiptapMark};
+
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}" office:version="1.3">
+  <office:automatic-styles>
+    <style:style style:name="MT1" style:family="text">
+      <style:text-properties fo:font-weight="bold"/>
+    </style:style>
+    <style:page-layout style:name="pm1"/>
+  </office:automatic-styles>
+  <office:master-styles>
+    <style:master-page style:name="Standard" style:page-layout-name="pm1">
+      <style:header><text:p><text:span text:style-name="MT1">Annual</text:span> report</text:p></style:header>
+      <style:footer><text:p>Page <text:page-number>1</text:page-number></text:p></style:footer>
+      <style:footer-first><text:p>Cover</text:p></style:footer-first>
+    </style:master-page>
+  </office:master-styles>
+  <office:body>
+    <office:text><text:p>Text</text:p></office:text>
+  </office:body>
+</office:document>"#
+    );
+
+    let mut doc = parse_document(&xml).unwrap();
+    let mut setup = doc.page_setup.clone().expect("page setup");
+    let header = setup.master_pages[0].header.clone().expect("header");
+    let Block::Paragraph { content, .. } = &header.content[0] else {
+        panic!("expected a paragraph");
+    };
+    assert!(matches!(
+        &content[0],
+        Inline::Text { text, marks, .. } if text == "Annual" && marks.contains(&TiptapMark::Bold)
+    ));
+    let footer = setup.master_pages[0].footer.as_mut().expect("footer");
+    assert!(footer.first.is_some());
+
+    // An edited footer is written back, and the header keeps its formatting.
+    footer.first = None;
+    footer.content.push(Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Field {
+            kind: FieldKind::PageCount,
+            value: "9".to_string(),
+            fixed: false,
+            date_value: None,
+            data_style_name: None,
+        }],
+    });
+    doc.page_setup = Some(setup.clone());
+    let updated = doc.update_fodt(&xml).unwrap();
+    assert!(!updated.contains("Cover"), "{updated}");
+    let reparsed = parse_document(&updated).unwrap();
+    assert_eq!(reparsed.page_setup, Some(setup));
+}
+
+#[test]
+fn template_style_families_survive_fodt_update_and_styles_xml() {
+    use common_core::{ListLevelKind, PropertyGroup, StyleFamily};
+
+    let xml = format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="{NS_OFFICE}" xmlns:text="{NS_TEXT}"
+    xmlns:style="{NS_STYLE}" xmlns:fo="{NS_FO}"
+    xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+    xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+    xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0"
+    office:version="1.3">
+  <office:styles>
+    <style:default-style style:family="graphic">
+      <style:graphic-properties svg:stroke-color="#3465a4" draw:fill-color="#729fcf"
+          style:flow-with-text="false"/>
+      <style:paragraph-properties style:writing-mode="lr-tb"/>
+      <style:text-properties style:use-window-font-color="true" fo:language="en"/>
+    </style:default-style>
+    <style:default-style style:family="paragraph">
+      <style:paragraph-properties style:tab-stop-distance="1.251cm"/>
+      <style:text-properties style:font-name="Liberation Serif" fo:font-size="12pt"
+          fo:language="en" fo:country="US"/>
+    </style:default-style>
+    <style:default-style style:family="table">
+      <style:table-properties table:border-model="collapsing"/>
+    </style:default-style>
+    <style:default-style style:family="table-row">
+      <style:table-row-properties fo:keep-together="auto"/>
+    </style:default-style>
+    <style:style style:name="Standard" style:family="paragraph"/>
+    <style:style style:name="Frame" style:family="graphic">
+      <style:graphic-properties text:anchor-type="paragraph" svg:x="0cm" style:wrap="parallel"/>
+    </style:style>
+    <style:style style:name="Cell" style:family="table-cell">
+      <style:table-cell-properties fo:padding="0.1cm" fo:border="0.5pt solid #000000"/>
+      <style:paragraph-properties fo:text-align="center"/>
+    </style:style>
+    <style:style style:name="Boxed" style:family="section">
+      <style:section-properties fo:background-color="#eeeeee"/>
+    </style:style>
+    <text:list-style style:name="Numbering 123" style:display-name="Numbering 123">
+      <text:list-level-style-number text:level="1" style:num-suffix="." style:num-format="1">
+        <style:list-level-properties text:list-level-position-and-space-mode="label-alignment">
+          <style:list-level-label-alignment text:label-followed-by="listtab"
+              fo:margin-left="0.635cm" fo:text-indent="-0.635cm"/>
+        </style:list-level-properties>
+      </text:list-level-style-number>
+      <text:list-level-style-bullet text:level="2" text:bullet-char="•">
+        <style:list-level-properties/>
+        <style:text-properties style:font-name="OpenSymbol"/>
+      </text:list-level-style-bullet>
+    </text:list-style>
+  </office:styles>
+  <office:body>
+    <office:text>
+      <text:list text:style-name="Numbering 123">
+        <text:list-item><text:p text:style-name="Standard">One</text:p></text:list-item>
+      </text:list>
+    </office:text>
+  </office:body>
+</office:document>"##
+    );
+
+    let doc1 = parse_document(&xml).unwrap();
+    let graphic = &doc1.styles["_Default_Graphic"];
+    assert_eq!(graphic.family, StyleFamily::Graphic);
+    assert_eq!(
+        graphic.property(PropertyGroup::Graphic, "draw:fill-color"),
+        Some("#729fcf")
+    );
+    assert_eq!(
+        doc1.styles["_Default_Table"].property(PropertyGroup::Table, "table:border-model"),
+        Some("collapsing")
+    );
+    assert_eq!(
+        doc1.styles["_Default_TableRow"].property(PropertyGroup::TableRow, "fo:keep-together"),
+        Some("auto")
+    );
+    assert_eq!(
+        doc1.styles["Frame"].parent.as_deref(),
+        Some("_Default_Graphic")
+    );
+    let cell = &doc1.styles["Cell"];
+    assert_eq!(cell.family, StyleFamily::TableCell);
+    assert_eq!(
+        cell.property(PropertyGroup::TableCell, "fo:padding"),
+        Some("0.1cm")
+    );
+    assert_eq!(doc1.styles["Boxed"].family, StyleFamily::Section);
+    let list = &doc1.styles["Numbering 123"];
+    assert_eq!(list.family, StyleFamily::List);
+    assert_eq!(list.list_levels.len(), 2);
+    assert_eq!(list.list_levels[1].kind, ListLevelKind::Bullet);
+    assert_eq!(
+        list.list_levels[0].label_alignment.get("fo:margin-left"),
+        Some(&"0.635cm".to_string())
+    );
+
+    let same = |doc: &Document, compare_parents: bool| {
+        for (name, style) in &doc1.styles {
+            let other = doc
+                .styles
+                .get(name)
+                .unwrap_or_else(|| panic!("style '{name}' was lost"));
+            assert_eq!(other.family, style.family, "family of '{name}'");
+            assert_eq!(other.attributes, style.attributes, "attributes of '{name}'");
+            assert_eq!(other.properties, style.properties, "properties of '{name}'");
+            assert_eq!(other.list_levels, style.list_levels, "levels of '{name}'");
+            if compare_parents {
+                assert_eq!(other.parent, style.parent, "parent of '{name}'");
+            }
+        }
+    };
+    let list_style_count = |xml: &str| {
+        xml.matches(r#"<text:list-style style:name="Numbering 123""#)
+            .count()
+    };
+
+    let written = doc1.to_xml().unwrap();
+    assert!(written.contains(r#"<style:default-style style:family="graphic">"#));
+    assert!(
+        !written.contains("_Default_"),
+        "defaults are not named styles"
+    );
+    assert_eq!(list_style_count(&written), 1);
+    same(&parse_document(&written).unwrap(), true);
+
+    let updated = doc1.update_fodt(&xml).unwrap();
+    assert_eq!(list_style_count(&updated), 1);
+    same(&parse_document(&updated).unwrap(), true);
+
+    let mut doc3 = Document::new();
+    doc3.add_styles_from_xml(&doc1.styles_to_xml().unwrap())
+        .unwrap();
+    same(&doc3, false);
+}
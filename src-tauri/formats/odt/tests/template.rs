@@ -0,0 +1,209 @@
+//! Template and style import integration tests.
+//!
+//! Templates are written as `.ott` packages and flat XML, then opened as new
+//! documents; style import is checked for each mode and conflict policy.
+
+use std::collections::HashMap;
+
+use common_core::{Block, Inline, Metadata, StyleDefinition, StyleFamily};
+use odt_format::template::{
+    import_styles, open_template, StyleConflictPolicy, StyleImportMode, StyleImportOptions,
+    TEMPLATE_MIMETYPE,
+};
+use odt_format::{Document, OdtPackage};
+
+fn style(name: &str, parent: Option<&str>, size: &str) -> StyleDefinition {
+    StyleDefinition {
+        name: name.to_string(),
+        family: StyleFamily::Paragraph,
+        parent: parent.map(str::to_string),
+        next: None,
+        display_name: None,
+        attributes: HashMap::from([("fo:font-size".to_string(), size.to_string())]),
+        text_transform: None,
+        outline_level: None,
+        autocomplete: None,
+        master_page_name: None,
+        font_colour: None,
+        background_colour: None,
+        properties: Default::default(),
+        list_levels: Vec::new(),
+    }
+}
+
+fn styles(list: Vec<StyleDefinition>) -> HashMap<String, StyleDefinition> {
+    list.into_iter().map(|s| (s.name.clone(), s)).collect()
+}
+
+fn template() -> Document {
+    let mut doc = Document::new();
+    doc.styles = styles(vec![
+        style("House", None, "11pt"),
+        style("House Quote", Some("House"), "10pt"),
+    ]);
+    doc.blocks = vec![Block::Paragraph {
+        style_name: Some("House".to_string()),
+        attrs: None,
+        content: vec![Inline::Text {
+            text: "Dear reader,".to_string(),
+            style_name: None,
+            marks: vec![],
+        }],
+    }];
+    doc.metadata = Metadata {
+        identifier: Some("template-id".to_string()),
+        title: Some("Letter".to_string()),
+        creation_date: Some("2020-01-01T00:00:00".to_string()),
+        generator: Some("LibreOffice".to_string()),
+        ..Metadata::default()
+    };
+    doc
+}
+
+fn assert_is_new_letter(doc: &Document) {
+    assert_eq!(doc.styles["House Quote"].parent.as_deref(), Some("House"));
+    assert_eq!(doc.blocks.len(), 1);
+    assert_eq!(doc.metadata.title.as_deref(), Some("Letter"));
+    assert!(doc.metadata.identifier.is_none());
+    assert!(doc.metadata.creation_date.is_none());
+    assert!(doc.metadata.generator.is_none());
+}
+
+#[test]
+fn ott_package_opens_as_a_new_document() {
+    let mut package = OdtPackage::from_document(&template(), None).unwrap();
+    package.mimetype = TEMPLATE_MIMETYPE.to_string();
+    let bytes = package.to_bytes().unwrap();
+
+    let doc = open_template(&bytes).unwrap();
+    assert_is_new_letter(&doc);
+
+    // Saved without the template package, the result is a plain document.
+    let saved = OdtPackage::from_document(&doc, None).unwrap();
+    assert_eq!(saved.mimetype, odt_format::package::MIMETYPE);
+}
+
+#[test]
+fn flat_template_opens_as_a_new_document() {
+    let xml = template().to_xml().unwrap();
+    assert_is_new_letter(&open_template(xml.as_bytes()).unwrap());
+}
+
+#[test]
+fn invalid_template_bytes_are_rejected() {
+    assert!(open_template(&[0xff, 0xfe, 0x00]).is_err());
+}
+
+fn merge(conflict: StyleConflictPolicy) -> StyleImportOptions {
+    StyleImportOptions {
+        mode: StyleImportMode::Merge,
+        conflict,
+    }
+}
+
+fn existing() -> HashMap<String, StyleDefinition> {
+    styles(vec![
+        style("Body", None, "12pt"),
+        style("Quote", Some("Body"), "12pt"),
+        style("Mine", None, "9pt"),
+    ])
+}
+
+fn imported() -> HashMap<String, StyleDefinition> {
+    styles(vec![
+        style("Body", None, "11pt"),
+        style("Quote", Some("Body"), "12pt"),
+        style("House", Some("Body"), "11pt"),
+    ])
+}
+
+#[test]
+fn merge_keep_adds_new_styles_only() {
+    let mut target = existing();
+    let report = import_styles(&mut target, &imported(), merge(StyleConflictPolicy::Keep));
+    assert_eq!(report.added, vec!["House"]);
+    assert_eq!(report.kept, vec!["Body", "Quote"]);
+    assert!(report.overwritten.is_empty());
+    assert_eq!(target["Body"].attributes["fo:font-size"], "12pt");
+    assert!(target.contains_key("Mine"));
+}
+
+#[test]
+fn merge_overwrite_replaces_conflicting_styles() {
+    let mut target = existing();
+    let report = import_styles(
+        &mut target,
+        &imported(),
+        merge(StyleConflictPolicy::Overwrite),
+    );
+    assert_eq!(report.overwritten, vec!["Body"]);
+    assert_eq!(
+        report.kept,
+        vec!["Quote"],
+        "identical styles are no conflict"
+    );
+    assert_eq!(target["Body"].attributes["fo:font-size"], "11pt");
+    assert!(target.contains_key("Mine"));
+}
+
+#[test]
+fn merge_rename_keeps_both_and_relinks_parents() {
+    let mut target = existing();
+    let mut taken = style("Body_20_Text_20_2", None, "8pt");
+    taken.display_name = Some("Body Text 2".to_string());
+    target.insert(taken.name.clone(), taken);
+    let mut source = imported();
+    source.get_mut("Body").unwrap().display_name = Some("Body Text".to_string());
+
+    let report = import_styles(&mut target, &source, merge(StyleConflictPolicy::Rename));
+    assert_eq!(
+        report.renamed,
+        vec![("Body".to_string(), "Body_20_Text_20_3".to_string())]
+    );
+    assert_eq!(target["Body"].attributes["fo:font-size"], "12pt");
+    let renamed = &target["Body_20_Text_20_3"];
+    assert_eq!(renamed.name, "Body_20_Text_20_3");
+    assert_eq!(renamed.display_name.as_deref(), Some("Body Text 3"));
+    assert_eq!(target["House"].parent.as_deref(), Some("Body_20_Text_20_3"));
+    assert_eq!(
+        target["Body_20_Text_20_2"].attributes["fo:font-size"],
+        "8pt"
+    );
+
+    let xml = Document {
+        styles: target,
+        ..Document::new()
+    }
+    .styles_to_xml()
+    .unwrap();
+    assert!(xml.contains(
+        r#"style:name="Body_20_Text_20_3" style:family="paragraph" style:display-name="Body Text 3""#
+    ));
+}
+
+#[test]
+fn rename_keeps_conflicting_default_styles() {
+    let mut target = styles(vec![style("_Default_Paragraph", None, "12pt")]);
+    let source = styles(vec![style("_Default_Paragraph", None, "10pt")]);
+    let report = import_styles(&mut target, &source, merge(StyleConflictPolicy::Rename));
+    assert_eq!(report.kept, vec!["_Default_Paragraph"]);
+    assert_eq!(target.len(), 1);
+}
+
+#[test]
+fn replace_discards_existing_styles() {
+    let mut target = existing();
+    let report = import_styles(
+        &mut target,
+        &imported(),
+        StyleImportOptions {
+            mode: StyleImportMode::Replace,
+            conflict: StyleConflictPolicy::Keep,
+        },
+    );
+    assert_eq!(report.removed, vec!["Mine"]);
+    assert_eq!(report.added, vec!["House"]);
+    assert_eq!(report.overwritten, vec!["Body"]);
+    assert_eq!(report.kept, vec!["Quote"]);
+    assert_eq!(target, imported());
+}
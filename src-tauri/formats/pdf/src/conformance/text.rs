@@ -43,8 +43,11 @@ pub fn validate_text(
 pub fn check_text_not_empty(blocks: &[Block], violations: &mut Vec<ConformanceViolation>) {
     let has_content = blocks.iter().any(|b| match b {
         Block::Paragraph { content, .. } | Block::Heading { content, .. } => !content.is_empty(),
-        Block::BulletList { content } | Block::OrderedList { content } => !content.is_empty(),
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::Section { content, .. } => !content.is_empty(),
         Block::Table { content } => !content.is_empty(),
+        Block::TableOfContents(toc) => toc.title.is_some() || !toc.entries.is_empty(),
         Block::HorizontalRule | Block::PageBreak => true,
         _ => false,
     });
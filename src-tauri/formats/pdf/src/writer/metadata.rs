@@ -14,15 +14,26 @@
 
 //! XMP metadata generation for PDF/X compliance.
 
+use common_core::Metadata;
+
 use crate::export_settings::{PdfExportSettings, PdfXStandard};
 
+/// The ODF meta namespace, used in XMP for the ODF properties that have no
+/// Dublin Core or XMP equivalent.
+const ODF_META_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:meta:1.0#";
+
 /// Build an XMP metadata packet for the PDF/X document.
 ///
-/// The packet declares the GTS_PDFXVersion and includes basic dc: metadata.
-pub fn build_xmp_packet(title: Option<&str>, settings: &PdfExportSettings) -> String {
+/// The packet declares the GTS_PDFXVersion and carries the document
+/// metadata: Dublin Core title, creator, description, subject, keywords,
+/// language and identifier; XMP creation and modification dates; PDF
+/// keywords; the ODF editing and statistics properties; and user-defined
+/// properties as `pdfx:` custom properties.
+pub fn build_xmp_packet(metadata: &Metadata, settings: &PdfExportSettings) -> String {
     let gts_version = settings.standard.gts_version_string();
-    let title_str = title.unwrap_or("Untitled");
+    let title_str = metadata.title.as_deref().unwrap_or("Untitled");
     let conformance_attr = xmp_conformance_attr(settings.standard);
+    let properties = xmp_properties(metadata);
 
     format!(
         r#"<?xpacket begin="\xef\xbb\xbf" id="W5M0MpCehiHzreSzNTczkc9d"?>
@@ -30,26 +41,138 @@ pub fn build_xmp_packet(title: Option<&str>, settings: &PdfExportSettings) -> St
   <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
     <rdf:Description rdf:about=""
         xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/"
         xmlns:pdfx="http://ns.adobe.com/pdfx/1.3/"
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/"
         xmlns:xmpMM="http://ns.adobe.com/xap/1.0/mm/"
+        xmlns:meta="{odf_meta_ns}"
         {conformance_attr}>
       <dc:title>
         <rdf:Alt>
           <rdf:li xml:lang="x-default">{title_str}</rdf:li>
         </rdf:Alt>
       </dc:title>
-      <pdfx:GTS_PDFXVersion>{gts_version}</pdfx:GTS_PDFXVersion>
+{properties}      <pdfx:GTS_PDFXVersion>{gts_version}</pdfx:GTS_PDFXVersion>
     </rdf:Description>
   </rdf:RDF>
 </x:xmpmeta>
 <?xpacket end="w"?>
 "#,
+        odf_meta_ns = ODF_META_NS,
         conformance_attr = conformance_attr,
         title_str = escape_xml(title_str),
+        properties = properties,
         gts_version = gts_version,
     )
 }
 
+/// Returns the XMP properties for everything in `metadata` but the title,
+/// one per line.
+fn xmp_properties(metadata: &Metadata) -> String {
+    let mut out = String::new();
+    if let Some(creator) = &metadata.creator {
+        push_array(&mut out, "dc:creator", "rdf:Seq", &[creator.as_str()]);
+    }
+    if let Some(description) = &metadata.description {
+        push_alt(&mut out, "dc:description", description);
+    }
+    // XMP keeps keywords in dc:subject; the ODF subject line goes first.
+    let subjects: Vec<&str> = metadata
+        .subject
+        .iter()
+        .chain(&metadata.keywords)
+        .map(String::as_str)
+        .collect();
+    if !subjects.is_empty() {
+        push_array(&mut out, "dc:subject", "rdf:Bag", &subjects);
+    }
+    if !metadata.keywords.is_empty() {
+        push_simple(&mut out, "pdf:Keywords", &metadata.keywords.join(", "));
+    }
+    if let Some(language) = &metadata.language {
+        push_array(&mut out, "dc:language", "rdf:Bag", &[language.as_str()]);
+    }
+    if let Some(identifier) = &metadata.identifier {
+        push_simple(&mut out, "dc:identifier", identifier);
+    }
+    if let Some(date) = &metadata.creation_date {
+        push_simple(&mut out, "xmp:CreateDate", date);
+    }
+    if let Some(date) = &metadata.date {
+        push_simple(&mut out, "xmp:ModifyDate", date);
+    }
+    if let Some(generator) = &metadata.generator {
+        push_simple(&mut out, "xmp:CreatorTool", generator);
+    }
+    if let Some(creator) = &metadata.initial_creator {
+        push_simple(&mut out, "meta:initial-creator", creator);
+    }
+    if let Some(cycles) = metadata.editing_cycles {
+        push_simple(&mut out, "meta:editing-cycles", &cycles.to_string());
+    }
+    if let Some(duration) = &metadata.editing_duration {
+        push_simple(&mut out, "meta:editing-duration", duration);
+    }
+    if let Some(stats) = &metadata.statistics {
+        if let Some(pages) = stats.page_count {
+            push_simple(&mut out, "meta:page-count", &pages.to_string());
+        }
+        for (name, value) in stats.odf_counts() {
+            push_simple(&mut out, &format!("meta:{name}"), &value.to_string());
+        }
+    }
+    for field in &metadata.user_defined {
+        push_simple(
+            &mut out,
+            &format!("pdfx:{}", xml_name(&field.name)),
+            &field.value,
+        );
+    }
+    out
+}
+
+fn push_simple(out: &mut String, name: &str, value: &str) {
+    out.push_str(&format!("      <{name}>{}</{name}>\n", escape_xml(value)));
+}
+
+fn push_alt(out: &mut String, name: &str, value: &str) {
+    out.push_str(&format!(
+        "      <{name}>\n        <rdf:Alt>\n          \
+         <rdf:li xml:lang=\"x-default\">{}</rdf:li>\n        </rdf:Alt>\n      </{name}>\n",
+        escape_xml(value)
+    ));
+}
+
+fn push_array(out: &mut String, name: &str, kind: &str, items: &[&str]) {
+    out.push_str(&format!("      <{name}>\n        <{kind}>\n"));
+    for item in items {
+        out.push_str(&format!(
+            "          <rdf:li>{}</rdf:li>\n",
+            escape_xml(item)
+        ));
+    }
+    out.push_str(&format!("        </{kind}>\n      </{name}>\n"));
+}
+
+/// Turns a property name into an XML local name, replacing the characters
+/// XML names cannot hold with `_`.
+fn xml_name(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !out.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    out
+}
+
 fn xmp_conformance_attr(standard: PdfXStandard) -> &'static str {
     match standard {
         PdfXStandard::X1a2001 => {
@@ -72,6 +195,14 @@ fn escape_xml(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common_core::{DocumentStatistics, UserDefinedField};
+
+    fn titled(title: &str) -> Metadata {
+        Metadata {
+            title: Some(title.to_string()),
+            ..Metadata::default()
+        }
+    }
 
     #[test]
     fn xmp_contains_gts_version_x4() {
@@ -79,7 +210,7 @@ mod tests {
             standard: PdfXStandard::X4_2008,
             ..Default::default()
         };
-        let xmp = build_xmp_packet(Some("Test Doc"), &settings);
+        let xmp = build_xmp_packet(&titled("Test Doc"), &settings);
         assert!(xmp.contains("PDF/X-4"), "XMP should declare PDF/X-4");
     }
 
@@ -90,7 +221,7 @@ mod tests {
             output_condition_identifier: "FOGRA39".to_string(),
             ..Default::default()
         };
-        let xmp = build_xmp_packet(Some("Print Doc"), &settings);
+        let xmp = build_xmp_packet(&titled("Print Doc"), &settings);
         assert!(
             xmp.contains("PDF/X-1a:2001"),
             "XMP should declare PDF/X-1a:2001"
@@ -100,7 +231,38 @@ mod tests {
     #[test]
     fn xmp_escapes_title_xml() {
         let settings = PdfExportSettings::default();
-        let xmp = build_xmp_packet(Some("A & B <test>"), &settings);
+        let xmp = build_xmp_packet(&titled("A & B <test>"), &settings);
         assert!(xmp.contains("A &amp; B &lt;test&gt;"));
     }
+
+    #[test]
+    fn xmp_carries_extended_metadata() {
+        let metadata = Metadata {
+            creator: Some("Ann".to_string()),
+            subject: Some("Reports".to_string()),
+            keywords: vec!["annual".to_string(), "finance".to_string()],
+            date: Some("2024-05-01T09:00:00".to_string()),
+            editing_cycles: Some(7),
+            initial_creator: Some("Bob".to_string()),
+            user_defined: vec![UserDefinedField {
+                name: "Client No.".to_string(),
+                value_type: None,
+                value: "A&B".to_string(),
+            }],
+            statistics: Some(DocumentStatistics {
+                word_count: 120,
+                ..DocumentStatistics::default()
+            }),
+            ..titled("Report")
+        };
+        let xmp = build_xmp_packet(&metadata, &PdfExportSettings::default());
+        assert!(xmp.contains("<rdf:li>Reports</rdf:li>"));
+        assert!(xmp.contains("<rdf:li>finance</rdf:li>"));
+        assert!(xmp.contains("<pdf:Keywords>annual, finance</pdf:Keywords>"));
+        assert!(xmp.contains("<xmp:ModifyDate>2024-05-01T09:00:00</xmp:ModifyDate>"));
+        assert!(xmp.contains("<meta:editing-cycles>7</meta:editing-cycles>"));
+        assert!(xmp.contains("<meta:initial-creator>Bob</meta:initial-creator>"));
+        assert!(xmp.contains("<meta:word-count>120</meta:word-count>"));
+        assert!(xmp.contains("<pdfx:Client_No.>A&amp;B</pdfx:Client_No.>"));
+    }
 }
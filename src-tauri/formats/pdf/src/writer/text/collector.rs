@@ -16,42 +16,45 @@ use crate::fonts::subset::UsedGlyphs;
 use common_core::block::Block;
 use common_core::inline::Inline;
 use common_core::marks::TiptapMark;
-use common_core::style::StyleDefinition;
+use common_core::style::{StyleDefinition, StyleFamily};
+use common_core::StyleResolver;
 use std::collections::HashMap;
 
+use super::style_props::resolve_block_props;
+use super::toc::toc_blocks;
+
 /// (family_id, weight_100_900, italic)
 pub type FontKey = (String, u16, bool);
 
 /// Default font family used when a style does not specify one.
-const DEFAULT_FONT_FAMILY: &str = "public sans";
+pub(super) const DEFAULT_FONT_FAMILY: &str = "public sans";
 
-/// Extract the font key for an inline based on block style + marks.
+/// Extract the font key for an inline based on its style, the effective
+/// font family of its block and its marks.
+///
+/// The character style is resolved through its parents, so a family, weight
+/// or slant it inherits counts as its own.
 pub fn inline_font_key(
     marks: &[TiptapMark],
     style_name: Option<&str>,
     styles: &HashMap<String, StyleDefinition>,
-    block_style_name: Option<&str>,
+    block_family: Option<&str>,
 ) -> FontKey {
-    let is_bold = marks.iter().any(|m| matches!(m, TiptapMark::Bold));
-    let is_italic = marks.iter().any(|m| matches!(m, TiptapMark::Italic));
+    let effective =
+        style_name.map(|n| StyleResolver::new(styles).resolve(Some(n), StyleFamily::Text));
+    let attr = |key: &str| effective.as_ref().and_then(|e| e.get(key));
+
+    let is_bold = marks.iter().any(|m| matches!(m, TiptapMark::Bold))
+        || attr("fo:font-weight")
+            .is_some_and(|w| w == "bold" || w.parse::<u16>().is_ok_and(|w| w >= 700));
+    let is_italic = marks.iter().any(|m| matches!(m, TiptapMark::Italic))
+        || attr("fo:font-style") == Some("italic");
     let weight = if is_bold { 700 } else { 400 };
 
-    // Determine font family: inline style → block style → default.
-    let family = style_name
-        .and_then(|n| styles.get(n))
-        .and_then(|s| {
-            s.attributes
-                .get("fo:font-family")
-                .or_else(|| s.attributes.get("style:font-name"))
-        })
-        .or_else(|| {
-            block_style_name.and_then(|n| styles.get(n)).and_then(|s| {
-                s.attributes
-                    .get("fo:font-family")
-                    .or_else(|| s.attributes.get("style:font-name"))
-            })
-        })
-        .map(|s| s.as_str())
+    // Determine font family: inline style → block → default.
+    let family = attr("fo:font-family")
+        .or_else(|| attr("style:font-name"))
+        .or(block_family)
         .unwrap_or(DEFAULT_FONT_FAMILY);
 
     (family.to_lowercase(), weight, is_italic)
@@ -76,25 +79,18 @@ fn collect_from_block(
     out: &mut HashMap<FontKey, UsedGlyphs>,
 ) {
     match block {
-        Block::Paragraph {
-            style_name,
-            content,
-            ..
-        }
-        | Block::Heading {
-            style_name,
-            content,
-            ..
-        } => {
-            let sname = style_name.as_deref();
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+            // The same resolution the renderer uses, so the subsets match.
+            let family = resolve_block_props(block, styles).font_family;
             for inline in content {
-                collect_from_inline(inline, sname, styles, out);
+                collect_from_inline(inline, Some(&family), styles, out);
             }
         }
-        Block::BulletList { content }
-        | Block::OrderedList { content }
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
         | Block::ListItem { content }
         | Block::Blockquote { content }
+        | Block::Section { content, .. }
         | Block::Table { content }
         | Block::TableRow { content }
         | Block::TableHeader { content, .. }
@@ -103,23 +99,146 @@ fn collect_from_block(
                 collect_from_block(child, styles, out);
             }
         }
+        Block::TableOfContents(toc) => {
+            let children = toc_blocks(toc);
+            for child in &children {
+                collect_from_block(child, styles, out);
+            }
+            // Page numbers are filled in during layout, in the font of the
+            // entry they end.
+            let entries = children.iter().skip(usize::from(toc.title.is_some()));
+            for entry in entries {
+                let family = resolve_block_props(entry, styles).font_family;
+                let key = inline_font_key(&[], None, styles, Some(&family));
+                out.entry(key).or_default().extend('0'..='9');
+            }
+        }
         Block::HorizontalRule | Block::PageBreak | Block::Image { .. } => {}
     }
 }
 
 fn collect_from_inline(
     inline: &Inline,
-    block_style: Option<&str>,
+    block_family: Option<&str>,
     styles: &HashMap<String, StyleDefinition>,
     out: &mut HashMap<FontKey, UsedGlyphs>,
 ) {
-    if let Inline::Text {
-        text,
-        style_name,
-        marks,
-    } = inline
-    {
-        let key = inline_font_key(marks, style_name.as_deref(), styles, block_style);
-        out.entry(key).or_default().extend(text.chars());
+    match inline {
+        Inline::Text {
+            text,
+            style_name,
+            marks,
+        } => {
+            let key = inline_font_key(marks, style_name.as_deref(), styles, block_family);
+            out.entry(key).or_default().extend(text.chars());
+        }
+        Inline::Note { citation, .. } => {
+            let key = inline_font_key(&[], None, styles, block_family);
+            out.entry(key).or_default().extend(citation.chars());
+            // Note bodies are flattened and set in the default font.
+            let note_key = inline_font_key(&[], None, styles, None);
+            out.entry(note_key)
+                .or_default()
+                .extend(super::notes::note_text(inline).chars());
+        }
+        Inline::Field { kind, value, .. } => {
+            let key = inline_font_key(&[], None, styles, block_family);
+            let glyphs = out.entry(key).or_default();
+            glyphs.extend(value.chars());
+            // Page fields are filled in during layout.
+            if kind.is_paged() {
+                glyphs.extend('0'..='9');
+            }
+        }
+        Inline::Reference(reference) => {
+            let key = inline_font_key(&[], None, styles, block_family);
+            out.entry(key).or_default().extend(reference.value.chars());
+        }
+        Inline::LineBreak
+        | Inline::Space { .. }
+        | Inline::Tab
+        | Inline::Bookmark { .. }
+        | Inline::BookmarkStart { .. }
+        | Inline::BookmarkEnd { .. }
+        | Inline::Comment(_)
+        | Inline::CommentEnd { .. }
+        | Inline::ChangeStart(_)
+        | Inline::ChangeEnd { .. }
+        | Inline::Change(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_core::toc::{TableOfContents, TocEntry};
+
+    fn text_style(name: &str, parent: Option<&str>, attrs: &[(&str, &str)]) -> StyleDefinition {
+        StyleDefinition {
+            name: name.to_string(),
+            family: StyleFamily::Text,
+            parent: parent.map(str::to_string),
+            next: None,
+            display_name: None,
+            attributes: attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            text_transform: None,
+            outline_level: None,
+            autocomplete: None,
+            master_page_name: None,
+            font_colour: None,
+            background_colour: None,
+            properties: HashMap::new(),
+            list_levels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn toc_page_numbers_use_the_entry_font() {
+        let mut entry = text_style("Contents_20_1", None, &[("fo:font-family", "Lora")]);
+        entry.family = StyleFamily::Paragraph;
+        let styles = HashMap::from([(entry.name.clone(), entry)]);
+        let toc = TableOfContents {
+            entries: vec![TocEntry {
+                level: 1,
+                text: "Intro".to_string(),
+                target: None,
+                page: None,
+            }],
+            ..TableOfContents::default()
+        };
+
+        let used = collect_used_glyphs(&[Block::TableOfContents(toc)], &styles);
+        let digits = &used[&("lora".to_string(), 400, false)];
+        assert!(('0'..='9').all(|d| digits.contains(&d)));
+    }
+
+    #[test]
+    fn character_styles_inherit_family_weight_and_slant() {
+        let styles: HashMap<String, StyleDefinition> = [
+            text_style(
+                "Code",
+                None,
+                &[
+                    ("fo:font-family", "Liberation Mono"),
+                    ("fo:font-weight", "bold"),
+                ],
+            ),
+            text_style(
+                "Code_20_Italic",
+                Some("Code"),
+                &[("fo:font-style", "italic")],
+            ),
+        ]
+        .into_iter()
+        .map(|s| (s.name.clone(), s))
+        .collect();
+
+        let key = inline_font_key(&[], Some("Code_20_Italic"), &styles, Some("public sans"));
+        assert_eq!(key, ("liberation mono".to_string(), 700, true));
+        let key = inline_font_key(&[], None, &styles, Some("public sans"));
+        assert_eq!(key, ("public sans".to_string(), 400, false));
     }
 }
@@ -0,0 +1,227 @@
+// Copyright 2024 AppThere
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multi-column layout of document sections.
+//!
+//! The blocks of a section are placed whole, top to bottom in each column
+//! and left to right across the columns. When the rest of the section fits
+//! on the page the columns are balanced to about the same height; otherwise
+//! they are filled to the page bottom and the remaining blocks continue on
+//! the next page.
+
+use common_core::block::{Block, SectionAttrs};
+use common_core::style::StyleDefinition;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::collector::FontKey;
+use super::layout::LayoutState;
+use super::para::block_height;
+use super::renderer::emit_block;
+use crate::fonts::subset::FontSubset;
+
+/// Points per CSS pixel (96 dpi).
+const PT_PER_PX: f64 = 0.75;
+
+/// Where the blocks of a section go on the current page.
+struct ColumnPlan {
+    /// The blocks of each column, as ranges of the laid-out blocks.
+    columns: Vec<Range<usize>>,
+    /// The height of the tallest column in points.
+    height: f64,
+}
+
+impl ColumnPlan {
+    /// The number of blocks placed.
+    fn placed(&self) -> usize {
+        self.columns.last().map_or(0, |c| c.end)
+    }
+}
+
+/// Returns the number of columns, the column width and the gap between
+/// columns, in points.
+fn column_geometry(attrs: Option<&SectionAttrs>, usable_width: f64) -> (usize, f64, f64) {
+    let count = attrs.map_or(1, SectionAttrs::columns) as usize;
+    let gaps = (count - 1) as f64;
+    let gap = attrs.and_then(|a| a.column_gap).unwrap_or(0.0) * PT_PER_PX;
+    let gap = if gaps > 0.0 {
+        gap.min(usable_width / (2.0 * gaps))
+    } else {
+        0.0
+    };
+    let width = (usable_width - gap * gaps) / count as f64;
+    (count, width, gap)
+}
+
+/// Fills up to `count` columns no taller than `limit`.
+///
+/// A block taller than `limit` still gets a column of its own when `force`
+/// is set. Returns the plan and, if some blocks did not fit, the smallest
+/// increase of `limit` that would have let another block into a column.
+fn fill(heights: &[f64], count: usize, limit: f64, force: bool) -> (ColumnPlan, Option<f64>) {
+    let mut columns = Vec::new();
+    let mut height = 0.0f64;
+    let mut shortfall: Option<f64> = None;
+    let mut start = 0;
+    while start < heights.len() && columns.len() < count {
+        if heights[start] > limit && !force {
+            break;
+        }
+        let mut end = start + 1;
+        let mut column_height = heights[start];
+        while end < heights.len() && column_height + heights[end] <= limit {
+            column_height += heights[end];
+            end += 1;
+        }
+        if end < heights.len() {
+            let needed = column_height + heights[end] - limit;
+            shortfall = Some(shortfall.map_or(needed, |s| s.min(needed)));
+        }
+        height = height.max(column_height);
+        columns.push(start..end);
+        start = end;
+    }
+    (ColumnPlan { columns, height }, shortfall)
+}
+
+/// Plans the columns of blocks with the given heights, `available` points
+/// above the page bottom. `at_top` is set when nothing else is on the page
+/// yet, so at least one block must be placed.
+fn plan_columns(heights: &[f64], count: usize, available: f64, at_top: bool) -> ColumnPlan {
+    let total: f64 = heights.iter().sum();
+    let tallest = heights.iter().copied().fold(0.0, f64::max);
+    let mut limit = (total / count as f64).max(tallest);
+    loop {
+        let (plan, shortfall) = fill(heights, count, limit, true);
+        if plan.placed() == heights.len() {
+            if plan.height <= available {
+                return plan;
+            }
+            break;
+        }
+        match shortfall {
+            Some(step) if step > 0.0 => limit += step,
+            _ => break,
+        }
+    }
+    fill(heights, count, available, at_top).0
+}
+
+/// The height of a section laid out in its columns without a page break.
+pub(super) fn section_height(
+    attrs: Option<&SectionAttrs>,
+    content: &[Block],
+    styles: &HashMap<String, StyleDefinition>,
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    usable_width: f64,
+) -> f64 {
+    let (count, width, _) = column_geometry(attrs, usable_width);
+    let heights: Vec<f64> = content
+        .iter()
+        .map(|b| block_height(b, styles, font_map, width))
+        .collect();
+    plan_columns(&heights, count, f64::INFINITY, true).height
+}
+
+/// Emits the blocks of a section from block `start` on, in the section's
+/// columns.
+///
+/// Returns the number of blocks placed. Sets `overflowed` when the rest of
+/// the section has to continue on the next page.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn emit_section(
+    attrs: Option<&SectionAttrs>,
+    content: &[Block],
+    styles: &HashMap<String, StyleDefinition>,
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    state: &mut LayoutState,
+    overflowed: &mut bool,
+    out: &mut String,
+    start: usize,
+) -> usize {
+    let rest = &content[start.min(content.len())..];
+    let (count, width, gap) = column_geometry(attrs, state.usable_width);
+    let heights: Vec<f64> = rest
+        .iter()
+        .map(|b| block_height(b, styles, font_map, width))
+        .collect();
+    let available = state.page_height - state.bottom_margin - state.current_y_from_top;
+    let at_top = state.current_y_from_top <= state._top_margin;
+    let plan = plan_columns(&heights, count, available, at_top);
+
+    for (i, range) in plan.columns.iter().enumerate() {
+        let mut column = LayoutState {
+            current_y_from_top: state.current_y_from_top,
+            left_margin: state.left_margin + i as f64 * (width + gap),
+            _top_margin: state._top_margin,
+            usable_width: width,
+            page_height: state.page_height,
+            bottom_margin: state.bottom_margin,
+            page_number: state.page_number,
+            anchors: Vec::new(),
+            links: Vec::new(),
+        };
+        // The plan only places blocks that fit, apart from a block taller
+        // than a whole page, which is cut off at the page bottom.
+        let mut clipped = false;
+        for block in &rest[range.clone()] {
+            emit_block(block, styles, font_map, &mut column, &mut clipped, out, 0);
+        }
+        state.anchors.append(&mut column.anchors);
+        state.links.append(&mut column.links);
+    }
+
+    let placed = plan.placed();
+    if placed < rest.len() || state.advance(plan.height) {
+        *overflowed = true;
+    }
+    placed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_are_balanced_when_the_section_fits() {
+        let plan = plan_columns(&[10.0, 10.0, 10.0, 10.0], 2, 100.0, false);
+        assert_eq!(plan.columns, vec![0..2, 2..4]);
+        assert_eq!(plan.height, 20.0);
+    }
+
+    #[test]
+    fn columns_fill_to_the_page_bottom_when_the_section_does_not_fit() {
+        let plan = plan_columns(&[10.0; 10], 2, 30.0, false);
+        assert_eq!(plan.columns, vec![0..3, 3..6]);
+        assert_eq!(plan.placed(), 6);
+
+        let plan = plan_columns(&[50.0, 10.0], 2, 30.0, false);
+        assert_eq!(plan.placed(), 0);
+        let plan = plan_columns(&[50.0, 10.0], 2, 30.0, true);
+        assert_eq!(plan.columns, vec![0..1, 1..2]);
+    }
+
+    #[test]
+    fn gap_is_taken_from_the_usable_width() {
+        let attrs = SectionAttrs {
+            column_count: Some(2),
+            column_gap: Some(40.0),
+        };
+        let (count, width, gap) = column_geometry(Some(&attrs), 430.0);
+        assert_eq!(count, 2);
+        assert_eq!(gap, 30.0);
+        assert_eq!(width, 200.0);
+        assert_eq!(column_geometry(None, 430.0), (1, 430.0, 0.0));
+    }
+}
@@ -14,6 +14,8 @@
 
 //! Line-breaking and page layout for text document PDF generation.
 
+use common_core::page::PageLayout;
+
 use crate::fonts::subset::FontSubset;
 
 /// State for the single-pass text layout engine.
@@ -25,23 +27,48 @@ pub struct LayoutState {
     pub usable_width: f64,
     pub page_height: f64,
     pub bottom_margin: f64,
+    /// The number of the page being laid out, counting from 1.
+    pub page_number: usize,
+    /// Bookmarks placed on this page, with the PDF y of their line top.
+    pub anchors: Vec<(String, f64)>,
+    /// Cross-references placed on this page.
+    pub links: Vec<PageLink>,
+}
+
+/// A clickable area of a page that jumps to a bookmark.
+pub struct PageLink {
+    /// The name of the bookmark jumped to.
+    pub target: String,
+    /// The area `[x1, y1, x2, y2]` in PDF points.
+    pub rect: [f64; 4],
 }
 
 /// The result of laying out content for a single page.
 pub struct PageContent {
+    /// The page width in PDF points.
+    pub width: f64,
+    /// The page height in PDF points.
+    pub height: f64,
     /// The PDF content stream for this page.
     pub content_stream: String,
+    /// Bookmarks placed on this page, with the PDF y of their line top.
+    pub anchors: Vec<(String, f64)>,
+    /// Cross-references placed on this page.
+    pub links: Vec<PageLink>,
 }
 
 impl LayoutState {
-    pub fn new(page_width: f64, page_height: f64, margin: f64) -> Self {
+    pub fn new(layout: &PageLayout) -> Self {
         LayoutState {
-            current_y_from_top: margin,
-            left_margin: margin,
-            _top_margin: margin,
-            usable_width: page_width - 2.0 * margin,
-            page_height,
-            bottom_margin: margin,
+            current_y_from_top: layout.margin_top,
+            left_margin: layout.margin_left,
+            _top_margin: layout.margin_top,
+            usable_width: layout.content_width(),
+            page_height: layout.height,
+            bottom_margin: layout.margin_bottom,
+            page_number: 1,
+            anchors: Vec::new(),
+            links: Vec::new(),
         }
     }
 
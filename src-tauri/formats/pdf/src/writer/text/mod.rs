@@ -15,31 +15,45 @@
 //! Text document layout and PDF content stream generation.
 
 mod collector;
+mod columns;
 mod layout;
 mod measure;
 pub mod named_styles;
+mod notes;
 mod operators;
 mod para;
+mod regions;
 mod renderer;
 pub mod style_props;
+mod toc;
 
 pub use collector::{collect_used_glyphs, FontKey};
 pub use renderer::emit_blocks;
 
+use common_core::field::{evaluate_fields, has_field, FieldKind, FieldValues};
+use common_core::toc::regenerate_toc;
+
+use toc::{expand_tocs, fill_toc_pages, has_toc};
+
 use crate::error::PdfError;
 use crate::export_settings::PdfExportSettings;
 
 /// Write a text document (blocks + styles + metadata) to PDF/X-compliant bytes.
+///
+/// Pages are sized and laid out from `page_setup`, with the headers and
+/// footers of its master pages; an empty setup gives A4 pages with one-inch
+/// margins.
 pub fn write_text_pdf(
     blocks: &[common_core::Block],
     styles: &std::collections::HashMap<String, common_core::StyleDefinition>,
+    page_setup: &common_core::PageSetup,
     metadata: &common_core::Metadata,
     settings: &PdfExportSettings,
     font_resolver: &dyn crate::fonts::resolver::FontResolver,
 ) -> Result<Vec<u8>, PdfError> {
     use crate::conformance::validate_text;
     use crate::fonts::{create_subset, embed_font};
-    use pdf_writer::{Name, Pdf, Ref};
+    use pdf_writer::{Finish, Name, Pdf, Ref};
 
     // 1. Validate — reject hard (non-auto-fixable) violations.
     let violations = validate_text(blocks, styles, metadata, settings);
@@ -54,13 +68,27 @@ pub fn write_text_pdf(
         return Err(PdfError::Conformance(msg));
     }
 
-    // 2. Collect used glyphs per font variant (Pass 1).
-    let used_by_font = collect_used_glyphs(blocks, styles);
+    // 2. Bring tables of contents up to date and fill in text fields, in the
+    //    headers and footers too; page numbers and page fields are filled in
+    //    during layout.
+    let mut blocks = blocks.to_vec();
+    let mut page_setup = page_setup.clone();
+    regenerate_toc(&mut blocks);
+    let mut field_values = FieldValues::for_document(&blocks, metadata);
+    evaluate_fields(&mut blocks, &field_values);
+    for region in page_setup.header_footer_blocks_mut() {
+        evaluate_fields(region, &field_values);
+    }
+
+    // 3. Collect used glyphs per font variant (Pass 1).
+    let mut glyph_blocks = blocks.clone();
+    glyph_blocks.extend(page_setup.header_footer_blocks().flatten().cloned());
+    let used_by_font = collect_used_glyphs(&glyph_blocks, styles);
 
     let mut pdf = Pdf::new();
     let mut next_ref = 6i32; // 1-5 reserved for catalog/pages/page/content/xmp
 
-    // 3. Subset and embed each required font variant.
+    // 4. Subset and embed each required font variant.
     let mut font_map: std::collections::HashMap<FontKey, (String, Ref, crate::fonts::FontSubset)> =
         std::collections::HashMap::new();
 
@@ -110,26 +138,36 @@ pub fn write_text_pdf(
         font_map.insert(key, (pdf_name, font_ref, subset));
     }
 
-    // 4. Page geometry (A4 portrait: 595 × 842 pt).
-    let page_width_pt = 595.0_f64;
-    let page_height_pt = 842.0_f64;
-    let margin_pt = 72.0_f64; // 1 inch
-    let bleed = settings.bleed_pt;
-
-    // 5. Generate content streams (Pass 2).
+    // 5. Generate content streams (Pass 2), with the size and margins of
+    //    each page's master page.
     let emit_map: std::collections::HashMap<FontKey, (String, crate::fonts::FontSubset)> = font_map
         .iter()
         .map(|(k, v)| (k.clone(), (v.0.clone(), v.2.clone())))
         .collect();
 
-    let layout_result = emit_blocks(
-        blocks,
-        styles,
-        &emit_map,
-        page_width_pt,
-        page_height_pt,
-        margin_pt,
-    )?;
+    let mut layout_result = emit_blocks(&expand_tocs(&blocks), styles, &emit_map, &page_setup)?;
+
+    // The page count and the pages of headings are only known after layout;
+    // filling them in can change the layout, so lay the document out once
+    // more.
+    let has_page_count = has_field(&blocks, FieldKind::PageCount)
+        || page_setup
+            .header_footer_blocks()
+            .any(|region| has_field(region, FieldKind::PageCount));
+    let has_contents = has_toc(&blocks);
+    if has_page_count || has_contents {
+        if has_page_count {
+            field_values.page_count = Some(layout_result.pages.len());
+            evaluate_fields(&mut blocks, &field_values);
+            for region in page_setup.header_footer_blocks_mut() {
+                evaluate_fields(region, &field_values);
+            }
+        }
+        if has_contents {
+            fill_toc_pages(&mut blocks, &layout_result.pages);
+        }
+        layout_result = emit_blocks(&expand_tocs(&blocks), styles, &emit_map, &page_setup)?;
+    }
 
     // 6. Write PDF structure.
     let catalog_ref = Ref::new(1);
@@ -169,7 +207,7 @@ pub fn write_text_pdf(
         pages.count(page_refs.len() as i32);
     }
 
-    let xmp = crate::writer::metadata::build_xmp_packet(metadata.title.as_deref(), settings);
+    let xmp = crate::writer::metadata::build_xmp_packet(metadata, settings);
     {
         let xmp_bytes = xmp.into_bytes();
         let mut xmp_stream = pdf.stream(xmp_ref, &xmp_bytes);
@@ -177,22 +215,60 @@ pub fn write_text_pdf(
         xmp_stream.pair(Name(b"Subtype"), Name(b"XML"));
     }
 
-    let trim = pdf_writer::Rect::new(0.0, 0.0, page_width_pt as f32, page_height_pt as f32);
-    let bleed_rect = pdf_writer::Rect::new(
-        -bleed as f32,
-        -bleed as f32,
-        (page_width_pt + bleed) as f32,
-        (page_height_pt + bleed) as f32,
-    );
+    let bleed = settings.bleed_pt;
+
+    // Bookmarks by name, with the index of their page and their y.
+    let mut anchors: std::collections::HashMap<&str, (usize, f64)> =
+        std::collections::HashMap::new();
+    for (i, page_data) in layout_result.pages.iter().enumerate() {
+        for (name, top) in &page_data.anchors {
+            anchors.entry(name.as_str()).or_insert((i, *top));
+        }
+    }
 
     for (i, page_data) in layout_result.pages.iter().enumerate() {
         let page_ref = page_refs[i];
         let content_ref = content_refs[i];
 
+        // Cross-references jump to the line of their bookmark.
+        let mut annot_refs = Vec::new();
+        for link in &page_data.links {
+            let Some(&(target_page, top)) = anchors.get(link.target.as_str()) else {
+                continue;
+            };
+            let annot_ref = Ref::new(next_ref);
+            next_ref += 1;
+            let [x1, y1, x2, y2] = link.rect;
+            let mut annot = pdf.indirect(annot_ref).dict();
+            annot.pair(Name(b"Type"), Name(b"Annot"));
+            annot.pair(Name(b"Subtype"), Name(b"Link"));
+            annot.pair(
+                Name(b"Rect"),
+                pdf_writer::Rect::new(x1 as f32, y1 as f32, x2 as f32, y2 as f32),
+            );
+            annot.insert(Name(b"Border")).array().items([0.0f32; 3]);
+            let mut dest = annot.insert(Name(b"Dest")).array();
+            dest.item(page_refs[target_page]);
+            dest.item(Name(b"XYZ"));
+            dest.item(pdf_writer::Null);
+            dest.item(top as f32);
+            dest.item(pdf_writer::Null);
+            dest.finish();
+            annot.finish();
+            annot_refs.push(annot_ref);
+        }
+
         let content_compressed = crate::compress::compress(page_data.content_stream.as_bytes());
         pdf.stream(content_ref, &content_compressed)
             .filter(pdf_writer::Filter::FlateDecode);
 
+        let trim = pdf_writer::Rect::new(0.0, 0.0, page_data.width as f32, page_data.height as f32);
+        let bleed_rect = pdf_writer::Rect::new(
+            -bleed as f32,
+            -bleed as f32,
+            (page_data.width + bleed) as f32,
+            (page_data.height + bleed) as f32,
+        );
         let mut page = pdf.page(page_ref);
         page.parent(pages_ref);
         page.media_box(bleed_rect);
@@ -200,6 +276,11 @@ pub fn write_text_pdf(
         page.bleed_box(bleed_rect);
         page.contents(content_ref);
         page.pair(Name(b"Metadata"), xmp_ref);
+        if !annot_refs.is_empty() {
+            page.insert(Name(b"Annots"))
+                .array()
+                .items(annot_refs.iter().copied());
+        }
 
         let mut resources = page.resources();
         let mut fonts = resources.fonts();
@@ -64,6 +64,24 @@ pub fn get_default_style_props(style_name: &str) -> Option<ParagraphProps> {
             space_after: 10.0,
             ..ParagraphProps::default()
         }),
-        _ => None,
+        "Contents Heading" | "Contents_20_Heading" => Some(ParagraphProps {
+            font_size: 16.0,
+            space_before: 12.0,
+            space_after: 6.0,
+            ..ParagraphProps::default()
+        }),
+        _ => contents_level(style_name).map(|level| ParagraphProps {
+            margin_left: 14.0 * (level - 1) as f64,
+            ..ParagraphProps::default()
+        }),
     }
 }
+
+/// The level of a table of contents entry style (`Contents 2`).
+fn contents_level(style_name: &str) -> Option<u32> {
+    style_name
+        .strip_prefix("Contents_20_")
+        .or_else(|| style_name.strip_prefix("Contents "))
+        .and_then(|n| n.parse().ok())
+        .filter(|&level| level >= 1)
+}
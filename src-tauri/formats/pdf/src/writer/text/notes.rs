@@ -0,0 +1,290 @@
+// Copyright 2024 AppThere
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Footnote and endnote layout.
+//!
+//! Footnotes are placed at the bottom of the page on which their anchoring
+//! paragraph starts; the body area shrinks by the space they need. Endnotes
+//! are collected into plain paragraphs and laid out after the main text.
+
+use common_core::block::Block;
+use common_core::inline::{Inline, NoteClass};
+use std::collections::HashMap;
+
+use super::collector::{FontKey, DEFAULT_FONT_FAMILY};
+use super::layout::{break_words, wrap_words_with_indent, LayoutState};
+use super::measure::space_width;
+use super::operators::{write_horizontal_rule, write_text_run};
+use crate::fonts::subset::FontSubset;
+
+/// Font size used for footnote text, in points.
+const NOTE_FONT_SIZE: f64 = 8.5;
+/// Line height factor for footnote text.
+const NOTE_LINE_FACTOR: f64 = 1.3;
+/// Space reserved above the first footnote for the separator rule.
+const NOTE_SEPARATOR_GAP: f64 = 10.0;
+
+/// Return the footnotes anchored in a block, including those in the
+/// paragraphs of its lists, tables, quotes and sections.
+pub(super) fn footnotes_in_block(block: &Block) -> Vec<&Inline> {
+    let mut notes = Vec::new();
+    collect_notes(block, NoteClass::Footnote, &mut notes);
+    notes
+}
+
+/// Flatten a note into a single string: the citation followed by its body text.
+pub(super) fn note_text(note: &Inline) -> String {
+    match note {
+        Inline::Note {
+            citation, content, ..
+        } => {
+            let body: Vec<String> = content
+                .iter()
+                .map(block_plain_text)
+                .filter(|s| !s.is_empty())
+                .collect();
+            format!("{} {}", citation, body.join(" "))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Build one plain paragraph per endnote found in `blocks`, in document order.
+pub(super) fn endnote_blocks(blocks: &[Block]) -> Vec<Block> {
+    let mut notes = Vec::new();
+    for block in blocks {
+        collect_notes(block, NoteClass::Endnote, &mut notes);
+    }
+    notes
+        .into_iter()
+        .map(|note| Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![Inline::Text {
+                text: note_text(note),
+                style_name: None,
+                marks: vec![],
+            }],
+        })
+        .collect()
+}
+
+/// Vertical space the given footnotes need, including the separator gap.
+pub(super) fn footnotes_height(
+    notes: &[&Inline],
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    usable_width: f64,
+) -> f64 {
+    if notes.is_empty() {
+        return 0.0;
+    }
+    let Some((_, subset)) = note_font(font_map) else {
+        return 0.0;
+    };
+    let sw = space_width(&subset.bytes, NOTE_FONT_SIZE);
+    let lines: usize = notes
+        .iter()
+        .map(|n| {
+            let words = break_words(&note_text(n), subset, NOTE_FONT_SIZE);
+            wrap_words_with_indent(words, sw, usable_width, usable_width).len()
+        })
+        .sum();
+    NOTE_SEPARATOR_GAP + lines as f64 * NOTE_FONT_SIZE * NOTE_LINE_FACTOR
+}
+
+/// Emit footnotes into the area reserved below the body text.
+///
+/// `state.bottom_margin` must already include the space returned by
+/// [`footnotes_height`] for the same notes.
+pub(super) fn emit_footnotes(
+    notes: &[&Inline],
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    state: &LayoutState,
+    out: &mut String,
+) {
+    let Some((pdf_name, subset)) = note_font(font_map) else {
+        return;
+    };
+    let sw = space_width(&subset.bytes, NOTE_FONT_SIZE);
+    let line_height = NOTE_FONT_SIZE * NOTE_LINE_FACTOR;
+    let mut y_from_top = state.page_height - state.bottom_margin;
+
+    let rule_y = state.page_height - (y_from_top + NOTE_SEPARATOR_GAP / 2.0);
+    write_horizontal_rule(state.left_margin, rule_y, state.usable_width / 3.0, out);
+    y_from_top += NOTE_SEPARATOR_GAP;
+
+    for note in notes {
+        let words = break_words(&note_text(note), subset, NOTE_FONT_SIZE);
+        for line in wrap_words_with_indent(words, sw, state.usable_width, state.usable_width) {
+            let text = line
+                .iter()
+                .map(|w| w.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let y = state.page_height - y_from_top - NOTE_FONT_SIZE;
+            write_text_run(
+                &text,
+                subset,
+                pdf_name,
+                NOTE_FONT_SIZE,
+                state.left_margin,
+                y,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                out,
+            );
+            y_from_top += line_height;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn note_font(font_map: &HashMap<FontKey, (String, FontSubset)>) -> Option<&(String, FontSubset)> {
+    font_map
+        .get(&(DEFAULT_FONT_FAMILY.to_string(), 400, false))
+        .or_else(|| font_map.values().next())
+}
+
+fn collect_notes<'a>(block: &'a Block, class: NoteClass, out: &mut Vec<&'a Inline>) {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => {
+            out.extend(
+                content.iter().filter(
+                    |i| matches!(i, Inline::Note { note_class, .. } if *note_class == class),
+                ),
+            );
+        }
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => {
+            for child in content {
+                collect_notes(child, class, out);
+            }
+        }
+        Block::Image { .. }
+        | Block::HorizontalRule
+        | Block::PageBreak
+        | Block::TableOfContents(_) => {}
+    }
+}
+
+fn block_plain_text(block: &Block) -> String {
+    match block {
+        Block::Paragraph { content, .. } | Block::Heading { content, .. } => content
+            .iter()
+            .map(|i| match i {
+                Inline::Text { text, .. } => text.as_str(),
+                Inline::LineBreak | Inline::Space { .. } | Inline::Tab => " ",
+                Inline::Field { value, .. } => value.as_str(),
+                Inline::Reference(reference) => reference.value.as_str(),
+                Inline::Note { .. }
+                | Inline::Bookmark { .. }
+                | Inline::BookmarkStart { .. }
+                | Inline::BookmarkEnd { .. }
+                | Inline::Comment(_)
+                | Inline::CommentEnd { .. }
+                | Inline::ChangeStart(_)
+                | Inline::ChangeEnd { .. }
+                | Inline::Change(_) => "",
+            })
+            .collect(),
+        Block::BulletList { content, .. }
+        | Block::OrderedList { content, .. }
+        | Block::ListItem { content }
+        | Block::Blockquote { content }
+        | Block::Section { content, .. }
+        | Block::Table { content }
+        | Block::TableRow { content }
+        | Block::TableHeader { content, .. }
+        | Block::TableCell { content, .. } => content
+            .iter()
+            .map(block_plain_text)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+        Block::TableOfContents(toc) => toc
+            .entries
+            .iter()
+            .map(|e| e.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        Block::Image { .. } | Block::HorizontalRule | Block::PageBreak => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph(content: Vec<Inline>) -> Block {
+        Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content,
+        }
+    }
+
+    fn note(note_class: NoteClass, citation: &str) -> Inline {
+        Inline::Note {
+            id: None,
+            note_class,
+            citation: citation.to_string(),
+            content: vec![],
+        }
+    }
+
+    #[test]
+    fn footnotes_in_lists_and_tables_are_found() {
+        let list = Block::BulletList {
+            attrs: None,
+            content: vec![Block::ListItem {
+                content: vec![paragraph(vec![
+                    note(NoteClass::Footnote, "1"),
+                    note(NoteClass::Endnote, "i"),
+                ])],
+            }],
+        };
+        let table = Block::Table {
+            content: vec![Block::TableRow {
+                content: vec![Block::TableCell {
+                    attrs: None,
+                    content: vec![paragraph(vec![note(NoteClass::Footnote, "2")])],
+                }],
+            }],
+        };
+
+        let citations = |block: &Block| -> Vec<String> {
+            footnotes_in_block(block)
+                .into_iter()
+                .map(|n| match n {
+                    Inline::Note { citation, .. } => citation.clone(),
+                    _ => String::new(),
+                })
+                .collect()
+        };
+        assert_eq!(citations(&list), vec!["1"]);
+        assert_eq!(citations(&table), vec!["2"]);
+    }
+}
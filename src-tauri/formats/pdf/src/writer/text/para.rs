@@ -15,15 +15,20 @@
 //! Paragraph height measurement and content stream emission.
 
 use common_core::block::Block;
+use common_core::colour_management::Colour;
+use common_core::field::FieldKind;
 use common_core::inline::Inline;
+use common_core::marks::TiptapMark;
 use common_core::style::StyleDefinition;
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 use super::collector::FontKey;
-use super::layout::{break_words, wrap_words_with_indent, LayoutState};
-use super::measure::space_width;
+use super::columns::section_height;
+use super::layout::{break_words, wrap_words_with_indent, LayoutState, PageLink, Word};
+use super::measure::{measure_text, space_width};
 use super::operators::write_text_run;
-use super::style_props::{resolve_paragraph_props, TextAlign};
+use super::style_props::{resolve_block_props, TextAlign};
 use crate::fonts::subset::FontSubset;
 
 /// Compute the total vertical height a block occupies on a page.
@@ -35,15 +40,15 @@ pub(super) fn block_height(
 ) -> f64 {
     match block {
         Block::Paragraph { .. } | Block::Heading { .. } => {
-            let (content, style_name, level) = unpack_para_or_heading(block);
-            let props = resolve_paragraph_props(style_name, styles, level);
+            let (content, _, _) = unpack_para_or_heading(block);
+            let props = resolve_block_props(block, styles);
             let key = (
                 props.font_family.to_lowercase(),
                 if props.bold { 700 } else { 400 },
                 props.italic,
             );
             if let Some((_, subset)) = font_map.get(&key).or_else(|| font_map.values().next()) {
-                let full_text = collect_text(content);
+                let full_text = collect_text(content, None);
                 let font_size = props.font_size;
                 let sw = space_width(&subset.bytes, font_size);
                 let line_height = font_size * props.line_height_factor;
@@ -62,6 +67,9 @@ pub(super) fn block_height(
             }
         }
         Block::HorizontalRule => 12.0,
+        Block::Section { attrs, content, .. } => {
+            section_height(attrs.as_ref(), content, styles, font_map, usable_width)
+        }
         _ => 0.0,
     }
 }
@@ -79,7 +87,12 @@ pub(super) fn emit_para_content(
     out: &mut String,
     start_line_idx: usize,
 ) -> usize {
-    let full_text = collect_text(content);
+    let page = Some(state.page_number);
+    let full_text = collect_text(content, page);
+    let colours = collect_colours(content, page);
+    let links = collect_links(content, page);
+    let anchors = bookmark_offsets(content, page);
+    let mut next_anchor = 0;
     let key = (
         props.font_family.to_lowercase(),
         if props.bold { 700 } else { 400 },
@@ -102,8 +115,11 @@ pub(super) fn emit_para_content(
 
     let mut lines_emitted = 0;
     let mut total_lines_processed = 0;
+    let mut line_offset = 0;
 
     for (p_idx, line_text) in full_text.lines().enumerate() {
+        let chunk_offset = line_offset;
+        line_offset += line_text.chars().count() + 1;
         let is_last_chunk = p_idx == line_count - 1;
         let is_first_chunk = p_idx == 0 && start_line_idx == 0;
         let first_w = if is_first_chunk {
@@ -113,11 +129,28 @@ pub(super) fn emit_para_content(
         };
 
         let words = break_words(line_text, subset, font_size);
+        let word_starts = word_char_offsets(line_text, &words, chunk_offset);
         let wrapped = wrap_words_with_indent(words, sw, first_w, base_w);
         let total_wrapped = wrapped.len();
+        let mut word_idx = 0;
 
         for (l_idx, line_words) in wrapped.into_iter().enumerate() {
+            let line_starts = &word_starts[word_idx..word_idx + line_words.len()];
+            word_idx += line_words.len();
+            // Bookmarks up to the end of this line are placed at its top.
+            let line_end = if l_idx + 1 == total_wrapped {
+                chunk_offset + line_text.chars().count()
+            } else {
+                line_starts.last().copied().unwrap_or(chunk_offset)
+                    + line_words.last().map_or(0, |w| w.text.chars().count())
+            };
+            let line_anchors = anchors[next_anchor..]
+                .iter()
+                .take_while(|(offset, _)| *offset <= line_end)
+                .count();
             if total_lines_processed < start_line_idx {
+                // Placed on an earlier page.
+                next_anchor += line_anchors;
                 total_lines_processed += 1;
                 continue;
             }
@@ -125,6 +158,10 @@ pub(super) fn emit_para_content(
                 *overflowed = true;
                 return lines_emitted;
             }
+            for (_, name) in &anchors[next_anchor..next_anchor + line_anchors] {
+                state.anchors.push((name.to_string(), state.pdf_y()));
+            }
+            next_anchor += line_anchors;
 
             let is_first_line = is_first_chunk && l_idx == 0;
             let is_last_line = is_last_chunk && l_idx == total_wrapped - 1;
@@ -147,17 +184,7 @@ pub(super) fn emit_para_content(
                 sw,
             );
 
-            let line_str: String = line_words
-                .iter()
-                .enumerate()
-                .map(|(i, w)| {
-                    if i == 0 {
-                        w.text.clone()
-                    } else {
-                        format!(" {}", w.text)
-                    }
-                })
-                .collect();
+            let segments = run_segments(&line_words, line_starts, &colours, &links);
 
             let x = state.left_margin
                 + props.margin_left
@@ -168,24 +195,42 @@ pub(super) fn emit_para_content(
                 })
                 + x_offset;
             let y = state.pdf_y() - font_size;
-            write_text_run(
-                &line_str,
-                subset,
-                pdf_name,
-                font_size,
-                x,
-                y,
-                0.0,
-                0.0,
-                0.0,
-                word_spacing,
-                out,
-            );
+            // Runs of one colour and link target are written separately,
+            // each placed after the measured width of the runs before it.
+            let mut run_x = x;
+            for (text, [r, g, b], link) in &segments {
+                write_text_run(
+                    text,
+                    subset,
+                    pdf_name,
+                    font_size,
+                    run_x,
+                    y,
+                    *r,
+                    *g,
+                    *b,
+                    word_spacing,
+                    out,
+                );
+                let spaces = text.matches(' ').count() as f64;
+                let width = measure_text(text, &subset.bytes, font_size) + spaces * word_spacing;
+                if let Some((_, target)) = link {
+                    state.links.push(PageLink {
+                        target: target.to_string(),
+                        rect: [run_x, y - font_size * 0.25, run_x + width, y + font_size],
+                    });
+                }
+                run_x += width;
+            }
             state.advance(line_height);
             lines_emitted += 1;
             total_lines_processed += 1;
         }
     }
+    // Bookmarks in an empty paragraph, or after its last line break.
+    for (_, name) in &anchors[next_anchor..] {
+        state.anchors.push((name.to_string(), state.pdf_y()));
+    }
     lines_emitted
 }
 
@@ -194,16 +239,197 @@ pub(super) fn emit_para_content(
 // ---------------------------------------------------------------------------
 
 /// Collect all inline text into a single string, treating LineBreak as `\n`.
-fn collect_text(content: &[Inline]) -> String {
-    content
+///
+/// Notes contribute only their citation mark; the body is laid out separately.
+/// Page number fields show `page` when it is known.
+pub(super) fn collect_text(content: &[Inline], page: Option<usize>) -> String {
+    let mut text = String::new();
+    for inline in content {
+        match inline {
+            Inline::Text { text: run, .. } => text.push_str(run),
+            Inline::LineBreak => text.push('\n'),
+            Inline::Space { .. } | Inline::Tab => text.push(' '),
+            Inline::Note { citation, .. } => text.push_str(citation),
+            Inline::Field { kind, value, .. } => text.push_str(&field_text(*kind, value, page)),
+            Inline::Reference(reference) => text.push_str(&reference.value),
+            // Comments and change markers are review annotations, not
+            // printed text.
+            Inline::Comment(_)
+            | Inline::CommentEnd { .. }
+            | Inline::ChangeStart(_)
+            | Inline::ChangeEnd { .. }
+            | Inline::Change(_)
+            | Inline::Bookmark { .. }
+            | Inline::BookmarkStart { .. }
+            | Inline::BookmarkEnd { .. } => {}
+        }
+    }
+    text
+}
+
+/// Collect the fill colour of every character of [`collect_text`]'s output.
+///
+/// Text without a `TextStyle` colour is black.
+fn collect_colours(content: &[Inline], page: Option<usize>) -> Vec<[f32; 3]> {
+    let mut colours = Vec::new();
+    for inline in content {
+        let (len, colour) = match inline {
+            Inline::Text { text, marks, .. } => (text.chars().count(), run_colour(marks)),
+            Inline::LineBreak | Inline::Space { .. } | Inline::Tab => (1, BLACK),
+            Inline::Note { citation, .. } => (citation.chars().count(), BLACK),
+            Inline::Field { kind, value, .. } => {
+                (field_text(*kind, value, page).chars().count(), BLACK)
+            }
+            Inline::Reference(reference) => (reference.value.chars().count(), BLACK),
+            Inline::Comment(_)
+            | Inline::CommentEnd { .. }
+            | Inline::ChangeStart(_)
+            | Inline::ChangeEnd { .. }
+            | Inline::Change(_)
+            | Inline::Bookmark { .. }
+            | Inline::BookmarkStart { .. }
+            | Inline::BookmarkEnd { .. } => (0, BLACK),
+        };
+        colours.extend(std::iter::repeat_n(colour, len));
+    }
+    colours
+}
+
+/// The cross-reference of every character of [`collect_text`]'s output,
+/// as its index in `content` and its target. Adjacent references to the
+/// same target, such as the text and page number of a contents entry, stay
+/// separate links.
+fn collect_links(content: &[Inline], page: Option<usize>) -> Vec<LinkRef<'_>> {
+    let mut links = Vec::new();
+    for (i, inline) in content.iter().enumerate() {
+        let link = match inline {
+            Inline::Reference(reference) => Some((i, reference.name.as_str())),
+            _ => None,
+        };
+        links.extend(std::iter::repeat_n(link, char_len(inline, page)));
+    }
+    links
+}
+
+/// The offset in [`collect_text`]'s output of every bookmark, in order.
+fn bookmark_offsets(content: &[Inline], page: Option<usize>) -> Vec<(usize, &str)> {
+    let mut offsets = Vec::new();
+    let mut offset = 0;
+    for inline in content {
+        if let Inline::Bookmark { name } | Inline::BookmarkStart { name } = inline {
+            offsets.push((offset, name.as_str()));
+        }
+        offset += char_len(inline, page);
+    }
+    offsets
+}
+
+/// The number of characters an inline contributes to [`collect_text`].
+fn char_len(inline: &Inline, page: Option<usize>) -> usize {
+    match inline {
+        Inline::Text { text, .. } => text.chars().count(),
+        Inline::LineBreak | Inline::Space { .. } | Inline::Tab => 1,
+        Inline::Note { citation, .. } => citation.chars().count(),
+        Inline::Field { kind, value, .. } => field_text(*kind, value, page).chars().count(),
+        Inline::Reference(reference) => reference.value.chars().count(),
+        Inline::Comment(_)
+        | Inline::CommentEnd { .. }
+        | Inline::ChangeStart(_)
+        | Inline::ChangeEnd { .. }
+        | Inline::Change(_)
+        | Inline::Bookmark { .. }
+        | Inline::BookmarkStart { .. }
+        | Inline::BookmarkEnd { .. } => 0,
+    }
+}
+
+/// The text a field shows on page `page`.
+fn field_text(kind: FieldKind, value: &str, page: Option<usize>) -> Cow<'_, str> {
+    match (kind, page) {
+        (FieldKind::PageNumber, Some(page)) => Cow::Owned(page.to_string()),
+        _ => Cow::Borrowed(value),
+    }
+}
+
+const BLACK: [f32; 3] = [0.0, 0.0, 0.0];
+
+/// The RGB fill colour of a text run, from its `TextStyle` mark.
+fn run_colour(marks: &[TiptapMark]) -> [f32; 3] {
+    let colour = marks.iter().find_map(|m| match m {
+        TiptapMark::TextStyle { attrs } => attrs.colour.as_ref(),
+        _ => None,
+    });
+    let rgb = match colour {
+        Some(Colour::Rgb { r, g, b, .. }) => Some(Colour::Rgb {
+            r: *r,
+            g: *g,
+            b: *b,
+            a: 1.0,
+        }),
+        // Other colour spaces use their sRGB approximation.
+        Some(other) => Colour::from_hex(&other.to_css_string()),
+        None => None,
+    };
+    match rgb {
+        Some(Colour::Rgb { r, g, b, .. }) => [r, g, b],
+        _ => BLACK,
+    }
+}
+
+/// The character offset in the paragraph text of each word of a line.
+///
+/// A word that cannot be found after the previous one is given the offset
+/// where the search stopped, and the search does not move on.
+fn word_char_offsets(line_text: &str, words: &[Word], line_offset: usize) -> Vec<usize> {
+    let mut cursor = 0;
+    words
         .iter()
-        .map(|i| match i {
-            Inline::Text { text, .. } => text.as_str(),
-            Inline::LineBreak => "\n",
+        .map(|w| {
+            let pos = match line_text[cursor..].find(&w.text) {
+                Some(found) => {
+                    let pos = cursor + found;
+                    cursor = pos + w.text.len();
+                    pos
+                }
+                None => cursor,
+            };
+            line_offset + line_text[..pos].chars().count()
         })
         .collect()
 }
 
+/// A reference on a line: the index of its inline and its target.
+type LinkRef<'a> = Option<(usize, &'a str)>;
+
+/// Split a wrapped line into runs of one colour and link target. Words are
+/// joined by single spaces, which belong to the run of the preceding word.
+fn run_segments<'a>(
+    words: &[Word],
+    starts: &[usize],
+    colours: &[[f32; 3]],
+    links: &[LinkRef<'a>],
+) -> Vec<(String, [f32; 3], LinkRef<'a>)> {
+    let mut segments: Vec<(String, [f32; 3], LinkRef<'a>)> = Vec::new();
+    for (i, (word, start)) in words.iter().zip(starts).enumerate() {
+        if i > 0 {
+            if let Some((text, _, _)) = segments.last_mut() {
+                text.push(' ');
+            }
+        }
+        for (j, c) in word.text.chars().enumerate() {
+            let colour = colours.get(start + j).copied().unwrap_or(BLACK);
+            let link = links.get(start + j).copied().flatten();
+            match segments.last_mut() {
+                Some((text, last, last_link)) if *last == colour && *last_link == link => {
+                    text.push(c)
+                }
+                _ => segments.push((c.to_string(), colour, link)),
+            }
+        }
+    }
+    segments
+}
+
 /// Count the total number of wrapped lines for a block of text.
 fn count_wrapped_lines(
     full_text: &str,
@@ -263,3 +489,21 @@ pub(super) fn unpack_para_or_heading(block: &Block) -> (&[Inline], Option<&str>,
         _ => unreachable!("unpack_para_or_heading called on non-paragraph/heading block"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str) -> Word {
+        Word {
+            text: text.to_string(),
+            width: 0.0,
+        }
+    }
+
+    #[test]
+    fn word_offsets_skip_words_missing_from_the_text() {
+        let words = [word("héllo"), word("gone"), word("world")];
+        assert_eq!(word_char_offsets("héllo world", &words, 3), vec![3, 8, 9]);
+    }
+}
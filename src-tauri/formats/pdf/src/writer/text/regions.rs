@@ -0,0 +1,135 @@
+// Copyright 2024 AppThere
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Running header and footer layout.
+//!
+//! The header of a page's master page is placed at the top margin and the
+//! footer above the bottom margin; the body area shrinks by the space they
+//! need. Page number fields show the number of the page they are on.
+
+use common_core::block::Block;
+use common_core::page::{HeaderFooter, MasterPage, PageLayout};
+use common_core::style::StyleDefinition;
+use std::collections::HashMap;
+
+use super::collector::FontKey;
+use super::layout::LayoutState;
+use super::renderer::emit_block;
+use crate::fonts::subset::FontSubset;
+
+/// Space between a header or footer and the body text.
+const REGION_SPACING: f64 = 12.0;
+
+/// The header and footer of one page.
+pub(super) struct PageRegions {
+    /// The content stream drawing both.
+    pub content_stream: String,
+    /// The space the header takes from the top of the body area.
+    pub header_height: f64,
+    /// The space the footer takes from the bottom of the body area.
+    pub footer_height: f64,
+}
+
+/// Lays out the header and footer of `master` for a page.
+///
+/// `first` is whether the page starts its master page run.
+pub(super) fn emit_page_regions(
+    master: Option<&MasterPage>,
+    layout: &PageLayout,
+    styles: &HashMap<String, StyleDefinition>,
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    page_number: usize,
+    first: bool,
+) -> PageRegions {
+    let mut regions = PageRegions {
+        content_stream: String::new(),
+        header_height: 0.0,
+        footer_height: 0.0,
+    };
+    let header = region_content(master.and_then(|m| m.header.as_ref()), first, page_number);
+    let footer = region_content(master.and_then(|m| m.footer.as_ref()), first, page_number);
+
+    if let Some(blocks) = header {
+        let height = emit_region(
+            blocks,
+            layout,
+            styles,
+            font_map,
+            page_number,
+            layout.margin_top,
+            &mut regions.content_stream,
+        );
+        regions.header_height = height + REGION_SPACING;
+    }
+    if let Some(blocks) = footer {
+        // Measure first, then place the footer so it ends at the margin.
+        let height = emit_region(
+            blocks,
+            layout,
+            styles,
+            font_map,
+            page_number,
+            0.0,
+            &mut String::new(),
+        );
+        emit_region(
+            blocks,
+            layout,
+            styles,
+            font_map,
+            page_number,
+            layout.height - layout.margin_bottom - height,
+            &mut regions.content_stream,
+        );
+        regions.footer_height = height + REGION_SPACING;
+    }
+    regions
+}
+
+/// Returns the content of `region` shown on a page, unless it is empty.
+fn region_content(
+    region: Option<&HeaderFooter>,
+    first: bool,
+    page_number: usize,
+) -> Option<&[Block]> {
+    region
+        .map(|r| r.content_for(first, page_number))
+        .filter(|blocks| !blocks.is_empty())
+}
+
+/// Lays out `blocks` from `top` (points from the page top) and returns the
+/// height they take.
+fn emit_region(
+    blocks: &[Block],
+    layout: &PageLayout,
+    styles: &HashMap<String, StyleDefinition>,
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    page_number: usize,
+    top: f64,
+    out: &mut String,
+) -> f64 {
+    let mut state = LayoutState::new(layout);
+    state.page_number = page_number;
+    state.current_y_from_top = top;
+    state._top_margin = top;
+    state.bottom_margin = 0.0;
+    let mut overflowed = false;
+    for block in blocks {
+        emit_block(block, styles, font_map, &mut state, &mut overflowed, out, 0);
+        if overflowed {
+            break;
+        }
+    }
+    state.current_y_from_top - top
+}
@@ -14,15 +14,19 @@
 
 use common_core::block::Block;
 use common_core::inline::Inline;
+use common_core::page::{PageSetup, DEFAULT_MASTER_PAGE};
 use common_core::style::StyleDefinition;
 use std::collections::HashMap;
 
 use super::collector::FontKey;
+use super::columns::emit_section;
 use super::layout::{break_words, wrap_words_with_indent, LayoutState, PageContent};
 use super::measure::space_width;
+use super::notes::{emit_footnotes, endnote_blocks, footnotes_height, footnotes_in_block};
 use super::operators::write_horizontal_rule;
-use super::para::{block_height, emit_para_content, unpack_para_or_heading};
-use super::style_props::{resolve_paragraph_props, ParagraphProps};
+use super::para::{block_height, collect_text, emit_para_content, unpack_para_or_heading};
+use super::regions::emit_page_regions;
+use super::style_props::{resolve_block_props, ParagraphProps};
 use crate::error::PdfError;
 use crate::fonts::subset::FontSubset;
 
@@ -31,25 +35,71 @@ pub struct LayoutResult {
     pub pages: Vec<PageContent>,
 }
 
+/// Lays out `blocks` on pages.
+///
+/// Pages take their size, margins, header and footer from the master page in
+/// effect: the default one, then the one named by the style of a paragraph
+/// that starts a new master page, followed by its next master page.
 pub fn emit_blocks(
     blocks: &[Block],
     styles: &HashMap<String, StyleDefinition>,
     font_map: &HashMap<FontKey, (String, FontSubset)>,
-    page_width: f64,
-    page_height: f64,
-    margin: f64,
+    page_setup: &PageSetup,
+) -> Result<LayoutResult, PdfError> {
+    let master = page_setup
+        .default_master_page()
+        .map_or(DEFAULT_MASTER_PAGE, |m| m.name.as_str());
+    emit_pages(blocks, styles, font_map, page_setup, master, 1, None)
+}
+
+/// Lays out `blocks` on pages numbered from `first_page`, starting with the
+/// master page `master`. `previous_master` is the master page of the page
+/// before, if any.
+fn emit_pages(
+    blocks: &[Block],
+    styles: &HashMap<String, StyleDefinition>,
+    font_map: &HashMap<FontKey, (String, FontSubset)>,
+    page_setup: &PageSetup,
+    master: &str,
+    first_page: usize,
+    previous_master: Option<&str>,
 ) -> Result<LayoutResult, PdfError> {
     let mut pages = Vec::new();
     let mut current_block_idx = 0;
     let mut current_line_offset = 0;
+    let mut master = master;
+    let mut previous_master = previous_master;
 
     while current_block_idx < blocks.len() {
-        let mut state = LayoutState::new(page_width, page_height, margin);
+        if current_line_offset == 0 {
+            if let Some(name) = master_page_of(&blocks[current_block_idx], styles) {
+                master = name;
+            }
+        }
+        let layout = page_setup.layout_for(Some(master));
+        let mut state = LayoutState::new(&layout);
+        state.page_number = first_page + pages.len();
+        let regions = emit_page_regions(
+            page_setup.master_page(master),
+            &layout,
+            styles,
+            font_map,
+            state.page_number,
+            previous_master != Some(master),
+        );
+        previous_master = Some(master);
+        state.current_y_from_top += regions.header_height;
+        state._top_margin += regions.header_height;
+        state.bottom_margin += regions.footer_height;
+        let body_bottom = state.bottom_margin;
         let mut overflowed = false;
-        let mut content_stream = String::new();
+        let mut content_stream = regions.content_stream;
 
         let mut page_end_block_idx = current_block_idx;
-        let mut next_line_offset = 0;
+        // The block and line the next page starts at, when a block did not
+        // fit completely on this one.
+        let mut resume: Option<(usize, usize)> = None;
+        let mut page_notes: Vec<&Inline> = Vec::new();
 
         for (i, block) in blocks.iter().enumerate().skip(current_block_idx) {
             let start_offset = if i == current_block_idx {
@@ -58,12 +108,18 @@ pub fn emit_blocks(
                 0
             };
 
+            // A paragraph that starts a new master page starts a new page.
+            if start_offset == 0 && i > current_block_idx && master_page_of(block, styles).is_some()
+            {
+                resume = Some((i, 0));
+                break;
+            }
+
             // Look-ahead for break-before or keep-with-next
             if start_offset == 0 && state.current_y_from_top > state._top_margin {
                 let props = get_block_props(block, styles);
                 if props.break_before {
-                    overflowed = true;
-                    page_end_block_idx = i;
+                    resume = Some((i, 0));
                     break;
                 }
 
@@ -75,13 +131,24 @@ pub fn emit_blocks(
                     if state.current_y_from_top + current_h + next_h
                         > state.page_height - state.bottom_margin
                     {
-                        overflowed = true;
-                        page_end_block_idx = i;
+                        resume = Some((i, 0));
                         break;
                     }
                 }
             }
 
+            // Reserve room at the page bottom for footnotes anchored here.
+            let notes_before = page_notes.len();
+            let bottom_before = state.bottom_margin;
+            if start_offset == 0 {
+                let notes = footnotes_in_block(block);
+                if !notes.is_empty() {
+                    page_notes.extend(notes);
+                    state.bottom_margin =
+                        body_bottom + footnotes_height(&page_notes, font_map, state.usable_width);
+                }
+            }
+
             let lines_emitted = emit_block(
                 block,
                 styles,
@@ -92,30 +159,68 @@ pub fn emit_blocks(
                 start_offset,
             );
 
+            if overflowed && start_offset == 0 && lines_emitted == 0 {
+                // The block moves to the next page, and its footnotes with it.
+                page_notes.truncate(notes_before);
+                state.bottom_margin = bottom_before;
+            }
+
             page_end_block_idx = i;
             if overflowed {
-                next_line_offset = start_offset + lines_emitted;
-                if is_finished(block, next_line_offset, styles, font_map) {
-                    next_line_offset = 0;
-                    page_end_block_idx = i;
-                } else {
-                    break;
+                let next_line_offset = start_offset + lines_emitted;
+                if !is_finished(
+                    block,
+                    next_line_offset,
+                    styles,
+                    font_map,
+                    state.usable_width,
+                ) {
+                    resume = Some((i, next_line_offset));
                 }
                 break;
             }
         }
 
-        pages.push(PageContent { content_stream });
+        if !page_notes.is_empty() {
+            emit_footnotes(&page_notes, font_map, &state, &mut content_stream);
+        }
+        pages.push(PageContent {
+            width: layout.width,
+            height: layout.height,
+            content_stream,
+            anchors: state.anchors,
+            links: state.links,
+        });
 
-        if overflowed && next_line_offset > 0 {
-            current_block_idx = page_end_block_idx;
-            current_line_offset = next_line_offset;
-        } else {
-            current_block_idx = page_end_block_idx + 1;
-            current_line_offset = 0;
+        master = page_setup.next_master(master);
+        match resume {
+            // A block that cannot make progress even on a fresh page is
+            // skipped rather than retried forever.
+            Some(next) if next != (current_block_idx, current_line_offset) => {
+                (current_block_idx, current_line_offset) = next;
+            }
+            _ => {
+                current_block_idx = page_end_block_idx + 1;
+                current_line_offset = 0;
+            }
         }
     }
 
+    // Endnotes follow the main text, starting on a fresh page.
+    let endnotes = endnote_blocks(blocks);
+    if !endnotes.is_empty() {
+        let mut tail = emit_pages(
+            &endnotes,
+            styles,
+            font_map,
+            page_setup,
+            master,
+            first_page + pages.len(),
+            previous_master,
+        )?;
+        pages.append(&mut tail.pages);
+    }
+
     Ok(LayoutResult { pages })
 }
 
@@ -124,25 +229,21 @@ fn is_finished(
     offset: usize,
     styles: &HashMap<String, StyleDefinition>,
     font_map: &HashMap<FontKey, (String, FontSubset)>,
+    usable_width: f64,
 ) -> bool {
     match block {
         Block::PageBreak => true,
+        Block::Section { content, .. } => offset >= content.len(),
         Block::Paragraph { .. } | Block::Heading { .. } => {
-            let (content, style_name, level) = unpack_para_or_heading(block);
-            let props = resolve_paragraph_props(style_name, styles, level);
+            let (content, _, _) = unpack_para_or_heading(block);
+            let props = resolve_block_props(block, styles);
             let key = (props.font_family.to_lowercase(), 400, false);
             if let Some((_, subset)) = font_map.get(&key).or_else(|| font_map.values().next()) {
-                let full_text: String = content
-                    .iter()
-                    .map(|i| match i {
-                        Inline::Text { text, .. } => text.as_str(),
-                        Inline::LineBreak => "\n",
-                    })
-                    .collect();
+                let full_text = collect_text(content, None);
                 let font_size = props.font_size;
                 let sw = space_width(&subset.bytes, font_size);
 
-                let base_usable_width = (595.0 - 144.0) - props.margin_left - props.margin_right;
+                let base_usable_width = usable_width - props.margin_left - props.margin_right;
                 let mut total_lines = 0;
                 for (p_idx, line_text) in full_text.lines().enumerate() {
                     let first_line_width = if p_idx == 0 {
@@ -164,19 +265,35 @@ fn is_finished(
     }
 }
 
-fn get_block_props(block: &Block, styles: &HashMap<String, StyleDefinition>) -> ParagraphProps {
-    match block {
-        Block::Paragraph { style_name, .. } => {
-            resolve_paragraph_props(style_name.as_deref(), styles, None)
-        }
-        Block::Heading {
-            level, style_name, ..
-        } => resolve_paragraph_props(style_name.as_deref(), styles, Some(*level)),
-        _ => ParagraphProps::default(),
+/// Returns the master page a paragraph or heading starts: the one of its
+/// direct formatting, which comes from an automatic style, or of its style.
+fn master_page_of<'a>(
+    block: &'a Block,
+    styles: &'a HashMap<String, StyleDefinition>,
+) -> Option<&'a str> {
+    let (Block::Paragraph {
+        style_name, attrs, ..
+    }
+    | Block::Heading {
+        style_name, attrs, ..
+    }) = block
+    else {
+        return None;
+    };
+    if let Some(master) = attrs.as_ref().and_then(|a| a.master_page_name.as_deref()) {
+        return Some(master);
     }
+    styles
+        .get(style_name.as_deref()?)?
+        .master_page_name
+        .as_deref()
+}
+
+fn get_block_props(block: &Block, styles: &HashMap<String, StyleDefinition>) -> ParagraphProps {
+    resolve_block_props(block, styles)
 }
 
-fn emit_block(
+pub(super) fn emit_block(
     block: &Block,
     styles: &HashMap<String, StyleDefinition>,
     font_map: &HashMap<FontKey, (String, FontSubset)>,
@@ -187,8 +304,8 @@ fn emit_block(
 ) -> usize {
     match block {
         Block::Paragraph { .. } | Block::Heading { .. } => {
-            let (content, style_name, level) = unpack_para_or_heading(block);
-            let props = resolve_paragraph_props(style_name, styles, level);
+            let (content, _, level) = unpack_para_or_heading(block);
+            let props = resolve_block_props(block, styles);
 
             if start_offset == 0 {
                 let h = block_height(block, styles, font_map, state.usable_width);
@@ -224,14 +341,7 @@ fn emit_block(
             );
 
             if !*overflowed {
-                let (style_name, level) = match block {
-                    Block::Paragraph { style_name, .. } => (style_name.as_deref(), None),
-                    Block::Heading {
-                        style_name, level, ..
-                    } => (style_name.as_deref(), Some(*level)),
-                    _ => unreachable!(),
-                };
-                let props = resolve_paragraph_props(style_name, styles, level);
+                let props = resolve_block_props(block, styles);
                 let after = props.space_after.max(if level.is_some() {
                     0.0
                 } else {
@@ -258,6 +368,16 @@ fn emit_block(
             *overflowed = true;
             1
         }
+        Block::Section { attrs, content, .. } => emit_section(
+            attrs.as_ref(),
+            content,
+            styles,
+            font_map,
+            state,
+            overflowed,
+            out,
+            start_offset,
+        ),
         _ => 0,
     }
 }
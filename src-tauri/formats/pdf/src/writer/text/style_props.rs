@@ -14,7 +14,9 @@
 
 //! Style property resolution and ODF attribute parsing.
 
-use common_core::style::StyleDefinition;
+use common_core::style::{StyleDefinition, StyleFamily};
+use common_core::units::length_to_pt;
+use common_core::{Block, BlockAttrs, EffectiveStyle, StyleResolver};
 use std::collections::HashMap;
 
 /// Text alignment within a paragraph.
@@ -64,9 +66,36 @@ impl Default for ParagraphProps {
     }
 }
 
-/// Resolve paragraph properties by merging named style attributes into defaults.
+/// Resolve the properties of a paragraph or heading block, including its
+/// direct formatting. Other blocks get the defaults.
+pub fn resolve_block_props(
+    block: &Block,
+    styles: &HashMap<String, StyleDefinition>,
+) -> ParagraphProps {
+    match block {
+        Block::Paragraph {
+            style_name, attrs, ..
+        } => resolve_paragraph_props(style_name.as_deref(), attrs.as_ref(), styles, None),
+        Block::Heading {
+            style_name,
+            attrs,
+            level,
+            ..
+        } => resolve_paragraph_props(style_name.as_deref(), attrs.as_ref(), styles, Some(*level)),
+        _ => ParagraphProps::default(),
+    }
+}
+
+/// Resolve paragraph properties by merging the effective style attributes
+/// into defaults.
+///
+/// The style's properties are resolved through its parents and the default
+/// paragraph style by [`StyleResolver`], with `direct` formatting on top.
+/// Headings without a style keep the built-in heading defaults, which the
+/// default paragraph style would otherwise undo.
 pub fn resolve_paragraph_props(
     style_name: Option<&str>,
+    direct: Option<&BlockAttrs>,
     styles: &HashMap<String, StyleDefinition>,
     level: Option<u32>,
 ) -> ParagraphProps {
@@ -80,87 +109,81 @@ pub fn resolve_paragraph_props(
         })
         .unwrap_or_default();
 
-    // 1. Apply named style if it exists
-    if let Some(style) = style_name.and_then(|n| styles.get(n)) {
-        if let Some(family) = style
-            .attributes
-            .get("fo:font-family")
-            .or_else(|| style.attributes.get("style:font-name"))
-        {
-            props.font_family = family.to_lowercase();
+    let effective = if style_name.is_none() && level.is_some() {
+        EffectiveStyle {
+            attributes: direct
+                .map(BlockAttrs::to_odf_attributes)
+                .unwrap_or_default(),
+            ..EffectiveStyle::default()
         }
+    } else {
+        StyleResolver::new(styles).resolve_with(style_name, StyleFamily::Paragraph, direct)
+    };
+    let attrs = &effective.attributes;
+
+    if let Some(family) = attrs
+        .get("fo:font-family")
+        .or_else(|| attrs.get("style:font-name"))
+    {
+        props.font_family = family.to_lowercase();
+    }
 
-        if let Some(size_str) = style.attributes.get("fo:font-size") {
-            if let Some(size) = parse_length(size_str) {
-                props.font_size = size;
-            }
+    if let Some(size_str) = attrs.get("fo:font-size") {
+        if let Some(size) = parse_length(size_str) {
+            props.font_size = size;
         }
+    }
 
-        if let Some(lh_str) = style.attributes.get("fo:line-height") {
-            props.line_height_factor = parse_line_height(lh_str, props.font_size);
-        }
+    if let Some(lh_str) = attrs.get("fo:line-height") {
+        props.line_height_factor = parse_line_height(lh_str, props.font_size);
+    }
 
-        if let Some(align_str) = style.attributes.get("fo:text-align") {
-            props.text_align = match align_str.as_str() {
-                "center" => TextAlign::Center,
-                "right" | "end" => TextAlign::Right,
-                "justify" => TextAlign::Justify,
-                _ => TextAlign::Left,
-            };
-        }
+    if let Some(align_str) = attrs.get("fo:text-align") {
+        props.text_align = match align_str.as_str() {
+            "center" => TextAlign::Center,
+            "right" | "end" => TextAlign::Right,
+            "justify" => TextAlign::Justify,
+            _ => TextAlign::Left,
+        };
+    }
 
-        if let Some(val) = style.attributes.get("fo:margin-left") {
-            props.margin_left = parse_length(val).unwrap_or(0.0);
-        }
-        if let Some(val) = style.attributes.get("fo:margin-right") {
-            props.margin_right = parse_length(val).unwrap_or(0.0);
-        }
-        if let Some(val) = style.attributes.get("fo:text-indent") {
-            props.text_indent = parse_length(val).unwrap_or(0.0);
-        }
-        if let Some(val) = style.attributes.get("fo:margin-top") {
-            props.space_before = parse_length(val).unwrap_or(0.0);
-        }
-        if let Some(val) = style.attributes.get("fo:margin-bottom") {
-            props.space_after = parse_length(val).unwrap_or(0.0);
-        }
+    if let Some(val) = attrs.get("fo:margin-left") {
+        props.margin_left = parse_length(val).unwrap_or(0.0);
+    }
+    if let Some(val) = attrs.get("fo:margin-right") {
+        props.margin_right = parse_length(val).unwrap_or(0.0);
+    }
+    if let Some(val) = attrs.get("fo:text-indent") {
+        props.text_indent = parse_length(val).unwrap_or(0.0);
+    }
+    if let Some(val) = attrs.get("fo:margin-top") {
+        props.space_before = parse_length(val).unwrap_or(0.0);
+    }
+    if let Some(val) = attrs.get("fo:margin-bottom") {
+        props.space_after = parse_length(val).unwrap_or(0.0);
+    }
 
-        if let Some(val) = style.attributes.get("fo:break-before") {
-            props.break_before = val == "page";
-        }
-        if let Some(val) = style.attributes.get("fo:keep-with-next") {
-            props.keep_with_next = val == "always" || val == "true";
-        }
+    if let Some(val) = attrs.get("fo:break-before") {
+        props.break_before = val == "page";
+    }
+    if let Some(val) = attrs.get("fo:keep-with-next") {
+        props.keep_with_next = val == "always" || val == "true";
+    }
 
-        if let Some(val) = style.attributes.get("fo:font-weight") {
-            props.bold = val == "bold" || val.parse::<u16>().map(|w| w >= 700).unwrap_or(false);
-        }
-        if let Some(val) = style.attributes.get("fo:font-style") {
-            props.italic = val == "italic";
-        }
+    if let Some(val) = attrs.get("fo:font-weight") {
+        props.bold = val == "bold" || val.parse::<u16>().map(|w| w >= 700).unwrap_or(false);
+    }
+    if let Some(val) = attrs.get("fo:font-style") {
+        props.italic = val == "italic";
     }
 
     props
 }
 
-/// Parse a length string (e.g. "12pt", "1in", "2.54cm") into points.
+/// Parse a length string (e.g. "12pt", "1in", "2.54cm") into points; a bare
+/// number is taken as points.
 pub fn parse_length(s: &str) -> Option<f64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
-
-    if s.ends_with("pt") {
-        s[..s.len() - 2].parse::<f64>().ok()
-    } else if s.ends_with("in") {
-        s[..s.len() - 2].parse::<f64>().ok().map(|v| v * 72.0)
-    } else if s.ends_with("cm") {
-        s[..s.len() - 2].parse::<f64>().ok().map(|v| v * 28.346)
-    } else if s.ends_with("mm") {
-        s[..s.len() - 2].parse::<f64>().ok().map(|v| v * 2.8346)
-    } else {
-        s.parse::<f64>().ok()
-    }
+    length_to_pt(s).or_else(|| s.trim().parse::<f64>().ok())
 }
 
 /// Parse line height which can be a factor ("1.2"), a percentage ("120%"), or a length ("15pt").
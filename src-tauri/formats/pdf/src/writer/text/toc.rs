@@ -0,0 +1,122 @@
+// Copyright 2024 AppThere
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tables of contents.
+//!
+//! A table of contents is laid out as ordinary paragraphs: its title, then
+//! one `Contents N` paragraph per entry. The text and page number of an
+//! entry are cross-references to the bookmark of its heading, so they link
+//! to the heading like any other reference. Page numbers are only known
+//! after layout; [`fill_toc_pages`] takes them from the laid-out pages.
+
+use std::collections::HashMap;
+
+use common_core::block::Block;
+use common_core::inline::Inline;
+use common_core::toc::{for_each_toc_mut, TableOfContents};
+use common_core::CrossReference;
+
+use super::layout::PageContent;
+
+/// Returns `blocks` with every table of contents replaced by its
+/// paragraphs.
+pub(super) fn expand_tocs(blocks: &[Block]) -> Vec<Block> {
+    let mut out = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        match block {
+            Block::TableOfContents(toc) => out.extend(toc_blocks(toc)),
+            Block::Section {
+                name,
+                attrs,
+                protected,
+                content,
+            } => out.push(Block::Section {
+                name: name.clone(),
+                attrs: attrs.clone(),
+                protected: *protected,
+                content: expand_tocs(content),
+            }),
+            other => out.push(other.clone()),
+        }
+    }
+    out
+}
+
+/// The paragraphs a table of contents is laid out as.
+pub(super) fn toc_blocks(toc: &TableOfContents) -> Vec<Block> {
+    let mut blocks = Vec::with_capacity(toc.entries.len() + 1);
+    if let Some(title) = &toc.title {
+        blocks.push(Block::Paragraph {
+            style_name: Some("Contents_20_Heading".to_string()),
+            attrs: None,
+            content: vec![Inline::Text {
+                text: title.clone(),
+                style_name: None,
+                marks: vec![],
+            }],
+        });
+    }
+    for entry in &toc.entries {
+        let part = |value: &str| match &entry.target {
+            Some(target) => Inline::Reference(CrossReference {
+                name: target.clone(),
+                value: value.to_string(),
+                ..CrossReference::default()
+            }),
+            None => Inline::Text {
+                text: value.to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+        };
+        let mut content = vec![part(&entry.text)];
+        if let Some(page) = &entry.page {
+            content.push(Inline::Tab);
+            content.push(part(page));
+        }
+        blocks.push(Block::Paragraph {
+            style_name: Some(format!("Contents_20_{}", entry.level)),
+            attrs: None,
+            content,
+        });
+    }
+    blocks
+}
+
+/// Sets the page number of every entry whose heading bookmark is on one of
+/// `pages`, the first of which is page 1.
+pub(super) fn fill_toc_pages(blocks: &mut [Block], pages: &[PageContent]) {
+    let mut page_of: HashMap<&str, usize> = HashMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        for (name, _) in &page.anchors {
+            page_of.entry(name.as_str()).or_insert(i + 1);
+        }
+    }
+    for_each_toc_mut(blocks, &mut |toc| {
+        for entry in &mut toc.entries {
+            if let Some(page) = entry.target.as_deref().and_then(|t| page_of.get(t)) {
+                entry.page = Some(page.to_string());
+            }
+        }
+    });
+}
+
+/// Whether `blocks` contain a table of contents.
+pub(super) fn has_toc(blocks: &[Block]) -> bool {
+    blocks.iter().any(|block| match block {
+        Block::TableOfContents(_) => true,
+        Block::Section { content, .. } => has_toc(content),
+        _ => false,
+    })
+}
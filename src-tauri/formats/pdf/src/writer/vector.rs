@@ -99,7 +99,7 @@ pub fn write_pdf_x(
     pdf.stream(content_ref, &content_compressed)
         .filter(pdf_writer::Filter::FlateDecode);
 
-    let xmp = build_xmp_packet(prepared.metadata.title.as_deref(), settings);
+    let xmp = build_xmp_packet(&prepared.metadata, settings);
     {
         let xmp_bytes = xmp.into_bytes();
         let mut xmp_stream = pdf.stream(xmp_ref, &xmp_bytes);
@@ -18,7 +18,7 @@ use std::collections::HashMap;
 
 use common_core::block::Block;
 use common_core::inline::Inline;
-use common_core::{Metadata, StyleDefinition};
+use common_core::{Metadata, PageSetup, StyleDefinition};
 use loki_pdf::export_settings::{PdfExportSettings, PdfXStandard};
 use loki_pdf::{write_text_pdf, MapFontResolver};
 
@@ -82,10 +82,13 @@ fn make_style(name: &str, attrs: HashMap<&str, &str>) -> (String, StyleDefinitio
             text_transform: None,
             outline_level: None,
             autocomplete: None,
+            master_page_name: None,
             #[cfg(feature = "colour-management")]
             font_colour: None,
             #[cfg(feature = "colour-management")]
             background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
         },
     )
 }
@@ -117,8 +120,15 @@ fn test_alignment_rendering() {
         title: Some("Alignment Test".to_string()),
         ..Default::default()
     };
-    let bytes = write_text_pdf(&blocks, &styles, &metadata, &default_settings(), &resolver)
-        .expect("Alignment export should succeed");
+    let bytes = write_text_pdf(
+        &blocks,
+        &styles,
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Alignment export should succeed");
 
     assert!(bytes.starts_with(b"%PDF-"));
 }
@@ -148,8 +158,15 @@ fn test_paragraph_spacing() {
         title: Some("Spacing Test".to_string()),
         ..Default::default()
     };
-    let bytes = write_text_pdf(&blocks, &styles, &metadata, &default_settings(), &resolver)
-        .expect("Spacing export should succeed");
+    let bytes = write_text_pdf(
+        &blocks,
+        &styles,
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Spacing export should succeed");
 
     assert!(bytes.starts_with(b"%PDF-"));
 }
@@ -181,8 +198,15 @@ fn test_indentation_and_margins() {
         title: Some("Indentation Test".to_string()),
         ..Default::default()
     };
-    let bytes = write_text_pdf(&blocks, &styles, &metadata, &default_settings(), &resolver)
-        .expect("Indentation export should succeed");
+    let bytes = write_text_pdf(
+        &blocks,
+        &styles,
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Indentation export should succeed");
 
     assert!(bytes.starts_with(b"%PDF-"));
 }
@@ -229,8 +253,15 @@ fn test_page_breaks_and_orphan_logic() {
         title: Some("Layout Logic Test".to_string()),
         ..Default::default()
     };
-    let bytes = write_text_pdf(&blocks, &styles, &metadata, &default_settings(), &resolver)
-        .expect("Layout logic export should succeed");
+    let bytes = write_text_pdf(
+        &blocks,
+        &styles,
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Layout logic export should succeed");
 
     let content = String::from_utf8_lossy(&bytes);
     let page_count = content.split("/Type /Page").count() - 1;
@@ -278,6 +309,7 @@ fn test_heading_defaults() {
     let bytes = write_text_pdf(
         &blocks,
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -17,9 +17,9 @@
 use std::collections::HashMap;
 
 use common_core::block::{Block, BlockAttrs};
-use common_core::inline::Inline;
+use common_core::inline::{Inline, NoteClass};
 use common_core::marks::TiptapMark;
-use common_core::{Metadata, StyleDefinition};
+use common_core::{Metadata, PageSetup, StyleDefinition};
 use loki_pdf::export_settings::{PdfExportSettings, PdfXStandard};
 use loki_pdf::{write_text_pdf, MapFontResolver};
 
@@ -91,6 +91,7 @@ fn write_text_pdf_empty_blocks_fails_validation() {
     let result = write_text_pdf(
         &[],
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -119,6 +120,7 @@ fn write_text_pdf_simple_paragraph_produces_pdf() {
     let result = write_text_pdf(
         &blocks,
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -170,6 +172,7 @@ fn write_text_pdf_mixed_blocks_succeed() {
     let bytes = write_text_pdf(
         &blocks,
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -194,6 +197,7 @@ fn write_text_pdf_missing_title_still_exports() {
     let result = write_text_pdf(
         &blocks,
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -233,10 +237,13 @@ fn write_text_pdf_uses_font_family_attribute() {
             text_transform: None,
             outline_level: None,
             autocomplete: None,
+            master_page_name: None,
             #[cfg(feature = "colour-management")]
             font_colour: None,
             #[cfg(feature = "colour-management")]
             background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
         },
     );
 
@@ -254,6 +261,59 @@ fn write_text_pdf_uses_font_family_attribute() {
         title: Some("Font Test".to_string()),
         ..Default::default()
     };
-    let result = write_text_pdf(&blocks, &styles, &metadata, &default_settings(), &resolver);
+    let result = write_text_pdf(
+        &blocks,
+        &styles,
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    );
     assert!(result.is_ok(), "Should succeed with fo:font-family style");
 }
+
+/// Footnotes and endnotes should lay out without errors; endnotes add a page.
+#[test]
+fn write_text_pdf_with_notes_succeeds() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => {
+            eprintln!("[skip] Font unavailable");
+            return;
+        }
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+    let note = |class: NoteClass, citation: &str, body: &str| Inline::Note {
+        id: None,
+        note_class: class,
+        citation: citation.to_string(),
+        content: vec![simple_paragraph(body)],
+    };
+    let blocks = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Text {
+                text: "A claim that needs a source".to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+            note(NoteClass::Footnote, "1", "The footnote source."),
+            note(NoteClass::Endnote, "i", "An endnote at the back."),
+        ],
+    }];
+    let metadata = Metadata {
+        title: Some("Notes".to_string()),
+        ..Default::default()
+    };
+    let bytes = write_text_pdf(
+        &blocks,
+        &HashMap::new(),
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("write_text_pdf should succeed with notes");
+    assert!(bytes.starts_with(b"%PDF-"));
+}
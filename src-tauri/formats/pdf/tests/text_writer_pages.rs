@@ -14,9 +14,12 @@
 
 //! Pagination tests for `write_text_pdf` — multi-page and overflow behaviour.
 
-use common_core::block::Block;
+use common_core::block::{Block, BlockAttrs};
 use common_core::inline::Inline;
-use common_core::{Metadata, StyleDefinition};
+use common_core::{
+    CrossReference, FieldKind, HeaderFooter, MasterPage, Metadata, PageLayout, PageSetup,
+    SectionAttrs, StyleDefinition, TableOfContents,
+};
 use loki_pdf::export_settings::{PdfExportSettings, PdfXStandard};
 use loki_pdf::{write_text_pdf, MapFontResolver};
 use std::collections::HashMap;
@@ -84,6 +87,7 @@ fn write_text_pdf_multi_page_support() {
     let bytes = write_text_pdf(
         &blocks,
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -126,6 +130,7 @@ fn write_text_pdf_paragraph_overflow() {
     let bytes = write_text_pdf(
         &blocks,
         &HashMap::new(),
+        &PageSetup::default(),
         &metadata,
         &default_settings(),
         &resolver,
@@ -140,3 +145,399 @@ fn write_text_pdf_paragraph_overflow() {
         page_count
     );
 }
+
+/// Verify that a two-column section longer than a page continues on the
+/// next page instead of being cut off.
+#[test]
+fn write_text_pdf_section_columns_overflow() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => return,
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+
+    let paragraphs = (0..200)
+        .map(|i| simple_paragraph(&format!("Column paragraph {i} with a few words.")))
+        .collect();
+    let blocks = vec![
+        simple_paragraph("Before the section"),
+        Block::Section {
+            name: "Columns".to_string(),
+            attrs: Some(SectionAttrs {
+                column_count: Some(2),
+                column_gap: Some(24.0),
+            }),
+            protected: false,
+            content: paragraphs,
+        },
+        simple_paragraph("After the section"),
+    ];
+    let metadata = Metadata {
+        title: Some("Section Test".to_string()),
+        ..Default::default()
+    };
+
+    let bytes = write_text_pdf(
+        &blocks,
+        &HashMap::new(),
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Section export should succeed");
+
+    let content = String::from_utf8_lossy(&bytes);
+    let page_count = content.split("/Type /Page").count() - 1;
+    assert!(
+        page_count >= 2,
+        "Expected the section to continue on a second page, got {}",
+        page_count
+    );
+}
+
+#[test]
+fn write_text_pdf_page_fields() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => return,
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+
+    let field = |kind| Inline::Field {
+        kind,
+        value: String::new(),
+        fixed: false,
+        date_value: None,
+        data_style_name: None,
+    };
+    let blocks: Vec<Block> = (0..120)
+        .map(|i| Block::Paragraph {
+            style_name: None,
+            attrs: None,
+            content: vec![
+                Inline::Text {
+                    text: format!("Paragraph {i} on page "),
+                    style_name: None,
+                    marks: vec![],
+                },
+                field(FieldKind::PageNumber),
+                Inline::Text {
+                    text: " of ".to_string(),
+                    style_name: None,
+                    marks: vec![],
+                },
+                field(FieldKind::PageCount),
+                Inline::Space { count: 1 },
+                field(FieldKind::Title),
+            ],
+        })
+        .collect();
+    let metadata = Metadata {
+        title: Some("Field Test".to_string()),
+        ..Default::default()
+    };
+
+    let bytes = write_text_pdf(
+        &blocks,
+        &HashMap::new(),
+        &PageSetup::default(),
+        &metadata,
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Export with fields should succeed");
+
+    let content = String::from_utf8_lossy(&bytes);
+    let page_count = content.split("/Type /Page").count() - 1;
+    assert!(
+        page_count >= 2,
+        "Expected several pages, got {}",
+        page_count
+    );
+}
+
+#[test]
+fn write_text_pdf_reference_links_to_bookmark() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => return,
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+
+    let mut blocks: Vec<Block> = vec![Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Text {
+                text: "See ".to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+            Inline::Reference(CrossReference {
+                name: "target".to_string(),
+                value: "the target".to_string(),
+                ..Default::default()
+            }),
+        ],
+    }];
+    blocks.extend((0..80).map(|i| simple_paragraph(&format!("Filler paragraph {i}."))));
+    blocks.push(Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Bookmark {
+                name: "target".to_string(),
+            },
+            Inline::Text {
+                text: "The target.".to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+        ],
+    });
+
+    let bytes = write_text_pdf(
+        &blocks,
+        &HashMap::new(),
+        &PageSetup::default(),
+        &Metadata::default(),
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Export with a cross-reference should succeed");
+
+    let content = String::from_utf8_lossy(&bytes);
+    assert_eq!(content.matches("/Subtype /Link").count(), 1);
+    assert!(content.contains("/Annots"));
+    assert!(content.contains("/XYZ"));
+}
+
+#[test]
+fn write_text_pdf_table_of_contents_links_to_headings() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => return,
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+
+    let heading = |text: &str| Block::Heading {
+        level: 1,
+        style_name: None,
+        attrs: None,
+        content: vec![Inline::Text {
+            text: text.to_string(),
+            style_name: None,
+            marks: vec![],
+        }],
+    };
+    let mut blocks = vec![
+        Block::TableOfContents(TableOfContents::default()),
+        heading("First chapter"),
+    ];
+    blocks.extend((0..80).map(|i| simple_paragraph(&format!("Filler paragraph {i}."))));
+    blocks.push(heading("Second chapter"));
+
+    let bytes = write_text_pdf(
+        &blocks,
+        &HashMap::new(),
+        &PageSetup::default(),
+        &Metadata::default(),
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Export with a table of contents should succeed");
+
+    // Each entry links its text and its page number to the heading.
+    let content = String::from_utf8_lossy(&bytes);
+    assert_eq!(content.matches("/Subtype /Link").count(), 4);
+}
+
+/// Pages are sized from the master page in effect, which a paragraph style
+/// or the automatic style behind a paragraph's direct formatting can switch
+/// for the rest of its page run.
+#[test]
+fn write_text_pdf_uses_master_page_sizes() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => return,
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+
+    let page_setup = PageSetup {
+        layouts: vec![
+            PageLayout {
+                name: "Letter".to_string(),
+                width: 612.0,
+                height: 792.0,
+                ..PageLayout::default()
+            },
+            PageLayout {
+                name: "A5".to_string(),
+                width: 420.0,
+                height: 595.0,
+                margin_left: 54.0,
+                margin_right: 54.0,
+                ..PageLayout::default()
+            },
+        ],
+        master_pages: vec![
+            MasterPage {
+                name: "Standard".to_string(),
+                page_layout: "Letter".to_string(),
+                ..MasterPage::default()
+            },
+            MasterPage {
+                name: "Small".to_string(),
+                page_layout: "A5".to_string(),
+                next: Some("Standard".to_string()),
+                ..MasterPage::default()
+            },
+        ],
+    };
+    let mut styles = HashMap::new();
+    styles.insert(
+        "Small Chapter".to_string(),
+        StyleDefinition {
+            name: "Small Chapter".to_string(),
+            family: common_core::StyleFamily::Paragraph,
+            parent: None,
+            next: None,
+            display_name: None,
+            attributes: HashMap::new(),
+            text_transform: None,
+            outline_level: None,
+            autocomplete: None,
+            master_page_name: Some("Small".to_string()),
+            font_colour: None,
+            background_colour: None,
+            properties: Default::default(),
+            list_levels: Vec::new(),
+        },
+    );
+    let blocks = vec![
+        simple_paragraph("On letter paper"),
+        Block::Paragraph {
+            style_name: Some("Small Chapter".to_string()),
+            attrs: None,
+            content: vec![Inline::Text {
+                text: "On A5 paper".to_string(),
+                style_name: None,
+                marks: vec![],
+            }],
+        },
+        Block::PageBreak,
+        simple_paragraph("Back on letter paper"),
+        Block::Paragraph {
+            style_name: None,
+            attrs: Some(BlockAttrs {
+                master_page_name: Some("Small".to_string()),
+                ..BlockAttrs::default()
+            }),
+            content: vec![Inline::Text {
+                text: "On A5 paper again".to_string(),
+                style_name: None,
+                marks: vec![],
+            }],
+        },
+    ];
+
+    let bytes = write_text_pdf(
+        &blocks,
+        &styles,
+        &page_setup,
+        &Metadata::default(),
+        &default_settings(),
+        &resolver,
+    )
+    .expect("Export with master pages should succeed");
+
+    let content = String::from_utf8_lossy(&bytes);
+    assert_eq!(content.matches("/TrimBox [0 0 612 792]").count(), 2);
+    assert_eq!(content.matches("/TrimBox [0 0 420 595]").count(), 2);
+}
+
+/// Headers and footers are drawn on every page and take their room from the
+/// body text, so the same text needs more pages.
+#[test]
+fn write_text_pdf_headers_and_footers_shrink_the_body() {
+    let font_bytes = match load_public_sans() {
+        Some(b) => b,
+        None => return,
+    };
+    let resolver = make_resolver_with_font(font_bytes);
+
+    // Enough text for two full pages without headers and footers.
+    let blocks: Vec<Block> = (0..64)
+        .map(|i| simple_paragraph(&format!("Body paragraph {i}")))
+        .collect();
+    let page_count = |page_setup: &PageSetup| {
+        let bytes = write_text_pdf(
+            &blocks,
+            &HashMap::new(),
+            page_setup,
+            &Metadata::default(),
+            &default_settings(),
+            &resolver,
+        )
+        .expect("Export with headers and footers should succeed");
+        String::from_utf8_lossy(&bytes).split("/Type /Page").count() - 1
+    };
+
+    let footer = Block::Paragraph {
+        style_name: None,
+        attrs: None,
+        content: vec![
+            Inline::Text {
+                text: "Page ".to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+            Inline::Field {
+                kind: FieldKind::PageNumber,
+                value: String::new(),
+                fixed: false,
+                date_value: None,
+                data_style_name: None,
+            },
+            Inline::Text {
+                text: " of ".to_string(),
+                style_name: None,
+                marks: vec![],
+            },
+            Inline::Field {
+                kind: FieldKind::PageCount,
+                value: String::new(),
+                fixed: false,
+                date_value: None,
+                data_style_name: None,
+            },
+        ],
+    };
+    let with_regions = PageSetup {
+        layouts: vec![PageLayout::default()],
+        master_pages: vec![MasterPage {
+            name: "Standard".to_string(),
+            page_layout: PageLayout::default().name,
+            header: Some(HeaderFooter {
+                content: (0..10)
+                    .map(|i| simple_paragraph(&format!("Header line {i}")))
+                    .collect(),
+                first: Some(vec![simple_paragraph("Title page")]),
+                ..HeaderFooter::default()
+            }),
+            footer: Some(HeaderFooter {
+                content: vec![footer],
+                ..HeaderFooter::default()
+            }),
+            ..MasterPage::default()
+        }],
+    };
+
+    let without = page_count(&PageSetup::default());
+    let with = page_count(&with_regions);
+    assert!(
+        with > without,
+        "Expected more than {without} pages with headers and footers, got {with}"
+    );
+}
@@ -1,4 +1,4 @@
-use common_core::{LexicalDocument, Metadata, StyleDefinition};
+use common_core::{LexicalDocument, Metadata, OutlineStyle, StyleDefinition};
 use odt_format::{lexical::from_lexical, tiptap::to_tiptap::document_to_tiptap};
 
 use std::collections::HashMap;
@@ -13,6 +13,7 @@ pub async fn save_epub<R: Runtime>(
     lexical_json: String,
     styles: HashMap<String, StyleDefinition>,
     metadata: Metadata,
+    outline_style: Option<OutlineStyle>,
     font_paths: Vec<String>,
 ) -> CommandResult<Option<Vec<u8>>> {
     app.emit("debug_log", format!("Exporting EPUB to: {}", path))
@@ -21,10 +22,12 @@ pub async fn save_epub<R: Runtime>(
     // Parse Lexical JSON → Document (common_core types)
     let lex_doc: LexicalDocument =
         serde_json::from_str(&lexical_json).map_err(|e| e.to_string())?;
-    let odt_doc = from_lexical(lex_doc, styles.clone(), metadata.clone());
+    let mut odt_doc = from_lexical(lex_doc, styles.clone(), metadata.clone());
+    odt_doc.outline_style = outline_style;
 
-    // Convert blocks to TiptapNode (common_core types used directly by epub_logic)
-    let common_node = document_to_tiptap(&odt_doc.blocks);
+    // Convert blocks to TiptapNode (common_core types used directly by
+    // epub_logic), with the outline numbers written into the headings
+    let common_node = document_to_tiptap(&odt_doc.numbered_blocks());
 
     // Load fonts
     let mut fonts = Vec::new();
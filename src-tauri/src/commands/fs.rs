@@ -1,24 +1,38 @@
-use common_core::{LexicalDocument, Metadata, StyleDefinition};
+use common_core::{
+    LexicalDocument, LexicalPageRegion, Metadata, OutlineStyle, PageSetup, StyleDefinition,
+};
 use odt_format::{
-    lexical::{from_lexical, to_lexical},
-    Document,
+    lexical::{from_lexical, page_regions_from_lexical, page_regions_to_lexical, to_lexical},
+    template::{import_styles, open_template, StyleImportOptions, StyleImportReport},
+    Document, OdtPackage,
 };
 use serde::Serialize;
-use std::{
-    collections::HashMap,
-    io::{Cursor, Read, Write},
-};
+use std::collections::HashMap;
+use std::path::Path;
 use tauri::{AppHandle, Emitter, Runtime};
-use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
-
-use super::odt_zip::write_odt_zip;
 
-/// Response payload for `open_document`: Lexical editor state + styles + metadata.
+/// Response payload for `open_document`: Lexical editor state + styles +
+/// metadata + heading numbering + page layouts + headers and footers +
+/// the track-changes switch.
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LexicalResponse {
     pub content: LexicalDocument,
     pub styles: HashMap<String, StyleDefinition>,
     pub metadata: Metadata,
+    pub outline_style: Option<OutlineStyle>,
+    pub page_setup: Option<PageSetup>,
+    pub page_regions: Vec<LexicalPageRegion>,
+    pub track_changes: bool,
+}
+
+/// Response payload for `import_document_styles`: the combined styles and
+/// what happened to each imported one.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleImportResponse {
+    pub styles: HashMap<String, StyleDefinition>,
+    pub report: StyleImportReport,
 }
 
 type CommandResult<T> = Result<T, String>;
@@ -30,6 +44,10 @@ pub async fn save_document<R: Runtime>(
     lexical_json: String,
     styles: HashMap<String, StyleDefinition>,
     metadata: Metadata,
+    outline_style: Option<OutlineStyle>,
+    page_setup: Option<PageSetup>,
+    page_regions: Option<Vec<LexicalPageRegion>>,
+    track_changes: Option<bool>,
     original_path: Option<String>,
     original_content: Option<Vec<u8>>,
 ) -> CommandResult<Option<Vec<u8>>> {
@@ -40,7 +58,13 @@ pub async fn save_document<R: Runtime>(
     let lex_doc: LexicalDocument =
         serde_json::from_str(&lexical_json).map_err(|e| format!("Invalid Lexical JSON: {}", e))?;
 
-    let doc = from_lexical(lex_doc, styles, metadata);
+    let mut doc = from_lexical(lex_doc, styles, metadata);
+    doc.outline_style = outline_style;
+    doc.page_setup = page_setup;
+    if let (Some(setup), Some(regions)) = (doc.page_setup.as_mut(), page_regions) {
+        page_regions_from_lexical(setup, regions);
+    }
+    doc.track_changes = track_changes.unwrap_or_default();
 
     let mut original_bytes: Option<Vec<u8>> = original_content;
     if original_bytes.is_none() {
@@ -66,19 +90,19 @@ pub async fn save_document<R: Runtime>(
             doc.to_xml()?.into_bytes()
         }
     } else {
-        // ODT Generation (ZIP)
-        let mut buffer = Cursor::new(Vec::new());
-        if let Some(orig_bytes) = original_bytes {
-            if update_odt_zip(&orig_bytes, &mut buffer, &doc).is_ok() {
-                // Success
-            } else {
-                buffer = Cursor::new(Vec::new()); // Reset buffer
-                write_odt_zip(&mut buffer, &doc)?;
-            }
-        } else {
-            write_odt_zip(&mut buffer, &doc)?;
+        // ODT Generation (ZIP): update the original package so its
+        // settings, thumbnails and other entries are kept. Relative image
+        // paths are resolved against the document's folder.
+        let base_dir = (!path.starts_with("content://"))
+            .then(|| Path::new(&path).parent())
+            .flatten();
+        let updated = original_bytes
+            .and_then(|orig_bytes| OdtPackage::from_bytes(&orig_bytes).ok())
+            .and_then(|mut package| package.update(&doc, base_dir).ok().map(|_| package));
+        match updated {
+            Some(package) => package.to_bytes()?,
+            None => OdtPackage::from_document(&doc, base_dir)?.to_bytes()?,
         }
-        buffer.into_inner()
     };
 
     if path.starts_with("content://") {
@@ -99,190 +123,104 @@ pub async fn save_document<R: Runtime>(
     }
 }
 
-fn update_odt_zip<W: Write + std::io::Seek>(
-    old_bytes: &[u8],
-    writer: W,
-    doc: &Document,
-) -> Result<(), String> {
-    let reader = Cursor::new(old_bytes.to_vec());
-    let mut zip_in = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
-    let mut zip_out = ZipWriter::new(writer);
-
-    let options_mimetype =
-        SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
-    let options_deflated =
-        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+#[tauri::command]
+pub async fn open_document<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    file_content: Option<Vec<u8>>,
+) -> CommandResult<LexicalResponse> {
+    app.emit("debug_log", format!("Opening document: {}", path))
+        .ok();
 
-    // Write mimetype first (uncompressed) if it exists
-    for i in 0..zip_in.len() {
-        let mut file = zip_in.by_index(i).map_err(|e| e.to_string())?;
-        if file.name() == "mimetype" {
-            zip_out
-                .start_file("mimetype", options_mimetype)
-                .map_err(|e| e.to_string())?;
-            std::io::copy(&mut file, &mut zip_out).map_err(|e| e.to_string())?;
-            break;
-        }
-    }
+    let bytes = read_file(&app, &path, file_content)?;
 
-    // Write the rest
-    for i in 0..zip_in.len() {
-        let mut file = zip_in.by_index(i).map_err(|e| e.to_string())?;
-        let name = file.name().to_string();
-        if name == "mimetype" {
-            continue;
-        }
+    let doc = if bytes.starts_with(b"PK") {
+        // Zip archive (ODT)
+        OdtPackage::from_bytes(&bytes)
+            .map_err(|e| format!("Navalozh: Failed to read ODT package: {}", e))?
+            .to_document()?
+    } else {
+        // Plain text / XML (FODT)
+        let xml_content = String::from_utf8(bytes)
+            .map_err(|e| format!("Navalozh: Failed to decode text file (not UTF-8): {}", e))?;
+        Document::from_xml(&xml_content)?
+    };
 
-        zip_out
-            .start_file(&name, options_deflated)
-            .map_err(|e| e.to_string())?;
+    Ok(lexical_response(doc))
+}
 
-        if name == "content.xml" {
-            let mut original = String::new();
-            if file.read_to_string(&mut original).is_ok() {
-                if let Ok(updated) = doc.update_fodt(&original) {
-                    zip_out
-                        .write_all(updated.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                } else {
-                    zip_out
-                        .write_all(doc.to_content_xml()?.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                }
-            } else {
-                zip_out
-                    .write_all(doc.to_content_xml()?.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-        } else if name == "styles.xml" {
-            let mut original = String::new();
-            if file.read_to_string(&mut original).is_ok() {
-                if let Ok(updated) = doc.update_fodt(&original) {
-                    zip_out
-                        .write_all(updated.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                } else {
-                    zip_out
-                        .write_all(doc.styles_to_xml()?.as_bytes())
-                        .map_err(|e| e.to_string())?;
-                }
-            } else {
-                zip_out
-                    .write_all(doc.styles_to_xml()?.as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
-        } else if name == "meta.xml" {
-            zip_out
-                .write_all(doc.to_meta_xml()?.as_bytes())
-                .map_err(|e| e.to_string())?;
-        } else {
-            std::io::copy(&mut file, &mut zip_out).map_err(|e| e.to_string())?;
-        }
-    }
+/// Opens a template (`.ott` or flat XML) as a new untitled document. The
+/// frontend saves it with no original path, so it becomes a plain `.odt`.
+#[tauri::command]
+pub async fn new_from_template<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    file_content: Option<Vec<u8>>,
+) -> CommandResult<LexicalResponse> {
+    app.emit("debug_log", format!("New document from template: {}", path))
+        .ok();
 
-    zip_out.finish().map_err(|e| e.to_string())?;
-    Ok(())
+    let bytes = read_file(&app, &path, file_content)?;
+    let doc =
+        open_template(&bytes).map_err(|e| format!("Navalozh: Failed to read template: {}", e))?;
+    Ok(lexical_response(doc))
 }
 
+/// Imports the named styles of another document or template into `styles`.
 #[tauri::command]
-pub async fn open_document<R: Runtime>(
+pub async fn import_document_styles<R: Runtime>(
     app: AppHandle<R>,
     path: String,
     file_content: Option<Vec<u8>>,
-) -> CommandResult<LexicalResponse> {
-    app.emit("debug_log", format!("Opening document: {}", path))
+    mut styles: HashMap<String, StyleDefinition>,
+    options: StyleImportOptions,
+) -> CommandResult<StyleImportResponse> {
+    app.emit("debug_log", format!("Importing styles from: {}", path))
         .ok();
 
-    let bytes = if let Some(content) = file_content {
+    let bytes = read_file(&app, &path, file_content)?;
+    let source =
+        open_template(&bytes).map_err(|e| format!("Navalozh: Failed to read styles: {}", e))?;
+    let report = import_styles(&mut styles, &source.styles, options);
+    Ok(StyleImportResponse { styles, report })
+}
+
+/// Returns `file_content` when the frontend read the file (content:// URIs),
+/// otherwise reads `path` from disk.
+fn read_file<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &str,
+    file_content: Option<Vec<u8>>,
+) -> CommandResult<Vec<u8>> {
+    if let Some(content) = file_content {
         app.emit("debug_log", "Using provided file content (Memory)")
             .ok();
-        content
+        Ok(content)
     } else {
         app.emit("debug_log", "Reading file from disk").ok();
-        std::fs::read(&path).map_err(|e| {
+        std::fs::read(path).map_err(|e| {
             format!(
                 "Failed to read file {}: {}. Tip: On Android, ensure file_content is passed for content:// URIs.",
                 path, e
             )
-        })?
-    };
-
-    let doc = if bytes.starts_with(b"PK") {
-        // Zip archive (ODT)
-        let reader = Cursor::new(bytes);
-        let mut archive = zip::ZipArchive::new(reader)
-            .map_err(|e| format!("Navalozh: Failed to read zip archive: {}", e))?;
-
-        // 1. Read content.xml (Body and Automatic Styles)
-        let content_xml = {
-            let mut content_file = archive
-                .by_name("content.xml")
-                .map_err(|e| format!("Navalozh: content.xml not found in ODT: {}", e))?;
-            let mut content_xml = String::new();
-            content_file
-                .read_to_string(&mut content_xml)
-                .map_err(|e| format!("Navalozh: Failed to read content.xml: {}", e))?;
-            content_xml
-        };
-
-        let mut doc = Document::from_xml(&content_xml)?;
-
-        // 2. Read styles.xml (Common Styles)
-        {
-            if let Ok(mut styles_file) = archive.by_name("styles.xml") {
-                let mut styles_xml = String::new();
-                if styles_file.read_to_string(&mut styles_xml).is_ok() {
-                    let _ = doc.add_styles_from_xml(&styles_xml);
-                }
-            }
-        }
-
-        // 3. Read meta.xml (Metadata)
-        {
-            if let Ok(mut meta_file) = archive.by_name("meta.xml") {
-                let mut meta_xml = String::new();
-                if meta_file.read_to_string(&mut meta_xml).is_ok() {
-                    if let Ok(meta_doc) = Document::from_xml(&meta_xml) {
-                        if meta_doc.metadata.title.is_some() {
-                            doc.metadata.title = meta_doc.metadata.title;
-                        }
-                        if meta_doc.metadata.creator.is_some() {
-                            doc.metadata.creator = meta_doc.metadata.creator;
-                        }
-                        if meta_doc.metadata.description.is_some() {
-                            doc.metadata.description = meta_doc.metadata.description;
-                        }
-                        if meta_doc.metadata.subject.is_some() {
-                            doc.metadata.subject = meta_doc.metadata.subject;
-                        }
-                        if meta_doc.metadata.creation_date.is_some() {
-                            doc.metadata.creation_date = meta_doc.metadata.creation_date;
-                        }
-                        if meta_doc.metadata.generator.is_some() {
-                            doc.metadata.generator = meta_doc.metadata.generator;
-                        }
-                        if meta_doc.metadata.identifier.is_some() {
-                            doc.metadata.identifier = meta_doc.metadata.identifier;
-                        }
-                        if meta_doc.metadata.language.is_some() {
-                            doc.metadata.language = meta_doc.metadata.language;
-                        }
-                    }
-                }
-            }
-        }
-
-        doc
-    } else {
-        // Plain text / XML (FODT)
-        let xml_content = String::from_utf8(bytes)
-            .map_err(|e| format!("Navalozh: Failed to decode text file (not UTF-8): {}", e))?;
-        Document::from_xml(&xml_content)?
-    };
+        })
+    }
+}
 
-    Ok(LexicalResponse {
+/// Converts a document into the `open_document` response.
+fn lexical_response(doc: Document) -> LexicalResponse {
+    let mut page_setup = doc.page_setup;
+    let page_regions = page_setup
+        .as_mut()
+        .map(page_regions_to_lexical)
+        .unwrap_or_default();
+    LexicalResponse {
         content: to_lexical(&doc),
         styles: doc.styles,
         metadata: doc.metadata,
-    })
+        outline_style: doc.outline_style,
+        page_setup,
+        page_regions,
+        track_changes: doc.track_changes,
+    }
 }
@@ -2,7 +2,6 @@ pub mod android;
 pub mod export;
 pub mod fs;
 pub mod locale;
-pub mod odt_zip;
 pub mod pdf;
 pub mod session;
 pub mod vector;
@@ -14,12 +14,14 @@
 
 use std::collections::HashMap;
 
-use common_core::{LexicalDocument, Metadata, StyleDefinition};
+use common_core::{
+    LexicalDocument, LexicalPageRegion, Metadata, OutlineStyle, PageSetup, StyleDefinition,
+};
 use loki_pdf::conformance::validate;
 use loki_pdf::conformance::validate_text;
 use loki_pdf::export_settings::PdfExportSettings;
 use loki_pdf::{write_pdf_x, write_text_pdf};
-use odt_format::lexical::from_lexical;
+use odt_format::lexical::{from_lexical, page_regions_from_lexical};
 use vector_core::document::VectorDocument;
 
 /// Validate a vector document against a PDF/X standard.
@@ -62,6 +64,7 @@ pub fn validate_text_pdf_x_conformance(
     lexical_json: String,
     styles: HashMap<String, StyleDefinition>,
     metadata: Metadata,
+    outline_style: Option<OutlineStyle>,
     settings: PdfExportSettings,
 ) -> Vec<serde_json::Value> {
     use serde_json::json;
@@ -75,8 +78,9 @@ pub fn validate_text_pdf_x_conformance(
             })];
         }
     };
-    let doc = from_lexical(lex, styles, metadata.clone());
-    let violations = validate_text(&doc.blocks, &doc.styles, &metadata, &settings);
+    let mut doc = from_lexical(lex, styles, metadata.clone());
+    doc.outline_style = outline_style;
+    let violations = validate_text(&doc.numbered_blocks(), &doc.styles, &metadata, &settings);
     violations
         .iter()
         .map(|v| serde_json::to_value(v).unwrap())
@@ -92,14 +96,29 @@ pub fn export_text_pdf_x(
     lexical_json: String,
     styles: HashMap<String, StyleDefinition>,
     metadata: Metadata,
+    outline_style: Option<OutlineStyle>,
+    page_setup: Option<PageSetup>,
+    page_regions: Option<Vec<LexicalPageRegion>>,
     settings: PdfExportSettings,
     path: String,
 ) -> Result<(), String> {
     let lex: LexicalDocument =
         serde_json::from_str(&lexical_json).map_err(|e| format!("Invalid Lexical JSON: {e}"))?;
-    let doc = from_lexical(lex, styles, metadata.clone());
+    let mut doc = from_lexical(lex, styles, metadata.clone());
+    doc.outline_style = outline_style;
+    let mut page_setup = page_setup.unwrap_or_default();
+    if let Some(regions) = page_regions {
+        page_regions_from_lexical(&mut page_setup, regions);
+    }
     let resolver = crate::fonts::build_font_resolver();
-    let bytes = write_text_pdf(&doc.blocks, &doc.styles, &metadata, &settings, &resolver)
-        .map_err(|e| e.to_string())?;
+    let bytes = write_text_pdf(
+        &doc.numbered_blocks(),
+        &doc.styles,
+        &page_setup,
+        &metadata,
+        &settings,
+        &resolver,
+    )
+    .map_err(|e| e.to_string())?;
     std::fs::write(&path, &bytes).map_err(|e| format!("Failed to write PDF to '{path}': {e}"))
 }
@@ -6,25 +6,29 @@
 //! user's original file.
 
 use std::collections::HashMap;
-use std::io::{Cursor, Read};
 
-use common_core::{LexicalDocument, Metadata, StyleDefinition};
+use common_core::{
+    LexicalDocument, LexicalPageRegion, Metadata, OutlineStyle, PageSetup, StyleDefinition,
+};
 use odt_format::{
-    lexical::{from_lexical, to_lexical},
-    Document,
+    lexical::{from_lexical, page_regions_from_lexical, page_regions_to_lexical, to_lexical},
+    Document, OdtPackage,
 };
 use serde::Serialize;
 
-use super::odt_zip::write_odt_zip;
-
 type CommandResult<T> = Result<T, String>;
 
 /// Lexical editor state returned by `deserialize_document`.
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SessionLexicalResponse {
     pub content: LexicalDocument,
     pub styles: HashMap<String, StyleDefinition>,
     pub metadata: Metadata,
+    pub outline_style: Option<OutlineStyle>,
+    pub page_setup: Option<PageSetup>,
+    pub page_regions: Vec<LexicalPageRegion>,
+    pub track_changes: bool,
 }
 
 /// Serialise a Lexical document to ODT bytes without writing to disk.
@@ -36,14 +40,22 @@ pub fn serialize_document(
     lexical_json: String,
     styles: HashMap<String, StyleDefinition>,
     metadata: Metadata,
+    outline_style: Option<OutlineStyle>,
+    page_setup: Option<PageSetup>,
+    page_regions: Option<Vec<LexicalPageRegion>>,
+    track_changes: Option<bool>,
 ) -> CommandResult<Vec<u8>> {
     let lex: LexicalDocument =
         serde_json::from_str(&lexical_json).map_err(|e| format!("Invalid Lexical JSON: {e}"))?;
-    let doc = from_lexical(lex, styles, metadata);
+    let mut doc = from_lexical(lex, styles, metadata);
+    doc.outline_style = outline_style;
+    doc.page_setup = page_setup;
+    if let (Some(setup), Some(regions)) = (doc.page_setup.as_mut(), page_regions) {
+        page_regions_from_lexical(setup, regions);
+    }
+    doc.track_changes = track_changes.unwrap_or_default();
 
-    let mut buf = Cursor::new(Vec::new());
-    write_odt_zip(&mut buf, &doc)?;
-    Ok(buf.into_inner())
+    OdtPackage::from_document(&doc, None)?.to_bytes()
 }
 
 /// Deserialise raw ODT bytes into a Lexical editor state.
@@ -52,43 +64,29 @@ pub fn serialize_document(
 /// session file.
 #[tauri::command]
 pub fn deserialize_document(file_content: Vec<u8>) -> CommandResult<SessionLexicalResponse> {
-    let reader = Cursor::new(file_content);
-
-    let doc = if reader.get_ref().starts_with(b"PK") {
+    let doc = if file_content.starts_with(b"PK") {
         // ZIP-based ODT
-        let mut archive =
-            zip::ZipArchive::new(reader).map_err(|e| format!("Failed to open ODT zip: {e}"))?;
-
-        let content_xml = {
-            let mut f = archive
-                .by_name("content.xml")
-                .map_err(|e| format!("content.xml missing: {e}"))?;
-            let mut s = String::new();
-            f.read_to_string(&mut s)
-                .map_err(|e| format!("Failed to read content.xml: {e}"))?;
-            s
-        };
-
-        let mut doc = Document::from_xml(&content_xml)?;
-
-        if let Ok(mut f) = archive.by_name("styles.xml") {
-            let mut s = String::new();
-            if f.read_to_string(&mut s).is_ok() {
-                let _ = doc.add_styles_from_xml(&s);
-            }
-        }
-
-        doc
+        OdtPackage::from_bytes(&file_content)
+            .map_err(|e| format!("Failed to open ODT package: {e}"))?
+            .to_document()?
     } else {
         // FODT (flat XML)
-        let xml =
-            String::from_utf8(reader.into_inner()).map_err(|e| format!("Not valid UTF-8: {e}"))?;
+        let xml = String::from_utf8(file_content).map_err(|e| format!("Not valid UTF-8: {e}"))?;
         Document::from_xml(&xml)?
     };
 
+    let mut page_setup = doc.page_setup;
+    let page_regions = page_setup
+        .as_mut()
+        .map(page_regions_to_lexical)
+        .unwrap_or_default();
     Ok(SessionLexicalResponse {
         content: to_lexical(&doc),
         styles: doc.styles,
         metadata: doc.metadata,
+        outline_style: doc.outline_style,
+        page_setup,
+        page_regions,
+        track_changes: doc.track_changes,
     })
 }
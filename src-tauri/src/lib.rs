@@ -148,6 +148,8 @@ pub fn run() {
             commands::locale::get_system_locale,
             commands::fs::save_document,
             commands::fs::open_document,
+            commands::fs::new_from_template,
+            commands::fs::import_document_styles,
             commands::export::save_epub,
             commands::session::serialize_document,
             commands::session::deserialize_document,
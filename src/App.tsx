@@ -37,6 +37,7 @@ export default function App() {
     const {
         handleOpen,
         handleOpenTemplate,
+        handleImportStyles,
         handleSave,
         handleSaveAs,
         handleNew,
@@ -192,6 +193,7 @@ export default function App() {
                     onClose={handleClose}
                     onExportEPUB={handleExportEPUB}
                     onExportPDF={handleExportPDF}
+                    onImportStyles={handleImportStyles}
                     isLoading={isLoading}
                     onMetadataClick={() => setMetadataDialogOpen(true)}
                 />
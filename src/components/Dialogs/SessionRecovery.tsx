@@ -39,12 +39,16 @@ export function SessionRecovery() {
             if (!state) return handleDiscard(meta);
 
             // Load the recovered state into the store
-            const { setContent, setStyles, setMetadata, setPath, markDirty } =
+            const { setContent, setStyles, setMetadata, setOutlineStyle, setPageSetup, setPageRegions, setTrackChanges, setPath, markDirty } =
                 useDocumentStore.getState();
             setPath(meta.originalPath);
             setContent(state.content);
             setStyles(state.styles);
             setMetadata(state.metadata);
+            setOutlineStyle(state.outlineStyle ?? null);
+            setPageSetup(state.pageSetup ?? null);
+            setPageRegions(state.pageRegions ?? []);
+            setTrackChanges(state.trackChanges ?? false);
             setSession(mgr);
             markDirty(); // Unsaved changes exist
         } catch (err) {
@@ -6,7 +6,7 @@ import {
     DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
 import { Button } from "@/components/ui/button";
-import { FolderOpen, Save, FileDown, PencilLine, Menu, XCircle, Share } from 'lucide-react';
+import { FolderOpen, Save, FileDown, PencilLine, Menu, XCircle, Share, Palette } from 'lucide-react';
 import { useDocumentStore } from '@/lib/stores/documentStore';
 import { SaveIndicator } from '@/components/SaveIndicator';
 
@@ -18,11 +18,12 @@ interface TopBarProps {
     onClose: () => void;
     onExportEPUB: () => void;
     onExportPDF: () => void;
+    onImportStyles: () => void;
     isLoading: boolean;
     onMetadataClick: () => void;
 }
 
-export function TopBar({ onOpen, onNew, onSave, onSaveAs, onClose, onExportEPUB, onExportPDF, isLoading, onMetadataClick }: TopBarProps) {
+export function TopBar({ onOpen, onNew, onSave, onSaveAs, onClose, onExportEPUB, onExportPDF, onImportStyles, isLoading, onMetadataClick }: TopBarProps) {
     const { currentContent, currentPath, metadata } = useDocumentStore();
     const hasContent = !!currentContent;
 
@@ -75,6 +76,13 @@ export function TopBar({ onOpen, onNew, onSave, onSaveAs, onClose, onExportEPUB,
 
                         <DropdownMenuSeparator />
 
+                        <DropdownMenuItem onClick={onImportStyles} disabled={isLoading || !hasContent}>
+                            <Palette className="mr-2 h-4 w-4" />
+                            <span>Import Styles...</span>
+                        </DropdownMenuItem>
+
+                        <DropdownMenuSeparator />
+
                         <DropdownMenuItem onClick={onExportEPUB} disabled={isLoading || !hasContent}>
                             <Share className="mr-2 h-4 w-4" />
                             <span>Export to EPUB</span>
@@ -7,6 +7,13 @@ import { TableNode, TableCellNode, TableRowNode } from '@lexical/table';
 import { LinkNode } from '@lexical/link';
 import { ImageNode } from './nodes/ImageNode';
 import { PageBreakNode } from './nodes/PageBreakNode';
+import { NoteNode } from './nodes/NoteNode';
+import { CommentNode, CommentEndNode } from './nodes/CommentNode';
+import { ChangeStartNode, ChangeEndNode, ChangeNode } from './nodes/TrackedChangeNode';
+import { SectionNode } from './nodes/SectionNode';
+import { TableOfContentsNode } from './nodes/TableOfContentsNode';
+import { FieldNode } from './nodes/FieldNode';
+import { BookmarkNode, BookmarkStartNode, BookmarkEndNode, ReferenceNode } from './nodes/BookmarkNode';
 import { ParagraphStyleNode } from './nodes/ParagraphStyleNode';
 import { HeadingStyleNode } from './nodes/HeadingStyleNode';
 
@@ -45,6 +52,19 @@ export const editorConfig: InitialConfigType = {
         LinkNode,
         ImageNode,
         PageBreakNode,
+        NoteNode,
+        CommentNode,
+        CommentEndNode,
+        ChangeStartNode,
+        ChangeEndNode,
+        ChangeNode,
+        SectionNode,
+        TableOfContentsNode,
+        FieldNode,
+        BookmarkNode,
+        BookmarkStartNode,
+        BookmarkEndNode,
+        ReferenceNode,
         {
             replace: ParagraphNode,
             with: (_node: ParagraphNode) => {
@@ -0,0 +1,247 @@
+import * as React from 'react';
+import {
+    DecoratorNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedLexicalNode,
+    type Spread,
+} from 'lexical';
+
+export type SerializedBookmarkNode = Spread<
+    {
+        name: string;
+    },
+    SerializedLexicalNode
+>;
+
+export type ReferenceKind = 'bookmark' | 'referenceMark';
+
+export type SerializedReferenceNode = Spread<
+    {
+        kind: ReferenceKind;
+        name: string;
+        format?: string;
+        value: string;
+    },
+    SerializedLexicalNode
+>;
+
+function bookmarkDOM(className: string, name: string): HTMLElement {
+    const span = document.createElement('span');
+    span.className = className;
+    span.dataset.bookmark = name;
+    return span;
+}
+
+/**
+ * A bookmark at a single position (ODT `text:bookmark`). Renders nothing
+ * visible.
+ */
+export class BookmarkNode extends DecoratorNode<null> {
+    __name: string;
+
+    static getType(): string {
+        return 'bookmark';
+    }
+
+    static clone(node: BookmarkNode): BookmarkNode {
+        return new BookmarkNode(node.__name, node.__key);
+    }
+
+    constructor(name: string, key?: NodeKey) {
+        super(key);
+        this.__name = name;
+    }
+
+    getName(): string {
+        return this.__name;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        return bookmarkDOM('bookmark', this.__name);
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): null {
+        return null;
+    }
+
+    exportJSON(): SerializedBookmarkNode {
+        return { type: 'bookmark', version: 1, name: this.__name };
+    }
+
+    static importJSON(serializedNode: SerializedBookmarkNode): BookmarkNode {
+        return new BookmarkNode(serializedNode.name);
+    }
+}
+
+/**
+ * Marks the start of a bookmarked range (ODT `text:bookmark-start`).
+ * Renders nothing visible.
+ */
+export class BookmarkStartNode extends DecoratorNode<null> {
+    __name: string;
+
+    static getType(): string {
+        return 'bookmark-start';
+    }
+
+    static clone(node: BookmarkStartNode): BookmarkStartNode {
+        return new BookmarkStartNode(node.__name, node.__key);
+    }
+
+    constructor(name: string, key?: NodeKey) {
+        super(key);
+        this.__name = name;
+    }
+
+    getName(): string {
+        return this.__name;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        return bookmarkDOM('bookmark-start', this.__name);
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): null {
+        return null;
+    }
+
+    exportJSON(): SerializedBookmarkNode {
+        return { type: 'bookmark-start', version: 1, name: this.__name };
+    }
+
+    static importJSON(serializedNode: SerializedBookmarkNode): BookmarkStartNode {
+        return new BookmarkStartNode(serializedNode.name);
+    }
+}
+
+/**
+ * Marks the end of a bookmarked range (ODT `text:bookmark-end`). Renders
+ * nothing visible.
+ */
+export class BookmarkEndNode extends DecoratorNode<null> {
+    __name: string;
+
+    static getType(): string {
+        return 'bookmark-end';
+    }
+
+    static clone(node: BookmarkEndNode): BookmarkEndNode {
+        return new BookmarkEndNode(node.__name, node.__key);
+    }
+
+    constructor(name: string, key?: NodeKey) {
+        super(key);
+        this.__name = name;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        return bookmarkDOM('bookmark-end', this.__name);
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): null {
+        return null;
+    }
+
+    exportJSON(): SerializedBookmarkNode {
+        return { type: 'bookmark-end', version: 1, name: this.__name };
+    }
+
+    static importJSON(serializedNode: SerializedBookmarkNode): BookmarkEndNode {
+        return new BookmarkEndNode(serializedNode.name);
+    }
+}
+
+/**
+ * A cross-reference to a bookmark or reference mark. Shows the text it
+ * last had.
+ */
+export class ReferenceNode extends DecoratorNode<React.JSX.Element> {
+    __reference: Omit<SerializedReferenceNode, 'type' | 'version'>;
+
+    static getType(): string {
+        return 'reference';
+    }
+
+    static clone(node: ReferenceNode): ReferenceNode {
+        return new ReferenceNode(node.__reference, node.__key);
+    }
+
+    constructor(reference: Omit<SerializedReferenceNode, 'type' | 'version'>, key?: NodeKey) {
+        super(key);
+        this.__reference = reference;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'reference';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    getTextContent(): string {
+        return this.__reference.value;
+    }
+
+    decorate(): React.JSX.Element {
+        const { name, value } = this.__reference;
+        return (
+            <span className="text-blue-600 underline" title={`Reference to ${name}`}>
+                {value || name}
+            </span>
+        );
+    }
+
+    exportJSON(): SerializedReferenceNode {
+        return { ...this.__reference, type: 'reference', version: 1 };
+    }
+
+    static importJSON(serializedNode: SerializedReferenceNode): ReferenceNode {
+        return new ReferenceNode({
+            kind: serializedNode.kind ?? 'bookmark',
+            name: serializedNode.name,
+            format: serializedNode.format,
+            value: serializedNode.value ?? '',
+        });
+    }
+}
+
+export function $isBookmarkNode(node: LexicalNode | null | undefined): node is BookmarkNode {
+    return node instanceof BookmarkNode;
+}
+
+export function $isReferenceNode(node: LexicalNode | null | undefined): node is ReferenceNode {
+    return node instanceof ReferenceNode;
+}
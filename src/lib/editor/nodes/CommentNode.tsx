@@ -0,0 +1,138 @@
+import * as React from 'react';
+import {
+    DecoratorNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedLexicalNode,
+    type Spread,
+} from 'lexical';
+
+export type SerializedCommentNode = Spread<
+    {
+        commentId?: string;
+        author?: string;
+        date?: string;
+        resolved: boolean;
+        children: SerializedLexicalNode[];
+        replies: SerializedCommentNode[];
+    },
+    SerializedLexicalNode
+>;
+
+export type SerializedCommentEndNode = Spread<
+    {
+        commentId: string;
+    },
+    SerializedLexicalNode
+>;
+
+/**
+ * Inline reviewer comment anchor. The comment text and replies are kept as
+ * serialized JSON so they round-trip to the backend unchanged.
+ */
+export class CommentNode extends DecoratorNode<React.JSX.Element> {
+    __data: SerializedCommentNode;
+
+    static getType(): string {
+        return 'comment';
+    }
+
+    static clone(node: CommentNode): CommentNode {
+        return new CommentNode(node.__data, node.__key);
+    }
+
+    constructor(data: SerializedCommentNode, key?: NodeKey) {
+        super(key);
+        this.__data = data;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'comment-anchor';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): React.JSX.Element {
+        const { author, date, resolved, replies } = this.__data;
+        const title = [author ?? 'Comment', date, resolved ? 'resolved' : null]
+            .filter(Boolean)
+            .join(' · ');
+        return (
+            <sup className={resolved ? 'text-gray-400' : 'text-amber-600'} title={title}>
+                {replies.length > 0 ? `💬${replies.length + 1}` : '💬'}
+            </sup>
+        );
+    }
+
+    exportJSON(): SerializedCommentNode {
+        return { ...this.__data, type: 'comment', version: 1 };
+    }
+
+    static importJSON(serializedNode: SerializedCommentNode): CommentNode {
+        return new CommentNode({
+            ...serializedNode,
+            resolved: serializedNode.resolved ?? false,
+            children: serializedNode.children ?? [],
+            replies: serializedNode.replies ?? [],
+        });
+    }
+}
+
+/**
+ * Marks the end of a comment's range. Renders nothing visible.
+ */
+export class CommentEndNode extends DecoratorNode<null> {
+    __commentId: string;
+
+    static getType(): string {
+        return 'comment-end';
+    }
+
+    static clone(node: CommentEndNode): CommentEndNode {
+        return new CommentEndNode(node.__commentId, node.__key);
+    }
+
+    constructor(commentId: string, key?: NodeKey) {
+        super(key);
+        this.__commentId = commentId;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'comment-end';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): null {
+        return null;
+    }
+
+    exportJSON(): SerializedCommentEndNode {
+        return { type: 'comment-end', version: 1, commentId: this.__commentId };
+    }
+
+    static importJSON(serializedNode: SerializedCommentEndNode): CommentEndNode {
+        return new CommentEndNode(serializedNode.commentId);
+    }
+}
+
+export function $isCommentNode(node: LexicalNode | null | undefined): node is CommentNode {
+    return node instanceof CommentNode;
+}
@@ -0,0 +1,108 @@
+import * as React from 'react';
+import {
+    DecoratorNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedLexicalNode,
+    type Spread,
+} from 'lexical';
+
+export type FieldKind = 'pageNumber' | 'pageCount' | 'date' | 'title' | 'author' | 'wordCount';
+
+/** ODF details of a field that the editor keeps but does not change. */
+export interface FieldOptions {
+    fixed?: boolean;
+    dateValue?: string;
+    dataStyleName?: string;
+}
+
+export type SerializedFieldNode = Spread<
+    {
+        kind: FieldKind;
+        value: string;
+    } & FieldOptions,
+    SerializedLexicalNode
+>;
+
+const FIELD_LABELS: Record<FieldKind, string> = {
+    pageNumber: 'Page number',
+    pageCount: 'Page count',
+    date: 'Date',
+    title: 'Title',
+    author: 'Author',
+    wordCount: 'Word count',
+};
+
+/**
+ * A text field (page number, title, ...). Shows the value it last had; the
+ * exporters fill in current values.
+ */
+export class FieldNode extends DecoratorNode<React.JSX.Element> {
+    __kind: FieldKind;
+    __value: string;
+    __options: FieldOptions;
+
+    static getType(): string {
+        return 'field';
+    }
+
+    static clone(node: FieldNode): FieldNode {
+        return new FieldNode(node.__kind, node.__value, node.__options, node.__key);
+    }
+
+    constructor(kind: FieldKind, value: string, options: FieldOptions = {}, key?: NodeKey) {
+        super(key);
+        this.__kind = kind;
+        this.__value = value;
+        this.__options = options;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'field';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    getTextContent(): string {
+        return this.__value;
+    }
+
+    decorate(): React.JSX.Element {
+        const label = FIELD_LABELS[this.__kind];
+        return (
+            <span className="bg-gray-200 rounded-sm px-0.5" title={label}>
+                {this.__value || label}
+            </span>
+        );
+    }
+
+    exportJSON(): SerializedFieldNode {
+        return { ...this.__options, kind: this.__kind, value: this.__value, type: 'field', version: 1 };
+    }
+
+    static importJSON(serializedNode: SerializedFieldNode): FieldNode {
+        const { fixed, dateValue, dataStyleName } = serializedNode;
+        return $createFieldNode(serializedNode.kind, serializedNode.value ?? '', {
+            fixed,
+            dateValue,
+            dataStyleName,
+        });
+    }
+}
+
+export function $createFieldNode(kind: FieldKind, value: string, options: FieldOptions = {}): FieldNode {
+    return new FieldNode(kind, value, options);
+}
+
+export function $isFieldNode(node: LexicalNode | null | undefined): node is FieldNode {
+    return node instanceof FieldNode;
+}
@@ -14,16 +14,29 @@ import { $createParagraphStyleNode, type ParagraphStyleNode } from './ParagraphS
 export type SerializedHeadingStyleNode = Spread<
     {
         styleName: string | null;
+        number?: string;
     },
     SerializedHeadingNode
 >;
 
+/**
+ * A heading with an ODT paragraph style. `number` is the outline number
+ * ("2.3.1") computed by the backend when the document was opened; it is
+ * shown before the heading but is not part of its text.
+ */
 export class HeadingStyleNode extends HeadingNode {
     __styleName: string | null;
-
-    constructor(tag: HeadingTagType, styleName: string | null = null, key?: NodeKey) {
+    __number: string | null;
+
+    constructor(
+        tag: HeadingTagType,
+        styleName: string | null = null,
+        number: string | null = null,
+        key?: NodeKey,
+    ) {
         super(tag, key);
         this.__styleName = styleName;
+        this.__number = number;
     }
 
     static getType(): string {
@@ -31,7 +44,7 @@ export class HeadingStyleNode extends HeadingNode {
     }
 
     static clone(node: HeadingStyleNode): HeadingStyleNode {
-        return new HeadingStyleNode(node.getTag(), node.__styleName, node.__key);
+        return new HeadingStyleNode(node.getTag(), node.__styleName, node.__number, node.__key);
     }
 
     getStyleName(): string | null {
@@ -50,6 +63,9 @@ export class HeadingStyleNode extends HeadingNode {
             const safeClass = this.__styleName.replace(/[^a-zA-Z0-9_-]/g, '_');
             dom.classList.add(`odt-style-${safeClass}`);
         }
+        if (this.__number) {
+            dom.dataset.outlineNumber = this.__number;
+        }
         return dom;
     }
 
@@ -73,6 +89,14 @@ export class HeadingStyleNode extends HeadingNode {
             }
         }
 
+        if (prevNode.__number !== this.__number) {
+            if (this.__number) {
+                dom.dataset.outlineNumber = this.__number;
+            } else {
+                delete dom.dataset.outlineNumber;
+            }
+        }
+
         return replace;
     }
 
@@ -80,6 +104,7 @@ export class HeadingStyleNode extends HeadingNode {
         return {
             ...super.exportJSON(),
             styleName: this.__styleName,
+            ...(this.__number ? { number: this.__number } : {}),
             type: 'heading-style',
             version: 1,
         };
@@ -87,6 +112,7 @@ export class HeadingStyleNode extends HeadingNode {
 
     static importJSON(serializedNode: SerializedHeadingStyleNode): HeadingStyleNode {
         const node = $createHeadingStyleNode(serializedNode.tag, serializedNode.styleName || null);
+        node.__number = serializedNode.number ?? null;
         node.setFormat(serializedNode.format);
         node.setIndent(serializedNode.indent);
         node.setDirection(serializedNode.direction);
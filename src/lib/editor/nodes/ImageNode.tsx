@@ -8,10 +8,23 @@ import {
     type Spread,
 } from 'lexical';
 
+/** Size (pixels at 96 dpi), anchoring and text wrapping of an image frame. */
+export interface ImageFrame {
+    width?: number;
+    height?: number;
+    anchor?: 'paragraph' | 'char' | 'asChar' | 'page';
+    wrap?: 'none' | 'left' | 'right' | 'parallel' | 'dynamic' | 'runThrough';
+    /** The image links to a file outside the document instead of being embedded. */
+    linked?: boolean;
+}
+
 export type SerializedImageNode = Spread<
     {
         src: string;
         altText: string;
+        title?: string;
+        frame?: ImageFrame;
+        caption?: string;
     },
     SerializedLexicalNode
 >;
@@ -19,11 +32,24 @@ export type SerializedImageNode = Spread<
 export class ImageNode extends DecoratorNode<React.JSX.Element> {
     __src: string;
     __altText: string;
+    __title?: string;
+    __frame?: ImageFrame;
+    __caption?: string;
 
-    constructor(src: string, altText: string, key?: NodeKey) {
+    constructor(
+        src: string,
+        altText: string,
+        title?: string,
+        frame?: ImageFrame,
+        caption?: string,
+        key?: NodeKey,
+    ) {
         super(key);
         this.__src = src;
         this.__altText = altText;
+        this.__title = title;
+        this.__frame = frame;
+        this.__caption = caption;
     }
 
     static getType(): string {
@@ -31,7 +57,14 @@ export class ImageNode extends DecoratorNode<React.JSX.Element> {
     }
 
     static clone(node: ImageNode): ImageNode {
-        return new ImageNode(node.__src, node.__altText, node.__key);
+        return new ImageNode(
+            node.__src,
+            node.__altText,
+            node.__title,
+            node.__frame,
+            node.__caption,
+            node.__key,
+        );
     }
 
     createDOM(_config: EditorConfig): HTMLElement {
@@ -45,7 +78,25 @@ export class ImageNode extends DecoratorNode<React.JSX.Element> {
     }
 
     decorate(): React.JSX.Element {
-        return <img src={this.__src} alt={this.__altText} className="max-w-full h-auto" />;
+        const img = (
+            <img
+                src={this.__src}
+                alt={this.__altText}
+                title={this.__title}
+                width={this.__frame?.width}
+                height={this.__frame?.height}
+                className="max-w-full h-auto"
+            />
+        );
+        if (!this.__caption) {
+            return img;
+        }
+        return (
+            <figure className="inline-block">
+                {img}
+                <figcaption className="text-sm text-center">{this.__caption}</figcaption>
+            </figure>
+        );
     }
 
     exportJSON(): SerializedImageNode {
@@ -54,11 +105,20 @@ export class ImageNode extends DecoratorNode<React.JSX.Element> {
             version: 1,
             src: this.__src,
             altText: this.__altText,
+            ...(this.__title !== undefined && { title: this.__title }),
+            ...(this.__frame !== undefined && { frame: this.__frame }),
+            ...(this.__caption !== undefined && { caption: this.__caption }),
         };
     }
 
     static importJSON(serializedNode: SerializedImageNode): ImageNode {
-        return new ImageNode(serializedNode.src, serializedNode.altText);
+        return new ImageNode(
+            serializedNode.src,
+            serializedNode.altText,
+            serializedNode.title,
+            serializedNode.frame,
+            serializedNode.caption,
+        );
     }
 }
 
@@ -0,0 +1,98 @@
+import * as React from 'react';
+import {
+    DecoratorNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedLexicalNode,
+    type Spread,
+} from 'lexical';
+
+export type SerializedNoteNode = Spread<
+    {
+        noteClass: 'footnote' | 'endnote';
+        citation: string;
+        noteId?: string;
+        children: SerializedLexicalNode[];
+    },
+    SerializedLexicalNode
+>;
+
+/**
+ * Inline footnote/endnote anchor. The note body is kept as serialized JSON
+ * so it round-trips to the backend unchanged.
+ */
+export class NoteNode extends DecoratorNode<React.JSX.Element> {
+    __noteClass: 'footnote' | 'endnote';
+    __citation: string;
+    __noteId: string | undefined;
+    __body: SerializedLexicalNode[];
+
+    static getType(): string {
+        return 'note';
+    }
+
+    static clone(node: NoteNode): NoteNode {
+        return new NoteNode(node.__noteClass, node.__citation, node.__noteId, node.__body, node.__key);
+    }
+
+    constructor(
+        noteClass: 'footnote' | 'endnote',
+        citation: string,
+        noteId: string | undefined,
+        body: SerializedLexicalNode[],
+        key?: NodeKey,
+    ) {
+        super(key);
+        this.__noteClass = noteClass;
+        this.__citation = citation;
+        this.__noteId = noteId;
+        this.__body = body;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'note-anchor';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): React.JSX.Element {
+        return (
+            <sup className="text-blue-600" title={this.__noteClass}>
+                {this.__citation}
+            </sup>
+        );
+    }
+
+    exportJSON(): SerializedNoteNode {
+        return {
+            type: 'note',
+            version: 1,
+            noteClass: this.__noteClass,
+            citation: this.__citation,
+            ...(this.__noteId ? { noteId: this.__noteId } : {}),
+            children: this.__body,
+        };
+    }
+
+    static importJSON(serializedNode: SerializedNoteNode): NoteNode {
+        return new NoteNode(
+            serializedNode.noteClass,
+            serializedNode.citation,
+            serializedNode.noteId,
+            serializedNode.children ?? [],
+        );
+    }
+}
+
+export function $isNoteNode(node: LexicalNode | null | undefined): node is NoteNode {
+    return node instanceof NoteNode;
+}
@@ -0,0 +1,112 @@
+import {
+    ElementNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedElementNode,
+    type Spread,
+} from 'lexical';
+
+export type SerializedSectionNode = Spread<
+    {
+        name: string;
+        columnCount: number;
+        columnGap?: number;
+        protected: boolean;
+    },
+    SerializedElementNode
+>;
+
+export interface SectionData {
+    name: string;
+    columnCount: number;
+    columnGap?: number;
+    protected: boolean;
+}
+
+/**
+ * A named document section (ODT `text:section`). Its blocks are shown in
+ * the section's columns; protected sections cannot be edited.
+ */
+export class SectionNode extends ElementNode {
+    __section: SectionData;
+
+    static getType(): string {
+        return 'section';
+    }
+
+    static clone(node: SectionNode): SectionNode {
+        return new SectionNode(node.__section, node.__key);
+    }
+
+    constructor(section: SectionData, key?: NodeKey) {
+        super(key);
+        this.__section = section;
+    }
+
+    getSection(): SectionData {
+        return this.__section;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const dom = document.createElement('section');
+        applySection(dom, this.__section);
+        return dom;
+    }
+
+    updateDOM(prevNode: SectionNode, dom: HTMLElement): boolean {
+        if (prevNode.__section !== this.__section) {
+            applySection(dom, this.__section);
+        }
+        return false;
+    }
+
+    isShadowRoot(): boolean {
+        return true;
+    }
+
+    canBeEmpty(): boolean {
+        return false;
+    }
+
+    exportJSON(): SerializedSectionNode {
+        return {
+            ...super.exportJSON(),
+            ...this.__section,
+            type: 'section',
+            version: 1,
+        };
+    }
+
+    static importJSON(serializedNode: SerializedSectionNode): SectionNode {
+        const node = $createSectionNode({
+            name: serializedNode.name ?? '',
+            columnCount: serializedNode.columnCount ?? 1,
+            columnGap: serializedNode.columnGap,
+            protected: serializedNode.protected ?? false,
+        });
+        node.setFormat(serializedNode.format);
+        node.setIndent(serializedNode.indent);
+        node.setDirection(serializedNode.direction);
+        return node;
+    }
+}
+
+function applySection(dom: HTMLElement, section: SectionData): void {
+    dom.dataset.sectionName = section.name;
+    dom.style.columnCount = section.columnCount > 1 ? String(section.columnCount) : '';
+    dom.style.columnGap = section.columnGap !== undefined ? `${section.columnGap}px` : '';
+    if (section.protected) {
+        dom.contentEditable = 'false';
+    } else {
+        dom.removeAttribute('contenteditable');
+    }
+}
+
+export function $createSectionNode(section: SectionData): SectionNode {
+    return new SectionNode(section);
+}
+
+export function $isSectionNode(node: LexicalNode | null | undefined): node is SectionNode {
+    return node instanceof SectionNode;
+}
@@ -0,0 +1,105 @@
+import * as React from 'react';
+import {
+    DecoratorNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedLexicalNode,
+    type Spread,
+} from 'lexical';
+
+export interface TocEntry {
+    level: number;
+    text: string;
+    target?: string;
+    page?: string;
+}
+
+export interface TableOfContentsData {
+    name: string;
+    title?: string;
+    outlineLevel: number;
+    entries: TocEntry[];
+}
+
+export type SerializedTableOfContentsNode = Spread<TableOfContentsData, SerializedLexicalNode>;
+
+/**
+ * A generated table of contents (ODT `text:table-of-content`). Shows the
+ * entries it was last generated with; they are rebuilt from the headings
+ * on export.
+ */
+export class TableOfContentsNode extends DecoratorNode<React.JSX.Element> {
+    __toc: TableOfContentsData;
+
+    static getType(): string {
+        return 'table-of-contents';
+    }
+
+    static clone(node: TableOfContentsNode): TableOfContentsNode {
+        return new TableOfContentsNode(node.__toc, node.__key);
+    }
+
+    constructor(toc: TableOfContentsData, key?: NodeKey) {
+        super(key);
+        this.__toc = toc;
+    }
+
+    getTableOfContents(): TableOfContentsData {
+        return this.__toc;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const nav = document.createElement('nav');
+        nav.className = 'table-of-contents';
+        return nav;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    decorate(): React.JSX.Element {
+        const { title, entries } = this.__toc;
+        return (
+            <div className="border border-gray-200 rounded-sm p-2 my-2">
+                {title && <div className="font-bold mb-1">{title}</div>}
+                <ol className="list-none">
+                    {entries.map((entry, i) => (
+                        <li
+                            key={i}
+                            className="flex justify-between"
+                            style={{ marginLeft: `${(entry.level - 1) * 1.5}em` }}
+                        >
+                            <span>{entry.text}</span>
+                            {entry.page && <span>{entry.page}</span>}
+                        </li>
+                    ))}
+                </ol>
+            </div>
+        );
+    }
+
+    exportJSON(): SerializedTableOfContentsNode {
+        return { ...this.__toc, type: 'table-of-contents', version: 1 };
+    }
+
+    static importJSON(serializedNode: SerializedTableOfContentsNode): TableOfContentsNode {
+        return $createTableOfContentsNode({
+            name: serializedNode.name ?? '',
+            title: serializedNode.title,
+            outlineLevel: serializedNode.outlineLevel ?? 10,
+            entries: serializedNode.entries ?? [],
+        });
+    }
+}
+
+export function $createTableOfContentsNode(toc: TableOfContentsData): TableOfContentsNode {
+    return new TableOfContentsNode(toc);
+}
+
+export function $isTableOfContentsNode(
+    node: LexicalNode | null | undefined,
+): node is TableOfContentsNode {
+    return node instanceof TableOfContentsNode;
+}
@@ -0,0 +1,198 @@
+import * as React from 'react';
+import {
+    DecoratorNode,
+    type EditorConfig,
+    type LexicalNode,
+    type NodeKey,
+    type SerializedLexicalNode,
+    type Spread,
+} from 'lexical';
+
+export type ChangeKind = 'insertion' | 'deletion' | 'formatChange';
+
+export type SerializedChangeStartNode = Spread<
+    {
+        changeId: string;
+        kind: ChangeKind;
+        author?: string;
+        date?: string;
+    },
+    SerializedLexicalNode
+>;
+
+export type SerializedChangeEndNode = Spread<
+    {
+        changeId: string;
+    },
+    SerializedLexicalNode
+>;
+
+export type SerializedChangeNode = Spread<
+    {
+        changeId: string;
+        kind: ChangeKind;
+        author?: string;
+        date?: string;
+        children: SerializedLexicalNode[];
+    },
+    SerializedLexicalNode
+>;
+
+function changeTitle(kind: ChangeKind, author?: string, date?: string): string {
+    const label = kind === 'insertion' ? 'Inserted' : kind === 'deletion' ? 'Deleted' : 'Formatted';
+    return [label, author ? `by ${author}` : null, date].filter(Boolean).join(' ');
+}
+
+/**
+ * Start of a tracked insertion or format change. Renders a thin marker with
+ * the change author and date as a tooltip.
+ */
+export class ChangeStartNode extends DecoratorNode<React.JSX.Element> {
+    __data: SerializedChangeStartNode;
+
+    static getType(): string {
+        return 'change-start';
+    }
+
+    static clone(node: ChangeStartNode): ChangeStartNode {
+        return new ChangeStartNode(node.__data, node.__key);
+    }
+
+    constructor(data: SerializedChangeStartNode, key?: NodeKey) {
+        super(key);
+        this.__data = data;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'change-start';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): React.JSX.Element {
+        const { kind, author, date } = this.__data;
+        return (
+            <span className="border-l-2 border-green-600" title={changeTitle(kind, author, date)} />
+        );
+    }
+
+    exportJSON(): SerializedChangeStartNode {
+        return { ...this.__data, type: 'change-start', version: 1 };
+    }
+
+    static importJSON(serializedNode: SerializedChangeStartNode): ChangeStartNode {
+        return new ChangeStartNode({ ...serializedNode, kind: serializedNode.kind ?? 'insertion' });
+    }
+}
+
+/**
+ * End of a tracked change's range. Renders nothing visible.
+ */
+export class ChangeEndNode extends DecoratorNode<null> {
+    __changeId: string;
+
+    static getType(): string {
+        return 'change-end';
+    }
+
+    static clone(node: ChangeEndNode): ChangeEndNode {
+        return new ChangeEndNode(node.__changeId, node.__key);
+    }
+
+    constructor(changeId: string, key?: NodeKey) {
+        super(key);
+        this.__changeId = changeId;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'change-end';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): null {
+        return null;
+    }
+
+    exportJSON(): SerializedChangeEndNode {
+        return { type: 'change-end', version: 1, changeId: this.__changeId };
+    }
+
+    static importJSON(serializedNode: SerializedChangeEndNode): ChangeEndNode {
+        return new ChangeEndNode(serializedNode.changeId);
+    }
+}
+
+/**
+ * A tracked deletion. The removed content is kept as serialized JSON so it
+ * round-trips to the backend unchanged.
+ */
+export class ChangeNode extends DecoratorNode<React.JSX.Element> {
+    __data: SerializedChangeNode;
+
+    static getType(): string {
+        return 'change';
+    }
+
+    static clone(node: ChangeNode): ChangeNode {
+        return new ChangeNode(node.__data, node.__key);
+    }
+
+    constructor(data: SerializedChangeNode, key?: NodeKey) {
+        super(key);
+        this.__data = data;
+    }
+
+    createDOM(_config: EditorConfig): HTMLElement {
+        const span = document.createElement('span');
+        span.className = 'change-deletion';
+        return span;
+    }
+
+    updateDOM(): false {
+        return false;
+    }
+
+    isInline(): boolean {
+        return true;
+    }
+
+    decorate(): React.JSX.Element {
+        const { kind, author, date } = this.__data;
+        return (
+            <span className="border-l-2 border-red-600" title={changeTitle(kind, author, date)} />
+        );
+    }
+
+    exportJSON(): SerializedChangeNode {
+        return { ...this.__data, type: 'change', version: 1 };
+    }
+
+    static importJSON(serializedNode: SerializedChangeNode): ChangeNode {
+        return new ChangeNode({
+            ...serializedNode,
+            kind: serializedNode.kind ?? 'deletion',
+            children: serializedNode.children ?? [],
+        });
+    }
+}
+
+export function $isChangeNode(node: LexicalNode | null | undefined): node is ChangeNode {
+    return node instanceof ChangeNode;
+}
@@ -41,6 +41,10 @@ export function useAutoSave({
                     content: state.currentContent,
                     styles: state.styles,
                     metadata: state.metadata,
+                    outlineStyle: state.outlineStyle,
+                    pageSetup: state.pageSetup,
+                    pageRegions: state.pageRegions,
+                    trackChanges: state.trackChanges,
                 });
             } catch (err) {
                 console.error('[AutoSave] Session autosave failed:', err);
@@ -59,6 +63,10 @@ export function useAutoSave({
                     content: state.currentContent,
                     styles: state.styles,
                     metadata: state.metadata,
+                    outlineStyle: state.outlineStyle,
+                    pageSetup: state.pageSetup,
+                    pageRegions: state.pageRegions,
+                    trackChanges: state.trackChanges,
                 });
             } catch (err) {
                 console.error('[AutoSave] Snapshot failed:', err);
@@ -7,7 +7,7 @@ import { notifyError } from '@/lib/utils/notifyError';
 
 export function useFileExport() {
   const [isExporting, setIsExporting] = useState(false);
-  const { currentContent, styles, metadata } = useDocumentStore();
+  const { currentContent, styles, metadata, outlineStyle, pageSetup, pageRegions } = useDocumentStore();
 
   const handleExportEPUB = async () => {
     if (!currentContent) return;
@@ -26,7 +26,7 @@ export function useFileExport() {
       const path = typeof selected === 'string' ? selected : (selected as any).path;
       if (!path) return;
 
-      const bytes = await saveEpub(path, JSON.stringify(currentContent), styles, metadata, []);
+      const bytes = await saveEpub(path, JSON.stringify(currentContent), styles, metadata, outlineStyle, []);
       if (bytes && path.startsWith('content://')) await writeFile(path, bytes);
     } catch (error) {
       console.error('Failed to export EPUB:', error);
@@ -58,6 +58,9 @@ export function useFileExport() {
         JSON.stringify(currentContent),
         styles,
         metadata,
+        outlineStyle,
+        pageSetup,
+        pageRegions,
         DEFAULT_PDF_SETTINGS,
         path,
       );
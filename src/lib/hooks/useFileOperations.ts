@@ -1,7 +1,7 @@
 import { useState } from 'react';
 import { open, save } from '@tauri-apps/plugin-dialog';
 import { readFile, writeFile } from '@tauri-apps/plugin-fs';
-import { openDocument, saveDocument, takePersistableUriPermission, openFilePicker } from '../tauri/commands';
+import { openDocument, newFromTemplate, importDocumentStyles, saveDocument, takePersistableUriPermission, openFilePicker } from '../tauri/commands';
 import { useDocumentStore } from '../stores/documentStore';
 import { useHistoryStore } from '../stores/historyStore';
 import { useSessionPersistence } from './useSessionPersistence';
@@ -21,11 +21,19 @@ export function useFileOperations() {
         currentContent,
         styles,
         metadata,
+        outlineStyle,
+        pageSetup,
+        pageRegions,
+        trackChanges,
         session,
         setPath,
         setContent,
         setStyles,
         setMetadata,
+        setOutlineStyle,
+        setPageSetup,
+        setPageRegions,
+        setTrackChanges,
         markClean,
         markDirty,
         markSaving,
@@ -50,6 +58,10 @@ export function useFileOperations() {
             setContent(response.content);
             setStyles(response.styles);
             setMetadata({ ...response.metadata, title: 'Untitled Document', identifier: null });
+            setOutlineStyle(response.outlineStyle);
+            setPageSetup(response.pageSetup);
+            setPageRegions(response.pageRegions);
+            setTrackChanges(response.trackChanges);
             markDirty();
         } catch (error) {
             console.error('Failed to create new document:', error);
@@ -92,6 +104,10 @@ export function useFileOperations() {
             setContent(response.content);
             setStyles(response.styles);
             setMetadata(response.metadata);
+            setOutlineStyle(response.outlineStyle);
+            setPageSetup(response.pageSetup);
+            setPageRegions(response.pageRegions);
+            setTrackChanges(response.trackChanges);
             addDocument({
                 path,
                 name: response.metadata.title || path.split('/').pop() || 'Untitled',
@@ -104,6 +120,10 @@ export function useFileOperations() {
                 content: response.content,
                 styles: response.styles,
                 metadata: response.metadata,
+                outlineStyle: response.outlineStyle,
+                pageSetup: response.pageSetup,
+                pageRegions: response.pageRegions,
+                trackChanges: response.trackChanges,
             });
 
             markClean();
@@ -158,12 +178,16 @@ export function useFileOperations() {
                 await endSession();
                 clearSession();
                 const fileBytes = await readFile(path);
-                const response = await openDocument(path, fileBytes);
+                const response = await newFromTemplate(path, fileBytes);
 
                 setPath('');
                 setContent(response.content);
                 setStyles(response.styles);
                 setMetadata({ ...response.metadata, title: 'Untitled Document', identifier: null });
+                setOutlineStyle(response.outlineStyle);
+                setPageSetup(response.pageSetup);
+                setPageRegions(response.pageRegions);
+                setTrackChanges(response.trackChanges);
                 addTemplate('text', {
                     path,
                     name: response.metadata.title || path.split('/').pop() || 'Untitled',
@@ -184,6 +208,38 @@ export function useFileOperations() {
         }
     };
 
+    const handleImportStyles = async () => {
+        try {
+            const selected = await open({
+                title: 'Import Styles',
+                filters: [{ name: 'Document or Template', extensions: ['odt', 'fodt', 'ott'] }],
+            });
+            if (!selected) return;
+            const path = typeof selected === 'string' ? selected : (selected as any).path;
+            if (!path) return;
+
+            setIsLoading(true);
+            try {
+                const fileBytes = await readFile(path);
+                // Keep the document's own styles; clashing imports get a new name.
+                const response = await importDocumentStyles(
+                    path,
+                    styles,
+                    { mode: 'merge', conflict: 'rename' },
+                    fileBytes,
+                );
+                setStyles(response.styles);
+                markDirty();
+            } finally {
+                setIsLoading(false);
+            }
+        } catch (error) {
+            console.error('Failed to import styles:', error);
+            notifyError('Failed to import styles', error);
+            throw error;
+        }
+    };
+
     const handleSave = async (background = false) => {
         if (!currentPath || !currentContent) return handleSaveAs();
 
@@ -195,6 +251,10 @@ export function useFileOperations() {
                     content: currentContent,
                     styles,
                     metadata,
+                    outlineStyle,
+                    pageSetup,
+                    pageRegions,
+                    trackChanges,
                 });
             } else {
                 // No active session: serialize and write directly.
@@ -205,6 +265,10 @@ export function useFileOperations() {
                     JSON.stringify(currentContent),
                     styles,
                     metadata,
+                    outlineStyle,
+                    pageSetup,
+                    pageRegions,
+                    trackChanges,
                     currentPath,
                 );
                 if (bytes && currentPath.startsWith('content://')) {
@@ -249,6 +313,10 @@ export function useFileOperations() {
                 JSON.stringify(currentContent),
                 styles,
                 metadata,
+                outlineStyle,
+                pageSetup,
+                pageRegions,
+                trackChanges,
                 currentPath || undefined,
             );
             if (bytes && path.startsWith('content://')) {
@@ -286,6 +354,7 @@ export function useFileOperations() {
     return {
         handleOpen,
         handleOpenTemplate,
+        handleImportStyles,
         handleSave,
         handleSaveAs,
         handleNew,
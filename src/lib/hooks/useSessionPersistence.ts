@@ -18,6 +18,10 @@ export function useSessionPersistence() {
                 currentContent: store.currentContent,
                 styles: store.styles,
                 metadata: store.metadata,
+                outlineStyle: store.outlineStyle,
+                pageSetup: store.pageSetup,
+                pageRegions: store.pageRegions,
+                trackChanges: store.trackChanges,
                 isDirty: store.isDirty,
                 timestamp: Date.now(),
             };
@@ -15,7 +15,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import type { LexicalDocumentData, StyleDefinition, Metadata } from '../types/odt';
+import type { LexicalDocumentData, StyleDefinition, Metadata, OutlineStyle, PageSetup, PageRegion } from '../types/odt';
 import {
     SessionMeta,
     createSessionDir,
@@ -34,6 +34,10 @@ interface DocState {
     content: LexicalDocumentData;
     styles: Record<string, StyleDefinition>;
     metadata: Metadata;
+    outlineStyle?: OutlineStyle | null;
+    pageSetup?: PageSetup | null;
+    pageRegions?: PageRegion[];
+    trackChanges?: boolean;
 }
 
 // ─── Serialisation helpers ────────────────────────────────────────────────────
@@ -43,6 +47,10 @@ async function serializeToBytes(state: DocState): Promise<Uint8Array> {
         lexicalJson: JSON.stringify(state.content),
         styles: state.styles,
         metadata: state.metadata,
+        outlineStyle: state.outlineStyle ?? null,
+        pageSetup: state.pageSetup ?? null,
+        pageRegions: state.pageRegions ?? [],
+        trackChanges: state.trackChanges ?? false,
     });
     return new Uint8Array(result);
 }
@@ -1,5 +1,5 @@
 import { create } from 'zustand';
-import type { StyleDefinition, Metadata, LexicalDocumentData } from '../types/odt';
+import type { StyleDefinition, Metadata, LexicalDocumentData, OutlineStyle, PageSetup, PageRegion } from '../types/odt';
 import type { SessionManager } from '../session/SessionManager';
 
 interface DocumentState {
@@ -7,6 +7,14 @@ interface DocumentState {
     currentContent: LexicalDocumentData | null;
     styles: Record<string, StyleDefinition>;
     metadata: Metadata;
+    /** Heading numbering of the document, passed back on save and export. */
+    outlineStyle: OutlineStyle | null;
+    /** Page layouts and master pages of the document, passed back on save and PDF export. */
+    pageSetup: PageSetup | null;
+    /** Header and footer editor states, passed back on save and PDF export. */
+    pageRegions: PageRegion[];
+    /** Whether edits are recorded as tracked changes, passed back on save. */
+    trackChanges: boolean;
     currentStyle: string;
     isDirty: boolean;
     isSaving: boolean;
@@ -18,6 +26,10 @@ interface DocumentState {
     setContent: (content: LexicalDocumentData) => void;
     setStyles: (styles: Record<string, StyleDefinition>) => void;
     setMetadata: (metadata: Metadata) => void;
+    setOutlineStyle: (outlineStyle: OutlineStyle | null) => void;
+    setPageSetup: (pageSetup: PageSetup | null) => void;
+    setPageRegions: (pageRegions: PageRegion[]) => void;
+    setTrackChanges: (trackChanges: boolean) => void;
     setStyle: (style: string) => void;
     setSession: (session: SessionManager | null) => void;
     markDirty: () => void;
@@ -42,6 +54,10 @@ export const useDocumentStore = create<DocumentState>((set) => ({
         creationDate: null,
         generator: 'AppThere Loki Text',
     },
+    outlineStyle: null,
+    pageSetup: null,
+    pageRegions: [],
+    trackChanges: false,
     currentStyle: 'Standard',
     isDirty: false,
     isSaving: false,
@@ -52,6 +68,10 @@ export const useDocumentStore = create<DocumentState>((set) => ({
     setContent: (content) => set({ currentContent: content, isDirty: true }),
     setStyles: (styles) => set({ styles, isDirty: true }),
     setMetadata: (metadata) => set({ metadata, isDirty: true }),
+    setOutlineStyle: (outlineStyle) => set({ outlineStyle }),
+    setPageSetup: (pageSetup) => set({ pageSetup }),
+    setPageRegions: (pageRegions) => set({ pageRegions, isDirty: true }),
+    setTrackChanges: (trackChanges) => set({ trackChanges }),
     setStyle: (style) => set({ currentStyle: style }),
     setSession: (session) => set({ session }),
     markDirty: () => set({ isDirty: true }),
@@ -65,6 +85,10 @@ export const useDocumentStore = create<DocumentState>((set) => ({
         currentPath: null,
         currentContent: null,
         styles: {},
+        outlineStyle: null,
+        pageSetup: null,
+        pageRegions: [],
+        trackChanges: false,
         currentStyle: 'Standard',
         isDirty: false,
         isSaving: false,
@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { StyleDefinition, Metadata, LexicalDocumentData } from '../types/odt';
+import type { StyleDefinition, Metadata, LexicalDocumentData, OutlineStyle, PageSetup, PageRegion } from '../types/odt';
 
 /**
  * Android only: persist a content:// URI permission across app restarts.
@@ -27,11 +27,15 @@ export async function openFilePicker(): Promise<string> {
     return invoke<string>('pick_file_to_open');
 }
 
-/** Response from `open_document`: native Lexical editor state + styles + metadata. */
+/** Response from `open_document`: native Lexical editor state + styles + metadata + heading numbering + page layouts + headers and footers + the track-changes switch. */
 export interface LexicalResponse {
     content: LexicalDocumentData;
     styles: Record<string, StyleDefinition>;
     metadata: Metadata;
+    outlineStyle: OutlineStyle | null;
+    pageSetup: PageSetup | null;
+    pageRegions: PageRegion[];
+    trackChanges: boolean;
 }
 
 export async function openDocument(
@@ -44,11 +48,63 @@ export async function openDocument(
     });
 }
 
+/** Open a template (`.ott` or flat XML) as a new untitled document. */
+export async function newFromTemplate(
+    path: string,
+    fileContent?: Uint8Array
+): Promise<LexicalResponse> {
+    return await invoke('new_from_template', {
+        path,
+        fileContent: fileContent ? Array.from(fileContent) : null,
+    });
+}
+
+/** Options for `importDocumentStyles` — matches `odt_format::template::StyleImportOptions`. */
+export interface StyleImportOptions {
+    mode: 'merge' | 'replace';
+    conflict: 'keep' | 'overwrite' | 'rename';
+}
+
+/** What `importDocumentStyles` did, with each list sorted by style name. */
+export interface StyleImportReport {
+    added: string[];
+    overwritten: string[];
+    kept: string[];
+    /** `[imported, new]` name pairs. */
+    renamed: [string, string][];
+    removed: string[];
+}
+
+/** Response from `import_document_styles`: the combined styles and the report. */
+export interface StyleImportResponse {
+    styles: Record<string, StyleDefinition>;
+    report: StyleImportReport;
+}
+
+/** Import the named styles of another document or template into `styles`. */
+export async function importDocumentStyles(
+    path: string,
+    styles: Record<string, StyleDefinition>,
+    options: StyleImportOptions,
+    fileContent?: Uint8Array
+): Promise<StyleImportResponse> {
+    return await invoke('import_document_styles', {
+        path,
+        fileContent: fileContent ? Array.from(fileContent) : null,
+        styles,
+        options,
+    });
+}
+
 export async function saveDocument(
     path: string,
     lexicalJson: string,
     styles: Record<string, StyleDefinition>,
     metadata: Metadata,
+    outlineStyle: OutlineStyle | null,
+    pageSetup: PageSetup | null,
+    pageRegions: PageRegion[],
+    trackChanges: boolean,
     originalPath?: string,
     originalContent?: Uint8Array
 ): Promise<Uint8Array | null> {
@@ -57,6 +113,10 @@ export async function saveDocument(
         lexicalJson,
         styles,
         metadata,
+        outlineStyle,
+        pageSetup,
+        pageRegions,
+        trackChanges,
         originalPath: originalPath ?? null,
         originalContent: originalContent ? Array.from(originalContent) : null,
     });
@@ -71,11 +131,19 @@ export async function serializeDocument(
     lexicalJson: string,
     styles: Record<string, StyleDefinition>,
     metadata: Metadata,
+    outlineStyle: OutlineStyle | null,
+    pageSetup: PageSetup | null,
+    pageRegions: PageRegion[],
+    trackChanges: boolean,
 ): Promise<Uint8Array> {
     const result: number[] = await invoke('serialize_document', {
         lexicalJson,
         styles,
         metadata,
+        outlineStyle,
+        pageSetup,
+        pageRegions,
+        trackChanges,
     });
     return new Uint8Array(result);
 }
@@ -85,6 +153,7 @@ export async function saveEpub(
     lexicalJson: string,
     styles: Record<string, StyleDefinition>,
     metadata: Metadata,
+    outlineStyle: OutlineStyle | null,
     fontPaths: string[]
 ): Promise<Uint8Array | null> {
     const result: number[] | null = await invoke('save_epub', {
@@ -92,6 +161,7 @@ export async function saveEpub(
         lexicalJson,
         styles,
         metadata,
+        outlineStyle,
         fontPaths,
     });
     return result ? new Uint8Array(result) : null;
@@ -131,12 +201,14 @@ export async function validateTextPdfXConformance(
     lexicalJson: string,
     styles: Record<string, StyleDefinition>,
     metadata: Metadata,
+    outlineStyle: OutlineStyle | null,
     settings: PdfExportSettings,
 ): Promise<PdfConformanceViolation[]> {
     return await invoke('validate_text_pdf_x_conformance', {
         lexicalJson,
         styles,
         metadata,
+        outlineStyle,
         settings,
     });
 }
@@ -149,6 +221,9 @@ export async function exportTextPdfX(
     lexicalJson: string,
     styles: Record<string, StyleDefinition>,
     metadata: Metadata,
+    outlineStyle: OutlineStyle | null,
+    pageSetup: PageSetup | null,
+    pageRegions: PageRegion[],
     settings: PdfExportSettings,
     path: string,
 ): Promise<void> {
@@ -156,6 +231,9 @@ export async function exportTextPdfX(
         lexicalJson,
         styles,
         metadata,
+        outlineStyle,
+        pageSetup,
+        pageRegions,
         settings,
         path,
     });
@@ -6,6 +6,26 @@ export type { Colour };
 export enum StyleFamily {
     Paragraph = "Paragraph",
     Text = "Text",
+    Section = "Section",
+    Table = "Table",
+    TableColumn = "TableColumn",
+    TableRow = "TableRow",
+    TableCell = "TableCell",
+    Graphic = "Graphic",
+    List = "List",
+}
+
+/** Property groups other than paragraph and text — mirrors `common_core::PropertyGroup`. */
+export type PropertyGroup = 'Section' | 'Table' | 'TableColumn' | 'TableRow' | 'TableCell' | 'Graphic';
+
+/** One level of a named list style — mirrors `common_core::ListLevelStyle`. */
+export interface ListLevelStyle {
+    level: number;
+    kind: 'Number' | 'Bullet' | 'Image';
+    attributes: Record<string, string>;
+    properties: Record<string, string>;
+    labelAlignment: Record<string, string>;
+    textProperties: Record<string, string>;
 }
 
 export interface StyleDefinition {
@@ -18,10 +38,70 @@ export interface StyleDefinition {
     textTransform: string | null;
     outlineLevel: number | null;
     autocomplete: boolean | null;
+    /** The master page a paragraph with this style starts (`style:master-page-name`). */
+    masterPageName?: string;
     /** Typed font colour, populated from fo:color / loki:colour. Null when not set. */
     fontColour: Colour | null;
     /** Typed background colour, populated from fo:background-color. Null when not set. */
     backgroundColour: Colour | null;
+    /** Properties outside the paragraph and text groups, e.g. a frame style's graphic properties. */
+    properties?: Partial<Record<PropertyGroup, Record<string, string>>>;
+    /** The levels of a list style. */
+    listLevels?: ListLevelStyle[];
+}
+
+/** Numbering of one outline level — mirrors `common_core::OutlineLevel`. */
+export interface OutlineLevel {
+    level: number;
+    numFormat?: string;
+    numPrefix?: string;
+    numSuffix?: string;
+    start?: number;
+    displayLevels: number;
+}
+
+/** Heading numbering (`text:outline-style`) — mirrors `common_core::OutlineStyle`. */
+export interface OutlineStyle {
+    name: string;
+    levels: OutlineLevel[];
+}
+
+/** Page size, margins and orientation in points — mirrors `common_core::PageLayout`. */
+export interface PageLayout {
+    name: string;
+    width: number;
+    height: number;
+    marginTop: number;
+    marginBottom: number;
+    marginLeft: number;
+    marginRight: number;
+    orientation: 'portrait' | 'landscape';
+}
+
+/** A named page style — mirrors `common_core::MasterPage`. */
+export interface MasterPage {
+    name: string;
+    displayName?: string;
+    pageLayout: string;
+    next?: string;
+}
+
+/** Page layouts and master pages — mirrors `common_core::PageSetup`. */
+export interface PageSetup {
+    layouts: PageLayout[];
+    masterPages: MasterPage[];
+}
+
+/**
+ * One header or footer variant of a master page as its own editor state —
+ * mirrors `common_core::LexicalPageRegion`.
+ */
+export interface PageRegion {
+    masterPage: string;
+    kind: 'header' | 'footer';
+    /** `left` is shown on even pages, `first` on the first page of the run. */
+    variant: 'default' | 'left' | 'first';
+    content: LexicalDocumentData;
 }
 
 export interface Metadata {
@@ -33,6 +113,35 @@ export interface Metadata {
     creator: string | null;
     creationDate: string | null;
     generator: string | null;
+    keywords?: string[];
+    /** Last modification (dc:date). */
+    date?: string | null;
+    editingCycles?: number | null;
+    /** ISO 8601 duration, e.g. `PT1H30M`. */
+    editingDuration?: string | null;
+    initialCreator?: string | null;
+    userDefined?: UserDefinedField[];
+    /** Recomputed from the content on save. */
+    statistics?: DocumentStatistics | null;
+}
+
+/** A custom document property (`meta:user-defined`). */
+export interface UserDefinedField {
+    name: string;
+    /** `float`, `date`, `time`, `boolean` or `string`; absent means string. */
+    valueType?: string;
+    value: string;
+}
+
+/** Counts of the document content (`meta:document-statistic`). */
+export interface DocumentStatistics {
+    pageCount?: number;
+    paragraphCount: number;
+    wordCount: number;
+    characterCount: number;
+    nonWhitespaceCharacterCount: number;
+    tableCount: number;
+    imageCount: number;
 }
 
 // Lexical node representation (replaces TiptapNode)
@@ -54,13 +163,27 @@ export type LexicalNode =
     | ListNode
     | ListItemNode
     | QuoteNode
+    | SectionNode
+    | TableOfContentsNode
     | ImageNode
     | LinkNode
     | TableNode
     | TableRowNode
     | TableCellNode
     | PageBreakNode
-    | LineBreakNode;
+    | LineBreakNode
+    | TabNode
+    | NoteNode
+    | CommentNode
+    | CommentEndNode
+    | ChangeStartNode
+    | ChangeEndNode
+    | ChangeNode
+    | FieldNode
+    | BookmarkNode
+    | BookmarkStartNode
+    | BookmarkEndNode
+    | ReferenceNode;
 
 export interface ParagraphNode {
     type: "paragraph" | "paragraph-style";
@@ -78,6 +201,7 @@ export interface HeadingNode {
     tag: "h1" | "h2" | "h3" | "h4" | "h5" | "h6";
     children: LexicalNode[];
     styleName?: string;
+    number?: string;  // Outline number, e.g. "2.3.1"
     format?: string;
     indent?: number;
 }
@@ -93,9 +217,21 @@ export interface TextNode {
     version?: number;
 }
 
+export interface ListAttrs {
+    styleName?: string | null;
+    numFormat?: string | null;
+    numPrefix?: string | null;
+    numSuffix?: string | null;
+    bulletChar?: string | null;
+    start?: number | null;
+    continueNumbering?: boolean;
+}
+
 export interface ListNode {
     type: "list";
     listType: "bullet" | "number";
+    start?: number;
+    listAttrs?: ListAttrs;  // ODT list style, kept for round-trips
     children: ListItemNode[];
 }
 
@@ -109,10 +245,27 @@ export interface QuoteNode {
     children: LexicalNode[];
 }
 
+export interface SectionNode {
+    type: "section";
+    name: string;
+    columnCount: number;
+    columnGap?: number;  // pixels
+    protected: boolean;
+    children: LexicalNode[];
+}
+
 export interface ImageNode {
     type: "image";
     src: string;
     altText: string;
+    title?: string;
+    frame?: {
+        width?: number;
+        height?: number;
+        anchor?: "paragraph" | "char" | "asChar" | "page";
+        wrap?: "none" | "left" | "right" | "parallel" | "dynamic" | "runThrough";
+    };
+    caption?: string;
 }
 
 export interface LinkNode {
@@ -139,6 +292,8 @@ export interface TableCellNode {
     colSpan?: number;
     rowSpan?: number;
     headerState?: number;
+    width?: number;
+    backgroundColor?: string | null;
     children: LexicalNode[];
 }
 
@@ -152,8 +307,133 @@ export interface LineBreakNode {
     version: number;
 }
 
+export interface TabNode {
+    type: "tab";
+    text: "\t";
+    format?: number;
+    detail?: number;
+    version: number;
+}
+
+export interface NoteNode {
+    type: "note";
+    noteClass: "footnote" | "endnote";
+    citation: string;
+    noteId?: string;
+    children: LexicalNode[];
+    version: number;
+}
+
+export interface CommentNode {
+    type: "comment";
+    commentId?: string;
+    author?: string;
+    date?: string;
+    resolved: boolean;
+    children: LexicalNode[];
+    replies: CommentNode[];
+    version: number;
+}
+
+export interface CommentEndNode {
+    type: "comment-end";
+    commentId: string;
+    version: number;
+}
+
+export type ChangeKind = "insertion" | "deletion" | "formatChange";
+
+export interface ChangeStartNode {
+    type: "change-start";
+    changeId: string;
+    kind: ChangeKind;
+    author?: string;
+    date?: string;
+    version: number;
+}
+
+export interface ChangeEndNode {
+    type: "change-end";
+    changeId: string;
+    version: number;
+}
+
+export interface ChangeNode {
+    type: "change";
+    changeId: string;
+    kind: ChangeKind;
+    author?: string;
+    date?: string;
+    children: LexicalNode[];
+    version: number;
+}
+
+export type FieldKind = "pageNumber" | "pageCount" | "date" | "title" | "author" | "wordCount";
+
+export interface FieldNode {
+    type: "field";
+    kind: FieldKind;
+    value: string;
+    /** The value is fixed rather than evaluated (`text:fixed`). */
+    fixed?: boolean;
+    /** The date a date field shows, in ISO 8601 (`text:date-value`). */
+    dateValue?: string;
+    /** The number style that formats the value (`style:data-style-name`). */
+    dataStyleName?: string;
+    version: number;
+}
+
+export interface BookmarkNode {
+    type: "bookmark";
+    name: string;
+    version: number;
+}
+
+export interface BookmarkStartNode {
+    type: "bookmark-start";
+    name: string;
+    version: number;
+}
+
+export interface BookmarkEndNode {
+    type: "bookmark-end";
+    name: string;
+    version: number;
+}
+
+export type ReferenceKind = "bookmark" | "referenceMark";
+
+export interface ReferenceNode {
+    type: "reference";
+    kind: ReferenceKind;
+    name: string;
+    format?: string;
+    value: string;
+    version: number;
+}
+
+export interface TocEntry {
+    level: number;
+    text: string;
+    target?: string;
+    page?: string;
+}
+
+export interface TableOfContentsNode {
+    type: "table-of-contents";
+    name: string;
+    title?: string;
+    outlineLevel: number;
+    entries: TocEntry[];
+    version: number;
+}
+
 export interface DocumentResponse {
     content: LexicalDocumentData;
     styles: Record<string, StyleDefinition>;
     metadata: Metadata;
+    outlineStyle: OutlineStyle | null;
+    pageSetup: PageSetup | null;
+    pageRegions: PageRegion[];
+    trackChanges: boolean;
 }